@@ -0,0 +1,54 @@
+//! Benchmarks for the parts of a round that run without input or output.
+//!
+//! Run with `cargo bench`. The `simulate_match` benchmark plays a whole match
+//! from random stops, so its time per iteration tells how long
+//! `rust_game simulate` takes for a batch of games.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use rand::{SeedableRng, rngs::StdRng};
+use rust_game::config::GameConfig;
+use rust_game::player::Player;
+use rust_game::rules::{DamageModel, RuleSet};
+use rust_game::scoring;
+use rust_game::simulation;
+use rust_game::state::{GameState, TurnOutcome};
+
+/// Number of targets drawn each turn, as in the classic game.
+const TARGET_COUNT: usize = 5;
+
+fn players() -> [Player; 2] {
+    [
+        Player::new("Player1".to_string(), 100, 60, 70, 3),
+        Player::new("Player2".to_string(), 100, 60, 70, 3),
+    ]
+}
+
+fn bench_calculate_score(c: &mut Criterion) {
+    c.bench_function("calculate_score", |b| {
+        b.iter(|| scoring::calculate_score(black_box(42), black_box(57), black_box(70), black_box(1)))
+    });
+}
+
+fn bench_targets(c: &mut Criterion) {
+    let rules = RuleSet::default();
+    let mut rng = StdRng::seed_from_u64(1);
+    c.bench_function("targets", |b| b.iter(|| rules.targets(&mut rng, black_box(TARGET_COUNT))));
+}
+
+fn bench_resolve_round(c: &mut Criterion) {
+    let state = GameState::new(players());
+    c.bench_function("resolve_round", |b| {
+        b.iter(|| state.resolve_round(black_box([TurnOutcome::Played(80), TurnOutcome::Played(45)]), black_box(10), DamageModel::Difference, &[]))
+    });
+}
+
+fn bench_simulate_match(c: &mut Criterion) {
+    let config = GameConfig { vitality: 100, ..GameConfig::default() };
+    let mut rng = StdRng::seed_from_u64(1);
+    c.bench_function("simulate_match", |b| b.iter(|| simulation::simulate_match(&config, &mut rng)));
+}
+
+criterion_group!(benches, bench_calculate_score, bench_targets, bench_resolve_round, bench_simulate_match);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+//! Build script generating the manual page of the game.
+//!
+//! The page is written to `OUT_DIR` and printed by the `man` subcommand, so it
+//! always describes the options of the build it ships with.
+
+use std::env;
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "src/cli/args.rs"]
+mod args;
+
+/// Name the game is installed as, that of its binary.
+const BIN_NAME: &str = "rust_game";
+
+fn main() -> io::Result<()> {
+    println!("cargo::rerun-if-changed=src/cli/args.rs");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("cargo sets OUT_DIR for build scripts"));
+    let mut page = File::create(out_dir.join("rust_game.1"))?;
+    clap_mangen::Man::new(args::command().name(BIN_NAME)).render(&mut page)
+}
@@ -0,0 +1,10 @@
+//! Configuration files must parse or be rejected, never panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_game::config::ConfigFile;
+
+fuzz_target!(|contents: &str| {
+    let _ = ConfigFile::parse(contents);
+});
@@ -0,0 +1,13 @@
+//! Replay files must parse or be rejected, never panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_game::replay::Replay;
+
+fuzz_target!(|json: &str| {
+    if let Ok(replay) = Replay::parse(json) {
+        // A valid replay must be safe to race against
+        let _ = replay.check(replay.waves, &replay.stops, 0);
+    }
+});
@@ -0,0 +1,16 @@
+//! Saved matches and profiles must parse or be rejected, never panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_game::persistence;
+use rust_game::profile::Profile;
+
+fuzz_target!(|json: &str| {
+    if let Ok(state) = persistence::parse_game(json) {
+        // A valid save must be safe to resume
+        let _ = state.in_progress();
+        let _ = state.hash();
+    }
+    let _ = Profile::parse(json);
+});
@@ -0,0 +1,13 @@
+//! Input script lines must parse or be rejected, never panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_game::ui::source::ScriptedEvent;
+
+fuzz_target!(|line: &str| {
+    if let Ok(event) = line.parse::<ScriptedEvent>() {
+        // What a valid line prints must parse back to the same action
+        assert_eq!(event.to_string().parse::<ScriptedEvent>().ok(), Some(event));
+    }
+});
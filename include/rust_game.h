@@ -0,0 +1,122 @@
+#ifndef RUST_GAME_H
+#define RUST_GAME_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// What `rg_game_step` did.
+typedef enum RgStep {
+  // It played a round and the match goes on
+  RgStep_Played = 0,
+  // It played the last round of the match
+  RgStep_Over = 1,
+  // It was given no game, or a match already over
+  RgStep_Invalid = -1,
+} RgStep;
+
+// A match simulated with random stops, opaque to C.
+typedef struct RgGame RgGame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Calculates the score of a single stop on the default counter.
+//
+// # Arguments
+//
+// * `target` - The target number to match
+// * `counter_value` - The counter value when stopped
+// * `strength` - The player's strength attribute
+// * `miss` - The number of times the counter looped back to zero
+//
+// # Returns
+//
+// The score, the same as `scoring::calculate_score`
+uint32_t rg_calculate_score(uint32_t target,
+                            uint32_t counter_value,
+                            uint32_t strength,
+                            uint32_t miss);
+
+// Creates a match between two players with the same attributes.
+//
+// # Arguments
+//
+// * `vitality` - The starting vitality of both players
+// * `speed` - The starting speed of both players
+// * `strength` - The starting strength of both players
+// * `stamina` - The starting stamina of both players
+// * `target_count` - The number of targets of each turn
+// * `seed` - The seed of the targets and stops, the same seed plays the same match
+//
+// # Returns
+//
+// A new game at round 1, to release with `rg_game_free`
+struct RgGame *rg_game_new(uint32_t vitality,
+                           uint32_t speed,
+                           uint32_t strength,
+                           uint32_t stamina,
+                           uint32_t target_count,
+                           uint64_t seed);
+
+// Plays one round of a match, with a random stop for each target.
+//
+// # Arguments
+//
+// * `game` - The game, from `rg_game_new`
+//
+// # Returns
+//
+// `RgStep_Played` if the match goes on, `RgStep_Over` after its last round,
+// or `RgStep_Invalid` for a null or finished game
+//
+// # Safety
+//
+// `game` must be null or a game from `rg_game_new` not yet released.
+enum RgStep rg_game_step(struct RgGame *game);
+
+// Returns the current round of a match, 0 for a null game.
+//
+// # Safety
+//
+// `game` must be null or a game from `rg_game_new` not yet released.
+uint32_t rg_game_round(const struct RgGame *game);
+
+// Returns a player's vitality, 0 for a null game or an unknown player.
+//
+// # Safety
+//
+// `game` must be null or a game from `rg_game_new` not yet released.
+uint32_t rg_game_vitality(const struct RgGame *game, uint32_t player);
+
+// Returns the index of the winner, or -1 while the match goes on, after a draw or for a null game.
+//
+// # Safety
+//
+// `game` must be null or a game from `rg_game_new` not yet released.
+int32_t rg_game_winner(const struct RgGame *game);
+
+// Returns the hash of the state, the same as the terminal game and the server compute.
+//
+// # Safety
+//
+// `game` must be null or a game from `rg_game_new` not yet released.
+uint64_t rg_game_hash(const struct RgGame *game);
+
+// Releases a game.
+//
+// # Safety
+//
+// `game` must be null or a game from `rg_game_new` not yet released, and
+// is dangling afterwards.
+void rg_game_free(struct RgGame *game);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUST_GAME_H */
@@ -0,0 +1,744 @@
+//! API module for driving matches over HTTP with JSON, started with `--api`.
+//!
+//! External frontends and bots create a match, then poll it and answer
+//! what it waits for, with no terminal involved. Each match runs on its own
+//! thread with the same `Game` as a local match, reading the players'
+//! actions from the requests and keeping its output and events for the
+//! next poll:
+//!
+//! - `POST /games` creates a match, optionally with `player1`, `player2` and `seed`
+//! - `GET /games/{id}` returns the match: its output, events, players and what it waits for
+//! - `POST /games/{id}/stop` stops the counter of the player whose turn it is at a `value`
+//! - `POST /games/{id}/penalty` chooses the `attribute` the round's loser is penalized on
+//! - `POST /games/{id}/line` answers any other prompt with a line of `text`
+//! - `DELETE /games/{id}` ends the match and forgets it
+//!
+//! Every action names the `player` it comes from, and is refused with
+//! `409 Conflict` unless the match waits for that player and that kind of
+//! action.
+
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::audio::Audio;
+use crate::config::{GameConfig, KeyBindings};
+use crate::counter::CounterEvents;
+use crate::error::{GameError, GameResult};
+use crate::events::{self, GameEvent};
+use crate::game::Game;
+use crate::http::{self, Request, Response};
+use crate::i18n::{Msg, tr};
+use crate::player::Attribute;
+use crate::rules::{DraftItem, RuleSet};
+use crate::ui::render::{self, Renderer};
+use crate::ui::source::InputSource;
+use crate::ui::{CounterFrame, CounterView};
+
+/// Most matches kept at once, finished or not, until some are deleted.
+pub const MAX_GAMES: usize = 64;
+
+/// The kind of action a match waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expect {
+    /// The value to stop the counter at, sent to `/stop`
+    Stop,
+    /// A line of text, sent to `/line` or `/penalty`
+    Line,
+    /// One of the listed keys, sent to `/line` as a line starting with it, or to
+    /// `/penalty` when the penalty is chosen against a timer
+    Key,
+}
+
+/// What a match waits for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Waiting {
+    /// The index of the player expected to act
+    pub player: usize,
+    /// What they are expected to send
+    pub action: Expect,
+    /// The keys they can press, for a key
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<char>,
+}
+
+/// A player as the match shows them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlayerView {
+    /// The player's name, once their first turn started
+    pub name: String,
+    /// The player's vitality, once their first turn started
+    pub vitality: Option<u32>,
+}
+
+/// Everything a poll returns about a match.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GameView {
+    /// The id of the match in the API
+    pub id: u64,
+    /// Every line the match printed
+    pub output: Vec<String>,
+    /// The counter as it is drawn while it runs
+    pub counter: Option<String>,
+    /// Every event of the match
+    pub events: Vec<GameEvent>,
+    /// What the match waits for, None while it plays on by itself or once it is over
+    pub waiting_for: Option<Waiting>,
+    /// Whether the match is over
+    pub over: bool,
+    /// The index of the winner, once the match is over, None for a draw
+    pub winner: Option<usize>,
+    /// Why the match stopped early, if it did
+    pub error: Option<String>,
+}
+
+/// A match as a poll returns it, with the players worked out from its events.
+#[derive(Serialize)]
+struct Poll<'a> {
+    /// Both players' names and vitality
+    players: Vec<PlayerView>,
+    /// Everything else about the match
+    #[serde(flatten)]
+    view: &'a GameView,
+}
+
+impl<'a> Poll<'a> {
+    /// Works out both players' names and vitality from the events of a match.
+    ///
+    /// # Arguments
+    ///
+    /// * `view` - The match
+    ///
+    /// # Returns
+    ///
+    /// The match as a poll returns it
+    fn new(view: &'a GameView) -> Self {
+        let players = events::summarize(&view.events).into_iter()
+            .map(|summary| PlayerView { vitality: summary.vitality.last().copied(), name: summary.name })
+            .collect();
+        Self { players, view }
+    }
+}
+
+/// What a player sent to a match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    /// The value to stop the counter at
+    Stop(u32),
+    /// A line of text
+    Line(String),
+}
+
+/// Reads the players' actions from the requests sent to a match.
+struct ApiInput {
+    /// The actions, in the order they were accepted
+    actions: Receiver<Action>,
+    /// The match, to tell what it waits for
+    view: Arc<Mutex<GameView>>,
+    /// The index of the player the game waits for
+    player: usize,
+}
+
+impl ApiInput {
+    /// Waits for the expected player's next action.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The kind of action to wait for
+    /// * `keys` - The keys the player can press, for a key
+    /// * `timeout` - How long to wait at most, None to wait as long as it takes
+    ///
+    /// # Returns
+    ///
+    /// Result containing the action, None if the time ran out, or `GameError::Interrupted` if the match was deleted
+    fn next(&mut self, action: Expect, keys: &[char], timeout: Option<Duration>) -> GameResult<Option<Action>> {
+        self.view.lock().unwrap().waiting_for = Some(Waiting { player: self.player, action, keys: keys.to_vec() });
+        let received = match timeout {
+            Some(timeout) => match self.actions.recv_timeout(timeout) {
+                Ok(action) => Some(action),
+                Err(RecvTimeoutError::Timeout) => {
+                    let mut view = self.view.lock().unwrap();
+                    // An action accepted just as the time ran out is already on its way
+                    if view.waiting_for.take().is_some() {
+                        return Ok(None);
+                    }
+                    drop(view);
+                    self.actions.recv().ok()
+                },
+                Err(RecvTimeoutError::Disconnected) => None,
+            },
+            None => self.actions.recv().ok(),
+        };
+        received.map(Some).ok_or(GameError::Interrupted)
+    }
+
+    /// Waits for the expected player to press one of the given keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to wait for
+    /// * `timeout` - How long to wait at most, None to wait as long as it takes
+    ///
+    /// # Returns
+    ///
+    /// Result containing the index of the key, None if the time ran out
+    fn next_key(&mut self, keys: &[char], timeout: Option<Duration>) -> GameResult<Option<usize>> {
+        while let Some(Action::Line(line)) = self.next(Expect::Key, keys, timeout)? {
+            let pressed = line.trim().chars().next()
+                .and_then(|key| keys.iter().position(|k| k.eq_ignore_ascii_case(&key)));
+            if pressed.is_some() {
+                return Ok(pressed);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl InputSource for ApiInput {
+    fn read_line(&mut self) -> GameResult<String> {
+        match self.next(Expect::Line, &[], None)? {
+            Some(Action::Line(line)) => Ok(line),
+            _ => Ok(String::new()),
+        }
+    }
+
+    fn wait_for_key(&mut self, keys: &[char]) -> GameResult<usize> {
+        Ok(self.next_key(keys, None)?.unwrap_or_default())
+    }
+
+    fn wait_for_key_timeout(&mut self, keys: &[char], timeout: Duration) -> GameResult<Option<usize>> {
+        self.next_key(keys, Some(timeout))
+    }
+
+    fn expect_player(&mut self, player_idx: usize) {
+        self.player = player_idx;
+    }
+
+    fn stop_at(&mut self) -> GameResult<Option<u32>> {
+        match self.next(Expect::Stop, &[], None)? {
+            Some(Action::Stop(value)) => Ok(Some(value)),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Keeps the output of a match for the next poll.
+struct ApiRenderer {
+    /// The match the output goes to
+    view: Arc<Mutex<GameView>>,
+}
+
+impl Renderer for ApiRenderer {
+    fn info(&mut self, text: &str) {
+        self.view.lock().unwrap().output.push(text.to_string());
+    }
+
+    fn info_wrapped(&mut self, text: &str) {
+        self.info(text);
+    }
+
+    fn show_briefly(&mut self, text: &str, _duration: Duration) -> GameResult<()> {
+        // Nobody watches the match as it plays, so there is nothing to wait for
+        self.info(text);
+        Ok(())
+    }
+
+    fn show_scoreboard(&mut self, line: String) -> GameResult<()> {
+        self.info(&line);
+        Ok(())
+    }
+
+    fn hide_scoreboard(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn display_counter(&mut self, events: CounterEvents, frame: CounterFrame, _audio: Audio) -> GameResult<JoinHandle<()>> {
+        let view = Arc::clone(&self.view);
+        render::show_captions(events, frame, move |caption| view.lock().unwrap().counter = Some(caption))
+    }
+
+    fn clear_counter(&mut self, _view: CounterView) -> GameResult<()> {
+        self.view.lock().unwrap().counter = None;
+        Ok(())
+    }
+
+    fn new_line(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn prompt(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+}
+
+/// A match created through the API.
+struct ApiGame {
+    /// What the match shows, shared with its thread
+    view: Arc<Mutex<GameView>>,
+    /// Where the players' actions go
+    actions: Sender<Action>,
+}
+
+/// The settings a match is created with, all optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewGame {
+    /// The name of the first player
+    player1: Option<String>,
+    /// The name of the second player
+    player2: Option<String>,
+    /// The seed of the random number generator
+    seed: Option<u64>,
+}
+
+/// A request to stop the counter.
+#[derive(Debug, Deserialize)]
+struct StopRequest {
+    /// The index of the player stopping it
+    player: usize,
+    /// The value to stop it at, in ticks
+    value: u32,
+}
+
+/// A request to choose the penalty of a round.
+#[derive(Debug, Deserialize)]
+struct PenaltyRequest {
+    /// The index of the round's winner
+    player: usize,
+    /// The attribute the loser loses points of
+    attribute: Attribute,
+}
+
+/// A request to answer a prompt.
+#[derive(Debug, Deserialize)]
+struct LineRequest {
+    /// The index of the player answering
+    player: usize,
+    /// The answer, empty for ENTER alone
+    #[serde(default)]
+    text: String,
+}
+
+/// The matches of the API and the settings new ones start with.
+#[derive(Clone)]
+pub struct Api {
+    /// The settings every match starts from
+    config: GameConfig,
+    /// The matches, by id
+    games: Arc<Mutex<HashMap<u64, ApiGame>>>,
+    /// The id of the next match
+    next_id: Arc<Mutex<u64>>,
+}
+
+impl Api {
+    /// Creates the API, with no match yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings every match starts from
+    ///
+    /// # Returns
+    ///
+    /// A new Api
+    pub fn new(config: GameConfig) -> Self {
+        // The players act through the API only, never on a keyboard. There is no draft
+        // either, so the penalty options the API works out are the ones the match offers
+        let config = GameConfig {
+            keys: KeyBindings::default(),
+            duel: false,
+            reaction_game: false,
+            draft: false,
+            autosave: false,
+            ..config
+        };
+        Self { config, games: Arc::new(Mutex::new(HashMap::new())), next_id: Arc::new(Mutex::new(1)) }
+    }
+
+    /// Answers a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to answer
+    ///
+    /// # Returns
+    ///
+    /// The JSON response, with an `error` for a request that cannot be carried out
+    pub fn handle(&self, request: &Request) -> Response {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        let result = match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["games"]) => self.create(&request.body),
+            ("GET", ["games", id]) => self.poll(id),
+            ("DELETE", ["games", id]) => self.delete(id),
+            ("POST", ["games", id, "stop"]) => parse::<StopRequest>(&request.body)
+                .and_then(|stop| self.act(id, stop.player, &[Expect::Stop], Action::Stop(stop.value))),
+            ("POST", ["games", id, "penalty"]) => parse::<PenaltyRequest>(&request.body).and_then(|penalty| {
+                let choice = penalty_choice(&self.config.rules, penalty.attribute)
+                    .ok_or_else(|| ("409 Conflict", format!("The {:?} penalty was banned", penalty.attribute)))?;
+                // A penalty chosen against a timer waits for a key rather than a line
+                self.act(id, penalty.player, &[Expect::Line, Expect::Key], Action::Line(choice.to_string()))
+            }),
+            ("POST", ["games", id, "line"]) => parse::<LineRequest>(&request.body)
+                .and_then(|line| self.act(id, line.player, &[Expect::Line, Expect::Key], Action::Line(line.text))),
+            (_, ["games", ..]) => Err(("405 Method Not Allowed", format!("{} is not supported here", request.method))),
+            _ => Err(("404 Not Found", format!("No such resource: {}", request.path))),
+        };
+        result.unwrap_or_else(|(status, error)| Response::json(status, serde_json::json!({ "error": error }).to_string()))
+    }
+
+    /// Creates a match and starts it on its own thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The settings of the match, as JSON, or nothing for the defaults
+    ///
+    /// # Returns
+    ///
+    /// The id of the match, or why it could not be created
+    fn create(&self, body: &str) -> Result<Response, (&'static str, String)> {
+        let settings = if body.trim().is_empty() { NewGame::default() } else { parse::<NewGame>(body)? };
+        let mut games = self.games.lock().unwrap();
+        if games.len() >= MAX_GAMES {
+            return Err(("503 Service Unavailable", format!("There are already {} matches, delete some first", MAX_GAMES)));
+        }
+        let id = {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            *next_id - 1
+        };
+        let config = GameConfig {
+            player1_name: settings.player1.unwrap_or_else(|| self.config.player1_name.clone()),
+            player2_name: settings.player2.unwrap_or_else(|| self.config.player2_name.clone()),
+            seed: settings.seed.or(self.config.seed),
+            ..self.config.clone()
+        };
+        let view = Arc::new(Mutex::new(GameView { id, ..GameView::default() }));
+        let (actions, received) = mpsc::channel();
+        start(config, Arc::clone(&view), received);
+        games.insert(id, ApiGame { view, actions });
+        Ok(Response::json("201 Created", serde_json::json!({ "id": id }).to_string()))
+    }
+
+    /// Returns a match as it is now.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the match, from the path
+    ///
+    /// # Returns
+    ///
+    /// The match, or an error if there is no such match
+    fn poll(&self, id: &str) -> Result<Response, (&'static str, String)> {
+        let games = self.games.lock().unwrap();
+        let game = find(&games, id)?;
+        let view = game.view.lock().unwrap();
+        let json = serde_json::to_string(&Poll::new(&view)).map_err(|e| ("500 Internal Server Error", e.to_string()))?;
+        Ok(Response::json("200 OK", json))
+    }
+
+    /// Ends a match and forgets it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the match, from the path
+    ///
+    /// # Returns
+    ///
+    /// An empty answer, or an error if there is no such match
+    fn delete(&self, id: &str) -> Result<Response, (&'static str, String)> {
+        let mut games = self.games.lock().unwrap();
+        find(&games, id)?;
+        // Without anyone to send actions, the match stops the next time it waits for one
+        games.retain(|game_id, _| game_id.to_string() != id);
+        Ok(Response::json("200 OK", "{}"))
+    }
+
+    /// Sends a player's action to a match waiting for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the match, from the path
+    /// * `player` - The index of the player acting
+    /// * `accepted` - The kinds of action this one can answer
+    /// * `action` - The action
+    ///
+    /// # Returns
+    ///
+    /// The match, or an error if it does not wait for this player or this kind of action
+    fn act(&self, id: &str, player: usize, accepted: &[Expect], action: Action) -> Result<Response, (&'static str, String)> {
+        {
+            let games = self.games.lock().unwrap();
+            let game = find(&games, id)?;
+            let mut view = game.view.lock().unwrap();
+            match &view.waiting_for {
+                Some(waiting) if waiting.player == player && accepted.contains(&waiting.action) => {},
+                Some(waiting) => return Err(("409 Conflict",
+                                             format!("The match waits for a {:?} from player {}", waiting.action, waiting.player))),
+                None => return Err(("409 Conflict", "The match does not wait for anyone".to_string())),
+            }
+            // Taken now, so the same prompt cannot be answered twice
+            view.waiting_for = None;
+            let _ = game.actions.send(action);
+        }
+        Ok(Response::json("202 Accepted", "{}"))
+    }
+}
+
+/// Plays a match on its own thread, keeping what it shows in its view.
+///
+/// # Arguments
+///
+/// * `config` - The settings of the match
+/// * `view` - What the match shows
+/// * `actions` - The players' actions
+fn start(config: GameConfig, view: Arc<Mutex<GameView>>, actions: Receiver<Action>) {
+    let (relay, relayed) = mpsc::channel();
+    let events_view = Arc::clone(&view);
+    let relay_thread = thread::spawn(move || {
+        for event in relayed {
+            events_view.lock().unwrap().events.push(event);
+        }
+    });
+    thread::spawn(move || {
+        let input = ApiInput { actions, view: Arc::clone(&view), player: 0 };
+        let mut game = Game::new(config)
+            .with_input(input)
+            .with_renderer(ApiRenderer { view: Arc::clone(&view) })
+            .with_event_relay(relay);
+        let result = game.play_match();
+        drop(game);
+        let _ = relay_thread.join();
+
+        let mut view = view.lock().unwrap();
+        view.over = true;
+        view.waiting_for = None;
+        view.counter = None;
+        match result {
+            Ok(winner) => view.winner = Some(winner),
+            Err(e) => view.error = Some(e.to_string()),
+        }
+    });
+}
+
+/// Finds a match by the id in a path.
+///
+/// # Arguments
+///
+/// * `games` - The matches, by id
+/// * `id` - The id, as found in the path
+///
+/// # Returns
+///
+/// The match, or a `404 Not Found` error
+fn find<'a>(games: &'a HashMap<u64, ApiGame>, id: &str) -> Result<&'a ApiGame, (&'static str, String)> {
+    id.parse::<u64>().ok()
+        .and_then(|id| games.get(&id))
+        .ok_or_else(|| ("404 Not Found", format!("No match with id {}", id)))
+}
+
+/// Reads the JSON body of a request.
+///
+/// # Arguments
+///
+/// * `body` - The body
+///
+/// # Returns
+///
+/// The request, or a `400 Bad Request` error
+fn parse<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, (&'static str, String)> {
+    serde_json::from_str(body).map_err(|e| ("400 Bad Request", e.to_string()))
+}
+
+/// Works out which option of the penalty prompt applies a penalty.
+///
+/// # Arguments
+///
+/// * `rules` - The rules, for the penalties left after the draft
+/// * `attribute` - The attribute to penalize
+///
+/// # Returns
+///
+/// The number of the option, starting at 1, or None if the penalty was banned
+fn penalty_choice(rules: &RuleSet, attribute: Attribute) -> Option<usize> {
+    [Attribute::Speed, Attribute::Strength].into_iter()
+        .filter(|&penalty| rules.pool.allows(DraftItem::Penalty(penalty)))
+        .position(|penalty| penalty == attribute)
+        .map(|index| index + 1)
+}
+
+/// Serves the API until the listener fails.
+///
+/// # Arguments
+///
+/// * `port` - The port to listen on, on the local machine only
+/// * `config` - The settings every match starts from
+///
+/// # Returns
+///
+/// Result indicating why the API stopped, or an error if the port cannot be listened on
+pub fn serve(port: u16, config: GameConfig) -> GameResult<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    log::info!("{}", tr!(Msg::ApiListening, listener.local_addr()?));
+    let api = Api::new(config);
+    http::serve(listener, move |request: &Request| api.handle(request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::render::RenderMode;
+    use std::time::Instant;
+
+    /// Creates an API for one-target matches between players with 1 vitality.
+    fn api() -> Api {
+        Api::new(GameConfig {
+            vitality: 1,
+            stamina: 0,
+            target_count: 1,
+            seed: Some(1),
+            render_mode: RenderMode::Null,
+            // The counter still runs in real time up to the value sent
+            rules: RuleSet { base_tick_ms: 2, ..RuleSet::default() },
+            ..GameConfig::default()
+        })
+    }
+
+    fn request(method: &str, path: &str, body: &str) -> Response {
+        api_request(&api(), method, path, body)
+    }
+
+    fn api_request(api: &Api, method: &str, path: &str, body: &str) -> Response {
+        api.handle(&Request { method: method.to_string(), path: path.to_string(), body: body.to_string() })
+    }
+
+    /// Polls a match until it waits for someone or is over.
+    fn poll_until_waiting(api: &Api, id: u64) -> serde_json::Value {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let view: serde_json::Value = serde_json::from_str(&api_request(api, "GET", &format!("/games/{}", id), "").body).unwrap();
+            if !view["waiting_for"].is_null() || view["over"] == true {
+                return view;
+            }
+            assert!(Instant::now() < deadline, "the match neither waits nor ends");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_match_played_through_the_api() {
+        let api = api();
+        let created = api_request(&api, "POST", "/games", r#"{"player1": "Alice", "player2": "Bob"}"#);
+        assert_eq!(created.status, "201 Created");
+        let id = serde_json::from_str::<serde_json::Value>(&created.body).unwrap()["id"].as_u64().unwrap();
+
+        let view = loop {
+            let view = poll_until_waiting(&api, id);
+            if view["over"] == true {
+                break view;
+            }
+            let waiting = &view["waiting_for"];
+            let player = waiting["player"].as_u64().unwrap();
+            // Alice stops right on the target, Bob as far from it as he can
+            let response = match waiting["action"].as_str().unwrap() {
+                "stop" => {
+                    let target = view["events"].as_array().unwrap().iter().rev()
+                        .find(|event| event["event"] == "turn_start")
+                        .map(|event| event["targets"][0].as_u64().unwrap()).unwrap();
+                    let value = if player == 0 { target } else { (target + 50) % 100 };
+                    api_request(&api, "POST", &format!("/games/{}/stop", id), &format!(r#"{{"player": {}, "value": {}}}"#, player, value))
+                },
+                _ => api_request(&api, "POST", &format!("/games/{}/line", id), &format!(r#"{{"player": {}}}"#, player)),
+            };
+            assert_eq!(response.status, "202 Accepted", "{}", response.body);
+        };
+
+        assert_eq!(view["winner"], 0, "{}", view);
+        assert_eq!(view["players"][0]["name"], "Alice");
+        assert!(view["events"].as_array().unwrap().iter().any(|event| event["event"] == "game_over"));
+        assert!(!view["output"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_timed_penalty_chosen_through_the_api() {
+        let api = Api::new(GameConfig { vitality: 100, penalty_seconds: Some(30), ..api().config });
+        api_request(&api, "POST", "/games", "");
+
+        // Alice stops right on the target and Bob far from it, until Alice has a penalty to choose
+        let penalty = loop {
+            let view = poll_until_waiting(&api, 1);
+            let waiting = &view["waiting_for"];
+            let player = waiting["player"].as_u64().unwrap();
+            let (path, body) = match waiting["action"].as_str().unwrap() {
+                "stop" => {
+                    let target = view["events"].as_array().unwrap().iter().rev()
+                        .find(|event| event["event"] == "turn_start")
+                        .map(|event| event["targets"][0].as_u64().unwrap()).unwrap();
+                    let value = if player == 0 { target } else { (target + 50) % 100 };
+                    ("stop", format!(r#"{{"player": {}, "value": {}}}"#, player, value))
+                },
+                "key" => break api_request(&api, "POST", "/games/1/penalty", &format!(r#"{{"player": {}, "attribute": "strength"}}"#, player)),
+                _ => ("line", format!(r#"{{"player": {}}}"#, player)),
+            };
+            assert_eq!(api_request(&api, "POST", &format!("/games/1/{}", path), &body).status, "202 Accepted");
+        };
+
+        assert_eq!(penalty.status, "202 Accepted", "{}", penalty.body);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let view: serde_json::Value = serde_json::from_str(&api_request(&api, "GET", "/games/1", "").body).unwrap();
+            if view["events"].as_array().unwrap().iter().any(|event| event["event"] == "penalty" && event["attribute"] == "strength") {
+                break;
+            }
+            assert!(Instant::now() < deadline, "the penalty was not applied");
+            thread::sleep(Duration::from_millis(5));
+        }
+        api_request(&api, "DELETE", "/games/1", "");
+    }
+
+    #[test]
+    fn test_actions_out_of_turn_are_refused() {
+        let api = api();
+        api_request(&api, "POST", "/games", "");
+        let view = poll_until_waiting(&api, 1);
+        let other = 1 - view["waiting_for"]["player"].as_u64().unwrap();
+
+        let response = api_request(&api, "POST", "/games/1/line", &format!(r#"{{"player": {}}}"#, other));
+        assert_eq!(response.status, "409 Conflict");
+        let response = api_request(&api, "POST", "/games/1/stop", r#"{"player": 0}"#);
+        assert_eq!(response.status, "400 Bad Request");
+        assert_eq!(api_request(&api, "DELETE", "/games/1", "").status, "200 OK");
+        assert_eq!(api_request(&api, "GET", "/games/1", "").status, "404 Not Found");
+    }
+
+    #[test]
+    fn test_unknown_routes() {
+        assert_eq!(request("GET", "/games/7", "").status, "404 Not Found");
+        assert_eq!(request("GET", "/nowhere", "").status, "404 Not Found");
+        assert_eq!(request("PUT", "/games", "").status, "405 Method Not Allowed");
+        assert_eq!(request("POST", "/games", "{\"lives\": 3}").status, "400 Bad Request");
+    }
+
+    #[test]
+    fn test_matches_are_played_without_a_draft() {
+        // The game's own bans would shift the penalty options the API works out
+        let api = Api::new(GameConfig { draft: true, ..GameConfig::default() });
+        assert!(!api.config.draft);
+    }
+
+    #[test]
+    fn test_penalty_choice_skips_banned_penalties() {
+        let mut rules = RuleSet::default();
+        assert_eq!(penalty_choice(&rules, Attribute::Speed), Some(1));
+        assert_eq!(penalty_choice(&rules, Attribute::Strength), Some(2));
+
+        rules.pool.ban(DraftItem::Penalty(Attribute::Speed));
+        assert_eq!(penalty_choice(&rules, Attribute::Speed), None);
+        assert_eq!(penalty_choice(&rules, Attribute::Strength), Some(1));
+    }
+}
@@ -0,0 +1,211 @@
+//! Audio module for the game's sound effects.
+//!
+//! Sounds fall back to the terminal bell by default. When the `rodio` feature
+//! is enabled, real tones are synthesized on a dedicated audio thread instead.
+
+use std::io::{self, Write};
+use std::str::FromStr;
+
+#[cfg(feature = "rodio")]
+use std::{sync::mpsc, thread, time::Duration};
+
+use crate::error::GameError;
+
+/// The sound settings selectable from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SoundMode {
+    /// Play tones when available, otherwise fall back to the terminal bell
+    On,
+    /// Play no sound at all
+    #[default]
+    Off,
+    /// Only use the terminal bell, even when tones are available
+    BellOnly,
+}
+
+impl FromStr for SoundMode {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "on" => Ok(SoundMode::On),
+            "off" => Ok(SoundMode::Off),
+            "bell-only" => Ok(SoundMode::BellOnly),
+            other => Err(GameError::ConfigError(format!("Unknown sound mode: {}", other))),
+        }
+    }
+}
+
+/// The sound effects played during a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    /// The counter moved by one step
+    Tick,
+    /// The counter landed on a beat in rhythm mode
+    Beat,
+    /// The counter was stopped close to the target
+    Hit,
+    /// The counter wrapped around and a miss was counted
+    Miss,
+}
+
+#[cfg(feature = "rodio")]
+impl Sound {
+    /// Returns the frequency in Hz and the duration in milliseconds of the tone.
+    fn tone(self) -> (f32, u64) {
+        match self {
+            Sound::Tick => (1200.0, 10),
+            Sound::Beat => (880.0, 40),
+            Sound::Hit => (1320.0, 250),
+            Sound::Miss => (110.0, 300),
+        }
+    }
+}
+
+/// Plays sound effects according to the selected sound mode.
+///
+/// The handle is cheap to clone so it can be moved into display threads.
+#[derive(Debug, Clone)]
+pub struct Audio {
+    /// The selected sound mode
+    mode: SoundMode,
+    /// Channel to the audio thread, if a tone backend is available
+    #[cfg(feature = "rodio")]
+    tones: Option<mpsc::Sender<Sound>>,
+}
+
+impl Audio {
+    /// Creates a new Audio handle for the given mode.
+    ///
+    /// With the `rodio` feature and `SoundMode::On`, this opens the default
+    /// output device; if that fails the terminal bell is used instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The selected sound mode
+    ///
+    /// # Returns
+    ///
+    /// A new Audio instance
+    pub fn new(mode: SoundMode) -> Self {
+        Self {
+            mode,
+            #[cfg(feature = "rodio")]
+            tones: if mode == SoundMode::On { spawn_tone_thread() } else { None },
+        }
+    }
+
+    /// Plays a sound effect.
+    ///
+    /// Ticks are only played as tones, the terminal bell is reserved for
+    /// hits, misses, and beats.
+    ///
+    /// # Arguments
+    ///
+    /// * `sound` - The sound effect to play
+    pub fn play(&self, sound: Sound) {
+        #[cfg(feature = "rodio")]
+        if let Some(tones) = &self.tones {
+            // The audio thread only goes away if the device failed, nothing to do then
+            let _ = tones.send(sound);
+            return;
+        }
+
+        if self.rings_bell(sound) {
+            print!("\x07");
+            // Ignoring potential errors here as a missing beep is harmless
+            let _ = io::stdout().flush();
+        }
+    }
+
+    /// Returns whether the sound is played through the terminal bell.
+    fn rings_bell(&self, sound: Sound) -> bool {
+        if sound == Sound::Tick || self.has_tones() {
+            return false;
+        }
+        self.mode != SoundMode::Off
+    }
+
+    /// Returns whether a tone backend is available.
+    fn has_tones(&self) -> bool {
+        #[cfg(feature = "rodio")]
+        {
+            self.tones.is_some()
+        }
+        #[cfg(not(feature = "rodio"))]
+        {
+            false
+        }
+    }
+}
+
+/// Opens the default output device and starts the audio thread.
+///
+/// # Returns
+///
+/// A channel to send sounds to, or None if no output device could be opened
+#[cfg(feature = "rodio")]
+fn spawn_tone_thread() -> Option<mpsc::Sender<Sound>> {
+    use rodio::Source;
+
+    let (sender, receiver) = mpsc::channel::<Sound>();
+    let (ready_sender, ready_receiver) = mpsc::channel();
+
+    // The output stream cannot be moved between threads, so it lives on the audio thread
+    thread::spawn(move || {
+        let mut stream = match rodio::OutputStreamBuilder::open_default_stream() {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Could not open audio device, falling back to the terminal bell: {}", e);
+                let _ = ready_sender.send(false);
+                return;
+            }
+        };
+        stream.log_on_drop(false);
+        let _ = ready_sender.send(true);
+
+        for sound in receiver {
+            let (frequency, duration_ms) = sound.tone();
+            let source = rodio::source::SineWave::new(frequency)
+                .take_duration(Duration::from_millis(duration_ms))
+                .amplify(0.2);
+            stream.mixer().add(source);
+        }
+    });
+
+    if ready_receiver.recv().unwrap_or(false) {
+        Some(sender)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sound_mode_from_str() {
+        assert_eq!("on".parse::<SoundMode>().unwrap(), SoundMode::On);
+        assert_eq!("OFF".parse::<SoundMode>().unwrap(), SoundMode::Off);
+        assert_eq!("bell-only".parse::<SoundMode>().unwrap(), SoundMode::BellOnly);
+        assert!("loud".parse::<SoundMode>().is_err());
+    }
+
+    #[test]
+    fn test_off_never_rings_bell() {
+        let audio = Audio::new(SoundMode::Off);
+        assert!(!audio.rings_bell(Sound::Hit));
+        assert!(!audio.rings_bell(Sound::Miss));
+        assert!(!audio.rings_bell(Sound::Tick));
+    }
+
+    #[test]
+    fn test_bell_only_skips_ticks() {
+        let audio = Audio::new(SoundMode::BellOnly);
+        assert!(audio.rings_bell(Sound::Hit));
+        assert!(audio.rings_bell(Sound::Miss));
+        assert!(audio.rings_bell(Sound::Beat));
+        assert!(!audio.rings_bell(Sound::Tick));
+    }
+}
@@ -0,0 +1,102 @@
+//! # Matchmaking Server
+//!
+//! Pairs the players who connect with `rust_game connect` and runs their
+//! matches, see the `server` module.
+
+use std::net::TcpListener;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Arg, Command, value_parser};
+use rust_game::config::GameConfig;
+use rust_game::i18n::{self, Language, Msg, tr};
+use rust_game::metrics;
+use rust_game::net;
+use rust_game::server::Server;
+
+/// Builds the command line of the server.
+///
+/// # Returns
+///
+/// The command with every option
+fn command() -> Command {
+    Command::new("rust-game-server")
+        .version("1.0")
+        .about("Matchmaking server for the terminal-based turn-based game")
+        .arg(Arg::new("bind")
+            .long("bind")
+            .value_name("HOST:PORT")
+            .help("Address to listen on")
+            .default_value(net::DEFAULT_ADDRESS))
+        .arg(Arg::new("vitality")
+            .long("vitality")
+            .value_parser(value_parser!(u32).range(1..=999))
+            .value_name("AMOUNT")
+            .help("Starting vitality for both players (1 to 999)")
+            .default_value("50"))
+        .arg(Arg::new("objectives")
+            .long("objectives")
+            .value_parser(RangedU64ValueParser::<usize>::new().range(1..=20))
+            .value_name("COUNT")
+            .help("Number of targets per turn (1 to 20)")
+            .default_value("5"))
+        .arg(Arg::new("rejoin-grace")
+            .long("rejoin-grace")
+            .value_parser(value_parser!(u64).range(0..=600))
+            .value_name("SECONDS")
+            .help("How long a match waits for a player who lost their connection (0 to 600)")
+            .default_value("30"))
+        .arg(Arg::new("metrics")
+            .long("metrics")
+            .value_name("HOST:PORT")
+            .help("Address to serve Prometheus metrics on, at /metrics"))
+        .arg(Arg::new("lang")
+            .long("lang")
+            .value_parser(str::parse::<Language>)
+            .value_name("LANG")
+            .help("Language of the game messages (en, fr, es)")
+            .default_value("en"))
+}
+
+/// The entry point for the server.
+///
+/// Parses command line arguments and welcomes players until it is stopped.
+fn main() -> ExitCode {
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .format_timestamp(None)
+        .format_module_path(false)
+        .format_target(false)
+        .init();
+
+    let matches = command().get_matches();
+    i18n::set_language(*matches.get_one::<Language>("lang").unwrap());
+    let config = GameConfig {
+        vitality: *matches.get_one::<u32>("vitality").unwrap(),
+        target_count: *matches.get_one::<usize>("objectives").unwrap(),
+        ..GameConfig::default()
+    };
+
+    let address = matches.get_one::<String>("bind").unwrap();
+    let grace = Duration::from_secs(*matches.get_one::<u64>("rejoin-grace").unwrap());
+    let result = Server::bind(address, config).and_then(|server| {
+        let server = server.with_rejoin_grace(grace);
+        log::info!("{}", tr!(Msg::NetListening, server.local_addr()?));
+        if let Some(address) = matches.get_one::<String>("metrics") {
+            let listener = TcpListener::bind(address)?;
+            log::info!("{}", tr!(Msg::MetricsListening, listener.local_addr()?));
+            let metrics = server.metrics();
+            thread::spawn(move || metrics::serve(listener, metrics));
+        }
+        server.run()
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            log::error!("{}", e);
+            ExitCode::from(e.exit_code())
+        },
+    }
+}
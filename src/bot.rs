@@ -0,0 +1,195 @@
+//! Bot module for letting an external program play as player 2.
+//!
+//! With `--bot-command`, the game starts the command through the shell and
+//! asks it where to stop the counter, with one JSON line per target on its
+//! standard input:
+//!
+//! ```text
+//! {"round":1,"target":42,"tick_ms":25,"max_tick":100,"vitality":[50,50]}
+//! ```
+//!
+//! The bot answers each line with the tick it stops the counter at, on one
+//! line of its standard output:
+//!
+//! ```text
+//! {"stop":41}
+//! ```
+//!
+//! Targets and stops are in ticks, from 0 to `max_tick`. An answer that is
+//! not valid, out of range or later than `BOT_TIMEOUT` ends the match with an
+//! error. The bot's standard error is discarded, so that it cannot draw over
+//! the game: bots that need a log should write it to a file.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GameError, GameResult};
+
+/// How long a bot has to answer a target.
+pub const BOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What the bot is told about a target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BotRequest {
+    /// The current round
+    pub round: u32,
+    /// The target, in ticks
+    pub target: u32,
+    /// The delay between two counter ticks, in milliseconds
+    pub tick_ms: u32,
+    /// The highest value of the counter, in ticks, before it wraps around to 0
+    pub max_tick: u32,
+    /// The vitality of each player
+    pub vitality: [u32; 2],
+}
+
+/// The bot's answer to a target.
+#[derive(Debug, Deserialize)]
+struct BotReply {
+    /// The tick the bot stops the counter at
+    stop: u32,
+}
+
+/// An external program playing as a bot, kept running for the whole match.
+#[derive(Debug)]
+pub struct BotProcess {
+    /// The running program
+    child: Child,
+    /// Where the requests are written
+    stdin: ChildStdin,
+    /// The lines the program writes, read on a thread of their own
+    replies: Receiver<io::Result<String>>,
+    /// How long the program has to answer
+    timeout: Duration,
+}
+
+impl BotProcess {
+    /// Starts a bot through the shell.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command line starting the bot, e.g. `./mybot --level 2`
+    /// * `timeout` - How long the bot has to answer each target
+    ///
+    /// # Returns
+    ///
+    /// Result containing the running bot, or an error if the command could not be started
+    pub fn spawn(command: &str, timeout: Duration) -> GameResult<Self> {
+        #[cfg(windows)]
+        let mut shell = {
+            let mut shell = Command::new("cmd");
+            shell.args(["/C", command]);
+            shell
+        };
+        #[cfg(not(windows))]
+        let mut shell = {
+            let mut shell = Command::new("sh");
+            shell.args(["-c", command]);
+            shell
+        };
+        let mut child = shell
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(GameError::LogicError("The bot's standard input and output are not piped".to_string()));
+        };
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self { child, stdin, replies, timeout })
+    }
+
+    /// Asks the bot where it stops the counter for a target.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The target and the state of the match
+    ///
+    /// # Returns
+    ///
+    /// Result containing the tick to stop at, or an error if the bot did not give a valid answer in time
+    pub fn aim(&mut self, request: &BotRequest) -> GameResult<u32> {
+        let line = serde_json::to_string(request).map_err(|e| GameError::LogicError(e.to_string()))?;
+        writeln!(self.stdin, "{}", line)?;
+        self.stdin.flush()?;
+
+        let reply = match self.replies.recv_timeout(self.timeout) {
+            Ok(reply) => reply?,
+            Err(RecvTimeoutError::Timeout) => return Err(GameError::IoError(io::Error::new(
+                io::ErrorKind::TimedOut, format!("The bot did not answer within {} ms", self.timeout.as_millis())))),
+            Err(RecvTimeoutError::Disconnected) => return Err(GameError::IoError(io::Error::new(
+                io::ErrorKind::UnexpectedEof, "The bot stopped before answering"))),
+        };
+        let reply: BotReply = serde_json::from_str(&reply)
+            .map_err(|e| GameError::ParseError(format!("Invalid answer from the bot ({}): {}", e, reply)))?;
+        if reply.stop > request.max_tick {
+            return Err(GameError::ParseError(format!("The bot stopped at {}, past the last tick {}", reply.stop, request.max_tick)));
+        }
+        Ok(reply.stop)
+    }
+}
+
+impl Drop for BotProcess {
+    /// Stops the bot with the match.
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn request() -> BotRequest {
+        BotRequest { round: 1, target: 42, tick_ms: 25, max_tick: 100, vitality: [50, 50] }
+    }
+
+    fn spawn(command: &str) -> BotProcess {
+        BotProcess::spawn(command, Duration::from_millis(500)).unwrap()
+    }
+
+    #[test]
+    fn test_bot_answers_each_target() {
+        // Echoes the target back, one answer per request
+        let mut bot = spawn(r#"while read line; do echo "{\"stop\": $(echo "$line" | sed 's/.*"target":\([0-9]*\).*/\1/')}"; done"#);
+        assert_eq!(bot.aim(&request()).unwrap(), 42);
+        assert_eq!(bot.aim(&BotRequest { target: 7, ..request() }).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_invalid_answers_are_refused() {
+        let mut bot = spawn("read line; echo nonsense");
+        assert!(matches!(bot.aim(&request()), Err(GameError::ParseError(_))));
+
+        let mut bot = spawn(r#"read line; echo '{"stop": 101}'"#);
+        assert!(matches!(bot.aim(&request()), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_silent_bot_times_out() {
+        let mut bot = spawn("read line; sleep 5");
+        match bot.aim(&request()) {
+            Err(GameError::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::TimedOut),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_request_format() {
+        assert_eq!(serde_json::to_string(&request()).unwrap(),
+                   r#"{"round":1,"target":42,"tick_ms":25,"max_tick":100,"vitality":[50,50]}"#);
+    }
+}
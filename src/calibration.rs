@@ -0,0 +1,167 @@
+//! Calibration module for measuring how late key presses reach the game.
+//!
+//! The player presses ENTER as soon as "NOW!" appears, a few times in a row.
+//! Whatever the median response takes beyond a usual reaction time is put down
+//! to the terminal and keyboard, and kept in the profile as a latency offset.
+//! With `--latency-credit`, the counter is wound back by that offset when it
+//! is stopped, so slow terminals are not punished.
+
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::config::GameConfig;
+use crate::counter::REACTION_MS;
+use crate::dial::Dial;
+use crate::error::GameResult;
+use crate::i18n::{Msg, tr};
+use crate::profile::Profile;
+use crate::ui::render::Renderer;
+use crate::ui::source::InputSource;
+
+/// Number of prompts in a calibration.
+pub const CALIBRATION_PROMPTS: usize = 5;
+
+/// Highest latency offset a calibration can give, in milliseconds.
+pub const MAX_LATENCY_OFFSET_MS: u32 = 150;
+
+/// Responses faster than this are presses made before the signal, in milliseconds.
+const EARLY_PRESS_MS: u64 = 100;
+
+/// Shortest and longest wait before each signal, in milliseconds.
+const PROMPT_DELAY_MS: RangeInclusive<u64> = 1000..=3000;
+
+/// Works out the latency offset from the measured responses.
+///
+/// # Arguments
+///
+/// * `samples` - The time each response took, in milliseconds
+///
+/// # Returns
+///
+/// The median response beyond a usual reaction time, at most `MAX_LATENCY_OFFSET_MS`,
+/// or None without any response
+pub fn latency_offset(samples: &[u64]) -> Option<u32> {
+    let mut samples = samples.to_vec();
+    samples.sort_unstable();
+    let median = *samples.get(samples.len() / 2)?;
+    let offset = median.saturating_sub(u64::from(REACTION_MS));
+    Some(offset.min(u64::from(MAX_LATENCY_OFFSET_MS)) as u32)
+}
+
+/// Works out how far to wind the counter back for a latency credit.
+///
+/// # Arguments
+///
+/// * `credit_ms` - The latency credited back, in milliseconds
+/// * `delay` - The time the counter takes to move one whole unit, in milliseconds
+/// * `dial` - The values the counter runs through
+///
+/// # Returns
+///
+/// The number of ticks the counter moved during the credited time
+pub fn credit_ticks(credit_ms: u32, delay: u32, dial: Dial) -> u32 {
+    // Finer dials tick several times per unit
+    dial.ticks(credit_ms) / delay.max(1)
+}
+
+/// Measures how long the player takes to answer each signal.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the signals are drawn
+/// * `input` - Where the player's presses are read from
+/// * `delays` - How long to wait before each signal
+///
+/// # Returns
+///
+/// Result containing the time each response took, in milliseconds, without the early presses
+pub fn measure(renderer: &mut dyn Renderer, input: &mut dyn InputSource, delays: &[Duration]) -> GameResult<Vec<u64>> {
+    let mut samples = Vec::new();
+    for (prompt, &delay) in delays.iter().enumerate() {
+        renderer.info(&tr!(Msg::CalibrationReady, prompt + 1, delays.len()));
+        thread::sleep(delay);
+        renderer.info(&tr!(Msg::ReactionNow));
+        let shown = Instant::now();
+        input.read_line()?;
+        let response_ms = shown.elapsed().as_millis() as u64;
+        if response_ms < EARLY_PRESS_MS {
+            renderer.info(&tr!(Msg::CalibrationEarly));
+        } else {
+            renderer.info(&tr!(Msg::CalibrationSample, response_ms));
+            samples.push(response_ms);
+        }
+    }
+    Ok(samples)
+}
+
+/// Runs a calibration and keeps the latency offset in the profile.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, for the output mode
+/// * `profile_path` - The file the offset is kept in
+/// * `input` - Where the player's presses are read from
+///
+/// # Returns
+///
+/// Result indicating whether the profile could be read and saved
+pub fn play(config: &GameConfig, profile_path: &Path, input: &mut dyn InputSource) -> GameResult<()> {
+    let mut profile = Profile::load(profile_path)?;
+    let mut renderer = config.render_mode.renderer();
+    renderer.info_wrapped(&tr!(Msg::CalibrationStart, CALIBRATION_PROMPTS));
+
+    let mut rng = rand::rng();
+    let delays: Vec<Duration> = (0..CALIBRATION_PROMPTS)
+        .map(|_| Duration::from_millis(rng.random_range(PROMPT_DELAY_MS)))
+        .collect();
+    let samples = measure(&mut *renderer, input, &delays)?;
+    let Some(offset) = latency_offset(&samples) else {
+        renderer.info_wrapped(&tr!(Msg::CalibrationFailed));
+        return Ok(());
+    };
+
+    profile.latency_offset_ms = offset;
+    profile.save(profile_path)?;
+    renderer.info_wrapped(&tr!(Msg::CalibrationResult, offset, profile_path.display()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dial::Precision;
+    use crate::ui::render::NullRenderer;
+    use crate::ui::source::ScriptedInput;
+
+    #[test]
+    fn test_latency_offset_is_the_median_beyond_reaction() {
+        assert_eq!(latency_offset(&[330, 300, 900, 310, 320]), Some(70));
+        assert_eq!(latency_offset(&[180, 200, 240]), Some(0));
+        assert_eq!(latency_offset(&[]), None);
+    }
+
+    #[test]
+    fn test_latency_offset_is_capped() {
+        assert_eq!(latency_offset(&[2000]), Some(MAX_LATENCY_OFFSET_MS));
+    }
+
+    #[test]
+    fn test_credit_ticks_follow_the_precision() {
+        assert_eq!(credit_ticks(70, 20, Dial::default()), 3);
+        assert_eq!(credit_ticks(70, 20, Dial::new(100, Precision::Fine)), 35);
+        assert_eq!(credit_ticks(0, 20, Dial::new(100, Precision::Fine)), 0);
+    }
+
+    #[test]
+    fn test_measure_skips_early_presses() {
+        let mut input = ScriptedInput::new(["0 enter", "150 enter"].map(|l| l.parse().unwrap()));
+        let samples = measure(&mut NullRenderer, &mut input, &[Duration::ZERO; 2]).unwrap();
+
+        assert_eq!(samples.len(), 1);
+        assert!(samples[0] >= 150);
+    }
+}
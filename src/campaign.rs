@@ -0,0 +1,199 @@
+//! Campaign module for the single-player story mode.
+//!
+//! The player faces a fixed sequence of computer opponents, each one stronger
+//! than the last and with its own quirk. Beating an opponent unlocks the next
+//! one, and the progress is kept in the player profile.
+
+use std::path::Path;
+
+use rand::Rng;
+
+use crate::config::GameConfig;
+use crate::counter::Dial;
+use crate::error::GameResult;
+use crate::game::{Game, PENALTY_AMOUNT};
+use crate::i18n::{Msg, tr};
+use crate::profile::Profile;
+use crate::ui::source::TerminalInput;
+
+/// A rule an opponent bends in its own favour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quirk {
+    /// Plays by the usual rules
+    None,
+    /// The player's counter runs faster
+    FastCounter,
+    /// The opponent's penalties take twice as many points
+    HeavyPenalty,
+    /// Both the faster counter and the heavier penalties
+    Relentless,
+}
+
+impl Quirk {
+    /// Returns the delay between two ticks of the player's counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The usual delay, in milliseconds
+    ///
+    /// # Returns
+    ///
+    /// The delay once the quirk is applied
+    pub fn counter_delay(self, delay: u32) -> u32 {
+        match self {
+            Quirk::FastCounter => (delay * 3 / 4).max(1),
+            Quirk::Relentless => Quirk::FastCounter.counter_delay(delay),
+            Quirk::None | Quirk::HeavyPenalty => delay,
+        }
+    }
+
+    /// Returns the attribute points taken by the opponent's penalties.
+    pub fn penalty_amount(self) -> u32 {
+        match self {
+            Quirk::HeavyPenalty => 2 * PENALTY_AMOUNT,
+            Quirk::Relentless => Quirk::HeavyPenalty.penalty_amount(),
+            Quirk::None | Quirk::FastCounter => PENALTY_AMOUNT,
+        }
+    }
+
+    /// Returns the description shown when the opponent is introduced.
+    fn description(self) -> String {
+        match self {
+            Quirk::None => tr!(Msg::QuirkNone),
+            Quirk::FastCounter => tr!(Msg::QuirkFastCounter),
+            Quirk::HeavyPenalty => tr!(Msg::QuirkHeavyPenalty, self.penalty_amount()),
+            Quirk::Relentless => tr!(Msg::QuirkRelentless, self.penalty_amount()),
+        }
+    }
+}
+
+/// A computer opponent of the campaign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opponent {
+    /// Name shown in place of player 2
+    pub name: &'static str,
+    /// Starting vitality
+    pub vitality: u32,
+    /// Starting speed
+    pub speed: u32,
+    /// Starting strength
+    pub strength: u32,
+    /// Largest distance from the target the opponent stops at, in whole units
+    pub accuracy: u32,
+    /// The rule the opponent bends
+    pub quirk: Quirk,
+}
+
+impl Opponent {
+    /// Picks where the opponent stops the counter for a target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target, in ticks
+    /// * `dial` - The values the counter runs through
+    /// * `rng` - The random number generator of the game
+    ///
+    /// # Returns
+    ///
+    /// The counter value to stop at, in ticks
+    pub fn aim(&self, target: u32, dial: Dial, rng: &mut impl Rng) -> u32 {
+        let laps = dial.max() + 1;
+        let error = rng.random_range(0..=dial.ticks(self.accuracy)) % laps;
+        if rng.random_bool(0.5) {
+            (target + error) % laps
+        } else {
+            (target + laps - error) % laps
+        }
+    }
+}
+
+/// The opponents of the campaign, in the order they are faced.
+pub const OPPONENTS: [Opponent; 4] = [
+    Opponent { name: "Rookie", vitality: 60, speed: 60, strength: 30, accuracy: 15, quirk: Quirk::None },
+    Opponent { name: "Sprinter", vitality: 80, speed: 50, strength: 45, accuracy: 10, quirk: Quirk::FastCounter },
+    Opponent { name: "Brute", vitality: 100, speed: 50, strength: 60, accuracy: 6, quirk: Quirk::HeavyPenalty },
+    Opponent { name: "Champion", vitality: 120, speed: 40, strength: 70, accuracy: 2, quirk: Quirk::Relentless },
+];
+
+/// Plays the campaign from the first opponent the player has not beaten yet.
+///
+/// Each win is saved to the profile right away. The campaign stops after a
+/// loss, or when the player declines to face the next opponent.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, player 1 is the human player
+/// * `profile_path` - The file the progress is kept in
+///
+/// # Returns
+///
+/// Result indicating whether the campaign could be played and saved
+pub fn play(config: &GameConfig, profile_path: &Path) -> GameResult<()> {
+    let mut profile = Profile::load(profile_path)?;
+    // Both of these need a key for each player, the opponent has none
+    let config = GameConfig { duel: false, reaction_game: false, ..config.clone() };
+    let mut renderer = config.render_mode.renderer();
+
+    while let Some(&opponent) = OPPONENTS.get(profile.campaign_stage) {
+        renderer.info_wrapped(&tr!(Msg::CampaignStage,
+                   profile.campaign_stage + 1, OPPONENTS.len(), opponent.name, opponent.quirk.description()));
+
+        let mut game = Game::against(config.clone(), opponent);
+        if game.play_match()? != 0 {
+            renderer.info_wrapped(&tr!(Msg::CampaignLost, opponent.name, profile.campaign_stage + 1));
+            return Ok(());
+        }
+
+        profile.campaign_stage += 1;
+        profile.save(profile_path)?;
+        renderer.info_wrapped(&tr!(Msg::CampaignStageCleared, opponent.name, profile_path.display()));
+        if profile.campaign_stage < OPPONENTS.len() && !renderer.confirm(&mut TerminalInput, &tr!(Msg::CampaignContinue))? {
+            return Ok(());
+        }
+    }
+
+    renderer.info_wrapped(&tr!(Msg::CampaignComplete));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counter::Precision;
+    use crate::scoring;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn test_opponents_escalate() {
+        for pair in OPPONENTS.windows(2) {
+            assert!(pair[1].vitality >= pair[0].vitality);
+            assert!(pair[1].strength >= pair[0].strength);
+            assert!(pair[1].accuracy <= pair[0].accuracy);
+        }
+    }
+
+    #[test]
+    fn test_aim_stays_within_accuracy() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for dial in [Dial::default(), Dial::new(100, Precision::Fine)] {
+            for opponent in OPPONENTS {
+                for target in [0, dial.ticks(50), dial.max()] {
+                    let value = opponent.aim(target, dial, &mut rng);
+                    assert!(value <= dial.max());
+                    assert!(scoring::difference(target, value, dial) <= dial.ticks(opponent.accuracy));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_quirks() {
+        assert_eq!(Quirk::None.counter_delay(60), 60);
+        assert_eq!(Quirk::FastCounter.counter_delay(60), 45);
+        assert_eq!(Quirk::FastCounter.counter_delay(1), 1);
+        assert_eq!(Quirk::None.penalty_amount(), PENALTY_AMOUNT);
+        assert_eq!(Quirk::HeavyPenalty.penalty_amount(), 2 * PENALTY_AMOUNT);
+        assert_eq!(Quirk::Relentless.counter_delay(60), 45);
+        assert_eq!(Quirk::Relentless.penalty_amount(), 2 * PENALTY_AMOUNT);
+    }
+}
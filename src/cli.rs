@@ -0,0 +1,128 @@
+//! Command line interface of the game.
+//!
+//! The whole command line is described by a single `Command`, so that the
+//! shell completion scripts and the manual page are generated from the same
+//! definition the arguments are parsed with. The manual page is generated by
+//! the build script, from the options in `args`.
+
+mod args;
+
+use std::io::{self, Write};
+
+use clap::{Command, value_parser};
+use clap::builder::RangedU64ValueParser;
+use clap_complete::Shell;
+use rust_game::{mutators, rules, stages};
+use rust_game::audio::SoundMode;
+use rust_game::config::{SPEED_RANGE, STAMINA_RANGE, STRENGTH_RANGE, TARGET_COUNT_RANGE, VITALITY_RANGE};
+use rust_game::counter::Precision;
+use rust_game::i18n::Language;
+use rust_game::rules::{DamageModel, TargetDistribution, TurnOrder, WinCondition};
+use rust_game::scoring::StopRule;
+use rust_game::ui::CounterView;
+use rust_game::ui::render::RenderMode;
+
+/// Name the game is installed and completed as.
+const BIN_NAME: &str = env!("CARGO_BIN_NAME");
+
+/// Builds the command line of the game.
+///
+/// # Returns
+///
+/// The command with every option and subcommand, each option read as the type the game uses
+pub fn command() -> Command {
+    args::command()
+        .mut_arg("vitality", |arg| arg.value_parser(value_parser!(u32).range(VITALITY_RANGE)))
+        .mut_arg("speed", |arg| arg.value_parser(value_parser!(u32).range(SPEED_RANGE)))
+        .mut_arg("strength", |arg| arg.value_parser(value_parser!(u32).range(STRENGTH_RANGE)))
+        .mut_arg("stamina", |arg| arg.value_parser(value_parser!(u32).range(STAMINA_RANGE)))
+        .mut_arg("objectives", |arg| arg.value_parser(RangedU64ValueParser::<usize>::new().range(TARGET_COUNT_RANGE)))
+        .mut_arg("stop-rule", |arg| arg.value_parser(str::parse::<StopRule>))
+        .mut_arg("precision", |arg| arg.value_parser(str::parse::<Precision>))
+        .mut_arg("range", |arg| arg.value_parser(rules::parse_counter_max))
+        .mut_arg("stage", |arg| arg.value_parser(stages::find))
+        .mut_arg("mutators", |arg| arg.value_parser(mutators::parse))
+        .mut_arg("targets", |arg| arg.value_parser(str::parse::<TargetDistribution>))
+        .mut_arg("turn-order", |arg| arg.value_parser(str::parse::<TurnOrder>))
+        .mut_arg("damage", |arg| arg.value_parser(str::parse::<DamageModel>))
+        .mut_arg("win", |arg| arg.value_parser(str::parse::<WinCondition>))
+        .mut_arg("counter-view", |arg| arg.value_parser(str::parse::<CounterView>))
+        .mut_arg("sound", |arg| arg.value_parser(str::parse::<SoundMode>))
+        .mut_arg("output", |arg| arg.value_parser(str::parse::<RenderMode>))
+        .mut_arg("lang", |arg| arg.value_parser(str::parse::<Language>))
+        .mut_subcommand("completions", |sub| sub.mut_arg("shell", |arg| arg.value_parser(value_parser!(Shell))))
+}
+
+/// Prints the completion script of a shell to the standard output.
+///
+/// # Arguments
+///
+/// * `shell` - The shell to complete for
+pub fn print_completions(shell: Shell) {
+    clap_complete::generate(shell, &mut command(), BIN_NAME, &mut io::stdout());
+}
+
+/// Manual page of the game, generated by the build script.
+const MAN_PAGE: &str = include_str!(concat!(env!("OUT_DIR"), "/rust_game.1"));
+
+/// Prints the manual page to the standard output.
+///
+/// # Returns
+///
+/// Result indicating whether the page could be written
+pub fn print_man_page() -> io::Result<()> {
+    io::stdout().write_all(MAN_PAGE.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_game::{net, profile};
+    use rust_game::practice::PracticeMode;
+
+    #[test]
+    fn test_command_is_valid() {
+        command().debug_assert();
+    }
+
+    #[test]
+    fn test_completions_subcommand() {
+        let matches = command().try_get_matches_from([BIN_NAME, "completions", "bash"]).unwrap();
+        let (name, sub_matches) = matches.subcommand().unwrap();
+        assert_eq!(name, "completions");
+        assert_eq!(sub_matches.get_one::<Shell>("shell"), Some(&Shell::Bash));
+        assert!(command().try_get_matches_from([BIN_NAME, "completions", "cmd"]).is_err());
+    }
+
+    #[test]
+    fn test_config_subcommand_takes_a_file() {
+        let matches = command().try_get_matches_from([BIN_NAME, "config", "keys.toml"]).unwrap();
+        let sub_matches = matches.subcommand_matches("config").unwrap();
+        assert_eq!(sub_matches.get_one::<String>("file").map(String::as_str), Some("keys.toml"));
+    }
+
+    #[test]
+    fn test_man_page_lists_options() {
+        assert!(MAN_PAGE.starts_with(".ie"));
+        assert!(MAN_PAGE.contains("vitality"));
+        assert!(MAN_PAGE.contains("campaign"));
+        assert!(MAN_PAGE.contains("time\\-attack"));
+    }
+
+    #[test]
+    fn test_default_paths_are_the_game_ones() {
+        let command = command();
+        let default = |sub: &str, arg: &str| {
+            let sub = command.find_subcommand(sub).unwrap();
+            let arg = sub.get_arguments().find(|a| a.get_id() == arg).unwrap();
+            arg.get_default_values()[0].to_str().unwrap().to_string()
+        };
+        assert_eq!(default("stats", "profile"), profile::DEFAULT_PROFILE_PATH);
+        assert_eq!(default("connect", "address"), net::DEFAULT_ADDRESS);
+        assert_eq!(default("practice", "replay"), PracticeMode::Practice.default_replay_path());
+        assert_eq!(default("time-attack", "replay"), PracticeMode::TimeAttack.default_replay_path());
+        assert_eq!(default("replay", "file"), PracticeMode::Practice.default_replay_path());
+        let matches = command.try_get_matches_from([BIN_NAME, "--latency-credit"]).unwrap();
+        assert_eq!(matches.get_one::<String>("latency-credit").unwrap(), profile::DEFAULT_PROFILE_PATH);
+    }
+}
@@ -0,0 +1,465 @@
+//! Options and subcommands of the game, as shown in its help.
+//!
+//! This file depends on clap alone: the build script includes it to generate
+//! the manual page, which cannot use the game's own types. The options read
+//! as one of those types get their value parser in `cli::command`.
+
+use clap::{Command, Arg, ArgAction, value_parser};
+use clap::builder::RangedU64ValueParser;
+
+/// Builds the `--profile` option shared by the single-player modes.
+fn profile_arg() -> Arg {
+    Arg::new("profile")
+        .long("profile")
+        .value_name("FILE")
+        .help("File the progress and best scores are kept in")
+        .default_value("rust_game_profile.json")
+}
+
+/// Builds the `--replay` and `--ghost` options of a mode raced against a ghost.
+///
+/// # Arguments
+///
+/// * `replay_path` - The file the best run of the mode is recorded in
+///
+/// # Returns
+///
+/// The two options
+fn ghost_args(replay_path: &'static str) -> [Arg; 2] {
+    [
+        Arg::new("replay")
+            .long("replay")
+            .value_name("FILE")
+            .help("File the best run is recorded in, to be raced as a ghost")
+            .default_value(replay_path),
+        Arg::new("ghost")
+            .long("ghost")
+            .action(ArgAction::SetTrue)
+            .help("Race the ghost of the best recorded run, on the same targets"),
+    ]
+}
+
+/// Builds the options and subcommands of the game, with their help.
+///
+/// # Returns
+///
+/// The command, whose options are still read as plain strings
+pub fn command() -> Command {
+    Command::new("Turn-based Game")
+        .version("1.0")
+        .author("SEC3 Game Developer")
+        .about("A terminal-based turn-based game")
+        .arg(Arg::new("name1")
+            .long("name1")
+            .global(true)
+            .value_name("NAME")
+            .help("Name of player 1")
+            .default_value("Player 1"))
+        .arg(Arg::new("name2")
+            .long("name2")
+            .global(true)
+            .value_name("NAME")
+            .help("Name of player 2")
+            .default_value("Player 2"))
+        .arg(Arg::new("vitality")
+            .long("vitality")
+            .global(true)
+            .value_name("AMOUNT")
+            .help("Starting vitality for both players (1 to 999)")
+            .default_value("50"))
+        .arg(Arg::new("speed")
+            .long("speed")
+            .global(true)
+            .value_name("AMOUNT")
+            .help("Starting speed for both players (1 to 999): the counter ticks every base tick × 100 / speed milliseconds")
+            .default_value("50"))
+        .arg(Arg::new("strength")
+            .long("strength")
+            .global(true)
+            .value_name("AMOUNT")
+            .help("Starting strength for both players (0 to 999)")
+            .default_value("50"))
+        .arg(Arg::new("stamina")
+            .long("stamina")
+            .global(true)
+            .value_name("AMOUNT")
+            .help("Starting stamina for both players, spent on double or nothing gambles (0 to 99)")
+            .default_value("3"))
+        .arg(Arg::new("objectives")
+            .long("objectives")
+            .global(true)
+            .value_name("COUNT")
+            .help("Number of targets per turn (1 to 20)")
+            .default_value("5"))
+        .arg(Arg::new("progressive-targets")
+            .long("progressive-targets")
+            .global(true)
+            .value_parser(RangedU64ValueParser::<usize>::new().range(1..=20))
+            .value_name("CAP")
+            .num_args(0..=1)
+            .default_missing_value("10")
+            .help("One more target per turn each round, up to CAP (10 by default)"))
+        .arg(Arg::new("shared-pool")
+            .long("shared-pool")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Deal each round's targets out of one pool; after their turn, a player may steal one the opponent has not played"))
+        .arg(Arg::new("predict")
+            .long("predict")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Predict your average before each turn: landing within 5 of it adds 10 points to your round score"))
+        .arg(Arg::new("risk")
+            .long("risk")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Pick a risk before each target: s (safe: wider brackets, at most 60), n (normal), or r (risky: narrower brackets, up to 150)"))
+        .arg(Arg::new("seed")
+            .long("seed")
+            .global(true)
+            .value_parser(value_parser!(u64))
+            .value_name("NUMBER")
+            .help("Seed for the targets and critical hits, to replay the same match"))
+        .arg(Arg::new("betting")
+            .long("betting")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Let players wager vitality before each round: the round loser loses both wagers on top of the damage"))
+        .arg(Arg::new("consolation")
+            .long("consolation")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Let the loser of each round pick a small bonus: +3 speed, +3 strength, or +5 vitality"))
+        .arg(Arg::new("draft")
+            .long("draft")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Let each player ban a penalty or consolation bonus, then pick one for themselves, before the match (turns on --consolation)"))
+        .arg(Arg::new("heal-hits")
+            .long("heal-hits")
+            .global(true)
+            .value_parser(value_parser!(u32).range(0..=20))
+            .value_name("COUNT")
+            .help("Exact hits in one turn needed to recover vitality (0 disables healing, up to 20)")
+            .default_value("3"))
+        .arg(Arg::new("heal-amount")
+            .long("heal-amount")
+            .global(true)
+            .value_parser(value_parser!(u32).range(0..=999))
+            .value_name("AMOUNT")
+            .help("Vitality recovered after enough exact hits")
+            .default_value("10"))
+        .arg(Arg::new("memory-mode")
+            .long("memory-mode")
+            .global(true)
+            .value_parser(value_parser!(u64).range(1..=60))
+            .value_name("SECONDS")
+            .num_args(0..=1)
+            .default_missing_value("3")
+            .help("Hide the objectives after a few seconds (3 by default, up to 60), players must remember them"))
+        .arg(Arg::new("penalty-timer")
+            .long("penalty-timer")
+            .global(true)
+            .value_parser(value_parser!(u64).range(1..=60))
+            .value_name("SECONDS")
+            .num_args(0..=1)
+            .default_missing_value("10")
+            .help("Give the round winner a few seconds (10 by default, up to 60) to pick the penalty, then pick at random"))
+        .arg(Arg::new("pass")
+            .long("pass")
+            .global(true)
+            .value_parser(value_parser!(u32).range(1..))
+            .value_name("VITALITY")
+            .num_args(0..=1)
+            .default_missing_value("10")
+            .help("Let players pass their turn for some vitality (10 by default): the opponent wins the round but deals no damage"))
+        .arg(Arg::new("hidden-target")
+            .long("hidden-target")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Never show the targets, the counter tells whether it is getting warmer or colder"))
+        .arg(Arg::new("weather")
+            .long("weather")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Play each round under random weather: fog hides the targets, storm speeds up the counter, calm ignores misses"))
+        .arg(Arg::new("blind")
+            .long("blind")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Keep each turn's scores hidden until both players have played the round"))
+        .arg(Arg::new("moving-target")
+            .long("moving-target")
+            .global(true)
+            .value_parser(value_parser!(u32).range(1..))
+            .value_name("TICKS")
+            .num_args(0..=1)
+            .default_missing_value("5")
+            .help("Move each target one step up or down every few counter ticks (5 by default)"))
+        .arg(Arg::new("stops")
+            .long("stops")
+            .global(true)
+            .value_parser(value_parser!(u32).range(1..=3))
+            .value_name("COUNT")
+            .help("Number of times the counter is stopped for each target (1 to 3)")
+            .default_value("1"))
+        .arg(Arg::new("stop-rule")
+            .long("stop-rule")
+            .global(true)
+            .value_name("RULE")
+            .help("How multiple stops are scored: sum (they add up to the target) or bracket (they enclose it)")
+            .default_value("sum"))
+        .arg(Arg::new("precision")
+            .long("precision")
+            .global(true)
+            .value_name("PRECISION")
+            .help("Counter resolution: normal (whole numbers) or fine (tenths, with tighter scoring)")
+            .default_value("normal"))
+        .arg(Arg::new("range")
+            .long("range")
+            .global(true)
+            .value_name("RANGE")
+            .help("Counter range: percent (0-100), clock (0-60), degrees (0-360), or the highest value (at least 10)")
+            .default_value("percent"))
+        .arg(Arg::new("base-tick")
+            .long("base-tick")
+            .global(true)
+            .value_parser(value_parser!(u32).range(1..=1000))
+            .value_name("MS")
+            .help("Delay between two counter ticks at a speed of 100, in milliseconds (1 to 1000)")
+            .default_value("25"))
+        .arg(Arg::new("stage")
+            .long("stage")
+            .global(true)
+            .value_name("STAGE")
+            .help("Arena with its own counter range, speed curve and damage: classic, clocktower, colosseum or dojo (--range and --damage still apply)"))
+        .arg(Arg::new("mutators")
+            .long("mutators")
+            .global(true)
+            .value_name("LIST")
+            .help("Comma-separated rule changes: no-strength, double-damage, five-misses-lose"))
+        .arg(Arg::new("script")
+            .long("script")
+            .global(true)
+            .value_name("FILE")
+            .help("Lua script hooking into the rules: on_round_start, modify_score, on_penalty (needs the lua feature)"))
+        .arg(Arg::new("targets")
+            .long("targets")
+            .global(true)
+            .value_name("DISTRIBUTION")
+            .help("How targets are spread over the dial: uniform, center (near the middle), edges (near both ends), or sequence (evenly stepped)")
+            .default_value("uniform"))
+        .arg(Arg::new("unique-targets")
+            .long("unique-targets")
+            .global(true)
+            .value_parser(value_parser!(u32).range(1..))
+            .value_name("SPACING")
+            .num_args(0..=1)
+            .default_missing_value("1")
+            .help("No two targets of a turn alike, and at least SPACING units apart around the dial (1 by default)"))
+        .arg(Arg::new("turn-order")
+            .long("turn-order")
+            .global(true)
+            .value_name("ORDER")
+            .help("Who goes first each round: fixed (player 1), alternate, or loser-first")
+            .default_value("fixed"))
+        .arg(Arg::new("damage")
+            .long("damage")
+            .global(true)
+            .value_name("MODEL")
+            .help("Vitality lost by a round's loser: difference (the score difference), capped:N, percent:N (of their vitality), or fixed:N")
+            .default_value("difference"))
+        .arg(Arg::new("win")
+            .long("win")
+            .global(true)
+            .value_name("CONDITION")
+            .help("Another way to win besides vitality: rounds:N (first to N round wins), score:N (highest total score after N rounds), or sudden-death (first exact hit)")
+            .default_value("vitality"))
+        .arg(Arg::new("rhythm")
+            .long("rhythm")
+            .global(true)
+            .value_parser(value_parser!(u32).range(1..))
+            .value_name("UNITS")
+            .num_args(0..=1)
+            .default_missing_value("10")
+            .help("Beat every few counter units (10 by default), stopping on a beat scores bonus points"))
+        .arg(Arg::new("counter-view")
+            .long("counter-view")
+            .global(true)
+            .value_name("VIEW")
+            .help("How the counter is displayed (bar, dial, big)")
+            .default_value("bar"))
+        .arg(Arg::new("sound")
+            .long("sound")
+            .global(true)
+            .value_name("MODE")
+            .help("Sound effects (on, off, bell-only)")
+            .default_value("off"))
+        .arg(Arg::new("bot-command")
+            .long("bot-command")
+            .global(true)
+            .value_name("COMMAND")
+            .help("Let an external program play as player 2, answering each target with a JSON line on its standard output"))
+        .arg(Arg::new("discord")
+            .long("discord")
+            .global(true)
+            .value_name("APP_ID")
+            .help("Show the match as Discord Rich Presence under this application (needs the discord feature)"))
+        .arg(Arg::new("output")
+            .long("output")
+            .global(true)
+            .value_name("MODE")
+            .help("How the game is drawn (tui, plain, none)")
+            .default_value("tui"))
+        .arg(Arg::new("lang")
+            .long("lang")
+            .global(true)
+            .value_name("LANG")
+            .help("Language of the game messages (en, fr, es)")
+            .default_value("en"))
+        .arg(Arg::new("duel")
+            .long("duel")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Both players stop the same counter at once, with the keys from the config file"))
+        .arg(Arg::new("reaction-game")
+            .long("reaction-game")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Play a reaction game between rounds, with the keys from the config file"))
+        .arg(Arg::new("void-implausible")
+            .long("void-implausible")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Score nothing for targets stopped during a streak of stops faster than a human could make"))
+        .arg(Arg::new("handicap1")
+            .long("handicap1")
+            .global(true)
+            .value_parser(value_parser!(i32).range(-50..=50))
+            .allow_negative_numbers(true)
+            .value_name("TICKS")
+            .help("Ticks added to each of player 1's stops before scoring, negative to take them away (-50 to 50)")
+            .default_value("0"))
+        .arg(Arg::new("handicap2")
+            .long("handicap2")
+            .global(true)
+            .value_parser(value_parser!(i32).range(-50..=50))
+            .allow_negative_numbers(true)
+            .value_name("TICKS")
+            .help("Ticks added to each of player 2's stops before scoring, negative to take them away (-50 to 50)")
+            .default_value("0"))
+        .arg(Arg::new("latency-credit")
+            .long("latency-credit")
+            .global(true)
+            .value_name("FILE")
+            .num_args(0..=1)
+            .default_missing_value("rust_game_profile.json")
+            .help("Wind the counter back by the latency measured with `calibrate`, from the given profile (the default profile if left out)"))
+        .arg(Arg::new("config")
+            .long("config")
+            .global(true)
+            .value_name("FILE")
+            .help("Read settings such as the players' stop keys from a TOML file"))
+        .arg(Arg::new("log-file")
+            .long("log-file")
+            .global(true)
+            .value_name("FILE")
+            .help("Append the game events to this file as JSON lines"))
+        .arg(Arg::new("export")
+            .long("export")
+            .global(true)
+            .value_name("FILE")
+            .help("Export the per-target results after each match (.csv or .json)"))
+        .arg(Arg::new("verbose")
+            .short('v')
+            .long("verbose")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .help("Show extra details such as tick intervals and reaction times"))
+        .arg(Arg::new("quiet")
+            .short('q')
+            .long("quiet")
+            .global(true)
+            .action(ArgAction::SetTrue)
+            .conflicts_with("verbose")
+            .help("Hide the result of each target"))
+        .arg(Arg::new("api")
+            .long("api")
+            .value_parser(value_parser!(u16))
+            .value_name("PORT")
+            .help("Serve matches over HTTP/JSON on this local port instead of playing in the terminal"))
+        .arg(Arg::new("help-keys")
+            .long("help-keys")
+            .action(ArgAction::SetTrue)
+            .help("Print the keys and a summary of the rules for the given settings, then exit (type ? at the start of a turn to see them during play)"))
+        .arg(Arg::new("gui")
+            .long("gui")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("api")
+            .help("Play in a desktop window instead of the terminal (needs a build with the gui feature)"))
+        .subcommand(Command::new("play")
+            .about("Play a match between two players, the default when no subcommand is given")
+            .arg(Arg::new("load")
+                .long("load")
+                .value_name("FILE")
+                .help("Resume a match saved when the game was interrupted"))
+            .arg(Arg::new("input-script")
+                .long("input-script")
+                .value_name("FILE")
+                .help("Play the match from a script of timed key presses and answers instead of the keyboard")))
+        .subcommand(Command::new("campaign")
+            .about("Play against a series of computer opponents, one stage at a time")
+            .arg(profile_arg()))
+        .subcommand(Command::new("survival")
+            .about("Play alone against endless waves of targets, with a faster counter each wave")
+            .arg(profile_arg()))
+        .subcommand(Command::new("practice")
+            .about("Play a run of targets alone for points, racing the ghost of your best run with --ghost")
+            .args(ghost_args("rust_game_practice_ghost.json")))
+        .subcommand(Command::new("time-attack")
+            .about("Play a run of targets alone against the clock, racing the ghost of your fastest run with --ghost")
+            .args(ghost_args("rust_game_time_attack_ghost.json")))
+        .subcommand(Command::new("simulate")
+            .about("Play many matches with random stops and report how often each player wins")
+            .arg(Arg::new("games")
+                .long("games")
+                .value_name("COUNT")
+                .value_parser(value_parser!(u64).range(1..))
+                .help("Number of matches to simulate")
+                .default_value("1000")))
+        .subcommand(Command::new("connect")
+            .about("Join a matchmaking server and play against the next player to join, as player 1's name")
+            .arg(Arg::new("address")
+                .value_name("HOST:PORT")
+                .help("Address of the server, or ws://HOST:PORT/ to connect over WebSocket")
+                .default_value("127.0.0.1:7878")))
+        .subcommand(Command::new("calibrate")
+            .about("Measure how late key presses reach the game and keep the offset in the profile")
+            .arg(profile_arg()))
+        .subcommand(Command::new("tutorial")
+            .about("Play one guided turn against the tutor, with every step explained"))
+        .subcommand(Command::new("stats")
+            .about("Show the progress kept in the profile")
+            .arg(profile_arg()))
+        .subcommand(Command::new("replay")
+            .about("Inspect a recorded practice or time attack run: each stop and state hash, and whether the run is complete")
+            .arg(Arg::new("file")
+                .value_name("FILE")
+                .help("File the run is recorded in")
+                .default_value("rust_game_practice_ghost.json")))
+        .subcommand(Command::new("rules")
+            .about("Print the rules the settings give: the scoring table, damage, penalties and how a match is won"))
+        .subcommand(Command::new("config")
+            .about("Check a configuration file and print the settings it gives, as TOML")
+            .arg(Arg::new("file")
+                .value_name("FILE")
+                .help("File to check, the one given with --config if left out")))
+        .subcommand(Command::new("completions")
+            .about("Print a shell completion script for the game")
+            .arg(Arg::new("shell")
+                .value_name("SHELL")
+                .required(true)
+                .help("Shell to complete for (bash, elvish, fish, powershell, zsh)")))
+        .subcommand(Command::new("man")
+            .about("Print the manual page of the game, in roff format"))
+}
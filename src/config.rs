@@ -0,0 +1,423 @@
+//! Configuration module for the game settings.
+//!
+//! This module groups the settings chosen on the command line into a single
+//! structure that is handed to the game when it is created, and reads the
+//! settings that can also be kept in a TOML configuration file.
+
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::SoundMode;
+use crate::error::{GameError, GameResult};
+use crate::plugins::PluginRegistry;
+use crate::persistence;
+use crate::rules::{AttributeBounds, RuleSet};
+use crate::scoring::StopRule;
+use crate::ui::{CounterView, Verbosity};
+use crate::ui::render::RenderMode;
+
+/// Starting vitality accepted from the command line and the bindings.
+pub const VITALITY_RANGE: RangeInclusive<i64> = 1..=999;
+
+/// Starting speed accepted from the command line and the bindings.
+pub const SPEED_RANGE: RangeInclusive<i64> = 1..=999;
+
+/// Starting strength accepted from the command line and the bindings.
+pub const STRENGTH_RANGE: RangeInclusive<i64> = 0..=999;
+
+/// Starting stamina accepted from the command line and the bindings.
+pub const STAMINA_RANGE: RangeInclusive<i64> = 0..=99;
+
+/// Number of targets per turn accepted from the command line and the bindings.
+pub const TARGET_COUNT_RANGE: RangeInclusive<u64> = 1..=20;
+
+/// Settings used to create a new game.
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    /// Name of the first player
+    pub player1_name: String,
+    /// Name of the second player
+    pub player2_name: String,
+    /// Starting vitality for both players
+    pub vitality: u32,
+    /// Starting speed for both players
+    pub speed: u32,
+    /// Starting strength for both players
+    pub strength: u32,
+    /// Starting stamina for both players
+    pub stamina: u32,
+    /// Number of targets per turn
+    pub target_count: usize,
+    /// Most targets per turn, if the count grows by one each round
+    pub target_cap: Option<usize>,
+    /// Whether the targets of a round are dealt out of one pool, and may be stolen
+    pub shared_pool: bool,
+    /// Whether players predict their average before each turn, for a bonus
+    pub predictions: bool,
+    /// Whether players choose how much they risk before each target
+    pub risk_tiers: bool,
+    /// Seconds the objectives stay on the screen before players must remember them, if set
+    pub memory_seconds: Option<u64>,
+    /// Seconds a round's winner has to choose the penalty, if the choice is timed
+    pub penalty_seconds: Option<u64>,
+    /// Vitality a player pays to pass their turn, if passing is allowed
+    pub pass_cost: Option<u32>,
+    /// Whether targets are never shown, the counter gives warmer/colder hints instead
+    pub hidden_target: bool,
+    /// Whether turn results stay hidden until both players have played the round
+    pub blind: bool,
+    /// Whether each round is played under random weather
+    pub weather: bool,
+    /// Counter ticks between two moves of a drifting target, if targets move
+    pub drift_ticks: Option<u32>,
+    /// Number of times the counter is stopped for each target
+    pub stops: u32,
+    /// How the stops of a multi-stop target are combined
+    pub stop_rule: StopRule,
+    /// The rules the match is played with
+    pub rules: RuleSet,
+    /// The mutators, scripts and other plugins bending the rules, in the order they apply
+    pub plugins: PluginRegistry,
+    /// Counter units between two beats in rhythm mode, if the counter keeps a rhythm
+    pub beat: Option<u32>,
+    /// How the live counter is drawn
+    pub counter_view: CounterView,
+    /// Which sound effects are played
+    pub sound: SoundMode,
+    /// Discord application the match is shown under as Rich Presence, if any
+    pub discord_app_id: Option<String>,
+    /// How the game output is drawn
+    pub render_mode: RenderMode,
+    /// File the game events are appended to, if any
+    pub log_file: Option<PathBuf>,
+    /// How much detail is printed during a match
+    pub verbosity: Verbosity,
+    /// File the per-target results are exported to after each match, if any
+    pub export: Option<PathBuf>,
+    /// Seed of the random number generator, to replay the same targets and critical hits
+    pub seed: Option<u64>,
+    /// Whether players wager vitality before each round
+    pub betting: bool,
+    /// Exact hits needed in one turn to recover vitality (0 disables healing)
+    pub heal_hits: u32,
+    /// Vitality recovered after enough exact hits
+    pub heal_amount: u32,
+    /// Keys the players stop the counter with
+    pub keys: KeyBindings,
+    /// Whether both players stop the same counter at once, needs a key for each player
+    pub duel: bool,
+    /// Whether the loser of a round picks a small bonus after the penalty
+    pub consolation: bool,
+    /// Whether the players ban penalties and bonuses before the match
+    pub draft: bool,
+    /// Whether a reaction game is played between rounds, needs a key for each player
+    pub reaction_game: bool,
+    /// Whether targets stopped during a streak of implausibly fast stops score nothing
+    pub void_implausible: bool,
+    /// Ticks added to each player's stops before they are scored, negative to take them away
+    pub handicap: [i32; 2],
+    /// Time credited back to each stop for the terminal's latency, in milliseconds, 0 to credit nothing
+    pub latency_credit_ms: u32,
+    /// Whether the match is autosaved at the end of each round
+    pub autosave: bool,
+    /// Command line of an external program playing as player 2, see `bot.rs`
+    pub bot_command: Option<String>,
+}
+
+/// The key each player stops the counter with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    /// Stop key of the first player, ENTER if not set
+    pub player1: Option<char>,
+    /// Stop key of the second player, ENTER if not set
+    pub player2: Option<char>,
+}
+
+impl KeyBindings {
+    /// Returns whether both players have their own stop key.
+    pub fn is_complete(&self) -> bool {
+        self.player1.is_some() && self.player2.is_some()
+    }
+    
+    /// Returns the stop key of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player (0 or 1)
+    ///
+    /// # Returns
+    ///
+    /// The key, or None if the player stops the counter with ENTER
+    pub fn get(&self, player_idx: usize) -> Option<char> {
+        if player_idx == 0 { self.player1 } else { self.player2 }
+    }
+}
+
+/// Settings read from a configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// The `[keys]` table
+    pub keys: KeyBindings,
+    /// The `[bounds]` table
+    pub bounds: AttributeBounds,
+}
+
+impl ConfigFile {
+    /// Reads a configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The TOML file to read
+    ///
+    /// # Returns
+    ///
+    /// Result containing the settings, or an error if the file cannot be read or is not valid
+    pub fn load(path: &Path) -> GameResult<Self> {
+        Self::parse(&persistence::read_file(path)?)
+    }
+
+    /// Parses the contents of a configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The TOML text
+    ///
+    /// # Returns
+    ///
+    /// Result containing the settings, or `GameError::ParseError` if the file cannot be parsed
+    /// and `GameError::ConfigError` if the settings do not go together
+    pub fn parse(contents: &str) -> GameResult<Self> {
+        let file: Self = toml::from_str(contents)?;
+
+        let keys = file.keys;
+        let lowercase = |key: Option<char>| key.map(|key| key.to_ascii_lowercase());
+        if keys.player1.is_some() && lowercase(keys.player1) == lowercase(keys.player2) {
+            return Err(GameError::ConfigError("Both players cannot use the same stop key".to_string()));
+        }
+        if [keys.player1, keys.player2].into_iter().flatten().any(|key| !key.is_ascii_graphic()) {
+            return Err(GameError::ConfigError("Stop keys must be letters, digits, or punctuation".to_string()));
+        }
+        file.bounds.validate()?;
+        Ok(file)
+    }
+
+    /// Writes the settings in the format of a configuration file.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the TOML text, which `parse` reads back to the same settings
+    pub fn to_toml(&self) -> GameResult<String> {
+        toml::to_string(self).map_err(|e| GameError::LogicError(format!("Could not write the settings: {}", e)))
+    }
+}
+
+impl GameConfig {
+    /// Swaps the two players, so that the other one goes first.
+    ///
+    /// Each player keeps their name, stop key and handicap.
+    pub fn swap_players(&mut self) {
+        std::mem::swap(&mut self.player1_name, &mut self.player2_name);
+        std::mem::swap(&mut self.keys.player1, &mut self.keys.player2);
+        self.handicap.swap(0, 1);
+    }
+
+    /// Returns the number of targets of each turn in a round.
+    ///
+    /// With a target cap, the first round has `target_count` targets and each
+    /// round after it one more, until the cap is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - The round, starting at 1
+    ///
+    /// # Returns
+    ///
+    /// The number of targets per turn in that round
+    pub fn targets_in_round(&self, round: u32) -> usize {
+        match self.target_cap {
+            Some(cap) => (self.target_count + round.saturating_sub(1) as usize).min(cap.max(self.target_count)),
+            None => self.target_count,
+        }
+    }
+}
+
+impl Default for GameConfig {
+    /// Creates a configuration matching the command line defaults.
+    fn default() -> Self {
+        Self {
+            player1_name: "Player 1".to_string(),
+            player2_name: "Player 2".to_string(),
+            vitality: 50,
+            speed: 50,
+            strength: 50,
+            stamina: 3,
+            target_count: 5,
+            target_cap: None,
+            shared_pool: false,
+            predictions: false,
+            risk_tiers: false,
+            memory_seconds: None,
+            penalty_seconds: None,
+            pass_cost: None,
+            hidden_target: false,
+            blind: false,
+            weather: false,
+            drift_ticks: None,
+            stops: 1,
+            stop_rule: StopRule::Sum,
+            rules: RuleSet::default(),
+            plugins: PluginRegistry::default(),
+            beat: None,
+            counter_view: CounterView::Bar,
+            sound: SoundMode::Off,
+            discord_app_id: None,
+            log_file: None,
+            verbosity: Verbosity::Normal,
+            render_mode: RenderMode::Tui,
+            export: None,
+            seed: None,
+            betting: false,
+            heal_hits: 3,
+            heal_amount: 10,
+            keys: KeyBindings::default(),
+            duel: false,
+            consolation: false,
+            draft: false,
+            reaction_game: false,
+            void_implausible: false,
+            handicap: [0; 2],
+            latency_credit_ms: 0,
+            autosave: true,
+            bot_command: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Bounds, SpeedFloor};
+
+    #[test]
+    fn test_default_config() {
+        let config = GameConfig::default();
+        assert_eq!(config.player1_name, "Player 1");
+        assert_eq!(config.player2_name, "Player 2");
+        assert_eq!(config.vitality, 50);
+        assert_eq!(config.speed, 50);
+        assert_eq!(config.strength, 50);
+        assert_eq!(config.stamina, 3);
+        assert_eq!(config.target_count, 5);
+        assert_eq!(config.target_cap, None);
+        assert!(!config.shared_pool);
+        assert!(!config.predictions);
+        assert!(!config.risk_tiers);
+        assert_eq!(config.memory_seconds, None);
+        assert_eq!(config.penalty_seconds, None);
+        assert_eq!(config.pass_cost, None);
+        assert!(!config.hidden_target);
+        assert!(!config.blind);
+        assert!(!config.weather);
+        assert_eq!(config.drift_ticks, None);
+        assert_eq!(config.stops, 1);
+        assert_eq!(config.stop_rule, StopRule::Sum);
+        assert_eq!(config.rules, RuleSet::default());
+        assert!(config.plugins.is_empty());
+        assert_eq!(config.beat, None);
+        assert_eq!(config.counter_view, CounterView::Bar);
+        assert_eq!(config.sound, SoundMode::Off);
+        assert_eq!(config.discord_app_id, None);
+        assert_eq!(config.log_file, None);
+        assert_eq!(config.verbosity, Verbosity::Normal);
+        assert_eq!(config.render_mode, RenderMode::Tui);
+        assert_eq!(config.export, None);
+        assert_eq!(config.seed, None);
+        assert!(!config.betting);
+        assert_eq!(config.heal_hits, 3);
+        assert_eq!(config.heal_amount, 10);
+        assert_eq!(config.keys, KeyBindings::default());
+        assert!(!config.duel);
+        assert!(!config.consolation);
+        assert!(!config.draft);
+        assert!(!config.reaction_game);
+        assert!(!config.void_implausible);
+        assert_eq!(config.handicap, [0, 0]);
+        assert_eq!(config.latency_credit_ms, 0);
+        assert!(config.autosave);
+        assert_eq!(config.bot_command, None);
+    }
+
+    #[test]
+    fn test_swap_players() {
+        let mut config = GameConfig {
+            keys: KeyBindings { player1: Some('f'), player2: None },
+            handicap: [3, -2],
+            ..GameConfig::default()
+        };
+        config.swap_players();
+
+        assert_eq!(config.player1_name, "Player 2");
+        assert_eq!(config.player2_name, "Player 1");
+        assert_eq!(config.keys, KeyBindings { player1: None, player2: Some('f') });
+        assert_eq!(config.handicap, [-2, 3]);
+    }
+
+    #[test]
+    fn test_targets_grow_up_to_the_cap() {
+        let config = GameConfig::default();
+        assert_eq!(config.targets_in_round(9), 5);
+
+        let config = GameConfig { target_cap: Some(7), ..GameConfig::default() };
+        let counts: Vec<usize> = (1..=5).map(|round| config.targets_in_round(round)).collect();
+        assert_eq!(counts, [5, 6, 7, 7, 7]);
+        // A cap under the starting count keeps it
+        let config = GameConfig { target_cap: Some(3), ..GameConfig::default() };
+        assert_eq!(config.targets_in_round(4), 5);
+    }
+
+    #[test]
+    fn test_parse_config_file() {
+        let file = ConfigFile::parse("[keys]\nplayer1 = \"f\"\nplayer2 = \"j\"\n").unwrap();
+        assert_eq!(file.keys.get(0), Some('f'));
+        assert_eq!(file.keys.get(1), Some('j'));
+
+        // Everything is optional
+        assert_eq!(ConfigFile::parse("").unwrap(), ConfigFile::default());
+        assert_eq!(ConfigFile::parse("[keys]\nplayer2 = \"j\"").unwrap().keys.get(0), None);
+    }
+
+    #[test]
+    fn test_parse_bounds() {
+        let file = ConfigFile::parse("[bounds]\nspeed_floor = \"clamp\"\nspeed = { min = 20, max = 80 }\n").unwrap();
+        assert_eq!(file.bounds.speed, Bounds { min: 20, max: Some(80) });
+        assert_eq!(file.bounds.speed_floor, SpeedFloor::Clamp);
+        assert_eq!(file.bounds.strength, Bounds::default());
+
+        assert!(ConfigFile::parse("[bounds]\nstamina = { min = 3, max = 2 }").is_err());
+        // Nobody could be knocked out
+        assert!(matches!(ConfigFile::parse("[bounds]\nvitality = { min = 10 }"), Err(GameError::ConfigError(_))));
+        // The counter cannot run with a speed of 0
+        assert!(ConfigFile::parse("[bounds]\nspeed_floor = \"clamp\"").is_err());
+        assert!(ConfigFile::parse("[bounds]\nspeed = { floor = 20 }").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_config_file() {
+        assert!(ConfigFile::parse("[keys]\nplayer1 = \"f\"\nplayer2 = \"F\"").is_err());
+        assert!(ConfigFile::parse("[keys]\nplayer1 = \"fj\"").is_err());
+        assert!(ConfigFile::parse("[keys]\nplayer1 = \" \"").is_err());
+        assert!(ConfigFile::parse("[keyz]").is_err());
+    }
+
+    #[test]
+    fn test_to_toml_round_trip() {
+        let bounds = AttributeBounds { vitality: Bounds { min: 0, max: Some(150) }, ..AttributeBounds::default() };
+        let file = ConfigFile { keys: KeyBindings { player1: Some('f'), player2: None }, bounds };
+        assert_eq!(ConfigFile::parse(&file.to_toml().unwrap()).unwrap(), file);
+        assert_eq!(ConfigFile::parse(&ConfigFile::default().to_toml().unwrap()).unwrap(), ConfigFile::default());
+    }
+}
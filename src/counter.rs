@@ -1,23 +1,271 @@
 //! Counter module for the circular counter logic.
 //!
-//! This module provides functionality for a counter that runs in a separate thread
-//! and can be observed and controlled from the main thread.
+//! This module provides functionality for a counter that is run by a `TurnEngine`
+//! and can be observed and controlled from the main thread. The engine keeps one
+//! worker task for all the counters of a game, and is told to start and stop
+//! them over a channel.
 
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::error::GameResult;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use tokio::select;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::{self, Interval, MissedTickBehavior};
 
-/// Represents a circular counter that can be incremented in a separate thread.
-/// The counter loops back to 0 after reaching 100, incrementing the miss counter.
+pub use crate::dial::{DEFAULT_COUNTER_MAX, Dial, Precision};
+use crate::error::{GameError, GameResult};
+use crate::runtime;
+
+/// Shared, thread-safe handle to a piece of counter state.
+pub type Shared<T> = Arc<Mutex<T>>;
+
+/// Shortest time between two updates of the counter value, however fast it ticks.
+const MIN_POLL: Duration = Duration::from_millis(1);
+
+/// Time a player usually takes to react to the counter, in milliseconds.
+pub const REACTION_MS: u32 = 250;
+
+/// How hard a counter is to stop on target, from the fewest to the most units it
+/// moves through while a player reacts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DifficultyLevel {
+    /// Up to 2 units
+    Easy,
+    /// Up to 5 units
+    Normal,
+    /// Up to 10 units
+    Hard,
+    /// More than 10 units
+    Extreme,
+}
+
+/// An estimate of how hard a counter is to stop on target, from its pace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Difficulty {
+    /// Ticks the counter goes through before it wraps around
+    pub ticks_per_lap: u32,
+    /// Time the counter takes to go once around the dial, in milliseconds
+    pub lap_ms: u32,
+    /// Whole units the counter moves through while a player reacts
+    pub units_per_reaction: u32,
+    /// How hard stopping on target is, from the units per reaction
+    pub level: DifficultyLevel,
+}
+
+/// Estimates how hard a counter is to stop on target.
+///
+/// # Arguments
+///
+/// * `dial` - The values the counter runs through
+/// * `interval_ms` - The time to move one whole unit, in milliseconds
+///
+/// # Returns
+///
+/// The ticks and time in one lap of the dial, and how far the counter moves
+/// while a player reacts
+pub fn difficulty_estimate(dial: Dial, interval_ms: u32) -> Difficulty {
+    let interval_ms = interval_ms.max(1);
+    let units_per_reaction = REACTION_MS / interval_ms;
+    Difficulty {
+        ticks_per_lap: dial.max() + 1,
+        lap_ms: (dial.max() + 1).saturating_mul(interval_ms) / dial.ticks(1),
+        units_per_reaction,
+        level: match units_per_reaction {
+            0..=2 => DifficultyLevel::Easy,
+            3..=5 => DifficultyLevel::Normal,
+            6..=10 => DifficultyLevel::Hard,
+            _ => DifficultyLevel::Extreme,
+        },
+    }
+}
+
+/// A target that drifts by one step up or down every few counter ticks.
+pub struct TargetTracker {
+    /// The current target, shared with the display
+    target: Shared<u32>,
+    /// Number of counter ticks between two moves
+    drift_ticks: u32,
+    /// The values the target moves through
+    dial: Dial,
+    /// Ticks counted since the last move
+    ticks: u32,
+    /// Source of the drift directions
+    rng: StdRng,
+}
+
+impl TargetTracker {
+    /// Creates a tracker for a target that starts at the given value.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The initial target, in ticks
+    /// * `drift_ticks` - Number of counter ticks between two moves (at least 1)
+    /// * `dial` - The values the target moves through, one tick at a time
+    /// * `seed` - Seed for the drift directions
+    ///
+    /// # Returns
+    ///
+    /// A new TargetTracker
+    pub fn new(target: u32, drift_ticks: u32, dial: Dial, seed: u64) -> Self {
+        Self {
+            target: Arc::new(Mutex::new(target)),
+            drift_ticks: drift_ticks.max(1),
+            dial,
+            ticks: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+    
+    /// Gets a shared reference to the current target, for display and scoring.
+    ///
+    /// # Returns
+    ///
+    /// Arc<Mutex> reference to the target
+    pub fn shared(&self) -> Shared<u32> {
+        Arc::clone(&self.target)
+    }
+    
+    /// Counts one counter tick, moving the target when enough ticks have passed.
+    /// The target wraps around the dial like the counter.
+    fn tick(&mut self) {
+        self.ticks += 1;
+        if self.ticks < self.drift_ticks {
+            return;
+        }
+        self.ticks = 0;
+        
+        let mut target = self.target.lock().unwrap();
+        let max = self.dial.max();
+        *target = if self.rng.random_bool(0.5) {
+            if *target == max { 0 } else { *target + 1 }
+        } else {
+            target.checked_sub(1).unwrap_or(max)
+        };
+    }
+}
+
+/// When a running counter started and how fast it ticks.
+///
+/// The counter value at any moment follows from the time elapsed since the
+/// start, so the counter and its display agree however often they look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CounterClock {
+    /// When the counter started
+    started: Instant,
+    /// Time between two ticks
+    tick: Duration,
+    /// Highest counter value, in ticks
+    max: u32,
+}
+
+impl CounterClock {
+    /// Creates a clock for a counter started at the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `started` - When the counter started
+    /// * `tick` - Time between two ticks
+    /// * `max` - Highest counter value, in ticks
+    ///
+    /// # Returns
+    ///
+    /// A new CounterClock
+    pub fn new(started: Instant, tick: Duration, max: u32) -> Self {
+        Self { started, tick: tick.max(Duration::from_nanos(1)), max }
+    }
+    
+    /// Returns the number of ticks since the counter started.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time to count up to
+    ///
+    /// # Returns
+    ///
+    /// The number of whole ticks between the start and `now`
+    pub fn ticks_at(&self, now: Instant) -> u64 {
+        (now.saturating_duration_since(self.started).as_nanos() / self.tick.as_nanos()) as u64
+    }
+    
+    /// Works out the counter value after a number of ticks.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticks` - The number of ticks since the counter started
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the counter value and miss count
+    pub fn reading(&self, ticks: u64) -> (u32, u32) {
+        let lap = u64::from(self.max) + 1;
+        ((ticks % lap) as u32, (ticks / lap) as u32)
+    }
+    
+    /// Works out the counter value at a given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time to read the counter at
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the counter value and miss count
+    pub fn reading_at(&self, now: Instant) -> (u32, u32) {
+        self.reading(self.ticks_at(now))
+    }
+    
+    /// Returns the same clock, as if the counter had started later.
+    ///
+    /// # Arguments
+    ///
+    /// * `by` - How much later, e.g. the time the counter was paused
+    ///
+    /// # Returns
+    ///
+    /// The clock, reading what it read `by` earlier
+    pub fn delayed(self, by: Duration) -> Self {
+        Self { started: self.started + by, ..self }
+    }
+}
+
+/// What happens to a running counter, in the order it happens.
+///
+/// The counter's worker sends these over a channel, so the display follows
+/// the counter without sharing any state with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterEvent {
+    /// The counter started, and its value follows this clock
+    Started(CounterClock),
+    /// The counter moved to a value, in ticks
+    Tick(u32),
+    /// The counter paused where it was, until it starts again with a new clock
+    Paused,
+    /// The counter passed the end of its dial and went back to 0, just before the `Tick(0)`
+    Wrapped,
+    /// The counter stopped at a value, with a miss count
+    Stopped(u32, u32),
+}
+
+/// The receiving end of a counter's events.
+pub type CounterEvents = UnboundedReceiver<CounterEvent>;
+
+/// Represents a circular counter that is incremented by the worker of a `TurnEngine`.
+/// The counter loops back to 0 after reaching the end of its dial, incrementing the miss counter.
 pub struct Counter {
-    /// The current counter value
-    value: Arc<Mutex<u32>>,
-    /// Number of times the counter has reset to 0
-    miss: Arc<Mutex<u32>>,
-    /// Flag indicating if the counter is running
-    running: Arc<Mutex<bool>>,
+    /// The values the counter runs through
+    dial: Dial,
+    /// When the counter started, while it is running
+    clock: Option<CounterClock>,
+    /// The value and miss count the counter stopped at
+    stopped: (u32, u32),
+    /// When the counter was paused, while it is
+    paused: Option<Instant>,
+    /// Where the worker sends the counter's events
+    events: UnboundedSender<CounterEvent>,
+    /// The engine running the counter, once it started
+    engine: Option<TurnEngine>,
 }
 
 impl Counter {
@@ -27,44 +275,64 @@ impl Counter {
     ///
     /// A new Counter with values initialized to zero
     pub fn new() -> Self {
+        Self::with_dial(Dial::default())
+    }
+    
+    /// Creates a new Counter running through the given dial.
+    ///
+    /// # Arguments
+    ///
+    /// * `dial` - The values the counter runs through
+    ///
+    /// # Returns
+    ///
+    /// A new Counter with values initialized to zero
+    pub fn with_dial(dial: Dial) -> Self {
         Self {
-            value: Arc::new(Mutex::new(0)),
-            miss: Arc::new(Mutex::new(0)),
-            running: Arc::new(Mutex::new(false)),
+            dial,
+            clock: None,
+            stopped: (0, 0),
+            paused: None,
+            // Nobody listens until `events` is called
+            events: mpsc::unbounded_channel().0,
+            engine: None,
         }
     }
     
-    /// Starts the counter in a separate thread.
+    /// Starts the counter on the engine's worker.
     ///
     /// # Arguments
     ///
-    /// * `speed_ms` - The increment speed in milliseconds
-    pub fn start(&self, speed_ms: u32) -> GameResult<()> {
-        let value = Arc::clone(&self.value);
-        let miss = Arc::clone(&self.miss);
-        let running = Arc::clone(&self.running);
-        
-        // Reset counters
-        *self.value.lock().unwrap() = 0;
-        *self.miss.lock().unwrap() = 0;
-        *self.running.lock().unwrap() = true;
-        
-        // Start a thread to update the counter
-        thread::spawn(move || {
-            while *running.lock().unwrap() {
-                thread::sleep(Duration::from_millis(u64::from(speed_ms)));
-                let mut val = value.lock().unwrap();
-                *val += 1;
-                
-                // Reset counter and increment miss when exceeding 100
-                if *val > 100 {
-                    *val = 0;
-                    let mut m = miss.lock().unwrap();
-                    *m += 1;
-                }
-            }
-        });
+    /// * `engine` - The engine that makes the counter tick
+    /// * `speed_ms` - The time to move one whole unit, in milliseconds
+    pub fn start(&mut self, engine: &TurnEngine, speed_ms: u32) -> GameResult<()> {
+        self.run(engine, speed_ms, None)
+    }
+    
+    /// Starts the counter on the engine's worker, moving the target as it runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine that makes the counter tick
+    /// * `speed_ms` - The time to move one whole unit, in milliseconds
+    /// * `tracker` - The target to move, read it through `TargetTracker::shared`
+    pub fn start_with_target(&mut self, engine: &TurnEngine, speed_ms: u32, tracker: TargetTracker) -> GameResult<()> {
+        self.run(engine, speed_ms, Some(tracker))
+    }
+    
+    fn run(&mut self, engine: &TurnEngine, speed_ms: u32, tracker: Option<TargetTracker>) -> GameResult<()> {
+        // Finer dials tick more often, so a whole unit still takes `speed_ms`
+        let tick = Duration::from_micros(u64::from(speed_ms) * 1000 / u64::from(self.dial.ticks(1)));
+        let clock = CounterClock::new(Instant::now(), tick, self.dial.max());
         
+        engine.send(Command::Start {
+            interval: tick.max(MIN_POLL),
+            run: Box::new(Run { clock, tracker, done: 0, events: self.events.clone() }),
+        })?;
+        self.clock = Some(clock);
+        self.stopped = (0, 0);
+        self.paused = None;
+        self.engine = Some(engine.clone());
         Ok(())
     }
     
@@ -73,44 +341,317 @@ impl Counter {
     /// # Returns
     ///
     /// A tuple containing the current counter value and miss count.
-    pub fn stop(&self) -> (u32, u32) {
-        *self.running.lock().unwrap() = false;
-        let value = *self.value.lock().unwrap();
-        let miss = *self.miss.lock().unwrap();
-        (value, miss)
+    pub fn stop(&mut self) -> (u32, u32) {
+        if let (Some(clock), Some(engine)) = (self.clock.take(), &self.engine) {
+            // The counter stops where the clock is, even between two updates of the worker
+            let at = self.paused.take().unwrap_or_else(Instant::now);
+            self.stopped = clock.reading_at(at);
+            let _ = engine.send(Command::Stop { at });
+        }
+        self.stopped
+    }
+    
+    /// Pauses the running counter where it is, e.g. while a panel covers it.
+    pub fn pause(&mut self) {
+        if let (Some(_), Some(engine), None) = (self.clock, &self.engine, self.paused) {
+            let at = Instant::now();
+            self.paused = Some(at);
+            let _ = engine.send(Command::Pause { at });
+        }
     }
     
-    /// Gets shared references to the counter's internal state for display purposes.
+    /// Starts a paused counter again, from the value it was paused at.
+    pub fn resume(&mut self) {
+        if let (Some(clock), Some(engine), Some(paused)) = (self.clock.as_mut(), &self.engine, self.paused) {
+            let at = Instant::now();
+            *clock = clock.delayed(at.duration_since(paused));
+            self.paused = None;
+            let _ = engine.send(Command::Resume { at });
+        }
+    }
+    
+    /// Reads the counter without stopping it.
     ///
     /// # Returns
     ///
-    /// Tuple containing Arc<Mutex> references to value, miss, and running state
-    pub fn get_display_values(&self) -> (Arc<Mutex<u32>>, Arc<Mutex<u32>>, Arc<Mutex<bool>>) {
-        (
-            Arc::clone(&self.value),
-            Arc::clone(&self.miss),
-            Arc::clone(&self.running)
-        )
+    /// A tuple containing the current counter value and miss count.
+    pub fn current(&self) -> (u32, u32) {
+        match self.clock {
+            Some(clock) => clock.reading_at(self.paused.unwrap_or_else(Instant::now)),
+            None => self.stopped,
+        }
     }
     
-    /// Gets the current counter value.
+    /// Blocks until the counter reaches a value, wraps around, or is stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to wait for
+    pub fn wait_for(&self, value: u32) {
+        while self.clock.is_some() {
+            let (current, miss) = self.current();
+            if current >= value || miss > 0 {
+                return;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+    
+    /// Listens to the counter's events, for display purposes.
+    ///
+    /// The events of every tick are kept until they are read, so a display
+    /// polling slower than the counter ticks still sees every beat. Only the
+    /// last listener before the counter starts receives them.
     ///
     /// # Returns
     ///
-    /// The current counter value
-    #[cfg(test)]
-    pub fn get_value(&self) -> u32 {
-        *self.value.lock().unwrap()
+    /// The receiving end of the events
+    pub fn events(&mut self) -> CounterEvents {
+        let (events, receiver) = mpsc::unbounded_channel();
+        self.events = events;
+        receiver
+    }
+}
+
+/// A counter as the engine's worker runs it.
+struct Run {
+    /// When the counter started and how fast it ticks
+    clock: CounterClock,
+    /// The target moving as the counter runs, if any
+    tracker: Option<TargetTracker>,
+    /// Number of ticks already gone through
+    done: u64,
+    /// Where the counter's events are sent
+    events: UnboundedSender<CounterEvent>,
+}
+
+impl Run {
+    /// Sends an event, whether or not anybody still listens.
+    fn send(&self, event: CounterEvent) {
+        let _ = self.events.send(event);
     }
     
-    /// Gets the current miss count.
+    /// Catches up with the clock, going through every tick up to the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time to catch up to
+    fn step(&mut self, now: Instant) {
+        // A late wake-up goes through every tick it missed, so no event is skipped
+        let due = self.clock.ticks_at(now);
+        for ticks in self.done + 1..=due {
+            let (val, _) = self.clock.reading(ticks);
+            if val == 0 {
+                self.send(CounterEvent::Wrapped);
+            }
+            self.send(CounterEvent::Tick(val));
+            if let Some(tracker) = self.tracker.as_mut() {
+                tracker.tick();
+            }
+        }
+        self.done = self.done.max(due);
+    }
+}
+
+/// What the engine's worker is told to do.
+enum Command {
+    /// Run a counter, updating it every `interval`
+    Start { interval: Duration, run: Box<Run> },
+    /// Stop the running counter where it was at the given time
+    Stop { at: Instant },
+    /// Pause the running counter where it was at the given time
+    Pause { at: Instant },
+    /// Start the paused counter again at the given time
+    Resume { at: Instant },
+}
+
+/// Runs the counters of a game on one worker task, kept for the whole game.
+///
+/// Clones send their commands to the same worker, which ends once every
+/// clone has been dropped.
+#[derive(Clone)]
+pub struct TurnEngine {
+    /// Where the worker's commands are sent
+    commands: UnboundedSender<Command>,
+}
+
+impl TurnEngine {
+    /// Starts an engine and its worker on the shared runtime.
     ///
     /// # Returns
     ///
-    /// The current miss count
-    #[cfg(test)]
-    pub fn get_miss(&self) -> u32 {
-        *self.miss.lock().unwrap()
+    /// Result containing the engine, or an error if the runtime could not be started
+    pub fn new() -> GameResult<Self> {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        runtime::get()?.spawn(work(receiver));
+        Ok(Self { commands })
+    }
+    
+    /// Sends a command to the worker.
+    fn send(&self, command: Command) -> GameResult<()> {
+        self.commands.send(command)
+            .map_err(|_| GameError::LogicError("The counter worker has stopped".to_string()))
+    }
+}
+
+/// Waits for the next update of the running counter, or forever if none is running.
+async fn next_update(current: &mut Option<(Interval, Box<Run>)>) {
+    match current {
+        Some((interval, _)) => {
+            interval.tick().await;
+        },
+        None => std::future::pending().await,
+    }
+}
+
+/// Creates the timer updating a running counter, from one period on.
+fn updates(period: Duration) -> Interval {
+    let mut interval = time::interval_at(time::Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    interval
+}
+
+/// The engine's worker: runs one counter at a time, as the commands come.
+async fn work(mut commands: UnboundedReceiver<Command>) {
+    let mut current: Option<(Interval, Box<Run>)> = None;
+    let mut paused: Option<(Duration, Box<Run>, Instant)> = None;
+    loop {
+        select! {
+            // A stop is handled before any further update, so no tick is sent past it
+            biased;
+            command = commands.recv() => match command {
+                Some(Command::Start { interval, run }) => {
+                    run.send(CounterEvent::Started(run.clock));
+                    current = Some((updates(interval), run));
+                },
+                Some(Command::Stop { at }) => if let Some(mut run) = current.take().map(|(_, run)| run).or(paused.take().map(|(_, run, _)| run)) {
+                    run.step(at);
+                    let (value, miss) = run.clock.reading_at(at);
+                    run.send(CounterEvent::Stopped(value, miss));
+                },
+                Some(Command::Pause { at }) => if let Some((interval, mut run)) = current.take() {
+                    run.step(at);
+                    run.send(CounterEvent::Paused);
+                    paused = Some((interval.period(), run, at));
+                },
+                Some(Command::Resume { at }) => if let Some((period, mut run, paused_at)) = paused.take() {
+                    // The clock picks up where it was paused, so no tick is skipped or repeated
+                    run.clock = run.clock.delayed(at.saturating_duration_since(paused_at));
+                    run.send(CounterEvent::Started(run.clock));
+                    current = Some((updates(period), run));
+                },
+                None => break,
+            },
+            _ = next_update(&mut current) => {
+                if let Some((_, run)) = current.as_mut() {
+                    run.step(Instant::now());
+                }
+            },
+        }
+    }
+}
+
+/// A counter both players stop in the same pass, each with their own key.
+///
+/// The first press of each player locks the counter value for them, and the
+/// counter stops once both players have locked a value.
+pub struct DuelCounter {
+    /// The shared running counter
+    counter: Counter,
+    /// The value and miss count locked by each player, once they stopped
+    stops: [Option<(u32, u32)>; 2],
+    /// The players, in the order they stopped
+    order: Vec<usize>,
+}
+
+impl DuelCounter {
+    /// Creates a duel counter running through the given dial.
+    ///
+    /// # Arguments
+    ///
+    /// * `dial` - The values the counter runs through
+    ///
+    /// # Returns
+    ///
+    /// A new DuelCounter that no player has stopped yet
+    pub fn new(dial: Dial) -> Self {
+        Self {
+            counter: Counter::with_dial(dial),
+            stops: [None; 2],
+            order: Vec::new(),
+        }
+    }
+    
+    /// Starts the shared counter on the engine's worker.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine that makes the counter tick
+    /// * `speed_ms` - The time to move one whole unit, in milliseconds
+    pub fn start(&mut self, engine: &TurnEngine, speed_ms: u32) -> GameResult<()> {
+        self.counter.start(engine, speed_ms)
+    }
+    
+    /// Listens to the shared counter's events, for display purposes.
+    ///
+    /// # Returns
+    ///
+    /// The receiving end of the events
+    pub fn events(&mut self) -> CounterEvents {
+        self.counter.events()
+    }
+    
+    /// Locks the current counter value for a player.
+    /// Only the first press of each player counts.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player who pressed their key
+    pub fn lock(&mut self, player_idx: usize) {
+        if self.stops[player_idx].is_some() {
+            return;
+        }
+        self.stops[player_idx] = Some(self.counter.current());
+        self.order.push(player_idx);
+        
+        if self.is_complete() {
+            self.counter.stop();
+        }
+    }
+    
+    /// Returns whether both players have locked a value.
+    pub fn is_complete(&self) -> bool {
+        self.stops.iter().all(Option::is_some)
+    }
+    
+    /// Stops the counter, whether or not both players have locked a value.
+    pub fn stop(&mut self) {
+        self.counter.stop();
+    }
+    
+    /// Returns the value and miss count locked by each player.
+    ///
+    /// # Returns
+    ///
+    /// The stop of each player, None for a player who has not stopped yet
+    pub fn stops(&self) -> [Option<(u32, u32)>; 2] {
+        self.stops
+    }
+    
+    /// Returns the players in the order they stopped.
+    ///
+    /// # Returns
+    ///
+    /// The indexes of the players who stopped, first one first
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+}
+
+impl Drop for Counter {
+    /// Stops the counter, so it doesn't outlive an interrupted turn.
+    fn drop(&mut self) {
+        self.stop();
     }
 }
 
@@ -127,17 +668,94 @@ mod tests {
     use std::thread;
     use std::time::Duration;
 
+    fn engine() -> TurnEngine {
+        TurnEngine::new().unwrap()
+    }
+
+    /// Reads the events of a counter up to its stop.
+    fn until_stopped(mut events: CounterEvents) -> Vec<CounterEvent> {
+        let mut received = Vec::new();
+        while let Some(event) = events.blocking_recv() {
+            received.push(event);
+            if matches!(event, CounterEvent::Stopped(..)) {
+                break;
+            }
+        }
+        received
+    }
+
+    #[test]
+    fn test_clock_reading() {
+        let started = Instant::now();
+        let clock = CounterClock::new(started, Duration::from_millis(10), 10);
+        
+        assert_eq!(clock.reading_at(started), (0, 0));
+        assert_eq!(clock.reading_at(started + Duration::from_millis(109)), (10, 0));
+        // Eleven values per lap, from 0 to 10
+        assert_eq!(clock.ticks_at(started + Duration::from_millis(250)), 25);
+        assert_eq!(clock.reading_at(started + Duration::from_millis(250)), (3, 2));
+    }
+
+    #[test]
+    fn test_stopped_counter_keeps_its_value() {
+        let mut counter = Counter::new();
+        assert!(counter.start(&engine(), 1).is_ok());
+        thread::sleep(Duration::from_millis(20));
+        
+        let stopped = counter.stop();
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(counter.current(), stopped);
+    }
+
+    #[test]
+    fn test_paused_counter_picks_up_where_it_was() {
+        let mut counter = Counter::new();
+        let events = counter.events();
+        assert!(counter.start(&engine(), 10).is_ok());
+        thread::sleep(Duration::from_millis(35));
+
+        counter.pause();
+        let paused = counter.current();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(counter.current(), paused);
+
+        counter.resume();
+        let (value, miss) = counter.stop();
+        // Ten ticks went by while paused, none of them counted
+        assert_eq!(miss, 0);
+        assert!(value >= paused.0 && value < paused.0 + 5);
+
+        let received = until_stopped(events);
+        let paused_at = received.iter().position(|&event| event == CounterEvent::Paused).unwrap();
+        assert!(matches!(received[paused_at + 1], CounterEvent::Started(_)));
+    }
+
+    #[test]
+    fn test_engine_runs_one_counter_after_another() {
+        let engine = engine();
+        let mut first = Counter::new();
+        let mut second = Counter::new();
+        
+        assert!(first.start(&engine, 1).is_ok());
+        thread::sleep(Duration::from_millis(20));
+        let stopped = first.stop();
+        
+        assert!(second.start(&engine, 1).is_ok());
+        thread::sleep(Duration::from_millis(20));
+        assert!(second.stop().0 > 0);
+        assert_eq!(first.current(), stopped);
+    }
+
     #[test]
     fn test_counter_initial_state() {
         let counter = Counter::new();
-        assert_eq!(counter.get_value(), 0);
-        assert_eq!(counter.get_miss(), 0);
+        assert_eq!(counter.current(), (0, 0));
     }
 
     #[test]
     fn test_counter_stop() {
-        let counter = Counter::new();
-        assert!(counter.start(10).is_ok()); // Fast speed for testing
+        let mut counter = Counter::new();
+        assert!(counter.start(&engine(), 10).is_ok()); // Fast speed for testing
         
         // Let it run briefly
         thread::sleep(Duration::from_millis(50));
@@ -149,19 +767,157 @@ mod tests {
     }
     
     #[test]
-    fn test_get_display_values() {
-        let counter = Counter::new();
-        let (value, miss, running) = counter.get_display_values();
+    fn test_last_listener_receives_the_events() {
+        let mut counter = Counter::new();
+        let mut replaced = counter.events();
+        let events = counter.events();
+        assert!(counter.start(&engine(), 1).is_ok());
+        counter.stop();
+        
+        assert_eq!(replaced.blocking_recv(), None);
+        assert!(matches!(until_stopped(events).last(), Some(CounterEvent::Stopped(..))));
+    }
+    
+    #[test]
+    fn test_drop_stops_counter() {
+        let mut counter = Counter::new();
+        let events = counter.events();
+        assert!(counter.start(&engine(), 10).is_ok());
+
+        drop(counter);
+        assert!(matches!(until_stopped(events).last(), Some(CounterEvent::Stopped(..))));
+    }
+    
+    #[test]
+    fn test_target_tracker_drifts() {
+        let mut tracker = TargetTracker::new(50, 3, Dial::default(), 7);
+        let target = tracker.shared();
+        
+        tracker.tick();
+        tracker.tick();
+        assert_eq!(*target.lock().unwrap(), 50);
+        tracker.tick();
+        assert!(target.lock().unwrap().abs_diff(50) == 1);
+    }
+    
+    #[test]
+    fn test_target_tracker_wraps() {
+        for seed in 0..20 {
+            let mut tracker = TargetTracker::new(100, 1, Dial::default(), seed);
+            tracker.tick();
+            let target = *tracker.shared().lock().unwrap();
+            assert!(target == 0 || target == 99);
+        }
+    }
+    
+    #[test]
+    fn test_start_with_target() {
+        let mut counter = Counter::new();
+        let tracker = TargetTracker::new(50, 1, Dial::default(), 7);
+        let target = tracker.shared();
+        assert!(counter.start_with_target(&engine(), 10, tracker).is_ok());
+        
+        thread::sleep(Duration::from_millis(50));
+        
+        // The target moved at most one step per tick
+        let (value, miss) = counter.stop();
+        assert!(target.lock().unwrap().abs_diff(50) <= value + miss * 101 + 1);
+    }
+    
+    #[test]
+    fn test_difficulty_estimate() {
+        let classic = difficulty_estimate(Dial::default(), 50);
+        assert_eq!(classic, Difficulty { ticks_per_lap: 101, lap_ms: 5050, units_per_reaction: 5, level: DifficultyLevel::Normal });
+        
+        // A finer dial has more ticks, but a lap takes as long
+        let fine = difficulty_estimate(Dial::new(100, Precision::Fine), 50);
+        assert_eq!(fine.ticks_per_lap, 1001);
+        assert_eq!(fine.lap_ms, 5005);
+        
+        assert_eq!(difficulty_estimate(Dial::default(), 200).level, DifficultyLevel::Easy);
+        assert_eq!(difficulty_estimate(Dial::default(), 25).level, DifficultyLevel::Hard);
+        assert_eq!(difficulty_estimate(Dial::default(), 0).level, DifficultyLevel::Extreme);
+    }
+    
+    #[test]
+    fn test_counter_wraps_at_dial_end() {
+        let mut counter = Counter::with_dial(Dial::new(10, Precision::Normal));
+        assert!(counter.start(&engine(), 1).is_ok());
+        
+        thread::sleep(Duration::from_millis(50));
+        
+        let (value, miss) = counter.stop();
+        assert!(value <= 10);
+        assert!(miss > 0);
+    }
+    
+    #[test]
+    fn test_fine_counter_wraps_at_dial_end() {
+        let mut counter = Counter::with_dial(Dial::new(100, Precision::Fine));
+        assert!(counter.start(&engine(), 1).is_ok());
+        
+        thread::sleep(Duration::from_millis(50));
+        
+        let (value, _) = counter.stop();
+        assert!(value <= 1000);
+    }
+    
+    #[test]
+    fn test_wait_for_value() {
+        let mut counter = Counter::new();
+        assert!(counter.start(&engine(), 1).is_ok());
+        
+        counter.wait_for(20);
+        
+        let (value, miss) = counter.stop();
+        assert!(value >= 20 || miss > 0);
+    }
+    
+    #[test]
+    fn test_counter_sends_every_tick() {
+        let mut counter = Counter::with_dial(Dial::new(10, Precision::Normal));
+        let events = counter.events();
+        assert!(counter.start(&engine(), 1).is_ok());
+        
+        thread::sleep(Duration::from_millis(50));
+        
+        let (value, miss) = counter.stop();
+        let events = until_stopped(events);
+        assert!(matches!(events.first(), Some(CounterEvent::Started(_))));
+        assert_eq!(events.last(), Some(&CounterEvent::Stopped(value, miss)));
+        
+        // Eleven ticks per lap, each lap ending with a wrap back to 0
+        let ticks = events.iter().filter(|event| matches!(event, CounterEvent::Tick(_))).count();
+        let wraps = events.iter().filter(|event| **event == CounterEvent::Wrapped).count();
+        assert_eq!(ticks as u32, miss * 11 + value);
+        assert_eq!(wraps as u32, miss);
+    }
+    
+    #[test]
+    fn test_duel_counter_records_both_stops_in_order() {
+        let mut duel = DuelCounter::new(Dial::default());
+        let events = duel.events();
+        assert!(duel.start(&engine(), 5).is_ok());
+        
+        thread::sleep(Duration::from_millis(30));
+        duel.lock(1);
+        assert!(!duel.is_complete());
+        
+        thread::sleep(Duration::from_millis(30));
+        duel.lock(1); // Ignored, player 2 already stopped
+        duel.lock(0);
+        assert!(duel.is_complete());
+        assert!(matches!(until_stopped(events).last(), Some(CounterEvent::Stopped(..))));
         
-        assert_eq!(*value.lock().unwrap(), 0);
-        assert_eq!(*miss.lock().unwrap(), 0);
-        assert_eq!(*running.lock().unwrap(), false);
+        assert_eq!(duel.order(), &[1, 0]);
+        // Player 1 stopped later, so the counter went further
+        let [late, early] = duel.stops().map(Option::unwrap);
+        assert!(late.1 > early.1 || late.0 >= early.0);
     }
     
     #[test]
     fn test_default() {
         let counter = Counter::default();
-        assert_eq!(counter.get_value(), 0);
-        assert_eq!(counter.get_miss(), 0);
+        assert_eq!(counter.current(), (0, 0));
     }
 }
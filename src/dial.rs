@@ -0,0 +1,201 @@
+//! Dial module for the values the counter runs through.
+//!
+//! A dial knows the counter range and how finely it counts, and converts
+//! between whole units and ticks. It holds no timer, so the rules and the
+//! scoring can use it on any target, the browser included.
+
+use std::str::FromStr;
+
+use crate::error::GameError;
+
+/// Highest counter value, in whole units, before it wraps around to 0 in the classic game.
+pub const DEFAULT_COUNTER_MAX: u32 = 100;
+
+/// How finely the counter counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// The counter counts in whole units
+    #[default]
+    Normal,
+    /// The counter counts in tenths of a unit
+    Fine,
+}
+
+impl Precision {
+    /// Returns the number of counter ticks in one whole unit.
+    pub fn ticks_per_unit(self) -> u32 {
+        match self {
+            Precision::Normal => 1,
+            Precision::Fine => 10,
+        }
+    }
+}
+
+impl Default for Dial {
+    /// Creates the dial of the classic game, from 0 to 100 in whole units.
+    fn default() -> Self {
+        Self::new(DEFAULT_COUNTER_MAX, Precision::Normal)
+    }
+}
+
+impl FromStr for Precision {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "normal" => Ok(Precision::Normal),
+            "fine" => Ok(Precision::Fine),
+            other => Err(GameError::ConfigError(format!("Unknown precision: {}", other))),
+        }
+    }
+}
+
+/// The values the counter runs through, counted in ticks.
+///
+/// Counter values, targets, and differences are all measured in ticks:
+/// whole units in normal precision, tenths of a unit in fine precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    /// Highest counter value, in whole units
+    max_units: u32,
+    /// How finely the counter counts
+    precision: Precision,
+}
+
+impl Dial {
+    /// Creates a dial running from 0 to the given value.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_units` - Highest counter value, in whole units
+    /// * `precision` - How finely the counter counts
+    ///
+    /// # Returns
+    ///
+    /// A new Dial
+    pub fn new(max_units: u32, precision: Precision) -> Self {
+        Self { max_units, precision }
+    }
+    
+    /// Returns the highest counter value, in ticks.
+    pub fn max(self) -> u32 {
+        self.max_units * self.precision.ticks_per_unit()
+    }
+    
+    /// Converts whole units to ticks.
+    ///
+    /// # Arguments
+    ///
+    /// * `units` - A value in whole units
+    ///
+    /// # Returns
+    ///
+    /// The same value in ticks
+    pub fn ticks(self, units: u32) -> u32 {
+        units * self.precision.ticks_per_unit()
+    }
+    
+    /// Converts ticks to a percentage of the dial, rounding down.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticks` - A value in ticks
+    ///
+    /// # Returns
+    ///
+    /// The position of the value on the dial, from 0 to 100
+    pub fn percent(self, ticks: u32) -> u32 {
+        ticks.min(self.max()) * 100 / self.max().max(1)
+    }
+    
+    /// Moves a value around the dial, wrapping past either end.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A value in ticks
+    /// * `ticks` - How many ticks to move it, down if negative
+    ///
+    /// # Returns
+    ///
+    /// The moved value, in ticks
+    pub fn offset(self, value: u32, ticks: i32) -> u32 {
+        let lap = i64::from(self.max()) + 1;
+        (i64::from(value) + i64::from(ticks)).rem_euclid(lap) as u32
+    }
+    
+    /// Winds a stopped counter back by a number of ticks, across the laps it made.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Where the counter stopped, in ticks
+    /// * `miss` - How many times the counter wrapped around before it stopped
+    /// * `ticks` - How many ticks to go back
+    ///
+    /// # Returns
+    ///
+    /// The value and miss count the counter had that many ticks earlier, never before its start
+    pub fn rewind(self, value: u32, miss: u32, ticks: u32) -> (u32, u32) {
+        let lap = self.max() + 1;
+        let position = (miss * lap + value).saturating_sub(ticks);
+        (position % lap, position / lap)
+    }
+    
+    /// Formats a value for display, with one decimal in fine precision.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticks` - A value in ticks
+    ///
+    /// # Returns
+    ///
+    /// The value in units, e.g. "42" or "42.3"
+    pub fn format(self, ticks: u32) -> String {
+        match self.precision {
+            Precision::Normal => ticks.to_string(),
+            Precision::Fine => format!("{}.{}", ticks / 10, ticks % 10),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dial() {
+        let normal = Dial::default();
+        assert_eq!(normal.max(), 100);
+        assert_eq!(normal.format(42), "42");
+        
+        let fine = Dial::new(100, "fine".parse().unwrap());
+        assert_eq!(fine.max(), 1000);
+        assert_eq!(fine.ticks(42), 420);
+        assert_eq!(fine.percent(423), 42);
+        assert_eq!(fine.format(423), "42.3");
+        assert_eq!(fine.format(7), "0.7");
+        assert!("coarse".parse::<Precision>().is_err());
+        
+        let clock = Dial::new(60, Precision::Normal);
+        assert_eq!(clock.max(), 60);
+        assert_eq!(clock.percent(30), 50);
+    }
+    
+    #[test]
+    fn test_dial_offset() {
+        let dial = Dial::new(10, Precision::Normal);
+        assert_eq!(dial.offset(4, 3), 7);
+        assert_eq!(dial.offset(4, -3), 1);
+        assert_eq!(dial.offset(9, 3), 1);
+        assert_eq!(dial.offset(1, -3), 9);
+        assert_eq!(dial.offset(5, 0), 5);
+    }
+    
+    #[test]
+    fn test_dial_rewind() {
+        let dial = Dial::new(10, Precision::Normal);
+        assert_eq!(dial.rewind(7, 0, 3), (4, 0));
+        assert_eq!(dial.rewind(1, 2, 3), (9, 1));
+        assert_eq!(dial.rewind(2, 0, 5), (0, 0));
+        assert_eq!(dial.rewind(5, 1, 0), (5, 1));
+    }
+}
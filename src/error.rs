@@ -1,10 +1,28 @@
 //! Error handling for the game.
 //! 
 //! This module defines custom error types used throughout the application.
+//!
+//! Each kind of error ends the program with its own exit code, so scripts
+//! running the game can tell what went wrong:
+//!
+//! | Code | Error |
+//! |------|-------|
+//! | 1    | `LogicError` |
+//! | 3    | `ConfigError` |
+//! | 4    | `ParseError` |
+//! | 5    | `SaveCorrupted` |
+//! | 6    | `IoError` |
+//! | 7    | `Desync` |
+//! | 8    | `IncompatibleVersion` |
+//! | 9    | `NetworkError` |
+//! | 130  | `Interrupted` |
+//!
+//! Code 2 is left to command line usage errors, which clap reports itself.
 
 use std::fmt;
 use std::error::Error;
 use std::io;
+use std::path::{Path, PathBuf};
 
 /// Represents errors that can occur within the game.
 #[derive(Debug)]
@@ -13,6 +31,39 @@ pub enum GameError {
     IoError(io::Error),
     /// An error related to game logic.
     LogicError(String),
+    /// A setting given on the command line or in the configuration file is not valid.
+    ConfigError(String),
+    /// A script or configuration file is not valid.
+    ParseError(String),
+    /// A file the game wrote itself, such as a save, profile or replay, cannot be read back.
+    SaveCorrupted {
+        /// The file that cannot be read back
+        path: PathBuf,
+        /// What is wrong with it
+        reason: String,
+    },
+    /// The user interrupted the game with Ctrl-C.
+    Interrupted,
+    /// Two runs of the same match stopped agreeing on the game state.
+    Desync {
+        /// Round at which the states differ
+        round: u32,
+        /// Hash of the state in the run compared against
+        expected: u64,
+        /// Hash of the state in this run
+        actual: u64,
+    },
+    /// A network connection failed or the other end went away.
+    NetworkError(String),
+    /// A file or a connection uses a version of its format this build cannot read.
+    IncompatibleVersion {
+        /// The format, such as "save" or "network protocol"
+        format: String,
+        /// The version found
+        found: String,
+        /// The version this build reads
+        supported: String,
+    },
 }
 
 impl fmt::Display for GameError {
@@ -20,6 +71,53 @@ impl fmt::Display for GameError {
         match self {
             GameError::IoError(err) => write!(f, "I/O error: {}", err),
             GameError::LogicError(msg) => write!(f, "Game logic error: {}", msg),
+            GameError::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
+            GameError::ParseError(msg) => write!(f, "Parse error: {}", msg),
+            GameError::SaveCorrupted { path, reason } => write!(f, "{} is corrupted: {}", path.display(), reason),
+            GameError::Interrupted => write!(f, "Interrupted by the user"),
+            GameError::Desync { round, expected, actual } => write!(f,
+                "Game state diverged at round {}: expected hash {:016x}, got {:016x}", round, expected, actual),
+            GameError::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            GameError::IncompatibleVersion { format, found, supported } => write!(f,
+                "Incompatible {} version {}: this build supports version {}", format, found, supported),
+        }
+    }
+}
+
+impl GameError {
+    /// Reports a parse error as coming from a file the game wrote itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The save, profile or replay file being read
+    ///
+    /// # Returns
+    ///
+    /// `GameError::SaveCorrupted` for a parse error, any other error unchanged
+    pub fn in_save(self, path: &Path) -> Self {
+        match self {
+            GameError::ParseError(reason) => GameError::SaveCorrupted { path: path.to_path_buf(), reason },
+            other => other,
+        }
+    }
+
+    /// Returns the exit code the program ends with because of this error.
+    ///
+    /// # Returns
+    ///
+    /// The exit code, as listed in the module documentation
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            GameError::LogicError(_) => 1,
+            GameError::ConfigError(_) => 3,
+            GameError::ParseError(_) => 4,
+            GameError::SaveCorrupted { .. } => 5,
+            GameError::IoError(_) => 6,
+            GameError::Desync { .. } => 7,
+            GameError::IncompatibleVersion { .. } => 8,
+            GameError::NetworkError(_) => 9,
+            // 128 + SIGINT, as shells report it
+            GameError::Interrupted => 130,
         }
     }
 }
@@ -39,6 +137,19 @@ impl From<io::Error> for GameError {
     }
 }
 
+impl From<serde_json::Error> for GameError {
+    fn from(err: serde_json::Error) -> Self {
+        GameError::ParseError(err.to_string())
+    }
+}
+
+#[cfg(feature = "native")]
+impl From<toml::de::Error> for GameError {
+    fn from(err: toml::de::Error) -> Self {
+        GameError::ParseError(err.to_string())
+    }
+}
+
 /// Shorthand Result type for the game.
 pub type GameResult<T> = Result<T, GameError>;
 
@@ -48,11 +159,25 @@ mod tests {
     
     #[test]
     fn test_game_error_display() {
-        let io_error = GameError::IoError(io::Error::new(io::ErrorKind::Other, "test io error"));
+        let io_error = GameError::IoError(io::Error::other("test io error"));
         let logic_error = GameError::LogicError("game state error".to_string());
         
         assert!(io_error.to_string().contains("I/O error"));
         assert!(logic_error.to_string().contains("Game logic error: game state error"));
+        assert_eq!(GameError::Interrupted.to_string(), "Interrupted by the user");
+        assert_eq!(GameError::ParseError("bad line".to_string()).to_string(), "Parse error: bad line");
+        assert_eq!(GameError::ConfigError("bad key".to_string()).to_string(), "Configuration error: bad key");
+        assert_eq!(GameError::NetworkError("Bob left".to_string()).to_string(), "Network error: Bob left");
+        assert_eq!(GameError::ParseError("bad value".to_string()).in_save(Path::new("save.json")).to_string(),
+                   "save.json is corrupted: bad value");
+        assert_eq!(GameError::Desync { round: 2, expected: 0xab, actual: 0xcd }.to_string(),
+                   "Game state diverged at round 2: expected hash 00000000000000ab, got 00000000000000cd");
+        let incompatible = GameError::IncompatibleVersion {
+            format: "save".to_string(),
+            found: "2.0.0".to_string(),
+            supported: "1.0.0".to_string(),
+        };
+        assert_eq!(incompatible.to_string(), "Incompatible save version 2.0.0: this build supports version 1.0.0");
     }
     
     #[test]
@@ -60,9 +185,47 @@ mod tests {
         let io_error = io::Error::new(io::ErrorKind::NotFound, "file not found");
         let game_error = GameError::from(io_error);
         
-        match game_error {
-            GameError::IoError(_) => assert!(true),
-            _ => assert!(false, "Expected IoError variant"),
-        }
+        assert!(matches!(game_error, GameError::IoError(_)), "Expected IoError variant");
+    }
+    
+    #[test]
+    fn test_in_save_keeps_other_errors() {
+        let io_error = GameError::IoError(io::Error::new(io::ErrorKind::NotFound, "file not found"));
+        assert!(matches!(io_error.in_save(Path::new("save.json")), GameError::IoError(_)));
+    }
+    
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = [
+            GameError::LogicError(String::new()),
+            GameError::ConfigError(String::new()),
+            GameError::ParseError(String::new()),
+            GameError::SaveCorrupted { path: PathBuf::new(), reason: String::new() },
+            GameError::IoError(io::Error::other("")),
+            GameError::Desync { round: 1, expected: 0, actual: 1 },
+            GameError::IncompatibleVersion { format: String::new(), found: String::new(), supported: String::new() },
+            GameError::NetworkError(String::new()),
+            GameError::Interrupted,
+        ];
+        let mut codes: Vec<u8> = errors.iter().map(GameError::exit_code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        
+        assert_eq!(codes.len(), errors.len());
+        // 0 is success and 2 is left to clap's usage errors
+        assert!(!codes.contains(&0) && !codes.contains(&2));
+    }
+    
+    #[test]
+    fn test_from_parser_errors() {
+        let json_error = serde_json::from_str::<u32>("{").unwrap_err();
+        assert!(matches!(GameError::from(json_error), GameError::ParseError(_)));
+    }
+    
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_from_toml_errors() {
+        let toml_error = toml::from_str::<toml::Table>("= 1").unwrap_err();
+        assert!(matches!(GameError::from(toml_error), GameError::ParseError(_)));
     }
 }
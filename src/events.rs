@@ -0,0 +1,435 @@
+//! Events module for the structured game event log.
+//!
+//! Everything that changes the course of a match is recorded as a `GameEvent`.
+//! Events are kept in memory for the end-of-game summary and, when a log file
+//! is configured, appended to it as one JSON object per line.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameResult;
+use crate::fairness::Verdict;
+use crate::player::{Attribute, Consolation};
+
+/// Something that happened during a match.
+///
+/// Players are identified by their index (0 or 1).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// A player wagered vitality on the round
+    Wager {
+        round: u32,
+        player: usize,
+        amount: u32,
+    },
+    /// A player started their turn
+    TurnStart {
+        round: u32,
+        player: usize,
+        name: String,
+        vitality: u32,
+        speed: u32,
+        strength: u32,
+        targets: Vec<u32>,
+    },
+    /// A player spent stamina to gamble on a target
+    Gamble {
+        round: u32,
+        player: usize,
+        target: u32,
+        stamina: u32,
+    },
+    /// A player stopped the counter
+    CounterStop {
+        round: u32,
+        player: usize,
+        target: u32,
+        value: u32,
+        miss: u32,
+        reaction_ms: u64,
+    },
+    /// A stop close to the target was a critical hit
+    Critical {
+        round: u32,
+        player: usize,
+        target: u32,
+    },
+    /// A target was scored
+    Score {
+        round: u32,
+        player: usize,
+        target: u32,
+        score: u32,
+    },
+    /// A player took their opponent's momentum with an exact hit
+    MomentumSteal {
+        round: u32,
+        player: usize,
+        from: usize,
+        amount: u32,
+    },
+    /// A player took a target their opponent had not played yet, from the shared pool
+    TargetSteal {
+        round: u32,
+        player: usize,
+        from: usize,
+        target: u32,
+    },
+    /// A player finished their turn
+    TurnEnd {
+        round: u32,
+        player: usize,
+        average: u32,
+    },
+    /// A player lost vitality at the end of a round
+    VitalityChange {
+        round: u32,
+        player: usize,
+        amount: u32,
+        vitality: u32,
+    },
+    /// A player recovered vitality, after enough exact hits or by winning the reaction game
+    Heal {
+        round: u32,
+        player: usize,
+        amount: u32,
+        vitality: u32,
+    },
+    /// A player won the reaction game played between rounds
+    ReactionGame {
+        round: u32,
+        winner: usize,
+        reaction_ms: Option<u64>,
+    },
+    /// The round winner applied a penalty to the loser
+    Penalty {
+        round: u32,
+        winner: usize,
+        loser: usize,
+        attribute: Attribute,
+        amount: u32,
+    },
+    /// The round loser picked a consolation bonus
+    Consolation {
+        round: u32,
+        player: usize,
+        bonus: Consolation,
+        amount: u32,
+    },
+    /// A round started, with the hash of the game state at that point
+    StateHash {
+        round: u32,
+        hash: u64,
+    },
+    /// A player stopped the counter faster than a human could
+    ImplausibleStop {
+        round: u32,
+        player: usize,
+        reaction_ms: u64,
+        verdict: Verdict,
+        voided: bool,
+    },
+    /// A player resigned the match at the start of their turn
+    Forfeit {
+        round: u32,
+        player: usize,
+    },
+    /// The match ended
+    GameOver {
+        round: u32,
+        winner: usize,
+    },
+}
+
+/// Statistics of one player over a match, computed from its events.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerSummary {
+    /// Name of the player
+    pub name: String,
+    /// Average score of each turn, in round order
+    pub round_scores: Vec<u32>,
+    /// Vitality at the start of each round, followed by the final vitality
+    pub vitality: Vec<u32>,
+    /// Highest scoring target as (target, score)
+    pub best: Option<(u32, u32)>,
+    /// Lowest scoring target as (target, score)
+    pub worst: Option<(u32, u32)>,
+    /// Number of times the counter was stopped exactly on the target
+    pub exact_hits: u32,
+    /// Round the player resigned in, if they did
+    pub resigned: Option<u32>,
+}
+
+/// Computes the statistics of both players from the events of a match.
+///
+/// # Arguments
+///
+/// * `events` - The recorded events, in order
+///
+/// # Returns
+///
+/// The summary of each player, indexed like the players
+pub fn summarize(events: &[GameEvent]) -> [PlayerSummary; 2] {
+    let mut summaries: [PlayerSummary; 2] = Default::default();
+    let mut current_vitality: [Option<u32>; 2] = [None, None];
+
+    for event in events {
+        match event {
+            GameEvent::TurnStart { player, name, vitality, .. } => {
+                summaries[*player].name = name.clone();
+                summaries[*player].vitality.push(*vitality);
+                current_vitality[*player] = Some(*vitality);
+            },
+            GameEvent::CounterStop { player, target, value, .. } if value == target => {
+                summaries[*player].exact_hits += 1;
+            },
+            GameEvent::Score { player, target, score, .. } => {
+                let summary = &mut summaries[*player];
+                if summary.best.is_none_or(|(_, best)| *score > best) {
+                    summary.best = Some((*target, *score));
+                }
+                if summary.worst.is_none_or(|(_, worst)| *score < worst) {
+                    summary.worst = Some((*target, *score));
+                }
+            },
+            GameEvent::TurnEnd { player, average, .. } => summaries[*player].round_scores.push(*average),
+            GameEvent::VitalityChange { player, vitality, .. } | GameEvent::Heal { player, vitality, .. } => {
+                current_vitality[*player] = Some(*vitality);
+            },
+            GameEvent::Forfeit { round, player } => summaries[*player].resigned = Some(*round),
+            _ => {},
+        }
+    }
+
+    for (summary, vitality) in summaries.iter_mut().zip(current_vitality) {
+        summary.vitality.extend(vitality);
+    }
+
+    summaries
+}
+
+/// A log line: an event with the time it was recorded.
+#[derive(Serialize)]
+struct LogRecord<'a> {
+    /// RFC 3339 timestamp
+    timestamp: String,
+    /// The recorded event
+    #[serde(flatten)]
+    event: &'a GameEvent,
+}
+
+/// Records the events of a match, optionally appending them to a file.
+pub struct EventLog {
+    /// Every event recorded so far
+    events: Vec<GameEvent>,
+    /// File the events are appended to, if any
+    path: Option<PathBuf>,
+    /// Writer on the log file, opened on the first event
+    writer: Option<BufWriter<File>>,
+    /// Channels a copy of each event is sent to
+    relays: Vec<Sender<GameEvent>>,
+}
+
+impl EventLog {
+    /// Creates a new event log.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Optional file the events are appended to
+    ///
+    /// # Returns
+    ///
+    /// A new, empty EventLog
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            events: Vec::new(),
+            path,
+            writer: None,
+            relays: Vec::new(),
+        }
+    }
+
+    /// Sends a copy of every event recorded from now on to a channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `relay` - The channel the events are sent to, besides the ones already relayed to;
+    ///   events are dropped once its receiver is gone
+    pub fn relay_to(&mut self, relay: Sender<GameEvent>) {
+        self.relays.push(relay);
+    }
+
+    /// Records an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to record
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the event could be written to the log file
+    pub fn record(&mut self, event: GameEvent) -> GameResult<()> {
+        if let Some(path) = &self.path {
+            if self.writer.is_none() {
+                let file = OpenOptions::new().create(true).append(true).open(path)?;
+                self.writer = Some(BufWriter::new(file));
+            }
+            if let Some(writer) = self.writer.as_mut() {
+                let record = LogRecord {
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                    event: &event,
+                };
+                serde_json::to_writer(&mut *writer, &record).map_err(io::Error::from)?;
+                writeln!(writer)?;
+                // Flush every event so the log is complete even if the game is killed
+                writer.flush()?;
+            }
+        }
+
+        for relay in &self.relays {
+            // Nobody listening any more is no reason to stop the match
+            let _ = relay.send(event.clone());
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    /// Returns every event recorded so far.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = GameEvent::Penalty {
+            round: 2,
+            winner: 0,
+            loser: 1,
+            attribute: Attribute::Speed,
+            amount: 5,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+
+        assert!(json.contains("\"event\":\"penalty\""));
+        assert!(json.contains("\"attribute\":\"speed\""));
+        assert_eq!(serde_json::from_str::<GameEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn test_record_in_memory() {
+        let mut log = EventLog::new(None);
+        log.record(GameEvent::GameOver { round: 3, winner: 1 }).unwrap();
+
+        assert_eq!(log.events(), &[GameEvent::GameOver { round: 3, winner: 1 }]);
+    }
+
+    #[test]
+    fn test_record_relays_events() {
+        let (relay, relayed) = std::sync::mpsc::channel();
+        let mut log = EventLog::new(None);
+        log.record(GameEvent::TurnEnd { round: 1, player: 0, average: 70 }).unwrap();
+        log.relay_to(relay);
+        log.record(GameEvent::GameOver { round: 1, winner: 0 }).unwrap();
+        drop(relayed);
+        // A relay nobody listens to does not fail the match
+        log.record(GameEvent::GameOver { round: 2, winner: 1 }).unwrap();
+
+        assert_eq!(log.events().len(), 3);
+    }
+
+    #[test]
+    fn test_relayed_events_arrive_in_order() {
+        let (relay, relayed) = std::sync::mpsc::channel();
+        let mut log = EventLog::new(None);
+        log.relay_to(relay);
+        log.record(GameEvent::TurnEnd { round: 1, player: 0, average: 70 }).unwrap();
+        log.record(GameEvent::GameOver { round: 1, winner: 0 }).unwrap();
+
+        assert_eq!(relayed.try_iter().collect::<Vec<_>>(), log.events());
+    }
+
+    #[test]
+    fn test_events_are_relayed_to_every_channel() {
+        let (first, first_relayed) = std::sync::mpsc::channel();
+        let (second, second_relayed) = std::sync::mpsc::channel();
+        let mut log = EventLog::new(None);
+        log.relay_to(first);
+        log.relay_to(second);
+        log.record(GameEvent::GameOver { round: 1, winner: 0 }).unwrap();
+
+        assert_eq!(first_relayed.try_iter().count(), 1);
+        assert_eq!(second_relayed.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let turn_start = |player: usize, vitality: u32| GameEvent::TurnStart {
+            round: 1,
+            player,
+            name: format!("P{}", player + 1),
+            vitality,
+            speed: 50,
+            strength: 50,
+            targets: vec![10, 20],
+        };
+        let events = vec![
+            turn_start(0, 100),
+            GameEvent::CounterStop { round: 1, player: 0, target: 10, value: 10, miss: 0, reaction_ms: 900 },
+            GameEvent::Score { round: 1, player: 0, target: 10, score: 150 },
+            GameEvent::CounterStop { round: 1, player: 0, target: 20, value: 35, miss: 1, reaction_ms: 4000 },
+            GameEvent::Score { round: 1, player: 0, target: 20, score: 25 },
+            GameEvent::TurnEnd { round: 1, player: 0, average: 87 },
+            turn_start(1, 100),
+            GameEvent::TurnEnd { round: 1, player: 1, average: 40 },
+            GameEvent::VitalityChange { round: 1, player: 1, amount: 47, vitality: 53 },
+            turn_start(0, 100),
+            GameEvent::Forfeit { round: 2, player: 0 },
+        ];
+
+        let [p1, p2] = summarize(&events);
+
+        assert_eq!(p1.name, "P1");
+        assert_eq!(p1.round_scores, vec![87]);
+        assert_eq!(p1.vitality, vec![100, 100, 100]);
+        assert_eq!(p1.resigned, Some(2));
+        assert_eq!(p1.best, Some((10, 150)));
+        assert_eq!(p1.worst, Some((20, 25)));
+        assert_eq!(p1.exact_hits, 1);
+        assert_eq!(p2.vitality, vec![100, 53]);
+        assert_eq!(p2.best, None);
+        assert_eq!(p2.resigned, None);
+    }
+
+    #[test]
+    fn test_record_to_file() {
+        let path = std::env::temp_dir().join(format!("rust_game_events_{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut log = EventLog::new(Some(path.clone()));
+        log.record(GameEvent::TurnEnd { round: 1, player: 0, average: 70 }).unwrap();
+        log.record(GameEvent::GameOver { round: 1, winner: 0 }).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let lines: Vec<&str> = contents.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\"timestamp\""));
+        assert!(lines[0].contains("\"event\":\"turn_end\""));
+        assert_eq!(
+            serde_json::from_str::<GameEvent>(lines[1]).unwrap(),
+            GameEvent::GameOver { round: 1, winner: 0 }
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Export module for writing match results to a file.
+//!
+//! The per-target results are rebuilt from the event log and written as a
+//! table, in CSV or JSON depending on the extension of the output file.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::{GameError, GameResult};
+use crate::events::GameEvent;
+
+/// The file formats results can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// A JSON array of objects
+    Json,
+}
+
+impl ExportFormat {
+    /// Infers the export format from the extension of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to export to
+    ///
+    /// # Returns
+    ///
+    /// Result containing the format, or an error if the extension is not supported
+    pub fn from_path(path: &Path) -> GameResult<Self> {
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(GameError::ConfigError(format!(
+                "Unknown export format for {}, use a .csv or .json file", path.display()))),
+        }
+    }
+}
+
+/// The result of a single target.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TargetResult {
+    /// Round the target was played in
+    pub round: u32,
+    /// Name of the player
+    pub player: String,
+    /// The target number
+    pub target: u32,
+    /// Value the counter was stopped at
+    pub value: u32,
+    /// Number of times the counter wrapped around
+    pub miss: u32,
+    /// Final score for the target
+    pub score: u32,
+    /// Time between the counter starting and the player stopping it
+    pub reaction_ms: u64,
+}
+
+/// Rebuilds the per-target results from the events of a match.
+///
+/// # Arguments
+///
+/// * `events` - The recorded events, in order
+///
+/// # Returns
+///
+/// One result per scored target
+pub fn target_results(events: &[GameEvent]) -> Vec<TargetResult> {
+    let mut names = [String::new(), String::new()];
+    let mut stop = None;
+    let mut results = Vec::new();
+
+    for event in events {
+        match event {
+            GameEvent::TurnStart { player, name, .. } => names[*player] = name.clone(),
+            GameEvent::CounterStop { value, miss, reaction_ms, .. } => {
+                stop = Some((*value, *miss, *reaction_ms));
+            },
+            GameEvent::Score { round, player, target, score } => {
+                if let Some((value, miss, reaction_ms)) = stop.take() {
+                    results.push(TargetResult {
+                        round: *round,
+                        player: names[*player].clone(),
+                        target: *target,
+                        value,
+                        miss,
+                        score: *score,
+                        reaction_ms,
+                    });
+                }
+            },
+            _ => {},
+        }
+    }
+
+    results
+}
+
+/// Writes the per-target results of a match to a file.
+///
+/// # Arguments
+///
+/// * `path` - The file to write, its extension selects the format
+/// * `events` - The recorded events of the match
+///
+/// # Returns
+///
+/// Result indicating whether the results were written
+pub fn export_results(path: &Path, events: &[GameEvent]) -> GameResult<()> {
+    let format = ExportFormat::from_path(path)?;
+    let results = target_results(events);
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    match format {
+        ExportFormat::Csv => {
+            let mut csv = csv::Writer::from_writer(&mut writer);
+            for result in &results {
+                csv.serialize(result).map_err(io::Error::from)?;
+            }
+            csv.flush()?;
+        },
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &results).map_err(io::Error::from)?;
+            writeln!(writer)?;
+        },
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn sample_events() -> Vec<GameEvent> {
+        vec![
+            GameEvent::TurnStart {
+                round: 1,
+                player: 0,
+                name: "Alice".to_string(),
+                vitality: 50,
+                speed: 50,
+                strength: 50,
+                targets: vec![40],
+            },
+            GameEvent::CounterStop { round: 1, player: 0, target: 40, value: 42, miss: 0, reaction_ms: 2150 },
+            GameEvent::Score { round: 1, player: 0, target: 40, score: 95 },
+            GameEvent::TurnEnd { round: 1, player: 0, average: 95 },
+        ]
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(ExportFormat::from_path(Path::new("results.csv")).unwrap(), ExportFormat::Csv);
+        assert_eq!(ExportFormat::from_path(Path::new("out/Results.JSON")).unwrap(), ExportFormat::Json);
+        assert!(ExportFormat::from_path(Path::new("results.txt")).is_err());
+        assert!(ExportFormat::from_path(Path::new("results")).is_err());
+    }
+
+    #[test]
+    fn test_target_results() {
+        let results = target_results(&sample_events());
+
+        assert_eq!(results, vec![TargetResult {
+            round: 1,
+            player: "Alice".to_string(),
+            target: 40,
+            value: 42,
+            miss: 0,
+            score: 95,
+            reaction_ms: 2150,
+        }]);
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let path = std::env::temp_dir().join(format!("rust_game_export_{}.csv", std::process::id()));
+
+        export_results(&path, &sample_events()).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(contents, "round,player,target,value,miss,score,reaction_ms\n1,Alice,40,42,0,95,2150\n");
+    }
+
+    #[test]
+    fn test_export_json() {
+        let path = std::env::temp_dir().join(format!("rust_game_export_{}.json", std::process::id()));
+
+        export_results(&path, &sample_events()).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let rows: serde_json::Value = serde_json::from_str(&contents).unwrap();
+
+        assert_eq!(rows[0]["player"], "Alice");
+        assert_eq!(rows[0]["reaction_ms"], 2150);
+    }
+}
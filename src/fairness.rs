@@ -0,0 +1,95 @@
+//! Fairness module for spotting stops no human hand could make.
+//!
+//! A person needs some time to see the counter and press a key, so a stop
+//! made faster than `IMPLAUSIBLE_REACTION_MS` is flagged. One fast stop may be
+//! luck or a key held down, but several in a row point at a script or a
+//! macro: with `--void-implausible`, the targets stopped during such a streak
+//! score nothing.
+
+use serde::{Deserialize, Serialize};
+
+/// Reactions faster than this are physically implausible, in milliseconds.
+pub const IMPLAUSIBLE_REACTION_MS: u64 = 40;
+
+/// Implausible stops in a row after which a player is suspected of cheating.
+pub const SUSPICIOUS_STREAK: u32 = 3;
+
+/// How plausible a stop is, given the stops before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// The reaction time is within human reach
+    Plausible,
+    /// The reaction time is too fast, but it is the first few in a row
+    Implausible,
+    /// The reaction time is too fast, and so were the previous ones
+    Suspicious,
+}
+
+/// Keeps track of each player's implausible stops over a match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FairnessMonitor {
+    /// Implausible stops in a row of each player
+    streaks: [u32; 2],
+}
+
+impl FairnessMonitor {
+    /// Judges a stop and remembers it for the player's next ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player who stopped the counter
+    /// * `reaction_ms` - Time between the counter starting and the stop
+    ///
+    /// # Returns
+    ///
+    /// How plausible the stop is
+    pub fn check(&mut self, player_idx: usize, reaction_ms: u64) -> Verdict {
+        let streak = &mut self.streaks[player_idx];
+        if reaction_ms >= IMPLAUSIBLE_REACTION_MS {
+            *streak = 0;
+            return Verdict::Plausible;
+        }
+        *streak += 1;
+        if *streak >= SUSPICIOUS_STREAK { Verdict::Suspicious } else { Verdict::Implausible }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_stops_are_plausible() {
+        let mut monitor = FairnessMonitor::default();
+        assert_eq!(monitor.check(0, 250), Verdict::Plausible);
+        assert_eq!(monitor.check(0, IMPLAUSIBLE_REACTION_MS), Verdict::Plausible);
+    }
+
+    #[test]
+    fn test_streak_of_fast_stops_is_suspicious() {
+        let mut monitor = FairnessMonitor::default();
+        assert_eq!(monitor.check(0, 10), Verdict::Implausible);
+        assert_eq!(monitor.check(0, 12), Verdict::Implausible);
+        assert_eq!(monitor.check(0, 8), Verdict::Suspicious);
+        assert_eq!(monitor.check(0, 9), Verdict::Suspicious);
+    }
+
+    #[test]
+    fn test_plausible_stop_ends_the_streak() {
+        let mut monitor = FairnessMonitor::default();
+        monitor.check(0, 10);
+        monitor.check(0, 10);
+        assert_eq!(monitor.check(0, 300), Verdict::Plausible);
+        assert_eq!(monitor.check(0, 10), Verdict::Implausible);
+    }
+
+    #[test]
+    fn test_players_have_their_own_streak() {
+        let mut monitor = FairnessMonitor::default();
+        monitor.check(0, 10);
+        monitor.check(0, 10);
+        assert_eq!(monitor.check(1, 10), Verdict::Implausible);
+        assert_eq!(monitor.check(0, 10), Verdict::Suspicious);
+    }
+}
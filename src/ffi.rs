@@ -0,0 +1,273 @@
+//! FFI module exposing the scoring and the rules of a match to C, with the `ffi` feature.
+//!
+//! Tools written in other languages link against the `cdylib` to check the
+//! scores a client reports, or to simulate matches with random stops, with
+//! the same code as the terminal game and `simulation`. The declarations are in
+//! `include/rust_game.h`, generated with:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/rust_game.h
+//! ```
+//!
+//! A game is created with `rg_game_new`, played with `rg_game_step` and
+//! must be released with `rg_game_free`.
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::config::GameConfig;
+use crate::scoring;
+use crate::simulation::{self, MAX_ROUNDS};
+use crate::state::GameState;
+
+/// What `rg_game_step` did.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RgStep {
+    /// It played a round and the match goes on
+    Played = 0,
+    /// It played the last round of the match
+    Over = 1,
+    /// It was given no game, or a match already over
+    Invalid = -1,
+}
+
+/// A match simulated with random stops, opaque to C.
+pub struct RgGame {
+    /// The progress of the match
+    state: GameState,
+    /// The settings of the match, the rules being those of the classic game
+    config: GameConfig,
+    /// The random number generator for the targets and stops
+    rng: StdRng,
+}
+
+impl RgGame {
+    /// Plays a round, the way `simulation::simulate_match` does.
+    ///
+    /// # Returns
+    ///
+    /// `RgStep::Played`, `RgStep::Over`, or `RgStep::Invalid` if the match was already over
+    fn step(&mut self) -> RgStep {
+        if self.state.game_over {
+            return RgStep::Invalid;
+        }
+        let state = simulation::simulate_round(&self.config, &self.state, &mut self.rng);
+        if state.in_progress() && state.round <= MAX_ROUNDS {
+            self.state = state;
+            return RgStep::Played;
+        }
+        self.state = simulation::finish_match(state);
+        // The round the simulation moved on to is never played
+        self.state.round -= 1;
+        RgStep::Over
+    }
+}
+
+/// Calculates the score of a single stop on the default counter.
+///
+/// # Arguments
+///
+/// * `target` - The target number to match
+/// * `counter_value` - The counter value when stopped
+/// * `strength` - The player's strength attribute
+/// * `miss` - The number of times the counter looped back to zero
+///
+/// # Returns
+///
+/// The score, the same as `scoring::calculate_score`
+#[unsafe(no_mangle)]
+pub extern "C" fn rg_calculate_score(target: u32, counter_value: u32, strength: u32, miss: u32) -> u32 {
+    // The scoring saturates instead of overflowing, so no input can panic across the boundary
+    scoring::calculate_score(target, counter_value, strength, miss)
+}
+
+/// Creates a match between two players with the same attributes.
+///
+/// # Arguments
+///
+/// * `vitality` - The starting vitality of both players
+/// * `speed` - The starting speed of both players
+/// * `strength` - The starting strength of both players
+/// * `stamina` - The starting stamina of both players
+/// * `target_count` - The number of targets of each turn
+/// * `seed` - The seed of the targets and stops, the same seed plays the same match
+///
+/// # Returns
+///
+/// A new game at round 1, to release with `rg_game_free`
+#[unsafe(no_mangle)]
+pub extern "C" fn rg_game_new(vitality: u32, speed: u32, strength: u32, stamina: u32, target_count: u32, seed: u64) -> *mut RgGame {
+    let config = GameConfig {
+        vitality,
+        speed,
+        strength,
+        stamina,
+        target_count: target_count.max(1) as usize,
+        ..GameConfig::default()
+    };
+    let game = RgGame {
+        state: simulation::new_match(&config),
+        config,
+        rng: StdRng::seed_from_u64(seed),
+    };
+    Box::into_raw(Box::new(game))
+}
+
+/// Plays one round of a match, with a random stop for each target.
+///
+/// # Arguments
+///
+/// * `game` - The game, from `rg_game_new`
+///
+/// # Returns
+///
+/// `RgStep_Played` if the match goes on, `RgStep_Over` after its last round,
+/// or `RgStep_Invalid` for a null or finished game
+///
+/// # Safety
+///
+/// `game` must be null or a game from `rg_game_new` not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rg_game_step(game: *mut RgGame) -> RgStep {
+    // SAFETY: the caller passes null or a live game from `rg_game_new`
+    match unsafe { game.as_mut() } {
+        Some(game) => game.step(),
+        None => RgStep::Invalid,
+    }
+}
+
+/// Returns the current round of a match, 0 for a null game.
+///
+/// # Safety
+///
+/// `game` must be null or a game from `rg_game_new` not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rg_game_round(game: *const RgGame) -> u32 {
+    // SAFETY: the caller passes null or a live game from `rg_game_new`
+    unsafe { game.as_ref() }.map_or(0, |game| game.state.round)
+}
+
+/// Returns a player's vitality, 0 for a null game or an unknown player.
+///
+/// # Safety
+///
+/// `game` must be null or a game from `rg_game_new` not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rg_game_vitality(game: *const RgGame, player: u32) -> u32 {
+    // SAFETY: the caller passes null or a live game from `rg_game_new`
+    unsafe { game.as_ref() }
+        .and_then(|game| game.state.players.get(player as usize))
+        .map_or(0, |player| player.vitality())
+}
+
+/// Returns the index of the winner, or -1 while the match goes on, after a draw or for a null game.
+///
+/// # Safety
+///
+/// `game` must be null or a game from `rg_game_new` not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rg_game_winner(game: *const RgGame) -> i32 {
+    // SAFETY: the caller passes null or a live game from `rg_game_new`
+    unsafe { game.as_ref() }
+        .and_then(|game| game.state.winner_idx)
+        .map_or(-1, |winner| winner as i32)
+}
+
+/// Returns the hash of the state, the same as the terminal game and the server compute.
+///
+/// # Safety
+///
+/// `game` must be null or a game from `rg_game_new` not yet released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rg_game_hash(game: *const RgGame) -> u64 {
+    // SAFETY: the caller passes null or a live game from `rg_game_new`
+    unsafe { game.as_ref() }.map_or(0, |game| game.state.hash())
+}
+
+/// Releases a game.
+///
+/// # Safety
+///
+/// `game` must be null or a game from `rg_game_new` not yet released, and
+/// is dangling afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rg_game_free(game: *mut RgGame) {
+    if !game.is_null() {
+        // SAFETY: the game came from `Box::into_raw` in `rg_game_new` and is released once
+        drop(unsafe { Box::from_raw(game) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    /// Plays a whole match and returns its winner and hash.
+    fn play(seed: u64) -> (i32, u64, u32) {
+        let game = rg_game_new(30, 50, 50, 3, 3, seed);
+        unsafe {
+            while rg_game_step(game) == RgStep::Played {}
+            let result = (rg_game_winner(game), rg_game_hash(game), rg_game_round(game));
+            assert_eq!(rg_game_step(game), RgStep::Invalid);
+            rg_game_free(game);
+            result
+        }
+    }
+
+    #[test]
+    fn test_scores_like_the_terminal_game() {
+        assert_eq!(rg_calculate_score(40, 42, 50, 0), scoring::calculate_score(40, 42, 50, 0));
+        assert_eq!(rg_calculate_score(40, 40, 50, 1), scoring::calculate_score(40, 40, 50, 1));
+    }
+
+    #[test]
+    fn test_same_seed_plays_the_same_match() {
+        let (winner, hash, rounds) = play(7);
+        assert_eq!(play(7), (winner, hash, rounds));
+        assert!(winner == 0 || winner == 1);
+        assert!(rounds >= 1);
+    }
+
+    #[test]
+    fn test_match_ends_when_a_player_runs_out_of_vitality() {
+        let game = rg_game_new(30, 50, 50, 3, 3, 11);
+        unsafe {
+            while rg_game_step(game) == RgStep::Played {}
+            let winner = rg_game_winner(game) as u32;
+            assert!(rg_game_vitality(game, winner) > 0);
+            assert_eq!(rg_game_vitality(game, 1 - winner), 0);
+            assert_eq!(rg_game_vitality(game, 2), 0);
+            rg_game_free(game);
+        }
+    }
+
+    #[test]
+    fn test_match_nobody_can_lose_is_a_draw() {
+        let game = rg_game_new(30, 50, 50, 3, 3, 1);
+        unsafe {
+            // Without targets every turn scores 0, so no round is ever won
+            (*game).config.target_count = 0;
+            while rg_game_step(game) == RgStep::Played {}
+            assert_eq!(rg_game_winner(game), -1);
+            assert_eq!(rg_game_round(game), MAX_ROUNDS);
+            rg_game_free(game);
+        }
+    }
+
+    #[test]
+    fn test_extreme_inputs_do_not_overflow() {
+        assert!(rg_calculate_score(40, 40, u32::MAX, 0) > 0);
+        assert_eq!(rg_calculate_score(40, 40, u32::MAX, u32::MAX), 1);
+    }
+
+    #[test]
+    fn test_null_game_is_refused() {
+        unsafe {
+            assert_eq!(rg_game_step(ptr::null_mut()), RgStep::Invalid);
+            assert_eq!(rg_game_winner(ptr::null_mut()), -1);
+            assert_eq!(rg_game_round(ptr::null_mut()), 0);
+            rg_game_free(ptr::null_mut());
+        }
+    }
+}
@@ -3,24 +3,169 @@
 //! This module contains the main game logic including turn handling,
 //! score calculation, and player management.
 
-use crate::player::Player;
-use crate::counter::Counter;
-use crate::scoring;
-use crate::ui;
+use crate::audio::{Audio, Sound};
+use crate::bot::{BOT_TIMEOUT, BotProcess, BotRequest};
+use crate::calibration;
+use crate::campaign::Opponent;
+use crate::config::GameConfig;
+use crate::dial::Dial;
+use crate::player::{Attribute, Consolation, Player};
+use crate::counter::{self, Counter, CounterEvents, DifficultyLevel, DuelCounter, TargetTracker, TurnEngine};
+use crate::events::{self, EventLog, GameEvent};
+use crate::export;
+use crate::fairness::{self, FairnessMonitor, Verdict};
+use crate::help::{self, HELP_COMMAND, HELP_KEY};
+use crate::history::{RoundRecord, Snapshot, TIMELINE_COMMAND};
+use crate::observer::{self, Observer};
+use crate::persistence;
+use crate::plugins::GamePlugin;
+use crate::pool::{STEAL_BONUS_PERCENT, TargetPool};
+use crate::practice::{self, PracticeMode};
+use crate::presence;
+use crate::replay::Replay;
+pub use crate::rules::PENALTY_AMOUNT;
+use crate::rules::{DRAFT_ITEMS, DraftItem, RoundModifier, SpeedFloor, TurnOrder, WinCondition};
+use crate::runtime;
+use crate::scoring::{self, RiskTier, ScoreBreakdown};
+use crate::state::{GameState, RoundOutcome, TurnOutcome};
+use crate::survival;
+use crate::ui::{self, CounterFrame, Rematch};
+use crate::ui::render::Renderer;
+use crate::ui::source::{InputSource, TerminalInput};
 use crate::error::{GameError, GameResult};
-use rand::Rng;
-use std::io::{self, Write};
+use crate::i18n::{self, Msg, tr};
+use crate::minigames::{self, REACTION_STEAL, ReactionResult};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::cmp::Ordering;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+/// The bonuses offered to the loser of a round, and how much each one gives.
+const CONSOLATIONS: [(Consolation, u32); 3] = [
+    (Consolation::Speed, 3),
+    (Consolation::Strength, 3),
+    (Consolation::Vitality, 5),
+];
+
+/// What a player types at the start of their turn to resign the match.
+pub const RESIGN_COMMAND: &str = "r";
+
+/// What a player types at the start of their turn to pass it.
+pub const PASS_COMMAND: &str = "p";
+
+/// What a player chose to do at the start of their turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TurnStart {
+    /// Play the turn
+    Play,
+    /// Pay vitality to skip the turn
+    Pass,
+    /// Give the match up
+    Resign,
+}
+
+/// The keys a player presses to play a target safe, normal or risky, in the order of `RiskTier::ALL`.
+pub const RISK_KEYS: [char; 3] = ['s', 'n', 'r'];
+
+/// Stamina spent on a double or nothing gamble.
+const GAMBLE_COST: u32 = 1;
+
+/// Time left to the counter display to stop drawing before the help is shown over a paused counter.
+const OVERLAY_DELAY: Duration = Duration::from_millis(50);
+
+
+/// Where the counter was stopped for one target.
+#[derive(Debug, Clone, Copy)]
+struct TargetStop {
+    /// The target, in ticks, when the counter was stopped
+    target: u32,
+    /// The counter value, in ticks
+    value: u32,
+    /// Number of times the counter wrapped around
+    miss: u32,
+    /// Time between the counter starting and the player stopping it
+    reaction_ms: u64,
+    /// Whether the player gambled double or nothing on the target
+    gamble: bool,
+    /// Ticks the player's handicap moved the counter value by
+    handicap: i32,
+    /// How much the player risked on the target
+    risk: RiskTier,
+}
+
+/// How a stopped target went, for the recap of the round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TargetResult {
+    /// The target, in ticks, when the counter was stopped
+    target: u32,
+    /// The counter value it was scored on, in ticks
+    value: u32,
+    /// The score of the target
+    score: u32,
+}
+
+/// How a player's turn went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TurnResult {
+    /// Whether the turn was played, and for what average
+    outcome: TurnOutcome,
+    /// Number of targets hit exactly
+    exact_hits: u32,
+    /// Each target played, in order
+    targets: Vec<TargetResult>,
+}
+
+impl TurnResult {
+    /// Returns the result of a turn passed or given up, without any target played.
+    fn passed() -> Self {
+        Self { outcome: TurnOutcome::Passed, exact_hits: 0, targets: Vec::new() }
+    }
+}
 
 /// Represents the game state.
 pub struct Game {
-    /// The two players
-    players: [Player; 2],
-    /// Number of targets per turn
-    target_count: usize,
-    /// Flag indicating if the game is over
-    game_over: bool,
-    /// Index of the winner (if game is over)
-    winner_idx: Option<usize>,
+    /// The progress of the match
+    state: GameState,
+    /// The progress of the match at the start of the current round
+    round_start: GameState,
+    /// The settings the game was created with
+    config: GameConfig,
+    /// Sound effects player
+    audio: Audio,
+    /// Record of everything that happened during the match
+    events: EventLog,
+    /// Source of the targets and critical hits, seeded for reproducible matches
+    rng: StdRng,
+    /// Vitality each player wagered on the current round
+    wagers: [u32; 2],
+    /// Computer opponent playing as player 2, in the campaign
+    opponent: Option<Opponent>,
+    /// External program playing as player 2, started with its first target
+    bot: Option<BotProcess>,
+    /// Where the players' lines and key presses are read from
+    input: Box<dyn InputSource>,
+    /// Where the messages, prompts and counter are drawn
+    renderer: Box<dyn Renderer>,
+    /// Runs the counters, started with the first one
+    engine: Option<TurnEngine>,
+    /// Turn results held back until the end of the round, in blind mode
+    hidden_results: Vec<String>,
+    /// The player and the cells of each target scored this turn, for the table shown when it ends
+    target_rows: Vec<(usize, Vec<String>)>,
+    /// The weather of the current round
+    modifier: RoundModifier,
+    /// Each player's stops too fast to be human
+    fairness: FairnessMonitor,
+    /// The observers following the match
+    observers: Vec<Arc<dyn Observer>>,
+    /// The targets both players share this round, with a shared pool
+    pool: Option<TargetPool>,
+    /// Points each player earned this round by predicting their average
+    prediction_bonuses: [u32; 2],
+    /// Time the counters of the current turn were paused for the help, left out of its timers
+    help_pause: Duration,
 }
 
 impl Game {
@@ -28,95 +173,423 @@ impl Game {
     ///
     /// # Arguments
     ///
-    /// * `player1_name` - Name of the first player
-    /// * `player2_name` - Name of the second player
-    /// * `vitality` - Starting vitality for both players
-    /// * `speed` - Starting speed for both players
-    /// * `strength` - Starting strength for both players
-    /// * `target_count` - Number of targets per turn
+    /// * `config` - The player names, starting attributes, and game settings
+    ///
+    /// # Returns
+    ///
+    /// A new Game instance
+    pub fn new(config: GameConfig) -> Self {
+        let players = [
+            Player::new(config.player1_name.clone(), config.vitality, config.speed, config.strength, config.stamina),
+            Player::new(config.player2_name.clone(), config.vitality, config.speed, config.strength, config.stamina),
+        ];
+        
+        Self::from_state(config, GameState::new(players))
+    }
+    
+    /// Creates a game against a computer opponent, which plays as player 2.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The human player's name, starting attributes, and game settings
+    /// * `opponent` - The computer opponent
     ///
     /// # Returns
     ///
     /// A new Game instance
-    pub fn new(player1_name: String, player2_name: String, vitality: u32, speed: u32, strength: u32, target_count: usize) -> Self {
+    pub fn against(config: GameConfig, opponent: Opponent) -> Self {
         let players = [
-            Player::new(player1_name, vitality, speed, strength),
-            Player::new(player2_name, vitality, speed, strength),
+            Player::new(config.player1_name.clone(), config.vitality, config.speed, config.strength, config.stamina),
+            Player::new(opponent.name.to_string(), opponent.vitality, opponent.speed, opponent.strength, config.stamina),
         ];
         
+        Self { opponent: Some(opponent), ..Self::from_state(config, GameState::new(players)) }
+    }
+    
+    /// Creates a game resuming a saved match.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The game settings
+    /// * `state` - The progress of the match to resume
+    ///
+    /// # Returns
+    ///
+    /// A new Game instance continuing from the given state
+    pub fn from_state(mut config: GameConfig, mut state: GameState) -> Self {
+        // A resumed match keeps the bans and picks of its draft
+        if let Some(pool) = state.draft {
+            config.rules.pool = pool;
+        }
+        // The win condition decides the match after the other plugins
+        config.plugins.register(Box::new(config.rules.win_condition));
+        state.players = state.players.map(|player| player.with_bounds(config.rules.bounds));
+        let mut events = EventLog::new(config.log_file.clone());
+        if let Some(presence) = config.discord_app_id.as_deref().and_then(presence::publish) {
+            events.relay_to(presence);
+        }
         Self {
-            players,
-            target_count,
-            game_over: false,
-            winner_idx: None,
+            round_start: state.clone(),
+            state,
+            audio: Audio::new(config.sound),
+            events,
+            rng: match config.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_os_rng(),
+            },
+            wagers: [0, 0],
+            opponent: None,
+            bot: None,
+            input: Box::new(TerminalInput),
+            renderer: config.render_mode.renderer(),
+            engine: None,
+            hidden_results: Vec::new(),
+            target_rows: Vec::new(),
+            modifier: RoundModifier::Clear,
+            fairness: FairnessMonitor::default(),
+            observers: Vec::new(),
+            pool: None,
+            prediction_bonuses: [0, 0],
+            help_pause: Duration::ZERO,
+            config,
         }
     }
     
+    /// Reads the players' actions from another source than the keyboard.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' lines and key presses are read from
+    ///
+    /// # Returns
+    ///
+    /// The game, reading from the given source
+    pub fn with_input(self, input: impl InputSource + 'static) -> Self {
+        Self { input: Box::new(input), ..self }
+    }
+    
+    /// Draws the game output with another renderer than the one of the output mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `renderer` - Where the messages, prompts and counter are drawn
+    ///
+    /// # Returns
+    ///
+    /// The game, drawing with the given renderer
+    pub fn with_renderer(self, renderer: impl Renderer + 'static) -> Self {
+        Self { renderer: Box::new(renderer), ..self }
+    }
+    
+    /// Sends a copy of every event of the match to a channel as it is recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `relay` - The channel the events are sent to
+    ///
+    /// # Returns
+    ///
+    /// The game, relaying its events
+    pub fn with_event_relay(mut self, relay: Sender<GameEvent>) -> Self {
+        self.events.relay_to(relay);
+        self
+    }
+    
+    /// Registers a plugin, applied after the mutators and those already registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin` - The plugin hooking into the match
+    ///
+    /// # Returns
+    ///
+    /// The game, with the plugin in its registry
+    pub fn with_plugin(mut self, plugin: impl GamePlugin + 'static) -> Self {
+        self.config.plugins.register(Box::new(plugin));
+        self
+    }
+    
+    /// Registers an observer, told about the turns, counter ticks, scores and end of the match.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer following the match
+    ///
+    /// # Returns
+    ///
+    /// The game, reporting to the observer
+    pub fn with_observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+    
     /// Runs the game until one player's vitality reaches zero.
     ///
     /// # Returns
     ///
-    /// Result containing true if the player wants to play again, false otherwise
-    pub fn run(&mut self) -> bool {
-        if let Err(e) = self.run_game_loop() {
-            log::error!("Game error: {}", e);
-            return false;
+    /// Result containing what the players want to do next, the error that cut the
+    /// match short, or `GameError::Interrupted` if the user pressed Ctrl-C
+    pub fn run(&mut self) -> GameResult<Rematch> {
+        self.play_match()?;
+        
+        match ui::rematch_menu(&mut *self.renderer, &mut *self.input) {
+            Err(GameError::Interrupted) => Err(GameError::Interrupted),
+            Err(e) => {
+                log::error!("{}", tr!(Msg::ReadError, e));
+                Ok(Rematch::Quit)
+            },
+            choice => choice,
+        }
+    }
+    
+    /// Returns the state a rematch starts from when the players keep their attributes.
+    ///
+    /// # Returns
+    ///
+    /// A new match between the same players, with their speed, strength and
+    /// stamina as they are now and their vitality restored
+    pub fn rematch_state(&self) -> GameState {
+        let players = self.state.players.clone().map(|mut player| {
+            player.increase_vitality(player.max_vitality());
+            player.take_momentum();
+            player
+        });
+        GameState::new(players)
+    }
+    
+    /// Plays the match until one player's vitality reaches zero, then exports its results.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the index of the winner, the error that cut the match
+    /// short, or `GameError::Interrupted` if the user pressed Ctrl-C
+    pub fn play_match(&mut self) -> GameResult<usize> {
+        match self.run_game_loop() {
+            Ok(winner_idx) => {
+                self.export_results();
+                Ok(winner_idx)
+            },
+            Err(GameError::Interrupted) => {
+                self.handle_interrupt();
+                Err(GameError::Interrupted)
+            },
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Returns the progress of the match at the start of the current round.
+    ///
+    /// # Returns
+    ///
+    /// The state a match cut short during this round can be resumed from
+    pub fn round_start(&self) -> &GameState {
+        &self.round_start
+    }
+    
+    /// Prints a summary of the interrupted match and offers to save it.
+    fn handle_interrupt(&mut self) {
+        let _ = self.renderer.new_line();
+        self.renderer.info_wrapped(&tr!(Msg::MatchInterrupted, self.state.round));
+        for player in &self.state.players {
+            self.renderer.info_wrapped(&tr!(Msg::PlayerStatus, player.name(),
+                       ui::render_hp_bar(player.vitality(), player.max_vitality()), player.speed(), player.strength()));
         }
         
-        // Ask if player wants to play again
-        log::info!("Start a new game? [Y/N]");
-        print!("> ");
-        if let Err(e) = io::stdout().flush() {
-            eprintln!("Error flushing stdout: {}", e);
-            return false;
+        // A second Ctrl-C or a read error is taken as a "no"
+        if !self.renderer.confirm(&mut *self.input, i18n::text(Msg::SavePrompt)).unwrap_or(false) {
+            return;
         }
         
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => input.trim().eq_ignore_ascii_case("y"),
-            Err(e) => {
-                log::error!("Error reading input: {}", e);
-                false
-            }
+        let path = Path::new(persistence::DEFAULT_SAVE_PATH);
+        match persistence::save_game(path, &self.state) {
+            Ok(()) => self.renderer.info(&tr!(Msg::MatchSaved, path.display(), path.display())),
+            Err(e) => log::error!("{}", tr!(Msg::SaveFailed, e)),
+        }
+    }
+    
+    /// Records the hash of the game state at the start of a round.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the hash
+    fn record_state_hash(&mut self) -> GameResult<u64> {
+        let hash = self.state.hash();
+        self.events.record(GameEvent::StateHash { round: self.state.round, hash })?;
+        Ok(hash)
+    }
+    
+    /// Autosaves the match between two rounds.
+    ///
+    /// Campaign matches are left out: they are resumed from the profile instead.
+    /// A failed autosave only warns, the match goes on.
+    fn autosave(&self) {
+        if !self.config.autosave || self.opponent.is_some() {
+            return;
+        }
+        if let Err(e) = persistence::autosave(&persistence::autosave_dir(), &self.state) {
+            log::warn!("{}", tr!(Msg::AutosaveFailed, e));
+        }
+    }
+    
+    /// Writes the per-target results of the match, if an export file was given.
+    fn export_results(&mut self) {
+        let Some(path) = &self.config.export else {
+            return;
+        };
+        
+        match export::export_results(path, self.events.events()) {
+            Ok(()) => self.renderer.info(&tr!(Msg::ResultsExported, path.display())),
+            Err(e) => log::error!("{}", tr!(Msg::ExportFailed, e)),
         }
     }
     
     /// The main game loop implementation.
-    fn run_game_loop(&mut self) -> GameResult<()> {
-        log::info!("##### Game Started #####");        
-        let mut round = 1;
+    ///
+    /// # Returns
+    ///
+    /// Result containing the index of the winner
+    fn run_game_loop(&mut self) -> GameResult<usize> {
+        self.update_scoreboard()?;
+        if self.state.round > 1 {
+            self.renderer.info_wrapped(&tr!(Msg::MatchResumed, self.state.round));
+        } else {
+            self.renderer.info_wrapped(&tr!(Msg::GameStarted));
+        }
+        self.renderer.info_wrapped(&tr!(Msg::CounterRange, self.config.rules.counter_max));
+        self.renderer.info_wrapped(&tr!(Msg::HelpHint, HELP_COMMAND));
+        if self.config.draft && self.state.draft.is_none() {
+            self.play_draft()?;
+        }
+        if let Some(beat) = self.config.beat {
+            self.renderer.info_wrapped(&tr!(Msg::RhythmMode, beat, scoring::BEAT_BONUS));
+        }
+        match self.config.rules.win_condition {
+            WinCondition::Vitality => {},
+            WinCondition::RoundWins(wins) => self.renderer.info_wrapped(&tr!(Msg::WinGoalRounds, wins)),
+            WinCondition::HighScore(rounds) => self.renderer.info_wrapped(&tr!(Msg::WinGoalScore, rounds)),
+            WinCondition::SuddenDeath => self.renderer.info_wrapped(&tr!(Msg::WinGoalSuddenDeath)),
+        }
         
         // While both players have vitality, continue the game
-        while self.players[0].vitality() > 0 && self.players[1].vitality() > 0 && !self.game_over {
-            log::info!("## Round {} ##", round);
+        while self.state.in_progress() {
+            let round = self.state.round;
+            self.round_start = self.state.clone();
+            self.record_state_hash()?;
+            self.renderer.info_wrapped(&tr!(Msg::RoundStart, round));
+            for message in self.config.plugins.round_start(round) {
+                self.renderer.info_wrapped(&message);
+            }
+            if self.config.target_cap.is_some() {
+                self.renderer.info_wrapped(&tr!(Msg::RoundTargets, self.config.targets_in_round(round)));
+            }
+            if self.config.weather {
+                self.modifier = RoundModifier::draw(&mut self.rng);
+                self.renderer.info_wrapped(&tr!(weather_message(self.modifier)));
+            }
+            if self.config.betting {
+                self.collect_wagers()?;
+            }
             
-            // Player 1's turn
-            let p1_score = self.play_turn(0)?;
+            let (turns, exact_hits) = if self.config.duel {
+                let (scores, exact_hits) = self.play_duel()?;
+                (scores.map(TurnOutcome::Played), exact_hits)
+            } else {
+                let first = self.state.first_player;
+                if self.config.rules.turn_order != TurnOrder::Fixed {
+                    self.renderer.info(&tr!(Msg::FirstPlayer, self.state.players[first].name()));
+                }
+                if self.config.shared_pool {
+                    let targets = self.draw_targets(self.config.targets_in_round(round) * 2);
+                    self.pool = Some(TargetPool::deal(targets, first));
+                }
+                let mut results = [TurnResult::passed(), TurnResult::passed()];
+                for player_idx in [first, 1 - first] {
+                    if self.state.game_over {
+                        break;
+                    }
+                    // The second player sees the score to beat, unless results are kept blind
+                    let to_beat = match results[first].outcome {
+                        TurnOutcome::Played(score) if player_idx != first && !self.config.blind => Some(score),
+                        _ => None,
+                    };
+                    results[player_idx] = self.play_turn(player_idx, to_beat)?;
+                }
+                if !self.state.game_over {
+                    self.show_round_recap(&results);
+                }
+                (results.each_ref().map(|result| result.outcome), results.each_ref().map(|result| result.exact_hits))
+            };
+            if self.state.game_over {
+                // A player resigned, the rest of the round is not played
+                self.state.round += 1;
+                break;
+            }
             
-            // Player 2's turn
-            let p2_score = self.play_turn(1)?;
+            for (total, hits) in self.state.tally.exact_hits.iter_mut().zip(exact_hits) {
+                *total += hits;
+            }
             
             // Determine the winner of the round
-            self.process_round_result(p1_score, p2_score, None)?;
+            let (outcome, penalty) = self.process_round_result(turns)?;
+            self.apply_healing(exact_hits)?;
+            self.check_plugins();
             
-            log::info!("## End of round {} ##", round);
-            round += 1;
+            if self.config.reaction_game && self.state.in_progress() {
+                self.play_reaction_game()?;
+            }
+            let players = self.state.players.each_ref().map(Snapshot::of);
+            self.state.history.record(RoundRecord { round, turns, outcome, penalty, players });
+            
+            self.renderer.info_wrapped(&tr!(Msg::RoundEnd, round));
+            self.state.round += 1;
+            self.update_scoreboard()?;
+            if self.state.in_progress() {
+                self.autosave();
+            }
         }
         
         // One player has lost all vitality or speed reached 0, game over
-        log::info!("##### Game Over #####");
+        self.renderer.hide_scoreboard()?;
+        if self.config.autosave
+            && let Err(e) = persistence::mark_match_completed(&persistence::autosave_dir()) {
+            log::warn!("{}", tr!(Msg::AutosaveFailed, e));
+        }
+        self.renderer.info_wrapped(&tr!(Msg::GameOver));
         
         // Determine winner based on either winner_idx (speed = 0 case) or vitality
-        let winner = if let Some(idx) = self.winner_idx {
-            self.players[idx].name()
-        } else if self.players[0].vitality() > 0 {
-            self.players[0].name()
+        let winner_idx = if let Some(idx) = self.state.winner_idx {
+            idx
+        } else if self.state.players[0].vitality() > 0 {
+            0
         } else {
-            self.players[1].name()
+            1
         };
         
-        log::info!("Winner: {} \n", winner);
-        Ok(())
+        self.renderer.info(&tr!(Msg::Winner, self.state.players[winner_idx].name()));
+        self.events.record(GameEvent::GameOver { round: self.state.round - 1, winner: winner_idx })?;
+        for observer in &self.observers {
+            observer.on_game_over(winner_idx, &self.state);
+        }
+        for line in self.timeline() {
+            self.renderer.info(&line);
+        }
+        self.renderer.print_summary(&events::summarize(self.events.events()));
+        Ok(winner_idx)
+    }
+    
+    /// Lays out the timeline of the rounds played so far.
+    ///
+    /// # Returns
+    ///
+    /// The lines of the timeline, in the current language
+    fn timeline(&self) -> Vec<String> {
+        let [player1, player2] = &self.state.players;
+        self.state.history.timeline([player1.name(), player2.name()])
+    }
+    
+    /// Shows the current round and player attributes at the top of the screen.
+    fn update_scoreboard(&mut self) -> GameResult<()> {
+        self.renderer.show_scoreboard(ui::render_scoreboard(&self.state.players, self.state.round))
     }
     
     /// Executes a turn for the specified player.
@@ -124,255 +597,2523 @@ impl Game {
     /// # Arguments
     ///
     /// * `player_idx` - The index of the player (0 or 1)
+    /// * `to_beat` - The opponent's average score, if they already played this round
     ///
     /// # Returns
     ///
-    /// Result containing the player's average score for the turn
-    fn play_turn(&self, player_idx: usize) -> GameResult<u32> {
-        let player = &self.players[player_idx];
-        log::info!("{}'s turn (Vitality={}, Speed={}, Strength={})", 
-                   player.name(), player.vitality(), player.speed(), player.strength());
-        
-        // Generate random targets
-        let targets = self.generate_targets();
-        log::info!("→ Objectives: {:?}", targets);
-        log::info!("→ Press ENTER to start the turn..");
-        
-        ui::wait_for_enter()?;
+    /// Result containing how the turn went, target by target
+    fn play_turn(&mut self, player_idx: usize, to_beat: Option<u32>) -> GameResult<TurnResult> {
+        let round = self.state.round;
+        // With a shared pool, the player plays the targets dealt to them that were not stolen
+        let targets = match self.pool.as_mut() {
+            Some(pool) => pool.take_turn(player_idx),
+            None => self.generate_targets(),
+        };
+        self.start_turn(player_idx, &targets)?;
+        self.show_objectives(&targets)?;
+        let mut prediction = None;
+        // The computer opponent starts and stops the counter on its own
+        if !self.is_bot(player_idx) {
+            match self.wait_for_start(player_idx, true)? {
+                TurnStart::Play => {},
+                TurnStart::Pass => {
+                    self.pass_turn(player_idx)?;
+                    return Ok(TurnResult::passed());
+                },
+                TurnStart::Resign => {
+                    self.resign(player_idx)?;
+                    return Ok(TurnResult::passed());
+                },
+            }
+            // Only a turn that is actually played is worth a prediction
+            prediction = self.ask_prediction(player_idx)?;
+            self.show_stop_key(player_idx);
+        }
         let mut scores = Vec::new();
-
-        // Show the prompt on its own line and move to a new line
-        log::info!("Press ENTER to stop the counter.");
-        io::stdout().flush().map_err(GameError::from)?;
+        let mut results = Vec::new();
+        let mut sum = 0;
+        let mut exact_hits = 0;
+        let mut combo = 0;
+        let turn_started = Instant::now();
+        self.help_pause = Duration::ZERO;
         
+        let dial = self.config.rules.dial();
         for &target in targets.iter() {
-            let counter = Counter::new();
-            let (value_arc, miss_arc, running_arc) = counter.get_display_values();
-            // Capture the join handle from display_counter:
-            let ui_handle = ui::display_counter(value_arc, miss_arc, running_arc.clone(), target)?;
-            counter.start(player.speed())?;
-            ui::wait_for_enter()?;
-            let (value, miss) = counter.stop();
-            // Wait for the UI thread to finish
-            ui_handle.join().map_err(|_| GameError::LogicError("UI thread panicked".to_string()))?;
-            
-            // Clear the current line before printing final result
-            print!("\x1B[A\r\x1B[K"); // Move cursor up and clear line
-            io::stdout().flush().map_err(GameError::from)?;
-    
-            // Small pause
-            std::thread::sleep(std::time::Duration::from_millis(50));
-            
-            let score = scoring::calculate_score(target, value, player.strength(), miss);
+            let (result, voided) = self.play_target(player_idx, target, combo)?;
+            let TargetResult { target, value, score } = result;
             scores.push(score);
-            let base_score = score * (miss + 1) - player.strength();
-            
-            // Print the complete, final line
-            log::info!("→ Objective {}: Miss = {} | Counter = {} // Score = ({} + {}) / {} = {}",
-                       target, miss, value, base_score, player.strength(), miss + 1, score);
+            results.push(result);
+            sum += score;
+            if value == target && !voided {
+                exact_hits += 1;
+            }
+            // Accurate stops in a row raise the base score of the next targets
+            combo = if !voided && scoring::is_accurate(target, value, dial) { combo + 1 } else { 0 };
+            self.show_running_average(sum, scores.len(), targets.len(), to_beat);
         }
         
-        let avg_score = scoring::calculate_average_score(&scores);
+        let average = scoring::calculate_average_score(&scores);
+        let avg_score = average + self.play_steal(player_idx)?;
+        self.events.record(GameEvent::TurnEnd { round, player: player_idx, average: avg_score })?;
 
-        log::info!("# End of turn #");
-        log::info!("→ Average score: {} \n", avg_score);
+        self.renderer.info(&tr!(Msg::TurnEnd));
+        self.show_target_table();
+        self.report(tr!(Msg::TurnDuration, ui::format_seconds(turn_started.elapsed().saturating_sub(self.help_pause))));
+        self.report(tr!(Msg::AverageScore, avg_score));
+        // The prediction is about the player's own targets, so a stolen one does not count
+        if let Some(prediction) = prediction {
+            let bonus = scoring::prediction_bonus(prediction, average);
+            self.prediction_bonuses[player_idx] = bonus;
+            self.report(match bonus {
+                0 => tr!(Msg::PredictionMissed, prediction, average),
+                _ => tr!(Msg::PredictionHit, prediction, average, bonus),
+            });
+        }
         
-        Ok(avg_score)
+        Ok(TurnResult { outcome: TurnOutcome::Played(avg_score), exact_hits, targets: results })
     }
     
     
-    /// Generates random targets for a turn.
+    /// Plays one target: the risk and gamble questions, the stops of the counter and the score.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    /// * `target` - The target, in whole units
+    /// * `combo` - Accurate stops in a row before this target
     ///
     /// # Returns
     ///
-    /// A vector of random target numbers
-    fn generate_targets(&self) -> Vec<u32> {
-        let mut rng = rand::rng();
-        (0..self.target_count).map(|_| rng.random_range(0..=100)).collect()
+    /// Result containing how the target went, and whether it was voided for a stop too fast to be human
+    fn play_target(&mut self, player_idx: usize, target: u32, combo: u32) -> GameResult<(TargetResult, bool)> {
+        let dial = self.config.rules.dial();
+        // Targets are whole numbers, the counter may count in finer ticks
+        let target = dial.ticks(target);
+        self.show_difficulty(player_idx);
+        let risk = self.ask_risk(player_idx)?;
+        let gamble = self.offer_gamble(player_idx, target)?;
+        // The timer counts from the first stop of the target, without the risk and gamble questions
+        let target_started = Instant::now();
+        let help_pause = self.help_pause;
+        // Multi-stop targets are stopped several times and scored on the combined stops
+        let mut target = target;
+        let mut stops = Vec::new();
+        let (mut miss, mut reaction_ms) = (0, 0);
+        for stop in 1..=self.config.stops {
+            if stop > 1 {
+                self.renderer.info(&tr!(Msg::NextStop, stop, self.config.stops));
+            }
+            // The timer leaves out the time the help was shown on the earlier stops
+            let started = target_started + (self.help_pause - help_pause);
+            let (moved_target, value, stop_miss, stop_ms) = self.run_counter(player_idx, target, combo, None, Some(started))?;
+            target = moved_target;
+            stops.push(value);
+            miss += stop_miss;
+            reaction_ms += stop_ms;
+        }
+        let value = scoring::composite_value(self.config.stop_rule, target, &stops, dial);
+        if stops.len() > 1 {
+            let stops: Vec<_> = stops.iter().map(|&stop| dial.format(stop)).collect();
+            self.renderer.info(&tr!(Msg::MultiStopResult, format!("[{}]", stops.join(", ")), dial.format(value)));
+        }
+        // The handicap moves the stop before it is scored
+        let handicap = self.config.handicap[player_idx];
+        let value = dial.offset(value, handicap);
+        
+        let voided = self.check_fairness(player_idx, reaction_ms)?;
+        let stop = TargetStop { target, value, miss, reaction_ms, gamble, handicap, risk };
+        let score = if voided { 0 } else { self.score_stop(player_idx, &stop, combo)? };
+        Ok((TargetResult { target, value, score }, voided))
     }
     
-    /// Processes the result of a round and applies penalties.
+    /// Lets a player who finished their turn steal a target the opponent has not played yet.
+    ///
+    /// The stolen target is played like the others, and `STEAL_BONUS_PERCENT` of
+    /// its score is added to the player's average. The computer opponent never steals.
     ///
     /// # Arguments
     ///
-    /// * `p1_score` - The score of player 1
-    /// * `p2_score` - The score of player 2
-    /// * `test_choice` - Optional test choice for automated testing
+    /// * `player_idx` - The index of the player
     ///
     /// # Returns
     ///
-    /// Result indicating whether processing succeeded
-    fn process_round_result(&mut self, p1_score: u32, p2_score: u32, test_choice: Option<usize>) -> GameResult<()> {
-        if p1_score > p2_score {
-            // Player 1 wins
-            let diff = p1_score.saturating_sub(p2_score);
-            self.players[1].decrease_vitality(diff);
-            log::info!("{} wins the round. {} loses {} vitality points.", 
-                       self.players[0].name(), self.players[1].name(), diff);
-            
-            if self.players[1].vitality() > 0 {
-                self.apply_penalty(0, 1, test_choice)?;
-            }
-        } else if p2_score > p1_score {
-            // Player 2 wins
-            let diff = p2_score.saturating_sub(p1_score);
-            self.players[0].decrease_vitality(diff);
-            log::info!("{} wins the round. {} loses {} vitality points.", 
-                       self.players[1].name(), self.players[0].name(), diff);
-            
-            if self.players[0].vitality() > 0 {
-                self.apply_penalty(1, 0, test_choice)?;
-            }
-        } else {
-            // Draw
-            log::info!("It's a draw! No penalties applied.");
+    /// Result containing the bonus points, 0 if no target was stolen
+    fn play_steal(&mut self, player_idx: usize) -> GameResult<u32> {
+        let stealable = match &self.pool {
+            Some(pool) if !self.is_bot(player_idx) => pool.stealable(player_idx),
+            _ => return Ok(0),
+        };
+        if stealable.is_empty() {
+            return Ok(0);
         }
+        let options = self.steal_options(&stealable);
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        self.input.expect_player(player_idx);
+        let prompt = tr!(Msg::StealPrompt, self.state.players[1 - player_idx].name(), STEAL_BONUS_PERCENT);
+        let choice = self.renderer.get_user_choice(&mut *self.input, &prompt, &options)?;
+        let Some(&(idx, _)) = stealable.get(choice) else {
+            return Ok(0);
+        };
         
-        Ok(())
+        let Some(pool) = self.pool.as_mut() else {
+            return Ok(0);
+        };
+        let target = pool.steal(idx, player_idx);
+        self.events.record(GameEvent::TargetSteal { round: self.state.round, player: player_idx, from: 1 - player_idx, target })?;
+        let (result, _) = self.play_target(player_idx, target, 0)?;
+        let bonus = result.score * STEAL_BONUS_PERCENT / 100;
+        self.report(tr!(Msg::StealBonus, bonus));
+        Ok(bonus)
     }
     
-    /// Applies a penalty chosen by the winner to the loser.
+    /// Lists the targets a player may steal, then the option to steal none.
     ///
     /// # Arguments
     ///
-    /// * `winner_idx` - The index of the winning player
-    /// * `loser_idx` - The index of the losing player
-    /// * `test_choice` - Optional test choice for automated testing
+    /// * `stealable` - The position in the pool and the value of each target, from `TargetPool::stealable`
     ///
     /// # Returns
     ///
-    /// Result indicating whether applying the penalty succeeded
-    fn apply_penalty(&mut self, winner_idx: usize, loser_idx: usize, test_choice: Option<usize>) -> GameResult<()> {
-        log::info!("{}, you must choose which poison to apply to {}:", 
-                   self.players[winner_idx].name(), self.players[loser_idx].name());
-        
-        let options = ["-5 speed", "-5 strength"];
-        let choice = ui::get_user_choice("Choose a penalty:", &options, test_choice)?;
-        
-        match choice {
-            0 => {
-                self.players[loser_idx].decrease_speed(5);
-                log::info!("{}'s speed reduced by 5!", self.players[loser_idx].name());
-                
-                // Check if speed reached 0
-                if self.players[loser_idx].speed() == 0 {
-                    log::info!("Game Over! {} has lost because their speed reached 0!", 
-                               self.players[loser_idx].name());
-                    self.game_over = true;
-                    self.winner_idx = Some(winner_idx);
-                }
-            },
-            1 => {
-                self.players[loser_idx].decrease_strength(5);
-                log::info!("{}'s strength reduced by 5!", self.players[loser_idx].name());
-            },
-            _ => unreachable!(), // get_user_choice ensures a valid index
-        }
-        
-        Ok(())
+    /// The options of the steal menu, the targets masked like on the counter when they are kept off the screen
+    fn steal_options(&self, stealable: &[(usize, u32)]) -> Vec<String> {
+        let dial = self.config.rules.dial();
+        let hidden = self.targets_hidden();
+        let mut options: Vec<String> = stealable.iter()
+            .map(|&(_, target)| tr!(Msg::StealTarget, if hidden { "?".to_string() } else { dial.format(dial.ticks(target)) }))
+            .collect();
+        options.push(tr!(Msg::StealNone));
+        options
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_game_creation() {
-        let game = Game::new(
-            "Player1".to_string(), 
-            "Player2".to_string(),
-            100,
-            60,
-            70,
-            5
-        );
-        
-        assert_eq!(game.players[0].name(), "Player1");
-        assert_eq!(game.players[1].name(), "Player2");
-        assert_eq!(game.players[0].vitality(), 100);
-        assert_eq!(game.players[1].vitality(), 100);
-        assert_eq!(game.players[0].speed(), 60);
-        assert_eq!(game.players[0].strength(), 70);
+    
+    /// Asks the player to predict their average once they started the turn, when predictions are on.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    ///
+    /// # Returns
+    ///
+    /// Result containing the prediction, None if the player made none or is the computer opponent
+    fn ask_prediction(&mut self, player_idx: usize) -> GameResult<Option<u32>> {
+        if !self.config.predictions || self.is_bot(player_idx) {
+            return Ok(None);
+        }
+        let prompt = tr!(Msg::PredictionPrompt, self.state.players[player_idx].name(),
+                         scoring::PREDICTION_MARGIN, scoring::PREDICTION_BONUS);
+        self.input.expect_player(player_idx);
+        self.renderer.get_prediction(&mut *self.input, &prompt)
     }
-
-    #[test]
-    fn test_generate_targets() {
-        let game = Game::new(
-            "Player1".to_string(), 
-            "Player2".to_string(),
-            100,
-            60,  // speed
-            70,  // strength
-            5    // target_count
-        );
-        
-        let targets = game.generate_targets();
-        
-        // Check that the correct number of targets is generated
-        assert_eq!(targets.len(), 5);
-        
-        // Check that all targets are within range
-        for target in targets {
-            assert!(target <= 100);
+    
+    /// Shows the player's running average under the counter while targets remain.
+    ///
+    /// # Arguments
+    ///
+    /// * `sum` - The points scored so far this turn
+    /// * `played` - The number of targets played so far
+    /// * `total` - The number of targets in the turn
+    /// * `to_beat` - The opponent's average score, if it is known
+    fn show_running_average(&mut self, sum: u32, played: usize, total: usize, to_beat: Option<u32>) {
+        // The final average is shown at the end of the turn, and blind turns show no scores
+        if played >= total || self.config.blind || !self.config.verbosity.shows_targets() {
+            return;
         }
+        let average = sum.div_ceil(played as u32);
+        let needed = to_beat.and_then(|to_beat| {
+            scoring::needed_per_target(to_beat, sum, played, total).map(|needed| (to_beat, needed))
+        });
+        let line = match needed {
+            Some((to_beat, 0)) => tr!(Msg::RunningAverageBeaten, average, to_beat),
+            Some((to_beat, needed)) => tr!(Msg::RunningAverageToBeat, average, needed, to_beat),
+            None => tr!(Msg::RunningAverage, average),
+        };
+        self.renderer.info(&line);
     }
     
-    #[test]
-    fn test_process_round_result_player1_wins() {
-        let mut game = Game::new(
-            "Player1".to_string(), 
-            "Player2".to_string(),
-            100, 60, 70, 5
-        );
-        
-        // Use a test choice (0 = decrease speed)
-        let result = game.process_round_result(100, 50, Some(0));
-        
-        assert!(result.is_ok());
-        
-        // Verify that player2's vitality and speed were reduced
-        assert_eq!(game.players[1].vitality(), 50); // 100 - (100 - 50)
-        assert_eq!(game.players[1].speed(), 55);    // 60 - 5
+    /// Waits for the player to start their turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    /// * `resignable` - Whether the player may resign the match or pass the turn instead
+    ///
+    /// # Returns
+    ///
+    /// Result containing what the player chose to do
+    fn wait_for_start(&mut self, player_idx: usize, resignable: bool) -> GameResult<TurnStart> {
+        self.input.expect_player(player_idx);
+        // A player may not pass if paying for it would knock them out
+        let pass_cost = self.config.pass_cost
+            .filter(|&cost| resignable && self.state.players[player_idx].vitality() > cost);
+        loop {
+            match pass_cost {
+                _ if !resignable => self.renderer.info(&tr!(Msg::PressEnterStart)),
+                Some(cost) => self.renderer.info(&tr!(Msg::PressEnterStartOrPass, PASS_COMMAND, cost, RESIGN_COMMAND)),
+                None => self.renderer.info(&tr!(Msg::PressEnterStartOrResign, RESIGN_COMMAND)),
+            }
+            let line = self.input.read_line()?;
+            let command = line.trim();
+            // The help is shown over the turn, which then starts as it would have
+            if command == HELP_COMMAND {
+                self.renderer.show_overlay(&mut *self.input, &help::help_lines(&self.config))?;
+                continue;
+            }
+            if command.eq_ignore_ascii_case(TIMELINE_COMMAND) {
+                let timeline = self.timeline();
+                self.renderer.show_overlay(&mut *self.input, &timeline)?;
+                continue;
+            }
+            if resignable && command.eq_ignore_ascii_case(RESIGN_COMMAND) {
+                return Ok(TurnStart::Resign);
+            }
+            if pass_cost.is_some() && command.eq_ignore_ascii_case(PASS_COMMAND) {
+                return Ok(TurnStart::Pass);
+            }
+            break;
+        }
+        Ok(TurnStart::Play)
     }
     
-    #[test]
-    fn test_process_round_result_player2_wins() {
-        let mut game = Game::new(
-            "Player1".to_string(), 
-            "Player2".to_string(),
-            100, 60, 70, 5
-        );
-        
-        // Use a test choice (1 = decrease strength)
-        let result = game.process_round_result(50, 100, Some(1));
+    /// Tells the player how to stop the counter, right before it starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    fn show_stop_key(&mut self, player_idx: usize) {
+        // Show the prompt on its own line and move to a new line
+        match self.config.keys.get(player_idx) {
+            Some(key) => self.renderer.info(&tr!(Msg::PressKeyStop, key)),
+            None => self.renderer.info(&tr!(Msg::PressEnterStop)),
+        }
+    }
+    
+    /// Skips the player's turn, for the vitality passing costs.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player who passes
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the vitality change could be recorded
+    fn pass_turn(&mut self, player_idx: usize) -> GameResult<()> {
+        let cost = self.config.pass_cost.unwrap_or_default();
+        self.state.players[player_idx].decrease_vitality(cost);
+        self.record_vitality_change(player_idx, cost)?;
+        self.renderer.info(&tr!(Msg::TurnPassed, self.state.players[player_idx].name(), cost));
+        self.show_vitality(player_idx);
+        Ok(())
+    }
+    
+    /// Ends the match with the player resigning, their opponent wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player who resigns
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the forfeit could be recorded
+    fn resign(&mut self, player_idx: usize) -> GameResult<()> {
+        self.renderer.info(&tr!(Msg::Resigned, self.state.players[player_idx].name()));
+        self.state.game_over = true;
+        self.state.winner_idx = Some(1 - player_idx);
+        self.events.record(GameEvent::Forfeit { round: self.state.round, player: player_idx })
+    }
+    
+    /// Introduces a player's turn and records its start.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    /// * `targets` - The targets of the turn
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the event could be recorded
+    fn start_turn(&mut self, player_idx: usize, targets: &[u32]) -> GameResult<()> {
+        let player = &self.state.players[player_idx];
+        self.renderer.info_wrapped(&tr!(Msg::TurnHeader, player.name(),
+                   ui::render_hp_bar(player.vitality(), player.max_vitality()),
+                   player.speed(), player.strength(), player.momentum()));
+        for observer in &self.observers {
+            observer.on_turn_start(self.state.round, player_idx, targets);
+        }
+        self.events.record(GameEvent::TurnStart {
+            round: self.state.round,
+            player: player_idx,
+            name: player.name().to_string(),
+            vitality: player.vitality(),
+            speed: player.speed(),
+            strength: player.strength(),
+            targets: targets.to_vec(),
+        })
+    }
+    
+    /// Shows the targets of a turn, as the hidden target and memory modes allow.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - The targets of the turn
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether writing to the terminal succeeded
+    fn show_objectives(&mut self, targets: &[u32]) -> GameResult<()> {
+        if self.hidden_target() {
+            self.renderer.info_wrapped(&tr!(Msg::ObjectivesHidden, targets.len()));
+        } else {
+            let objectives = tr!(Msg::Objectives, format!("{:?}", targets));
+            match self.config.memory_seconds {
+                Some(seconds) => self.renderer.show_briefly(&objectives, Duration::from_secs(seconds))?,
+                None => self.renderer.info_wrapped(&objectives),
+            }
+        }
+        Ok(())
+    }
+    
+    /// Plays survival mode: player 1 alone faces waves of targets until their vitality runs out.
+    ///
+    /// Every stop further than `survival::TOLERANCE` from its target, or after the
+    /// counter went around, costs `survival::MISS_DAMAGE` vitality. The counter
+    /// gets faster after each wave, following the speed curve of the rules.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the number of waves survived
+    pub fn play_survival(&mut self) -> GameResult<u32> {
+        let dial = self.config.rules.dial();
+        let tolerance = dial.ticks(survival::TOLERANCE);
+        let mut waves = 0;
+        
+        while self.state.players[0].vitality() > 0 {
+            let wave = self.state.round;
+            self.record_state_hash()?;
+            let delay = self.counter_delay(self.config.rules.tick_interval(&self.state.players[0]));
+            self.renderer.info_wrapped(&tr!(Msg::SurvivalWave, wave, delay));
+            let targets = self.generate_targets();
+            self.start_turn(0, &targets)?;
+            self.show_objectives(&targets)?;
+            self.wait_for_start(0, false)?;
+            self.show_stop_key(0);
+            
+            for &target in targets.iter() {
+                let (target, value, miss, reaction_ms) = self.run_counter(0, dial.ticks(target), 0, None, None)?;
+                self.events.record(GameEvent::CounterStop {
+                    round: wave, player: 0, target, value, miss, reaction_ms,
+                })?;
+                if miss == 0 && scoring::difference(target, value, dial) <= tolerance {
+                    self.renderer.info(&tr!(Msg::SurvivalHit, dial.format(target), dial.format(value)));
+                    self.renderer.info(&ui::render_distance(target, value, dial));
+                    continue;
+                }
+                
+                let player = &mut self.state.players[0];
+                let before = player.vitality();
+                player.decrease_vitality(survival::MISS_DAMAGE);
+                let lost = before - player.vitality();
+                self.renderer.info(&tr!(Msg::SurvivalMiss, dial.format(target), dial.format(value), lost));
+                self.renderer.info(&ui::render_distance(target, value, dial));
+                self.show_vitality(0);
+                self.record_vitality_change(0, lost)?;
+                if self.state.players[0].vitality() == 0 {
+                    break;
+                }
+            }
+            
+            if self.state.players[0].vitality() > 0 {
+                waves += 1;
+                self.renderer.info_wrapped(&tr!(Msg::SurvivalWaveCleared, wave));
+            }
+            self.state.round += 1;
+        }
+        
+        Ok(waves)
+    }
+    
+    /// Plays a practice or time attack run: player 1 alone, one target per round.
+    ///
+    /// In practice every stop scores as in a match, and the run is worth the
+    /// total. In time attack every stop is timed, and one further than
+    /// `practice::TOLERANCE` from its target, or after the counter went around,
+    /// adds `practice::MISS_PENALTY_MS`. Vitality is never lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Practice or time attack
+    /// * `ghost` - A recorded run to race, played with the same seed
+    ///
+    /// # Returns
+    ///
+    /// Result containing the run, ready to be saved as a replay, or `GameError::Desync`
+    /// if the run made the same stops as the ghost but reached a different state
+    pub fn play_practice(&mut self, mode: PracticeMode, ghost: Option<&Replay>) -> GameResult<Replay> {
+        let dial = self.config.rules.dial();
+        let tolerance = dial.ticks(practice::TOLERANCE);
+        let mut run = Replay { seed: self.config.seed.unwrap_or_default(), mode, result: 0, stops: Vec::new(), hashes: Vec::new() };
+        
+        for _ in 0..practice::RUN_TARGETS {
+            let round = self.state.round;
+            let hash = self.record_state_hash()?;
+            if let Some(ghost) = ghost {
+                ghost.check(round, &run.stops, hash)?;
+            }
+            run.hashes.push(hash);
+            let targets = self.generate_targets();
+            self.show_objectives(&targets)?;
+            self.wait_for_start(0, false)?;
+            self.show_stop_key(0);
+            
+            for &target in targets.iter() {
+                let ghost_stop = ghost.and_then(|ghost| ghost.stops.get(run.stops.len()).copied());
+                let (target, value, miss, reaction_ms) = self.run_counter(0, dial.ticks(target), 0, ghost_stop, None)?;
+                self.events.record(GameEvent::CounterStop {
+                    round, player: 0, target, value, miss, reaction_ms,
+                })?;
+                run.stops.push(value);
+                if let Some(ghost_stop) = ghost_stop {
+                    self.renderer.info(&tr!(Msg::GhostStop, dial.format(ghost_stop)));
+                }
+                match mode {
+                    PracticeMode::Practice => {
+                        let score = ScoreBreakdown::new(target, value, self.state.players[0].strength(), miss, 0, dial).total();
+                        self.state.tally.scores[0] += score;
+                        run.result += u64::from(score);
+                        self.renderer.info(&tr!(Msg::PracticeStop, dial.format(target), dial.format(value), score));
+                    },
+                    PracticeMode::TimeAttack if miss == 0 && scoring::difference(target, value, dial) <= tolerance => {
+                        run.result += reaction_ms;
+                        self.renderer.info(&tr!(Msg::TimeAttackStop,
+                                                dial.format(target), dial.format(value), practice::seconds(reaction_ms)));
+                    },
+                    PracticeMode::TimeAttack => {
+                        self.state.tally.misses[0] += 1;
+                        run.result += reaction_ms + practice::MISS_PENALTY_MS;
+                        self.renderer.info(&tr!(Msg::TimeAttackMiss, dial.format(target), dial.format(value),
+                                                practice::seconds(reaction_ms), practice::seconds(practice::MISS_PENALTY_MS)));
+                    },
+                }
+                self.renderer.info(&ui::render_distance(target, value, dial));
+            }
+            self.state.round += 1;
+        }
+        
+        Ok(run)
+    }
+    
+    /// Plays a round as a duel: both players stop the same counter, each with their own key.
+    ///
+    /// Both players get the same targets. The counter runs at the average of the
+    /// players' tick intervals, and gambles and multiple stops are not offered.
+    ///
+    /// # Returns
+    ///
+    /// Result containing each player's average score and number of exact hits
+    fn play_duel(&mut self) -> GameResult<([u32; 2], [u32; 2])> {
+        let (Some(key1), Some(key2)) = (self.config.keys.get(0), self.config.keys.get(1)) else {
+            return Err(GameError::ConfigError("Duel mode needs a stop key for each player".to_string()));
+        };
+        let round = self.state.round;
+        let targets = self.generate_targets();
+        for player_idx in 0..2 {
+            self.start_turn(player_idx, &targets)?;
+        }
+        self.show_objectives(&targets)?;
+        self.renderer.info(&tr!(Msg::PressEnterStart));
+        ui::wait_for_enter(&mut *self.input)?;
+        let [player1, player2] = &self.state.players;
+        self.renderer.info(&tr!(Msg::DuelKeys, player1.name(), key1, player2.name(), key2));
+        
+        let dial = self.config.rules.dial();
+        let rules = &self.config.rules;
+        let delay = self.counter_delay((rules.tick_interval(player1) + rules.tick_interval(player2)) / 2);
+        let mut scores = [Vec::new(), Vec::new()];
+        let mut exact_hits = [0; 2];
+        let mut combo = [0; 2];
+        for &target in targets.iter() {
+            let target = dial.ticks(target);
+            let beat = self.beat_ticks();
+            let mut duel = DuelCounter::new(dial);
+            let events = self.counter_events(duel.events())?;
+            let ui_handle = self.renderer.display_counter(
+                events,
+                CounterFrame {
+                    view: self.config.counter_view,
+                    dial,
+                    target,
+                    moving_target: None,
+                    hide_target: self.targets_hidden(),
+                    hints: self.hidden_target(),
+                    combo: 0,
+                    beat,
+                    ghost: None,
+                    started: None,
+                },
+                self.audio.clone(),
+            )?;
+            duel.start(&self.engine()?, delay)?;
+            let started = Instant::now();
+            let mut reaction_ms = [0; 2];
+            let stopped = self.renderer.wait_for_both_keys(&mut *self.input, [key1, key2], &mut |player_idx| {
+                duel.lock(player_idx);
+                reaction_ms[player_idx] = started.elapsed().as_millis() as u64;
+            });
+            duel.stop();
+            // Wait for the display task to finish, even if the wait was interrupted
+            runtime::join(ui_handle)?;
+            stopped?;
+            self.renderer.clear_counter(self.config.counter_view)?;
+            
+            // Each player's result is shown in the order they stopped
+            let stops = duel.stops();
+            for (rank, &player_idx) in duel.order().iter().enumerate() {
+                let Some((value, miss)) = stops[player_idx] else { continue };
+                self.renderer.info(&tr!(Msg::DuelStop, rank + 1, self.state.players[player_idx].name(),
+                           dial.format(value), reaction_ms[player_idx]));
+                let handicap = self.config.handicap[player_idx];
+                let value = dial.offset(value, handicap);
+                let stop = TargetStop { target, value, miss, reaction_ms: reaction_ms[player_idx], gamble: false, handicap,
+                                        risk: RiskTier::Normal };
+                if self.check_fairness(player_idx, stop.reaction_ms)? {
+                    scores[player_idx].push(0);
+                    combo[player_idx] = 0;
+                    continue;
+                }
+                scores[player_idx].push(self.score_stop(player_idx, &stop, combo[player_idx])?);
+                if value == target {
+                    exact_hits[player_idx] += 1;
+                }
+                combo[player_idx] = if scoring::is_accurate(target, value, dial) { combo[player_idx] + 1 } else { 0 };
+            }
+        }
+        
+        self.renderer.info(&tr!(Msg::TurnEnd));
+        self.show_target_table();
+        let mut averages = [0; 2];
+        for player_idx in 0..2 {
+            averages[player_idx] = scoring::calculate_average_score(&scores[player_idx]);
+            self.events.record(GameEvent::TurnEnd { round, player: player_idx, average: averages[player_idx] })?;
+            self.renderer.info(&tr!(Msg::PlayerAverageScore, self.state.players[player_idx].name(), averages[player_idx]));
+        }
+        
+        Ok((averages, exact_hits))
+    }
+    
+    /// Scores a stopped target, records it, and prints the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    /// * `stop` - Where the counter was stopped
+    /// * `combo` - Accurate stops in a row before this target
+    ///
+    /// # Returns
+    ///
+    /// Result containing the score of the target
+    fn score_stop(&mut self, player_idx: usize, stop: &TargetStop, combo: u32) -> GameResult<u32> {
+        let round = self.state.round;
+        let dial = self.config.rules.dial();
+        let TargetStop { target, value, miss, reaction_ms, gamble, handicap, risk } = *stop;
+        self.events.record(GameEvent::CounterStop {
+            round, player: player_idx, target, value, miss, reaction_ms,
+        })?;
+        self.state.tally.misses[player_idx] += miss;
+        // In calm weather the counter may go around for free
+        let miss = if self.modifier.counts_misses() { miss } else { 0 };
+        
+        let player = &self.state.players[player_idx];
+        let mut breakdown = ScoreBreakdown {
+            base: risk.base_score(scoring::difference(target, value, dial)),
+            gamble,
+            critical: scoring::roll_critical(target, value, player.strength(), dial, &mut self.rng),
+            on_beat: self.beat_ticks().is_some_and(|beat| scoring::is_on_beat(value, beat)),
+            ..ScoreBreakdown::new(target, value, player.strength(), miss, combo, dial)
+        };
+        self.config.plugins.score(&mut breakdown);
+        let score = breakdown.total();
+        let counter_delay = self.config.rules.tick_interval(player);
+        if breakdown.critical {
+            self.events.record(GameEvent::Critical { round, player: player_idx, target })?;
+        }
+        self.events.record(GameEvent::Score { round, player: player_idx, target, score })?;
+        for observer in &self.observers {
+            observer.on_score(player_idx, target, value, score);
+        }
+        
+        // Chime when the stop lands within 5 of the target, unless results are kept blind
+        if breakdown.hit && !self.config.blind {
+            self.audio.play(Sound::Hit);
+        }
+        
+        // Print the complete, final line
+        if self.config.blind {
+            self.renderer.info(&tr!(Msg::ObjectiveHidden, dial.format(target)));
+        }
+        if self.config.verbosity.shows_targets() {
+            // A handicapped stop shows where the counter really stopped and the ticks it was moved by
+            let counter = match handicap {
+                0 => dial.format(value),
+                _ => tr!(Msg::HandicapValue, dial.format(value), dial.format(dial.offset(value, -handicap)),
+                         format!("{}{}", if handicap > 0 { "+" } else { "-" }, dial.format(handicap.unsigned_abs()))),
+            };
+            let distance = dial.format(scoring::difference(target, value, dial));
+            self.target_rows.push((player_idx, vec![dial.format(target), counter, distance, miss.to_string(), score.to_string()]));
+        }
+        if breakdown.combo > 0 {
+            self.report(tr!(Msg::ComboBonus,
+                       format!("{:.1}", scoring::combo_multiplier(combo)), breakdown.base, breakdown.combo_base()));
+        }
+        if breakdown.on_beat {
+            self.report(tr!(Msg::BeatBonus, scoring::BEAT_BONUS));
+        }
+        if breakdown.critical {
+            self.report(tr!(Msg::CriticalHit, score));
+        }
+        if gamble {
+            self.report(if score > 0 { tr!(Msg::GambleWon, score) } else { tr!(Msg::GambleLost) });
+        }
+        if self.config.verbosity.shows_details() {
+            self.report(tr!(Msg::ObjectiveDetails, counter_delay, reaction_ms));
+        }
+        if value == target {
+            self.steal_momentum(player_idx)?;
+        }
+        
+        Ok(score)
+    }
+    
+    /// Checks that a stop was within human reach, and warns about it otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player who stopped the counter
+    /// * `reaction_ms` - Time between the counter starting and the stop
+    ///
+    /// # Returns
+    ///
+    /// Result containing whether the target is voided and scores nothing
+    fn check_fairness(&mut self, player_idx: usize, reaction_ms: u64) -> GameResult<bool> {
+        // The computer opponent's stops are not timed by a hand
+        if self.is_bot(player_idx) {
+            return Ok(false);
+        }
+        let verdict = self.fairness.check(player_idx, reaction_ms);
+        if verdict == Verdict::Plausible {
+            return Ok(false);
+        }
+        let voided = verdict == Verdict::Suspicious && self.config.void_implausible;
+        let name = self.state.players[player_idx].name();
+        log::warn!("{}", tr!(Msg::ImplausibleStop, name, reaction_ms));
+        if voided {
+            self.renderer.info(&tr!(Msg::TargetVoided, name, fairness::SUSPICIOUS_STREAK));
+        }
+        self.events.record(GameEvent::ImplausibleStop {
+            round: self.state.round, player: player_idx, reaction_ms, verdict, voided,
+        })?;
+        Ok(voided)
+    }
+    
+    /// Shows a turn result, or holds it back until the end of the round in blind mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The result to show
+    fn report(&mut self, line: String) {
+        if self.config.blind {
+            self.hidden_results.push(line);
+        } else {
+            self.renderer.info(&line);
+        }
+    }
+    
+    /// Shows the targets scored this turn as a table, one per player who played in it.
+    ///
+    /// In blind mode, the tables are held back with the other results.
+    fn show_target_table(&mut self) {
+        let rows = std::mem::take(&mut self.target_rows);
+        let header = [Msg::TableTarget, Msg::TableStop, Msg::TableDistance, Msg::TableMiss, Msg::TableScore].map(|msg| tr!(msg));
+        // A duel turn has both players' targets, each player gets a table
+        let both = rows.iter().any(|&(player_idx, _)| player_idx != rows[0].0);
+        for player_idx in 0..2 {
+            let player_rows: Vec<_> = rows.iter().filter(|(idx, _)| *idx == player_idx).map(|(_, row)| row.clone()).collect();
+            if player_rows.is_empty() {
+                continue;
+            }
+            if both {
+                self.report(tr!(Msg::TableOwner, self.state.players[player_idx].name()));
+            }
+            self.report_table(&header, &player_rows);
+        }
+    }
+    
+    /// Shows both players' turns side by side, target by target, with who stopped closer to each.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - How each player's turn went
+    fn show_round_recap(&mut self, results: &[TurnResult; 2]) {
+        if !self.config.verbosity.shows_targets() || results.iter().any(|result| result.targets.is_empty()) {
+            return;
+        }
+        let names = [0, 1].map(|player_idx| self.state.players[player_idx].name().to_string());
+        let (rows, closer) = round_recap(results, &names, self.config.rules.dial());
+        let header = [tr!(Msg::RecapTarget), names[0].clone(), names[1].clone(), tr!(Msg::RecapCloser)];
+        self.report(tr!(Msg::RecapTitle));
+        self.report_table(&header, &rows);
+        self.report(tr!(Msg::RecapCount, names[0], closer[0], names[1], closer[1]));
+    }
+    
+    /// Prints a table, or holds it back with the other results in blind mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The title of each column
+    /// * `rows` - The cells of each row, one per column
+    fn report_table(&mut self, header: &[String], rows: &[Vec<String>]) {
+        if self.config.blind {
+            self.hidden_results.extend(ui::table_lines(header, rows));
+        } else {
+            ui::print_table(&mut *self.renderer, header, rows);
+        }
+    }
+    
+    /// Gives the player all of their opponent's momentum after an exact hit.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player who made the exact hit
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the steal could be recorded
+    fn steal_momentum(&mut self, player_idx: usize) -> GameResult<()> {
+        let opponent_idx = 1 - player_idx;
+        let amount = self.state.players[opponent_idx].take_momentum();
+        if amount == 0 {
+            return Ok(());
+        }
+        
+        self.state.players[player_idx].increase_momentum(amount);
+        self.report(tr!(Msg::MomentumStolen,
+                   self.state.players[player_idx].name(), amount, self.state.players[opponent_idx].name()));
+        self.events.record(GameEvent::MomentumSteal {
+            round: self.state.round,
+            player: player_idx,
+            from: opponent_idx,
+            amount,
+        })
+    }
+    
+    /// Runs the counter until the player stops it.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    /// * `target` - The target the counter is aimed at
+    /// * `combo` - Accurate stops in a row before this target
+    /// * `ghost` - Where a recorded run stopped the counter for this target, if racing a ghost
+    /// * `target_started` - When the player started on the target, to time it next to the counter
+    ///
+    /// # Returns
+    ///
+    /// Result containing the target at the time of the stop (it may have drifted),
+    /// the counter value, the miss count, and the reaction time in milliseconds
+    fn run_counter(&mut self, player_idx: usize, target: u32, combo: u32, ghost: Option<u32>, target_started: Option<Instant>)
+        -> GameResult<(u32, u32, u32, u64)> {
+        let dial = self.config.rules.dial();
+        let beat = self.beat_ticks();
+        let stop_at = if self.is_bot(player_idx) {
+            None
+        } else {
+            self.input.expect_player(player_idx);
+            self.input.stop_at()?.map(|value| value.min(dial.max()))
+        };
+        let delay = self.turn_delay(player_idx);
+        let aim = match self.opponent {
+            Some(opponent) if self.is_bot(player_idx) => Some(opponent.aim(target, dial, &mut self.rng)),
+            None if self.is_bot(player_idx) => Some(self.bot_aim(target, delay)?),
+            _ => stop_at,
+        };
+        let mut counter = Counter::with_dial(dial);
+        let tracker = self.config.drift_ticks
+            .map(|drift_ticks| TargetTracker::new(target, drift_ticks, dial, self.rng.random()));
+        let moving_target = tracker.as_ref().map(TargetTracker::shared);
+        let events = self.counter_events(counter.events())?;
+        // Capture the join handle from display_counter:
+        let ui_handle = self.renderer.display_counter(
+            events,
+            CounterFrame {
+                view: self.config.counter_view,
+                dial,
+                target,
+                moving_target: moving_target.clone(),
+                hide_target: self.targets_hidden(),
+                hints: self.hidden_target(),
+                combo,
+                beat,
+                ghost,
+                started: target_started,
+            },
+            self.audio.clone(),
+        )?;
+        match tracker {
+            Some(tracker) => counter.start_with_target(&self.engine()?, delay, tracker)?,
+            None => counter.start(&self.engine()?, delay)?,
+        }
+        self.input.expect_player(player_idx);
+        let started = Instant::now();
+        let stopped = match aim {
+            Some(aim) => {
+                counter.wait_for(aim);
+                self.renderer.new_line().map(|_| Duration::ZERO)
+            },
+            None => self.wait_for_stop(&mut counter, self.config.keys.get(player_idx)),
+        };
+        let (mut value, mut miss) = counter.stop();
+        // Credit back the time the key press took to reach the game
+        if aim.is_none() {
+            (value, miss) = dial.rewind(value, miss, calibration::credit_ticks(self.config.latency_credit_ms, delay, dial));
+        }
+        // A moving target is scored where it was when the counter stopped
+        let target = moving_target.map_or(target, |target| *target.lock().unwrap());
+        // Wait for the display task to finish, even if the wait was interrupted
+        runtime::join(ui_handle)?;
+        // The time spent reading the help does not count
+        let reaction_ms = started.elapsed().saturating_sub(stopped?).as_millis() as u64;
+        
+        // Clear the counter display before printing final result
+        self.renderer.clear_counter(self.config.counter_view)?;
+
+        // Small pause
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        
+        Ok((target, value, miss, reaction_ms))
+    }
+    
+    /// Waits for the player to stop the counter, showing the help whenever they ask for it.
+    ///
+    /// The counter is paused while the help is shown, so reading it costs the player nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - The running counter
+    /// * `key` - The player's stop key, or None to stop with ENTER
+    ///
+    /// # Returns
+    ///
+    /// Result containing how long the counter was paused
+    fn wait_for_stop(&mut self, counter: &mut Counter, key: Option<char>) -> GameResult<Duration> {
+        let mut paused = Duration::ZERO;
+        loop {
+            let help = match key {
+                Some(key) => self.input.wait_for_key(&[key, HELP_KEY])? == 1,
+                None => self.input.read_line()?.trim() == HELP_COMMAND,
+            };
+            if !help {
+                if key.is_some() {
+                    self.renderer.new_line()?;
+                }
+                return Ok(paused);
+            }
+            let since = Instant::now();
+            counter.pause();
+            // The display moves off the counter before the panel is drawn
+            std::thread::sleep(OVERLAY_DELAY);
+            self.renderer.show_overlay(&mut *self.input, &help::help_lines(&self.config))?;
+            counter.resume();
+            paused += since.elapsed();
+            self.help_pause += since.elapsed();
+        }
+    }
+    
+    /// Passes a counter's events through the observers, if any, on their way to the display.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The counter's events
+    ///
+    /// # Returns
+    ///
+    /// Result containing the events for the display
+    fn counter_events(&self, events: CounterEvents) -> GameResult<CounterEvents> {
+        if self.observers.is_empty() {
+            return Ok(events);
+        }
+        observer::relay_ticks(events, self.observers.clone())
+    }
+    
+    /// Returns the delay between two counter ticks on a player's turn, as the counter runs it.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player taking the turn
+    ///
+    /// # Returns
+    ///
+    /// The delay in milliseconds, after the stage, the weather and the opponent's quirk
+    fn turn_delay(&self, player_idx: usize) -> u32 {
+        let delay = self.counter_delay(self.config.rules.tick_interval(&self.state.players[player_idx]));
+        match self.opponent {
+            Some(opponent) if !self.is_bot(player_idx) => opponent.quirk.counter_delay(delay),
+            _ => delay,
+        }
+    }
+    
+    /// Shows how fast the counter will run for the next target, and how hard that makes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player taking the turn
+    fn show_difficulty(&mut self, player_idx: usize) {
+        if self.is_bot(player_idx) || !self.config.verbosity.shows_targets() {
+            return;
+        }
+        let delay = self.turn_delay(player_idx);
+        let dial = self.config.rules.dial();
+        let difficulty = counter::difficulty_estimate(dial, delay);
+        let level = match difficulty.level {
+            DifficultyLevel::Easy => Msg::DifficultyEasy,
+            DifficultyLevel::Normal => Msg::DifficultyNormal,
+            DifficultyLevel::Hard => Msg::DifficultyHard,
+            DifficultyLevel::Extreme => Msg::DifficultyExtreme,
+        };
+        let lap_seconds = format!("{:.1}", f64::from(difficulty.lap_ms) / 1000.0);
+        // Values as the counter and the scoreboard show them, in tenths with a fine precision
+        self.renderer.info(&tr!(Msg::TargetDifficulty, dial.format(dial.ticks(1)), delay,
+                                dial.format(0), dial.format(dial.max()), lap_seconds, i18n::text(level)));
+    }
+    
+    /// Adjusts the delay between two counter ticks to the stage and the weather of the round.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The delay set by the players' attributes, in milliseconds
+    ///
+    /// # Returns
+    ///
+    /// The delay the counter runs with
+    fn counter_delay(&self, delay: u32) -> u32 {
+        let delay = self.config.rules.speed_curve.counter_delay(delay, self.state.round);
+        self.modifier.counter_delay(delay)
+    }
+    
+    /// Returns the engine running the counters, starting it on first use.
+    ///
+    /// # Returns
+    ///
+    /// Result containing a handle to the engine, or an error if it could not be started
+    fn engine(&mut self) -> GameResult<TurnEngine> {
+        if let Some(engine) = &self.engine {
+            return Ok(engine.clone());
+        }
+        let engine = TurnEngine::new()?;
+        self.engine = Some(engine.clone());
+        Ok(engine)
+    }
+    
+    /// Asks the external bot where it stops the counter, starting it on first use.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target, in ticks
+    /// * `delay` - The delay between two counter ticks, in milliseconds
+    ///
+    /// # Returns
+    ///
+    /// Result containing the tick to stop at, or an error if the bot could not be started or did not answer
+    fn bot_aim(&mut self, target: u32, delay: u32) -> GameResult<u32> {
+        let bot = match &mut self.bot {
+            Some(bot) => bot,
+            None => {
+                let command = self.config.bot_command.as_deref().unwrap_or_default();
+                self.bot.insert(BotProcess::spawn(command, BOT_TIMEOUT)?)
+            },
+        };
+        bot.aim(&BotRequest {
+            round: self.state.round,
+            target,
+            tick_ms: delay,
+            max_tick: self.config.rules.dial().max(),
+            vitality: self.state.players.each_ref().map(Player::vitality),
+        })
+    }
+    
+    /// Returns whether a player is the computer opponent or an external bot.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    ///
+    /// # Returns
+    ///
+    /// True for player 2 in a game against a computer opponent or with `--bot-command`
+    fn is_bot(&self, player_idx: usize) -> bool {
+        player_idx == 1 && (self.opponent.is_some() || self.config.bot_command.is_some())
+    }
+    
+    /// Returns the number of ticks between two beats, in rhythm mode.
+    ///
+    /// # Returns
+    ///
+    /// The beat interval in ticks, or None if the counter keeps no rhythm
+    fn beat_ticks(&self) -> Option<u32> {
+        self.config.beat.map(|units| self.config.rules.dial().ticks(units))
+    }
+    
+    /// Returns whether the targets are kept off the screen while the counter runs.
+    ///
+    /// # Returns
+    ///
+    /// True in hidden target and memory modes, and in fog
+    fn targets_hidden(&self) -> bool {
+        self.hidden_target() || self.config.memory_seconds.is_some()
+    }
+    
+    /// Returns whether the targets are never shown, with warmer/colder hints instead.
+    ///
+    /// # Returns
+    ///
+    /// True in hidden target mode, and in fog
+    fn hidden_target(&self) -> bool {
+        self.config.hidden_target || self.modifier.hides_targets()
+    }
+    
+    /// Asks the player how much they risk on the next target, with a single key press.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    ///
+    /// # Returns
+    ///
+    /// Result containing the risk tier, `RiskTier::Normal` if risk tiers are off or for the computer opponent
+    fn ask_risk(&mut self, player_idx: usize) -> GameResult<RiskTier> {
+        if !self.config.risk_tiers || self.is_bot(player_idx) {
+            return Ok(RiskTier::Normal);
+        }
+        self.renderer.info(&tr!(Msg::RiskPrompt, RISK_KEYS[0], scoring::SAFE_CAP, RISK_KEYS[1], RISK_KEYS[2]));
+        self.input.expect_player(player_idx);
+        let risk = RiskTier::ALL[self.input.wait_for_key(&RISK_KEYS)?];
+        self.renderer.new_line()?;
+        Ok(risk)
+    }
+    
+    /// Offers the player to spend stamina on a double or nothing gamble for the next target.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    /// * `target` - The target about to be played
+    ///
+    /// # Returns
+    ///
+    /// Result containing true if the player gambles on the target
+    fn offer_gamble(&mut self, player_idx: usize, target: u32) -> GameResult<bool> {
+        let stamina = self.state.players[player_idx].stamina();
+        if stamina < GAMBLE_COST || self.is_bot(player_idx) {
+            return Ok(false);
+        }
+        
+        let prompt = if self.targets_hidden() {
+            tr!(Msg::GamblePromptHidden, GAMBLE_COST, stamina)
+        } else {
+            tr!(Msg::GamblePrompt, self.config.rules.dial().format(target), GAMBLE_COST, stamina)
+        };
+        self.input.expect_player(player_idx);
+        if !self.renderer.confirm(&mut *self.input, &prompt)? {
+            return Ok(false);
+        }
+        
+        self.state.players[player_idx].decrease_stamina(GAMBLE_COST);
+        self.events.record(GameEvent::Gamble {
+            round: self.state.round,
+            player: player_idx,
+            target,
+            stamina: self.state.players[player_idx].stamina(),
+        })?;
+        Ok(true)
+    }
+    
+    /// Generates random targets for a turn, as many as the round calls for, then lets the plugins adjust them.
+    ///
+    /// # Returns
+    ///
+    /// A vector of random target numbers
+    fn generate_targets(&mut self) -> Vec<u32> {
+        self.draw_targets(self.config.targets_in_round(self.state.round))
+    }
+    
+    /// Draws random targets, then lets the plugins adjust them.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - How many targets to draw
+    ///
+    /// # Returns
+    ///
+    /// A vector of random target numbers
+    fn draw_targets(&mut self, count: usize) -> Vec<u32> {
+        let mut targets = self.config.rules.targets(&mut self.rng, count);
+        self.config.plugins.targets(&mut targets, self.config.rules.counter_max);
+        targets
+    }
+    
+    /// Asks both players how much vitality they wager on the round.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the wagers could be read and recorded
+    fn collect_wagers(&mut self) -> GameResult<()> {
+        for player_idx in 0..2 {
+            let player = &self.state.players[player_idx];
+            let max = player.vitality();
+            // The computer opponent never wagers
+            let wager = if self.is_bot(player_idx) {
+                0
+            } else {
+                let prompt = tr!(Msg::WagerPrompt, player.name(), max);
+                self.input.expect_player(player_idx);
+                self.renderer.get_wager(&mut *self.input, &prompt, max)?
+            };
+            
+            self.wagers[player_idx] = wager;
+            self.events.record(GameEvent::Wager { round: self.state.round, player: player_idx, amount: wager })?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Processes the result of a round and applies penalties.
+    ///
+    /// The winner also takes the pot of both wagers: the loser loses it as
+    /// extra damage. On a draw the wagers are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `turns` - How each player's turn went
+    ///
+    /// # Returns
+    ///
+    /// Result containing what the round did to the players, and the penalty the winner chose
+    fn process_round_result(&mut self, turns: [TurnOutcome; 2]) -> GameResult<(RoundOutcome, Option<(Attribute, u32)>)> {
+        // In blind mode, both turns are revealed only now
+        let hidden_results = std::mem::take(&mut self.hidden_results);
+        if !hidden_results.is_empty() {
+            self.renderer.info(&tr!(Msg::BlindReveal));
+            for line in &hidden_results {
+                self.renderer.info(line);
+            }
+        }
+        let pot: u32 = std::mem::take(&mut self.wagers).iter().sum();
+        // Good predictions count towards the round, not the turn average
+        let bonuses = std::mem::take(&mut self.prediction_bonuses);
+        let scored = [0, 1].map(|player_idx| turns[player_idx].with_bonus(bonuses[player_idx]));
+        let (state, outcome) = self.state.resolve_round(scored, pot, self.config.rules.damage, &self.config.plugins);
+        self.state = state;
+        self.state.first_player = self.config.rules.turn_order.next_first(self.state.first_player, &outcome);
+        
+        let mut penalty = None;
+        match outcome {
+            RoundOutcome::Win { winner, loser, damage, pot, .. } => {
+                self.record_vitality_change(loser, damage + pot)?;
+                let (winner_name, loser_name) = (self.state.players[winner].name(), self.state.players[loser].name());
+                if turns[loser] == TurnOutcome::Passed {
+                    self.renderer.info(&tr!(Msg::PassedRoundWin, winner_name, loser_name));
+                } else {
+                    self.renderer.info(&tr!(Msg::RoundWin, winner_name, loser_name, damage));
+                }
+                self.announce_pot(winner, loser, pot);
+                self.show_vitality(loser);
+                
+                if self.state.players[loser].vitality() > 0 {
+                    penalty = Some(self.apply_penalty(winner, loser)?);
+                    if self.config.consolation && self.state.in_progress() {
+                        self.apply_consolation(loser)?;
+                    }
+                }
+            },
+            RoundOutcome::Draw => {
+                self.renderer.info(&tr!(Msg::Draw));
+                if pot > 0 {
+                    self.renderer.info(&tr!(Msg::WagersReturned));
+                }
+            },
+        }
+        
+        Ok((outcome, penalty))
+    }
+    
+    /// Ends the match if a plugin, such as a mutator or the win condition, decides who lost or won it.
+    fn check_plugins(&mut self) {
+        if !self.state.in_progress() {
+            return;
+        }
+        let (winner_idx, message) = if let Some((loser_idx, plugin)) = self.config.plugins.loser(&self.state.tally) {
+            (1 - loser_idx, tr!(Msg::MutatorLoss, self.state.players[loser_idx].name(), plugin))
+        } else if let Some(verdict) = self.config.plugins.winner(&self.state) {
+            verdict
+        } else {
+            return;
+        };
+        self.renderer.info(&message);
+        self.state.game_over = true;
+        self.state.winner_idx = Some(winner_idx);
+    }
+    
+    /// Restores vitality to the players who made enough exact hits during the round.
+    ///
+    /// Players who were knocked out this round are not healed.
+    ///
+    /// # Arguments
+    ///
+    /// * `exact_hits` - The number of exact hits of each player during the round
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the healing could be recorded
+    fn apply_healing(&mut self, exact_hits: [u32; 2]) -> GameResult<()> {
+        let threshold = self.config.heal_hits;
+        if threshold == 0 {
+            return Ok(());
+        }
+        
+        for (player_idx, hits) in exact_hits.into_iter().enumerate() {
+            let player = &mut self.state.players[player_idx];
+            if hits < threshold || player.vitality() == 0 {
+                continue;
+            }
+            
+            let before = player.vitality();
+            player.increase_vitality(self.config.heal_amount);
+            let amount = player.vitality() - before;
+            if amount == 0 {
+                continue;
+            }
+            
+            self.renderer.info(&tr!(Msg::Healed, player.name(), hits, amount));
+            self.show_vitality(player_idx);
+            self.events.record(GameEvent::Heal {
+                round: self.state.round,
+                player: player_idx,
+                amount,
+                vitality: self.state.players[player_idx].vitality(),
+            })?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Plays the reaction game between rounds and hands the stolen vitality to its winner.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the game could be played and recorded
+    fn play_reaction_game(&mut self) -> GameResult<()> {
+        let (Some(key1), Some(key2)) = (self.config.keys.get(0), self.config.keys.get(1)) else {
+            return Err(GameError::ConfigError("The reaction game needs a stop key for each player".to_string()));
+        };
+        let delay = minigames::reaction_delay(&mut self.rng);
+        let [player1, player2] = &self.state.players;
+        let names = [player1.name(), player2.name()];
+        let result = minigames::play_reaction(&mut *self.renderer, &mut *self.input, names, [key1, key2], delay)?;
+        self.apply_reaction_result(result)
+    }
+    
+    /// Moves vitality from the loser of the reaction game to its winner.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The outcome of the reaction game
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the outcome could be recorded
+    fn apply_reaction_result(&mut self, result: ReactionResult) -> GameResult<()> {
+        let (winner_idx, loser_idx) = (result.winner, result.loser());
+        let loser = &mut self.state.players[loser_idx];
+        let before = loser.vitality();
+        loser.decrease_vitality(REACTION_STEAL);
+        let stolen = before - loser.vitality();
+        let winner = &mut self.state.players[winner_idx];
+        let before = winner.vitality();
+        winner.increase_vitality(stolen);
+        let gained = winner.vitality() - before;
+        
+        let [winner, loser] = [&self.state.players[winner_idx], &self.state.players[loser_idx]];
+        match result.reaction_ms {
+            Some(ms) => self.renderer.info(&tr!(Msg::ReactionWin, winner.name(), ms, stolen, loser.name())),
+            None => self.renderer.info(&tr!(Msg::ReactionFalseStart, loser.name(), winner.name(), stolen)),
+        }
+        self.show_vitality(loser_idx);
+        self.show_vitality(winner_idx);
+        
+        self.events.record(GameEvent::ReactionGame {
+            round: self.state.round,
+            winner: winner_idx,
+            reaction_ms: result.reaction_ms,
+        })?;
+        self.record_vitality_change(loser_idx, stolen)?;
+        if gained > 0 {
+            self.events.record(GameEvent::Heal {
+                round: self.state.round,
+                player: winner_idx,
+                amount: gained,
+                vitality: self.state.players[winner_idx].vitality(),
+            })?;
+        }
+        Ok(())
+    }
+    
+    /// Announces that the round winner takes the pot, if anything was wagered.
+    ///
+    /// # Arguments
+    ///
+    /// * `winner_idx` - The index of the winning player
+    /// * `loser_idx` - The index of the losing player
+    /// * `pot` - The sum of both wagers
+    fn announce_pot(&mut self, winner_idx: usize, loser_idx: usize, pot: u32) {
+        if pot > 0 {
+            self.renderer.info(&tr!(Msg::PotWon,
+                       self.state.players[winner_idx].name(), pot, self.state.players[loser_idx].name(), pot));
+        }
+    }
+    
+    /// Prints a player's vitality bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    fn show_vitality(&mut self, player_idx: usize) {
+        let player = &self.state.players[player_idx];
+        self.renderer.info(&tr!(Msg::VitalityBar,
+                   player.name(), ui::render_hp_bar(player.vitality(), player.max_vitality())));
+    }
+    
+    /// Records that a player lost vitality.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    /// * `amount` - The vitality lost
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the event could be recorded
+    fn record_vitality_change(&mut self, player_idx: usize, amount: u32) -> GameResult<()> {
+        let vitality = self.state.players[player_idx].vitality();
+        self.events.record(GameEvent::VitalityChange {
+            round: self.state.round,
+            player: player_idx,
+            amount,
+            vitality,
+        })
+    }
+    
+    /// Applies a penalty chosen by the winner to the loser.
+    ///
+    /// # Arguments
+    ///
+    /// * `winner_idx` - The index of the winning player
+    /// * `loser_idx` - The index of the losing player
+    /// * `test_choice` - Optional test choice for automated testing
+    ///
+    /// # Returns
+    ///
+    /// Result containing the penalty applied, and how much it took
+    fn apply_penalty(&mut self, winner_idx: usize, loser_idx: usize) -> GameResult<(Attribute, u32)> {
+        let amount = match self.opponent {
+            Some(opponent) if self.is_bot(winner_idx) => opponent.quirk.penalty_amount(),
+            _ => PENALTY_AMOUNT,
+        };
+        let amount = self.config.plugins.penalty(amount);
+        // Only the penalties left to the winner after the draft can be chosen
+        let penalties: Vec<Attribute> = [Attribute::Speed, Attribute::Strength].into_iter()
+            .filter(|&attribute| self.config.rules.pool.allows_for(DraftItem::Penalty(attribute), winner_idx))
+            .collect();
+        let options: Vec<String> = penalties.iter().map(|&attribute| penalty_label(attribute, amount)).collect();
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        if options.len() > 1 {
+            self.renderer.info(&tr!(Msg::ChoosePoison,
+                       self.state.players[winner_idx].name(), self.state.players[loser_idx].name()));
+        }
+        // The computer opponent picks at random, and so does the timer when it runs out
+        self.input.expect_player(winner_idx);
+        let choice = if options.len() == 1 {
+            0
+        } else if self.is_bot(winner_idx) {
+            self.rng.random_range(0..options.len())
+        } else if let Some(seconds) = self.config.penalty_seconds {
+            let timeout = Duration::from_secs(seconds);
+            match self.renderer.get_timed_choice(&mut *self.input, i18n::text(Msg::ChoosePenalty), &options, timeout)? {
+                Some(choice) => choice,
+                None => {
+                    self.renderer.info(&tr!(Msg::PenaltyTimeout));
+                    self.rng.random_range(0..options.len())
+                },
+            }
+        } else {
+            self.renderer.get_user_choice(&mut *self.input, i18n::text(Msg::ChoosePenalty), &options)?
+        };
+        
+        // get_user_choice and get_timed_choice ensure a valid index
+        let attribute = penalties[choice];
+        self.events.record(GameEvent::Penalty {
+            round: self.state.round,
+            winner: winner_idx,
+            loser: loser_idx,
+            attribute,
+            amount,
+        })?;
+        
+        let bounds = self.config.rules.bounds;
+        let player = &mut self.state.players[loser_idx];
+        let at_floor = match attribute {
+            Attribute::Speed => {
+                let before = player.speed();
+                player.decrease_speed(amount);
+                let lost = before - player.speed();
+                if lost > 0 {
+                    self.renderer.info(&tr!(Msg::SpeedReduced, player.name(), lost));
+                }
+                player.speed() == bounds.speed.min
+            },
+            Attribute::Strength => {
+                let before = player.strength();
+                player.decrease_strength(amount);
+                let lost = before - player.strength();
+                if lost > 0 {
+                    self.renderer.info(&tr!(Msg::StrengthReduced, player.name(), lost));
+                }
+                player.strength() == bounds.strength.min
+            },
+        };
+        
+        if !at_floor {
+            return Ok((attribute, amount));
+        }
+        let name = self.state.players[loser_idx].name();
+        match (attribute, bounds.speed_floor) {
+            // Reaching the speed floor loses the match, unless the rules keep speed there
+            (Attribute::Speed, SpeedFloor::Knockout) => {
+                self.renderer.info(&tr!(Msg::SpeedKnockout, name, bounds.speed.min));
+                self.state.game_over = true;
+                self.state.winner_idx = Some(winner_idx);
+            },
+            (Attribute::Speed, SpeedFloor::Clamp) => self.renderer.info(&tr!(Msg::SpeedAtFloor, name)),
+            (Attribute::Strength, _) => self.renderer.info(&tr!(Msg::StrengthAtFloor, name)),
+        }
+        
+        Ok((attribute, amount))
+    }
+    
+    /// Lets each player in turn ban a penalty or bonus from the match, then pick one for themselves, player 1 first.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the choices could be read
+    fn play_draft(&mut self) -> GameResult<()> {
+        self.renderer.info_wrapped(&tr!(Msg::DraftStart));
+        for (picking, player_idx) in [(false, 0), (false, 1), (true, 0), (true, 1)] {
+            let pool = self.config.rules.pool;
+            let items: Vec<DraftItem> = DRAFT_ITEMS.into_iter()
+                .filter(|&item| if picking { pool.can_pick(item, player_idx) } else { pool.can_ban(item) })
+                .collect();
+            let options: Vec<String> = items.iter().map(|&item| draft_label(item)).collect();
+            let options: Vec<&str> = options.iter().map(String::as_str).collect();
+            let name = self.state.players[player_idx].name();
+            // The computer opponent bans and picks at random
+            let choice = if self.is_bot(player_idx) {
+                self.rng.random_range(0..options.len())
+            } else {
+                let prompt = if picking { tr!(Msg::DraftPick, name) } else { tr!(Msg::DraftBan, name) };
+                self.input.expect_player(player_idx);
+                self.renderer.get_user_choice(&mut *self.input, &prompt, &options)?
+            };
+            if picking {
+                self.config.rules.pool.pick(items[choice], player_idx);
+                self.renderer.info(&tr!(Msg::DraftPicked, name, options[choice]));
+            } else {
+                self.config.rules.pool.ban(items[choice]);
+                self.renderer.info(&tr!(Msg::DraftBanned, name, options[choice]));
+            }
+        }
+        // Saved with the match, so a resumed match keeps it
+        self.state.draft = Some(self.config.rules.pool);
+        Ok(())
+    }
+    
+    /// Lets the loser of a round pick a consolation bonus.
+    ///
+    /// # Arguments
+    ///
+    /// * `loser_idx` - The index of the round loser
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the choice could be read and recorded
+    fn apply_consolation(&mut self, loser_idx: usize) -> GameResult<()> {
+        // Only the bonuses left to the loser after the draft can be picked
+        let bonuses: Vec<(Consolation, u32)> = CONSOLATIONS.into_iter()
+            .filter(|&(bonus, _)| self.config.rules.pool.allows_for(DraftItem::Bonus(bonus), loser_idx))
+            .collect();
+        let options: Vec<String> = bonuses.iter().map(|&(bonus, amount)| consolation_label(bonus, amount)).collect();
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        // The computer opponent picks at random
+        self.input.expect_player(loser_idx);
+        let choice = if options.len() == 1 {
+            0
+        } else if self.is_bot(loser_idx) {
+            self.rng.random_range(0..options.len())
+        } else {
+            let prompt = tr!(Msg::ChooseConsolation, self.state.players[loser_idx].name());
+            self.renderer.get_user_choice(&mut *self.input, &prompt, &options)?
+        };
+        let (bonus, amount) = bonuses[choice];
+        
+        // Attributes never go above their starting value, so the bonus may be smaller
+        let player = &mut self.state.players[loser_idx];
+        let gained = match bonus {
+            Consolation::Speed => {
+                let before = player.speed();
+                player.increase_speed(amount);
+                player.speed() - before
+            },
+            Consolation::Strength => {
+                let before = player.strength();
+                player.increase_strength(amount);
+                player.strength() - before
+            },
+            Consolation::Vitality => {
+                let before = player.vitality();
+                player.increase_vitality(amount);
+                player.vitality() - before
+            },
+        };
+        self.renderer.info(&tr!(Msg::ConsolationGained, player.name(), consolation_label(bonus, gained)));
+        self.events.record(GameEvent::Consolation {
+            round: self.state.round,
+            player: loser_idx,
+            bonus,
+            amount: gained,
+        })
+    }
+}
+
+/// Returns the message announcing the weather of a round.
+///
+/// # Arguments
+///
+/// * `modifier` - The weather
+///
+/// # Returns
+///
+/// The message key
+fn weather_message(modifier: RoundModifier) -> Msg {
+    match modifier {
+        RoundModifier::Clear => Msg::WeatherClear,
+        RoundModifier::Fog => Msg::WeatherFog,
+        RoundModifier::Storm => Msg::WeatherStorm,
+        RoundModifier::Calm => Msg::WeatherCalm,
+    }
+}
+
+/// Lines both players' turns up target by target.
+///
+/// Each cell shows where the player stopped, the target and how far from it
+/// they were. The last column names the player who stopped closer.
+///
+/// # Arguments
+///
+/// * `results` - How each player's turn went
+/// * `names` - The players' names
+/// * `dial` - The values the counter runs through
+///
+/// # Returns
+///
+/// The rows of the recap, and the number of targets each player stopped closer to
+fn round_recap(results: &[TurnResult; 2], names: &[String; 2], dial: Dial) -> (Vec<Vec<String>>, [u32; 2]) {
+    let mut closer = [0; 2];
+    let rows = results[0].targets.iter().zip(&results[1].targets).enumerate()
+        .map(|(idx, (first, second))| {
+            let distances = [first, second].map(|result| scoring::difference(result.target, result.value, dial));
+            let cells = [(first, distances[0]), (second, distances[1])].map(|(result, distance)| {
+                tr!(Msg::RecapCell, dial.format(result.value), dial.format(result.target), dial.format(distance))
+            });
+            let winner = match distances[0].cmp(&distances[1]) {
+                Ordering::Less => Some(0),
+                Ordering::Greater => Some(1),
+                Ordering::Equal => None,
+            };
+            if let Some(player_idx) = winner {
+                closer[player_idx] += 1;
+            }
+            let [first, second] = cells;
+            vec![(idx + 1).to_string(), first, second, winner.map_or_else(|| tr!(Msg::RecapTie), |player_idx| names[player_idx].clone())]
+        })
+        .collect();
+    (rows, closer)
+}
+
+/// Describes a penalty, as offered to the winner of a round.
+///
+/// # Arguments
+///
+/// * `attribute` - The attribute the penalty lowers
+/// * `amount` - How much it lowers it
+///
+/// # Returns
+///
+/// The penalty as shown to the players, e.g. "-5 speed"
+pub(crate) fn penalty_label(attribute: Attribute, amount: u32) -> String {
+    match attribute {
+        Attribute::Speed => tr!(Msg::PenaltySpeed, amount),
+        Attribute::Strength => tr!(Msg::PenaltyStrength, amount),
+    }
+}
+
+/// Describes a penalty or bonus, as offered in the draft.
+///
+/// # Arguments
+///
+/// * `item` - The penalty or bonus
+///
+/// # Returns
+///
+/// The item as shown to the players, with the amount a human player deals or gains
+pub(crate) fn draft_label(item: DraftItem) -> String {
+    match item {
+        DraftItem::Penalty(attribute) => penalty_label(attribute, PENALTY_AMOUNT),
+        DraftItem::Bonus(bonus) => {
+            let amount = CONSOLATIONS.iter().find(|&&(other, _)| other == bonus).map_or(0, |&(_, amount)| amount);
+            consolation_label(bonus, amount)
+        },
+    }
+}
+
+/// Describes a consolation bonus, as offered to the loser of a round.
+///
+/// # Arguments
+///
+/// * `bonus` - The attribute the bonus raises
+/// * `amount` - How much it raises it
+///
+/// # Returns
+///
+/// The bonus as shown to the players, e.g. "+3 speed"
+fn consolation_label(bonus: Consolation, amount: u32) -> String {
+    match bonus {
+        Consolation::Speed => tr!(Msg::ConsolationSpeed, amount),
+        Consolation::Strength => tr!(Msg::ConsolationStrength, amount),
+        Consolation::Vitality => tr!(Msg::ConsolationVitality, amount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::KeyBindings;
+    use super::*;
+    use crate::campaign::{OPPONENTS, Quirk};
+    use crate::mutators;
+    use crate::plugins::PluginRegistry;
+    use crate::rules::RuleSet;
+    use crate::ui::render::RenderMode;
+    use crate::rules::Bounds;
+    use crate::ui::source::ScriptedInput;
+    use crate::state::testing::played;
+    use crate::ui::source::testing::scripted;
+
+    /// Builds the configuration shared by the tests below.
+    fn test_config() -> GameConfig {
+        GameConfig {
+            player1_name: "Player1".to_string(),
+            player2_name: "Player2".to_string(),
+            vitality: 100,
+            speed: 60,
+            strength: 70,
+            target_count: 5,
+            autosave: false,
+            render_mode: RenderMode::Null,
+            ..GameConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_game_creation() {
+        let game = Game::new(test_config());
+        
+        assert_eq!(game.state.players[0].name(), "Player1");
+        assert_eq!(game.state.players[1].name(), "Player2");
+        assert_eq!(game.state.players[0].vitality(), 100);
+        assert_eq!(game.state.players[1].vitality(), 100);
+        assert_eq!(game.state.players[0].speed(), 60);
+        assert_eq!(game.state.players[0].strength(), 70);
+    }
+
+    #[test]
+    fn test_generate_targets() {
+        let mut game = Game::new(test_config());
+        
+        let targets = game.generate_targets();
+        
+        // Check that the correct number of targets is generated
+        assert_eq!(targets.len(), 5);
+        
+        // Check that all targets are within range
+        for target in targets {
+            assert!(target <= 100);
+        }
+    }
+    
+    #[test]
+    fn test_registered_plugin_adjusts_the_targets() {
+        /// Replaces every target with the middle of the counter.
+        #[derive(Debug, Clone, Copy)]
+        struct Centered;
+        
+        impl GamePlugin for Centered {
+            fn name(&self) -> &'static str {
+                "centered"
+            }
+            
+            fn targets(&self, targets: &mut Vec<u32>, counter_max: u32) {
+                targets.iter_mut().for_each(|target| *target = counter_max / 2);
+            }
+            
+            fn clone_box(&self) -> Box<dyn GamePlugin> {
+                Box::new(*self)
+            }
+        }
+        
+        let mut game = Game::new(test_config()).with_plugin(Centered);
+        
+        assert_eq!(game.generate_targets(), [50; 5]);
+    }
+    
+    #[test]
+    fn test_targets_follow_counter_range() {
+        let rules = RuleSet { counter_max: 360, ..RuleSet::default() };
+        let mut game = Game::new(GameConfig { rules, target_count: 200, seed: Some(3), ..test_config() });
+        
+        let targets = game.generate_targets();
+        assert!(targets.iter().all(|&target| target <= 360));
+        assert!(targets.iter().any(|&target| target > 100));
+    }
+    
+    #[test]
+    fn test_seeded_targets_are_reproducible() {
+        let config = GameConfig { seed: Some(42), ..test_config() };
+        let mut game1 = Game::new(config.clone());
+        let mut game2 = Game::new(config);
+        
+        assert_eq!(game1.generate_targets(), game2.generate_targets());
+        assert_eq!(game1.generate_targets(), game2.generate_targets());
+    }
+    
+    #[test]
+    fn test_process_round_result_player1_wins() {
+        let mut game = Game::new(test_config()).with_input(scripted(&["1"]));
+        
+        // The winner picks the first penalty, speed
+        let result = game.process_round_result(played([100, 50]));
+        
+        assert!(result.is_ok());
+        
+        // Verify that player2's vitality and speed were reduced
+        assert_eq!(game.state.players[1].vitality(), 50); // 100 - (100 - 50)
+        assert_eq!(game.state.players[1].speed(), 55);    // 60 - 5
+    }
+    
+    #[test]
+    fn test_process_round_result_player2_wins() {
+        let mut game = Game::new(test_config()).with_input(scripted(&["2"]));
+        
+        // The winner picks the second penalty, strength
+        let result = game.process_round_result(played([50, 100]));
         
         assert!(result.is_ok());
         
         // Verify that player1's vitality and strength were reduced
-        assert_eq!(game.players[0].vitality(), 50); // 100 - (100 - 50)
-        assert_eq!(game.players[0].strength(), 65); // 70 - 5
+        assert_eq!(game.state.players[0].vitality(), 50); // 100 - (100 - 50)
+        assert_eq!(game.state.players[0].strength(), 65); // 70 - 5
     }
     
     #[test]
     fn test_process_round_result_draw() {
-        let mut game = Game::new(
-            "Player1".to_string(), 
-            "Player2".to_string(),
-            100, 60, 70, 5
-        );
+        let mut game = Game::new(test_config());
         
         // In a draw, no penalties are applied
-        let result = game.process_round_result(50, 50, None);
+        let result = game.process_round_result(played([50, 50]));
         
         assert!(result.is_ok());
         
         // Verify that no attributes were changed
-        assert_eq!(game.players[0].vitality(), 100);
-        assert_eq!(game.players[1].vitality(), 100);
-        assert_eq!(game.players[0].speed(), 60);
-        assert_eq!(game.players[1].speed(), 60);
-        assert_eq!(game.players[0].strength(), 70);
-        assert_eq!(game.players[1].strength(), 70);
+        assert_eq!(game.state.players[0].vitality(), 100);
+        assert_eq!(game.state.players[1].vitality(), 100);
+        assert_eq!(game.state.players[0].speed(), 60);
+        assert_eq!(game.state.players[1].speed(), 60);
+        assert_eq!(game.state.players[0].strength(), 70);
+        assert_eq!(game.state.players[1].strength(), 70);
+    }
+    
+    #[test]
+    fn test_process_round_result_pot() {
+        let mut game = Game::new(test_config()).with_input(scripted(&["2"]));
+        game.wagers = [10, 20];
+        
+        game.process_round_result(played([80, 50])).unwrap();
+        
+        // Player2 loses the score difference plus both wagers
+        assert_eq!(game.state.players[0].vitality(), 100);
+        assert_eq!(game.state.players[1].vitality(), 40);
+        assert_eq!(game.wagers, [0, 0]);
+    }
+    
+    #[test]
+    fn test_process_round_result_draw_returns_wagers() {
+        let mut game = Game::new(test_config());
+        game.wagers = [10, 20];
+        
+        game.process_round_result(played([70, 70])).unwrap();
+        
+        assert_eq!(game.state.players[0].vitality(), 100);
+        assert_eq!(game.state.players[1].vitality(), 100);
+        assert_eq!(game.wagers, [0, 0]);
+    }
+    
+    #[test]
+    fn test_prediction_bonus_counts_towards_the_round() {
+        let mut game = Game::new(GameConfig { predictions: true, ..test_config() }).with_input(scripted(&["1"]));
+        game.prediction_bonuses = [scoring::PREDICTION_BONUS, 0];
+        
+        game.process_round_result(played([50, 55])).unwrap();
+        
+        // 60 against 55 once player 1's bonus is added
+        assert_eq!(game.state.tally.round_wins, [1, 0]);
+        assert_eq!(game.state.players[1].vitality(), 95);
+        assert_eq!(game.prediction_bonuses, [0, 0]);
+    }
+
+    #[test]
+    fn test_round_winner_gains_momentum() {
+        let mut game = Game::new(test_config()).with_input(scripted(&["2", "2"]));
+        
+        game.process_round_result(played([50, 100])).unwrap();
+        game.process_round_result(played([90, 100])).unwrap();
+        
+        assert_eq!(game.state.players[0].momentum(), 0);
+        assert_eq!(game.state.players[1].momentum(), 2);
+    }
+    
+    #[test]
+    fn test_game_against_opponent() {
+        let game = Game::against(test_config(), OPPONENTS[0]);
+        
+        let opponent = &game.state.players[1];
+        assert_eq!(opponent.name(), "Rookie");
+        assert_eq!(opponent.vitality(), OPPONENTS[0].vitality);
+        assert_eq!(opponent.strength(), OPPONENTS[0].strength);
+        assert!(!game.is_bot(0));
+        assert!(game.is_bot(1));
+        assert!(!Game::new(test_config()).is_bot(1));
+    }
+    
+    #[test]
+    fn test_heavy_penalty_opponent() {
+        let brute = OPPONENTS.into_iter().find(|opponent| opponent.quirk == Quirk::HeavyPenalty).unwrap();
+        let mut game = Game::against(test_config(), brute);
+        
+        game.process_round_result(played([0, 30])).unwrap();
+        
+        // The opponent picks the attribute, either way it takes twice the usual points
+        let player = &game.state.players[0];
+        assert_eq!(player.speed() + player.strength(), 60 + 70 - 2 * PENALTY_AMOUNT);
+    }
+    
+    #[test]
+    fn test_apply_reaction_result() {
+        let mut game = Game::new(test_config());
+        game.state.players[1].decrease_vitality(10);
+        
+        game.apply_reaction_result(ReactionResult { winner: 1, reaction_ms: Some(240) }).unwrap();
+        
+        assert_eq!(game.state.players[0].vitality(), 100 - REACTION_STEAL);
+        assert_eq!(game.state.players[1].vitality(), 90 + REACTION_STEAL);
+        assert_eq!(game.events.events(), &[
+            GameEvent::ReactionGame { round: 1, winner: 1, reaction_ms: Some(240) },
+            GameEvent::VitalityChange { round: 1, player: 0, amount: 3, vitality: 97 },
+            GameEvent::Heal { round: 1, player: 1, amount: 3, vitality: 93 },
+        ]);
+    }
+    
+    #[test]
+    fn test_reaction_false_start_at_full_vitality() {
+        let mut game = Game::new(test_config());
+        
+        game.apply_reaction_result(ReactionResult { winner: 0, reaction_ms: None }).unwrap();
+        
+        // The winner cannot go above their maximum vitality
+        assert_eq!(game.state.players[0].vitality(), 100);
+        assert_eq!(game.state.players[1].vitality(), 100 - REACTION_STEAL);
+        assert_eq!(game.events.events().len(), 2);
+    }
+    
+    #[test]
+    fn test_steal_momentum() {
+        let mut game = Game::new(test_config());
+        game.state.players[1].increase_momentum(3);
+        
+        game.steal_momentum(0).unwrap();
+        
+        assert_eq!(game.state.players[0].momentum(), 3);
+        assert_eq!(game.state.players[1].momentum(), 0);
+        assert_eq!(game.events.events(), &[
+            GameEvent::MomentumSteal { round: 1, player: 0, from: 1, amount: 3 },
+        ]);
+        
+        // Nothing to steal back from an opponent without momentum
+        game.steal_momentum(0).unwrap();
+        assert_eq!(game.events.events().len(), 1);
+    }
+    
+    #[test]
+    fn test_apply_healing() {
+        let mut game = Game::new(GameConfig { heal_hits: 3, heal_amount: 10, ..test_config() });
+        game.state.players[0].decrease_vitality(30);
+        game.state.players[1].decrease_vitality(5);
+        
+        let result = game.apply_healing([3, 4]);
+        
+        assert!(result.is_ok());
+        assert_eq!(game.state.players[0].vitality(), 80);
+        // Healing never goes above the starting vitality
+        assert_eq!(game.state.players[1].vitality(), 100);
+        assert_eq!(game.events.events(), &[
+            GameEvent::Heal { round: 1, player: 0, amount: 10, vitality: 80 },
+            GameEvent::Heal { round: 1, player: 1, amount: 5, vitality: 100 },
+        ]);
+    }
+
+    #[test]
+    fn test_apply_healing_below_threshold() {
+        let mut game = Game::new(GameConfig { heal_hits: 3, heal_amount: 10, ..test_config() });
+        game.state.players[0].decrease_vitality(30);
+        game.state.players[1].decrease_vitality(100);
+        
+        let result = game.apply_healing([2, 5]);
+        
+        assert!(result.is_ok());
+        assert_eq!(game.state.players[0].vitality(), 70);
+        // Knocked out players are not healed
+        assert_eq!(game.state.players[1].vitality(), 0);
+        assert!(game.events.events().is_empty());
+    }
+
+    #[test]
+    fn test_scripted_match() {
+        let config = GameConfig { vitality: 1, stamina: 0, target_count: 1, seed: Some(1), ..test_config() };
+        // Each player starts their turn and stops the counter right away, on 0
+        let script = ScriptedInput::new(["0 enter", "0 enter", "0 enter", "0 enter"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script.clone());
+        
+        let winner = game.run_game_loop().unwrap();
+        
+        assert!(!game.state.in_progress());
+        assert_eq!(game.state.players[1 - winner].vitality(), 0);
+        let stops = game.events.events().iter()
+            .filter(|event| matches!(event, GameEvent::CounterStop { value: 0, .. }))
+            .count();
+        assert_eq!(stops, 2);
+        let rounds = game.state.history.rounds();
+        assert_eq!(rounds.len(), 1);
+        assert_eq!(rounds[0].players[1 - winner].vitality, 0);
+        // The whole script was played
+        assert!(script.clone().read_line().is_err());
+    }
+    
+    /// Writes down what it is told about a match.
+    #[derive(Debug, Default)]
+    struct Recorder(std::sync::Mutex<Vec<String>>);
+    
+    impl Observer for Recorder {
+        fn on_turn_start(&self, round: u32, player_idx: usize, targets: &[u32]) {
+            self.0.lock().unwrap().push(format!("turn {} {} {}", round, player_idx, targets.len()));
+        }
+        
+        fn on_score(&self, player_idx: usize, _target: u32, value: u32, _score: u32) {
+            self.0.lock().unwrap().push(format!("score {} {}", player_idx, value));
+        }
+        
+        fn on_game_over(&self, winner_idx: usize, state: &GameState) {
+            self.0.lock().unwrap().push(format!("over {} {}", winner_idx, state.game_over || !state.in_progress()));
+        }
+    }
+    
+    #[test]
+    fn test_help_leaves_the_turn_as_it_was() {
+        let config = GameConfig { vitality: 1, stamina: 0, target_count: 1, seed: Some(1), ..test_config() };
+        // Player 1 asks for the help before starting their turn
+        let script = ScriptedInput::new(["0 type ?", "0 enter", "0 enter", "0 enter", "0 enter"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script.clone());
+        
+        game.run_game_loop().unwrap();
+        
+        let stops = game.events.events().iter()
+            .filter(|event| matches!(event, GameEvent::CounterStop { value: 0, .. }))
+            .count();
+        assert_eq!(stops, 2);
+        assert!(script.clone().read_line().is_err());
+    }
+
+    #[test]
+    fn test_help_key_pauses_the_counter() {
+        let keys = KeyBindings { player1: Some('f'), player2: Some('j') };
+        // A counter moving a unit every millisecond or so
+        let rules = RuleSet { base_tick_ms: 1, ..RuleSet::default() };
+        let config = GameConfig { vitality: 1, stamina: 0, target_count: 1, seed: Some(1), keys, rules, ..test_config() };
+        // Player 1 asks for the help while their counter runs, then stops it
+        let script = ScriptedInput::new(["0 enter", "0 key ?", "0 key f", "0 enter", "0 key j"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script.clone());
+
+        game.run_game_loop().unwrap();
+
+        let stops: Vec<_> = game.events.events().iter()
+            .filter_map(|event| match event {
+                GameEvent::CounterStop { value, reaction_ms, .. } => Some((*value, *reaction_ms)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stops.len(), 2);
+        // Neither the counter nor the reaction time run while the help is shown
+        let (value, reaction_ms) = stops[0];
+        assert!(value < 25, "the counter ran on to {}", value);
+        assert!(reaction_ms < OVERLAY_DELAY.as_millis() as u64);
+        assert!(script.clone().read_line().is_err());
+    }
+
+    #[test]
+    fn test_observers_follow_the_match() {
+        let config = GameConfig { vitality: 1, stamina: 0, target_count: 1, seed: Some(1), ..test_config() };
+        let script = ScriptedInput::new(["0 enter", "0 enter", "0 enter", "0 enter"].map(|line| line.parse().unwrap()));
+        let recorder = Arc::new(Recorder::default());
+        let mut game = Game::new(config).with_input(script).with_observer(recorder.clone());
+        
+        let winner = game.run_game_loop().unwrap();
+        
+        assert_eq!(*recorder.0.lock().unwrap(), [
+            "turn 1 0 1".to_string(),
+            "score 0 0".to_string(),
+            "turn 1 1 1".to_string(),
+            "score 1 0".to_string(),
+            format!("over {} true", winner),
+        ]);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_external_bot_plays_player_2() {
+        let config = GameConfig {
+            vitality: 1,
+            stamina: 0,
+            target_count: 1,
+            seed: Some(1),
+            bot_command: Some(r#"while read line; do echo '{"stop": 5}'; done"#.to_string()),
+            ..test_config()
+        };
+        // Only player 1 is scripted, the bot starts and stops its counter on its own
+        let script = ScriptedInput::new(["0 enter", "0 enter"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script);
+        
+        game.run_game_loop().unwrap();
+        
+        assert!(game.is_bot(1));
+        assert!(game.events.events().iter()
+            .any(|event| matches!(event, GameEvent::CounterStop { player: 1, value: 5, .. })));
+    }
+    
+    #[test]
+    fn test_round_start_is_kept_when_the_match_is_cut_short() {
+        let config = GameConfig { vitality: 1, stamina: 0, target_count: 1, seed: Some(1), ..test_config() };
+        // The script runs out during player 2's turn
+        let script = ScriptedInput::new(["0 enter", "0 enter"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script);
+        
+        assert!(game.run_game_loop().is_err());
+        
+        let hashes: Vec<u64> = game.events.events().iter()
+            .filter_map(|event| match event {
+                GameEvent::StateHash { round: 1, hash } => Some(*hash),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(game.round_start().round, 1);
+        assert_eq!(hashes, [game.round_start().hash()]);
+    }
+    
+    #[test]
+    fn test_process_round_result_records_events() {
+        let mut game = Game::new(test_config()).with_input(scripted(&["2"]));
+        
+        let result = game.process_round_result(played([100, 50]));
+        
+        assert!(result.is_ok());
+        assert_eq!(game.events.events(), &[
+            GameEvent::VitalityChange { round: 1, player: 1, amount: 50, vitality: 50 },
+            GameEvent::Penalty { round: 1, winner: 0, loser: 1, attribute: Attribute::Strength, amount: 5 },
+        ]);
+    }
+    
+    #[test]
+    fn test_rematch_state_carries_attributes_over() {
+        let mut game = Game::new(test_config());
+        game.state.players[0].decrease_vitality(100);
+        game.state.players[0].decrease_strength(20);
+        game.state.players[1].increase_momentum(2);
+        game.state.round = 7;
+        
+        let state = game.rematch_state();
+        
+        assert_eq!(state.round, 1);
+        assert!(state.in_progress());
+        assert_eq!(state.players[0].vitality(), 100);
+        assert_eq!(state.players[0].strength(), 50);
+        assert_eq!(state.players[1].momentum(), 0);
+    }
+
+    #[test]
+    fn test_loser_goes_first_next_round() {
+        let mut config = test_config();
+        config.rules.turn_order = TurnOrder::LoserFirst;
+        let mut game = Game::new(config).with_input(scripted(&["2"]));
+        
+        game.process_round_result(played([100, 50])).unwrap();
+        
+        assert_eq!(game.state.first_player, 1);
+    }
+
+    #[test]
+    fn test_blind_results_are_revealed_with_the_round() {
+        let config = GameConfig { blind: true, ..test_config() };
+        let mut game = Game::new(config).with_input(scripted(&["2"]));
+        
+        game.report(tr!(Msg::AverageScore, 80));
+        assert_eq!(game.hidden_results.len(), 1);
+        
+        game.process_round_result(played([80, 50])).unwrap();
+        assert!(game.hidden_results.is_empty());
+    }
+
+    #[test]
+    fn test_round_loser_picks_a_consolation() {
+        let config = GameConfig { consolation: true, ..test_config() };
+        // Player 1 picks the strength penalty, player 2 the vitality bonus
+        let mut game = Game::new(config).with_input(scripted(&["2", "3"]));
+        
+        game.process_round_result(played([100, 80])).unwrap();
+        
+        // 20 lost for the round, 5 back from the bonus
+        assert_eq!(game.state.players[1].vitality(), 85);
+        assert!(game.events.events().contains(&GameEvent::Consolation {
+            round: 1, player: 1, bonus: Consolation::Vitality, amount: 5,
+        }));
+    }
+
+    #[test]
+    fn test_draft_bans_are_enforced() {
+        let config = GameConfig { draft: true, consolation: true, ..test_config() };
+        // Player 1 bans the speed penalty, player 2 the vitality bonus (third of those left),
+        // then player 1 picks the speed bonus and player 2 the strength bonus, the only one left
+        let mut game = Game::new(config).with_input(scripted(&["1", "3", "1", "1"]));
+        
+        game.play_draft().unwrap();
+        assert!(!game.config.rules.pool.allows(DraftItem::Penalty(Attribute::Speed)));
+        assert!(!game.config.rules.pool.allows(DraftItem::Bonus(Consolation::Vitality)));
+        assert!(!game.config.rules.pool.allows_for(DraftItem::Bonus(Consolation::Speed), 1));
+        assert_eq!(game.state.draft, Some(game.config.rules.pool));
+        
+        // Only the strength penalty is left, so player 1 is not asked; nor is player 2, who only has their pick
+        game.process_round_result(played([100, 80])).unwrap();
+        assert_eq!(game.state.players[1].strength(), 70 - PENALTY_AMOUNT + 3);
+        assert_eq!(game.state.players[1].speed(), 60);
+    }
+
+    #[test]
+    fn test_resumed_match_keeps_the_draft() {
+        let config = GameConfig { draft: true, consolation: true, ..test_config() };
+        let mut game = Game::new(config.clone()).with_input(scripted(&["1", "3", "1", "1"]));
+        game.play_draft().unwrap();
+        let json = serde_json::to_string(&game.state).unwrap();
+        
+        let resumed = Game::from_state(config, serde_json::from_str(&json).unwrap());
+        assert_eq!(resumed.config.rules.pool, game.config.rules.pool);
+        assert!(resumed.state.draft.is_some());
+    }
+
+    #[test]
+    fn test_round_recap_compares_targets_in_order() {
+        let turn = |stops: [(u32, u32); 3]| TurnResult {
+            outcome: TurnOutcome::Played(0),
+            exact_hits: 0,
+            targets: stops.into_iter().map(|(target, value)| TargetResult { target, value, score: 0 }).collect(),
+        };
+        let results = [turn([(50, 52), (10, 30), (90, 5)]), turn([(20, 27), (60, 60), (40, 44)])];
+        let names = ["Alice".to_string(), "Bob".to_string()];
+        let (rows, closer) = round_recap(&results, &names, Dial::default());
+        
+        assert_eq!(rows[0], ["1", "52 for 50 (2 off)", "27 for 20 (7 off)", "Alice"]);
+        assert_eq!(rows[1][3], "Bob");
+        // 90 and 5 are 16 apart going around the dial, as far as 40 and 44 are not
+        assert_eq!(rows[2][1], "5 for 90 (16 off)");
+        assert_eq!(closer, [1, 2]);
+    }
+
+    #[test]
+    fn test_scored_targets_fill_the_turn_table() {
+        let mut game = Game::new(test_config());
+        let stop = TargetStop { target: 50, value: 80, miss: 1, reaction_ms: 0, gamble: false, handicap: 0, risk: RiskTier::Normal };
+        let score = game.score_stop(0, &stop, 0).unwrap();
+        
+        assert_eq!(game.target_rows, [(0, vec!["50".to_string(), "80".to_string(), "30".to_string(), "1".to_string(), score.to_string()])]);
+        game.show_target_table();
+        assert!(game.target_rows.is_empty());
+    }
+
+    #[test]
+    fn test_calm_weather_ignores_misses() {
+        let mut game = Game::new(test_config());
+        // Far enough from the target to never be a critical hit
+        let stop = TargetStop { target: 50, value: 80, miss: 2, reaction_ms: 0, gamble: false, handicap: 0, risk: RiskTier::Normal };
+        let clear = game.score_stop(0, &stop, 0).unwrap();
+        
+        game.modifier = RoundModifier::Calm;
+        let calm = game.score_stop(0, &stop, 0).unwrap();
+        
+        assert_eq!(calm, scoring::calculate_score(50, 80, 70, 0));
+        assert!(calm > clear);
+    }
+
+    #[test]
+    fn test_mutator_ends_the_match() {
+        let config = GameConfig { plugins: PluginRegistry::new(mutators::parse("five-misses-lose").unwrap()), ..test_config() };
+        let mut game = Game::new(config);
+        game.state.tally.misses = [1, 5];
+        
+        game.check_plugins();
+        
+        assert!(!game.state.in_progress());
+        assert_eq!(game.state.winner_idx, Some(0));
+    }
+    
+    #[test]
+    fn test_win_condition_ends_the_match() {
+        let mut config = test_config();
+        config.rules.win_condition = WinCondition::HighScore(2);
+        let mut game = Game::new(config);
+        game.state.tally.scores = [150, 170];
+        
+        game.check_plugins();
+        assert!(game.state.in_progress());
+        
+        game.state.round = 2;
+        game.check_plugins();
+        assert!(!game.state.in_progress());
+        assert_eq!(game.state.winner_idx, Some(1));
+    }
+    
+    #[test]
+    fn test_errors_cut_the_match_short() {
+        let mut game = Game::new(test_config()).with_input(scripted(&[]));
+        
+        let error = game.play_match().unwrap_err();
+        
+        assert!(matches!(error, GameError::LogicError(_)));
+        assert_eq!(error.exit_code(), 1);
+    }
+    
+    #[test]
+    fn test_resigning_ends_the_match() {
+        let config = GameConfig { stamina: 0, target_count: 1, seed: Some(1), ..test_config() };
+        // Player 1 plays their turn, then player 2 resigns instead of starting theirs
+        let script = ScriptedInput::new(["0 enter", "0 enter", "0 type R"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script);
+        
+        let winner = game.run_game_loop().unwrap();
+        
+        assert_eq!(winner, 0);
+        assert_eq!(game.state.winner_idx, Some(0));
+        assert_eq!(game.state.round, 2);
+        assert_eq!(game.state.players[1].vitality(), 100);
+        let events = game.events.events();
+        assert!(events.contains(&GameEvent::Forfeit { round: 1, player: 1 }));
+        assert!(!events.iter().any(|event| matches!(event, GameEvent::TurnEnd { player: 1, .. })));
+        assert_eq!(events.last(), Some(&GameEvent::GameOver { round: 1, winner: 0 }));
+    }
+    
+    #[test]
+    fn test_stealing_from_the_shared_pool() {
+        let config = GameConfig { stamina: 0, target_count: 3, shared_pool: true, ..test_config() };
+        // Player 1 plays their three targets and steals the second one of player 2, who plays the other two
+        let mut game = Game::new(config).with_input(scripted(&["", "", "", "", "2", "", "", "", ""]));
+        game.pool = Some(TargetPool::deal(vec![10, 20, 30, 40, 50, 60], 0));
+        
+        let turn = game.play_turn(0, None).unwrap();
+        let own: Vec<u32> = turn.targets.iter().map(|result| result.score).collect();
+        let stolen = game.events.events().iter().rev()
+            .find_map(|event| match event {
+                GameEvent::Score { player: 0, target: 40, score, .. } => Some(*score),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(turn.targets.iter().map(|result| result.target).collect::<Vec<_>>(), [10, 30, 50]);
+        assert_eq!(turn.outcome, TurnOutcome::Played(scoring::calculate_average_score(&own) + stolen * STEAL_BONUS_PERCENT / 100));
+        assert!(game.events.events().contains(&GameEvent::TargetSteal { round: 1, player: 0, from: 1, target: 40 }));
+        
+        let turn = game.play_turn(1, None).unwrap();
+        assert_eq!(turn.targets.iter().map(|result| result.target).collect::<Vec<_>>(), [20, 60]);
+    }
+
+    #[test]
+    fn test_prediction_leaves_out_the_stolen_target() {
+        let config = GameConfig { speed: 1, stamina: 0, target_count: 3, shared_pool: true, predictions: true, ..test_config() };
+        // Player 1 predicts the average of their own three targets, then steals the second one of player 2
+        let mut game = Game::new(config).with_input(scripted(&["", "104", "", "", "", "2", ""]));
+        game.pool = Some(TargetPool::deal(vec![10, 20, 30, 40, 50, 60], 0));
+        
+        let turn = game.play_turn(0, None).unwrap();
+        let own: Vec<u32> = turn.targets.iter().map(|result| result.score).collect();
+        assert_eq!(scoring::calculate_average_score(&own), 104);
+        assert!(matches!(turn.outcome, TurnOutcome::Played(average) if average > 104 + scoring::PREDICTION_MARGIN));
+        assert_eq!(game.prediction_bonuses, [scoring::PREDICTION_BONUS, 0]);
+    }
+
+    #[test]
+    fn test_steal_options_keep_hidden_targets_hidden() {
+        let stealable = [(1, 20), (3, 40)];
+        let shown = Game::new(GameConfig { shared_pool: true, ..test_config() }).steal_options(&stealable);
+        assert_eq!(shown, [tr!(Msg::StealTarget, 20), tr!(Msg::StealTarget, 40), tr!(Msg::StealNone)]);
+        
+        for config in [GameConfig { hidden_target: true, ..test_config() }, GameConfig { memory_seconds: Some(3), ..test_config() }] {
+            let options = Game::new(GameConfig { shared_pool: true, ..config }).steal_options(&stealable);
+            assert_eq!(options, [tr!(Msg::StealTarget, "?"), tr!(Msg::StealTarget, "?"), tr!(Msg::StealNone)]);
+        }
+    }
+
+    #[test]
+    fn test_passing_a_turn() {
+        let config = GameConfig { stamina: 0, target_count: 1, seed: Some(1), pass_cost: Some(10), ..test_config() };
+        // Player 1 passes, player 2 plays their turn, then player 1 picks a penalty
+        let script = ScriptedInput::new(["0 type p", "0 enter", "0 enter", "0 type 1"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script);
+        
+        let turn = game.play_turn(0, None).unwrap();
+        assert_eq!(turn, TurnResult::passed());
+        assert_eq!(game.state.players[0].vitality(), 90);
+        let turn = game.play_turn(1, None).unwrap();
+        assert_eq!(turn.targets.len(), 1);
+        game.process_round_result([TurnOutcome::Passed, turn.outcome]).unwrap();
+        
+        assert_eq!(game.state.players[0].vitality(), 90);
+        assert_eq!(game.state.tally.round_wins, [0, 1]);
+    }
+    
+    #[test]
+    fn test_passing_player_is_not_asked_for_a_prediction() {
+        let config = GameConfig { stamina: 0, target_count: 1, pass_cost: Some(10), predictions: true, ..test_config() };
+        let mut game = Game::new(config).with_input(scripted(&["p"]));
+        
+        assert_eq!(game.play_turn(0, None).unwrap(), TurnResult::passed());
+        assert_eq!(game.prediction_bonuses, [0, 0]);
+    }
+    
+    #[test]
+    fn test_handicap_moves_the_stop() {
+        // The slowest counter is stopped before its first tick, on 0
+        let config = GameConfig { speed: 1, stamina: 0, target_count: 1, handicap: [0, -3], ..test_config() };
+        let script = ScriptedInput::new(["0 enter", "0 enter", "0 enter", "0 enter"].map(|line| line.parse().unwrap()));
+        let mut game = Game::new(config).with_input(script);
+        
+        game.play_turn(0, None).unwrap();
+        game.play_turn(1, None).unwrap();
+        
+        let stops: Vec<_> = game.events.events().iter().filter_map(|event| match event {
+            GameEvent::CounterStop { player, value, .. } => Some((*player, *value)),
+            _ => None,
+        }).collect();
+        assert_eq!(stops, [(0, 0), (1, 98)]);
+    }
+    
+    #[test]
+    fn test_risk_tier_changes_the_base_score() {
+        // The slowest counter is stopped on 0, 20 away from the target
+        let play = |key: &str| {
+            let config = GameConfig { speed: 1, stamina: 0, risk_tiers: true, ..test_config() };
+            let script = ScriptedInput::new([key, "0 enter"].map(|line| line.parse().unwrap()));
+            let mut game = Game::new(config).with_input(script);
+            game.play_target(0, 20, 0).unwrap().0.score
+        };
+        
+        // Safe scores 60 base points, normal 40 and risky 30
+        assert_eq!(play("0 key s") - play("0 key n"), 20);
+        assert_eq!(play("0 key n") - play("0 key r"), 10);
+    }
+    
+    #[test]
+    fn test_streak_of_instant_stops_voids_the_target() {
+        let config = GameConfig { stamina: 0, target_count: 3, void_implausible: true, ..test_config() };
+        let mut game = Game::new(config).with_input(scripted(&["", "", "", ""]));
+        
+        game.play_turn(0, None).unwrap();
+        
+        let events = game.events.events();
+        let voided: Vec<_> = events.iter().filter_map(|event| match event {
+            GameEvent::ImplausibleStop { verdict, voided, .. } => Some((*verdict, *voided)),
+            _ => None,
+        }).collect();
+        assert_eq!(voided, [(Verdict::Implausible, false), (Verdict::Implausible, false), (Verdict::Suspicious, true)]);
+        assert_eq!(events.iter().filter(|event| matches!(event, GameEvent::Score { .. })).count(), 2);
+    }
+    
+    #[test]
+    fn test_speed_floor() {
+        let mut config = test_config();
+        config.rules.bounds.speed = Bounds { min: 55, max: None };
+        let mut game = Game::new(config.clone()).with_input(scripted(&["1"]));
+        // Speed 60 goes down to the floor of 55, which knocks the player out
+        game.apply_penalty(0, 1).unwrap();
+        assert_eq!(game.state.players[1].speed(), 55);
+        assert_eq!(game.state.winner_idx, Some(0));
+        
+        config.rules.bounds.speed_floor = SpeedFloor::Clamp;
+        let mut game = Game::new(config).with_input(scripted(&["1", "1"]));
+        game.apply_penalty(0, 1).unwrap();
+        game.apply_penalty(0, 1).unwrap();
+        assert_eq!(game.state.players[1].speed(), 55);
+        assert!(game.state.in_progress());
+    }
+
+    #[test]
+    fn test_practice_run_is_recorded_stop_by_stop() {
+        let config = GameConfig { seed: Some(5), target_count: 1, ..test_config() };
+        let mut game = Game::new(config.clone()).with_input(scripted(&["0 enter"; 2 * practice::RUN_TARGETS]));
+        let run = game.play_practice(PracticeMode::Practice, None).unwrap();
+
+        assert_eq!(run.seed, 5);
+        assert_eq!(run.stops.len(), practice::RUN_TARGETS);
+        assert_eq!(run.hashes.len(), practice::RUN_TARGETS);
+        assert_eq!(run.result, u64::from(game.state.tally.scores[0]));
+
+        // A ghost whose states differ from the very first target is caught before any stop
+        let ghost = Replay { hashes: vec![run.hashes[0] + 1], ..run };
+        let mut game = Game::new(config).with_input(scripted(&[]));
+        assert!(matches!(game.play_practice(PracticeMode::Practice, Some(&ghost)), Err(GameError::Desync { round: 1, .. })));
+    }
+
+    #[test]
+    fn test_time_attack_adds_a_penalty_for_each_miss() {
+        let config = GameConfig { seed: Some(5), target_count: 1, ..test_config() };
+        let mut game = Game::new(config).with_input(scripted(&["0 enter"; 2 * practice::RUN_TARGETS]));
+        let run = game.play_practice(PracticeMode::TimeAttack, None).unwrap();
+
+        assert_eq!(run.mode, PracticeMode::TimeAttack);
+        assert!(game.state.tally.misses[0] > 0);
+        assert!(run.result >= u64::from(game.state.tally.misses[0]) * practice::MISS_PENALTY_MS);
     }
 }
@@ -0,0 +1,527 @@
+//! Gui module for playing in a desktop window, with the `gui` feature.
+//!
+//! `--gui` opens a window instead of drawing in the terminal. The match is
+//! played by the same `Game` as in the terminal, on a thread of its own:
+//! `GuiRenderer` sends the window what the terminal would show, and
+//! `GuiInput` reads the buttons, the answers typed and the keys pressed in it.
+//! The window draws the counter as a dial, with the target marked on its rim.
+
+use crate::config::GameConfig;
+use crate::error::GameResult;
+
+#[cfg(feature = "gui")]
+use std::f32::consts::TAU;
+#[cfg(feature = "gui")]
+use std::rc::Rc;
+#[cfg(feature = "gui")]
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+#[cfg(feature = "gui")]
+use std::thread;
+#[cfg(feature = "gui")]
+use std::time::Duration;
+
+#[cfg(feature = "gui")]
+use eframe::egui;
+#[cfg(feature = "gui")]
+use tokio::task::JoinHandle;
+
+#[cfg(feature = "gui")]
+use crate::audio::Audio;
+#[cfg(feature = "gui")]
+use crate::counter::{CounterEvent, CounterEvents, Dial};
+#[cfg(feature = "gui")]
+use crate::error::GameError;
+#[cfg(feature = "gui")]
+use crate::game::{self, Game};
+#[cfg(feature = "gui")]
+use crate::i18n::{Msg, tr};
+#[cfg(feature = "gui")]
+use crate::runtime;
+#[cfg(feature = "gui")]
+use crate::ui::render::Renderer;
+#[cfg(feature = "gui")]
+use crate::ui::source::InputSource;
+#[cfg(feature = "gui")]
+use crate::ui::{CounterFrame, CounterView, Rematch};
+
+/// The most lines of output the window keeps.
+#[cfg(feature = "gui")]
+const MAX_LINES: usize = 500;
+
+/// What a player does in the window.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    /// A line typed and sent, empty for the start and stop button
+    Line(String),
+    /// A key pressed outside the answer field
+    Key(char),
+}
+
+/// The counter as the window draws it.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DialView {
+    /// The values the counter runs through
+    dial: Dial,
+    /// The counter value, in ticks
+    value: u32,
+    /// The target, in ticks, None while it is hidden
+    target: Option<u32>,
+    /// Times the counter went around
+    miss: u32,
+}
+
+/// What the game tells the window.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Update {
+    /// A line of output
+    Line(String),
+    /// A message shown until the next one replaces it, None to take it off
+    Brief(Option<String>),
+    /// The scoreboard, None to take it off
+    Scoreboard(Option<String>),
+    /// The running counter, None once it is cleared
+    Counter(Option<DialView>),
+}
+
+/// Reads the players' actions from the window.
+///
+/// Clones share the same window, so the input carries on from one match to the next.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+struct GuiInput {
+    /// The actions of the window, in order
+    actions: Rc<Receiver<Action>>,
+}
+
+#[cfg(feature = "gui")]
+impl GuiInput {
+    /// Waits for the next action, for at most `timeout` if one is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait at most, None to wait as long as it takes
+    ///
+    /// # Returns
+    ///
+    /// Result containing the action, None if the time ran out, or `GameError::Interrupted` once the window is closed
+    fn next(&self, timeout: Option<Duration>) -> GameResult<Option<Action>> {
+        match timeout {
+            Some(timeout) => match self.actions.recv_timeout(timeout) {
+                Ok(action) => Ok(Some(action)),
+                Err(RecvTimeoutError::Timeout) => Ok(None),
+                Err(RecvTimeoutError::Disconnected) => Err(GameError::Interrupted),
+            },
+            None => self.actions.recv().map(Some).map_err(|_| GameError::Interrupted),
+        }
+    }
+
+    /// Waits for a key press or the stop button.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to wait for, the stop button counting as the first one
+    /// * `timeout` - How long to wait at most, None to wait as long as it takes
+    ///
+    /// # Returns
+    ///
+    /// Result containing the index of the key that was pressed, or None if the time ran out
+    fn key(&self, keys: &[char], timeout: Option<Duration>) -> GameResult<Option<usize>> {
+        loop {
+            match self.next(timeout)? {
+                Some(Action::Line(_)) => return Ok(Some(0)),
+                Some(Action::Key(key)) => {
+                    if let Some(index) = keys.iter().position(|k| k.eq_ignore_ascii_case(&key)) {
+                        return Ok(Some(index));
+                    }
+                },
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl InputSource for GuiInput {
+    fn read_line(&mut self) -> GameResult<String> {
+        loop {
+            // Keys pressed outside the answer field only stop the counter
+            if let Some(Action::Line(line)) = self.next(None)? {
+                return Ok(line);
+            }
+        }
+    }
+
+    fn wait_for_key(&mut self, keys: &[char]) -> GameResult<usize> {
+        Ok(self.key(keys, None)?.unwrap_or_default())
+    }
+
+    fn wait_for_key_timeout(&mut self, keys: &[char], timeout: Duration) -> GameResult<Option<usize>> {
+        self.key(keys, Some(timeout))
+    }
+}
+
+/// Sends the game output to the window.
+#[cfg(feature = "gui")]
+#[derive(Clone)]
+struct GuiRenderer {
+    /// Where the output is sent
+    updates: Sender<Update>,
+    /// The window, repainted after each update
+    ctx: egui::Context,
+}
+
+#[cfg(feature = "gui")]
+impl GuiRenderer {
+    /// Sends an update to the window, whether or not it is still open.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - What changed
+    fn send(&self, update: Update) {
+        let _ = self.updates.send(update);
+        self.ctx.request_repaint();
+    }
+}
+
+#[cfg(feature = "gui")]
+impl Renderer for GuiRenderer {
+    fn info(&mut self, text: &str) {
+        self.send(Update::Line(text.trim_end().to_string()));
+    }
+
+    fn info_wrapped(&mut self, text: &str) {
+        self.info(text);
+    }
+
+    fn show_briefly(&mut self, text: &str, duration: Duration) -> GameResult<()> {
+        self.send(Update::Brief(Some(text.to_string())));
+        thread::sleep(duration);
+        self.send(Update::Brief(None));
+        Ok(())
+    }
+
+    fn show_scoreboard(&mut self, line: String) -> GameResult<()> {
+        self.send(Update::Scoreboard(Some(line)));
+        Ok(())
+    }
+
+    fn hide_scoreboard(&mut self) -> GameResult<()> {
+        self.send(Update::Scoreboard(None));
+        Ok(())
+    }
+
+    fn display_counter(&mut self, mut events: CounterEvents, frame: CounterFrame, _audio: Audio) -> GameResult<JoinHandle<()>> {
+        let renderer = self.clone();
+        let handle = runtime::get()?.spawn(async move {
+            let mut view = DialView {
+                dial: frame.dial,
+                value: 0,
+                target: (!frame.hide_target).then_some(frame.target),
+                miss: 0,
+            };
+            renderer.send(Update::Counter(Some(view)));
+            while let Some(event) = events.recv().await {
+                match event {
+                    CounterEvent::Tick(value) => view.value = value,
+                    CounterEvent::Wrapped => view.miss += 1,
+                    CounterEvent::Started(_) | CounterEvent::Paused => continue,
+                    CounterEvent::Stopped(..) => break,
+                }
+                if let (Some(target), Some(moving)) = (view.target.as_mut(), &frame.moving_target) {
+                    *target = *moving.lock().unwrap();
+                }
+                renderer.send(Update::Counter(Some(view)));
+            }
+        });
+        Ok(handle)
+    }
+
+    fn clear_counter(&mut self, _view: CounterView) -> GameResult<()> {
+        self.send(Update::Counter(None));
+        Ok(())
+    }
+
+    fn new_line(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn prompt(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+}
+
+/// The window the match is played in.
+#[cfg(feature = "gui")]
+struct GameWindow {
+    /// Where the players' actions are sent
+    actions: Sender<Action>,
+    /// What the game tells the window
+    updates: Receiver<Update>,
+    /// The lines of output, oldest first
+    lines: Vec<String>,
+    /// The message shown for a while, if any
+    brief: Option<String>,
+    /// The scoreboard, if shown
+    scoreboard: Option<String>,
+    /// The counter, while it is shown
+    counter: Option<DialView>,
+    /// The answer being typed
+    answer: String,
+}
+
+#[cfg(feature = "gui")]
+impl GameWindow {
+    /// Takes the updates of the game into account.
+    fn apply_updates(&mut self) {
+        while let Ok(update) = self.updates.try_recv() {
+            match update {
+                Update::Line(line) => self.lines.push(line),
+                Update::Brief(brief) => self.brief = brief,
+                Update::Scoreboard(scoreboard) => self.scoreboard = scoreboard,
+                Update::Counter(counter) => self.counter = counter,
+            }
+        }
+        if self.lines.len() > MAX_LINES {
+            self.lines.drain(..self.lines.len() - MAX_LINES);
+        }
+    }
+
+    /// Sends an action to the game, whether or not it still plays.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - What the player did
+    fn send(&self, action: Action) {
+        let _ = self.actions.send(action);
+    }
+}
+
+#[cfg(feature = "gui")]
+impl eframe::App for GameWindow {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.apply_updates();
+
+        // Keys pressed outside the answer field go to the game, like on the keyboard
+        if !ctx.wants_keyboard_input() {
+            for event in ctx.input(|input| input.events.clone()) {
+                match event {
+                    egui::Event::Key { key: egui::Key::Enter | egui::Key::Space, pressed: true, repeat: false, .. } =>
+                        self.send(Action::Line(String::new())),
+                    egui::Event::Text(text) => text.chars().for_each(|key| self.send(Action::Key(key))),
+                    _ => {},
+                }
+            }
+        }
+
+        egui::TopBottomPanel::top("scoreboard").show(ctx, |ui| {
+            ui.label(egui::RichText::new(self.scoreboard.as_deref().unwrap_or_default()).monospace());
+        });
+        egui::TopBottomPanel::bottom("controls").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button(tr!(Msg::GuiStartStop)).clicked() {
+                    self.send(Action::Line(String::new()));
+                }
+                if ui.button(tr!(Msg::GuiPass)).clicked() {
+                    self.send(Action::Line(game::PASS_COMMAND.to_string()));
+                }
+                if ui.button(tr!(Msg::GuiResign)).clicked() {
+                    self.send(Action::Line(game::RESIGN_COMMAND.to_string()));
+                }
+                let field = ui.text_edit_singleline(&mut self.answer);
+                let entered = field.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                if ui.button(tr!(Msg::GuiSend)).clicked() || entered {
+                    let answer = std::mem::take(&mut self.answer);
+                    self.send(Action::Line(answer));
+                }
+            });
+        });
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(counter) = self.counter {
+                draw_dial(ui, counter);
+            }
+            if let Some(brief) = &self.brief {
+                ui.heading(brief);
+            }
+            egui::ScrollArea::vertical().stick_to_bottom(true).auto_shrink(false).show(ui, |ui| {
+                for line in &self.lines {
+                    ui.label(line);
+                }
+            });
+        });
+    }
+}
+
+/// Draws the counter as a dial, with a hand on its value and a mark on the target.
+///
+/// # Arguments
+///
+/// * `ui` - Where to draw
+/// * `counter` - The counter to draw
+#[cfg(feature = "gui")]
+fn draw_dial(ui: &mut egui::Ui, counter: DialView) {
+    let size = egui::vec2(ui.available_width(), 220.0);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let center = response.rect.center();
+    let radius = response.rect.height() / 2.0 - 10.0;
+    let visuals = ui.visuals();
+    // 0 at the top, going clockwise like a clock
+    let point = |ticks: u32, length: f32| {
+        let angle = ticks as f32 / (counter.dial.max() + 1) as f32 * TAU - TAU / 4.0;
+        center + length * egui::vec2(angle.cos(), angle.sin())
+    };
+
+    painter.circle_stroke(center, radius, egui::Stroke::new(2.0, visuals.text_color()));
+    if let Some(target) = counter.target {
+        painter.circle_filled(point(target, radius), 7.0, egui::Color32::from_rgb(220, 60, 60));
+    }
+    painter.line_segment([center, point(counter.value, radius - 12.0)], egui::Stroke::new(4.0, visuals.strong_text_color()));
+    painter.circle_filled(center, 5.0, visuals.strong_text_color());
+    painter.text(center + egui::vec2(0.0, radius / 2.0), egui::Align2::CENTER_CENTER, counter.dial.format(counter.value),
+                 egui::FontId::monospace(24.0), visuals.strong_text_color());
+    if counter.miss > 0 {
+        painter.text(center - egui::vec2(0.0, radius / 2.0), egui::Align2::CENTER_CENTER, format!("× {}", counter.miss),
+                     egui::FontId::proportional(16.0), visuals.warn_fg_color);
+    }
+}
+
+/// Plays matches in the window until the players quit or close it.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+/// * `input` - The actions of the window
+/// * `renderer` - Where the game output is sent
+///
+/// # Returns
+///
+/// Result indicating whether the matches were played without error
+#[cfg(feature = "gui")]
+fn play(mut config: GameConfig, input: GuiInput, renderer: GuiRenderer) -> GameResult<()> {
+    let mut saved_state = None;
+    loop {
+        let game = match saved_state.take() {
+            Some(state) => Game::from_state(config.clone(), state),
+            None => Game::new(config.clone()),
+        };
+        let mut game = game.with_input(input.clone()).with_renderer(renderer.clone());
+        match game.run() {
+            Ok(Rematch::Quit) | Err(GameError::Interrupted) => return Ok(()),
+            Ok(Rematch::Same) => {},
+            Ok(Rematch::SwapFirst) => config.swap_players(),
+            Ok(Rematch::CarryOver) => saved_state = Some(game.rematch_state()),
+            Err(e) => {
+                renderer.clone().info(&tr!(Msg::GameError, e));
+                return Err(e);
+            },
+        }
+    }
+}
+
+/// Plays in a desktop window until it is closed.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+///
+/// # Returns
+///
+/// Result indicating whether the window could be opened and the matches were played without error
+#[cfg(feature = "gui")]
+pub fn run(config: GameConfig) -> GameResult<()> {
+    let (actions, action_receiver) = mpsc::channel();
+    let (update_sender, updates) = mpsc::channel();
+    let mut game_thread = None;
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([720.0, 640.0]),
+        ..eframe::NativeOptions::default()
+    };
+    eframe::run_native("Turn-based Game", options, Box::new(|cc| {
+        let renderer = GuiRenderer { updates: update_sender, ctx: cc.egui_ctx.clone() };
+        game_thread = Some(thread::spawn(move || {
+            play(config, GuiInput { actions: Rc::new(action_receiver) }, renderer)
+        }));
+        Ok(Box::new(GameWindow {
+            actions,
+            updates,
+            lines: Vec::new(),
+            brief: None,
+            scoreboard: None,
+            counter: None,
+            answer: String::new(),
+        }))
+    })).map_err(|e| GameError::LogicError(format!("Could not open the window: {}", e)))?;
+
+    // Closing the window interrupts the game at its next read
+    match game_thread.map(thread::JoinHandle::join) {
+        Some(Ok(result)) => result,
+        Some(Err(_)) => Err(GameError::LogicError("The game stopped unexpectedly".to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Plays in a desktop window, which this build cannot open.
+///
+/// # Arguments
+///
+/// * `_config` - The game settings
+///
+/// # Returns
+///
+/// `GameError::ConfigError`, the game was built without the `gui` feature
+#[cfg(not(feature = "gui"))]
+pub fn run(_config: GameConfig) -> GameResult<()> {
+    Err(crate::error::GameError::ConfigError("The window needs a build with the gui feature".to_string()))
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+
+    fn input(actions: impl IntoIterator<Item = Action>) -> GuiInput {
+        let (sender, receiver) = mpsc::channel();
+        actions.into_iter().for_each(|action| sender.send(action).unwrap());
+        GuiInput { actions: Rc::new(receiver) }
+    }
+
+    #[test]
+    fn test_lines_skip_stray_keys() {
+        let mut input = input([Action::Key('x'), Action::Line("2".to_string())]);
+        assert_eq!(input.read_line().unwrap(), "2");
+        assert!(matches!(input.read_line(), Err(GameError::Interrupted)));
+    }
+
+    #[test]
+    fn test_keys_and_stop_button() {
+        let mut input = input([Action::Key('z'), Action::Key('K'), Action::Line(String::new())]);
+        assert_eq!(input.wait_for_key(&['f', 'k']).unwrap(), 1);
+        assert_eq!(input.wait_for_key(&['f', 'k']).unwrap(), 0);
+        // The window was closed
+        assert!(matches!(input.wait_for_key_timeout(&['f'], Duration::from_millis(10)), Err(GameError::Interrupted)));
+    }
+
+    #[test]
+    fn test_window_keeps_the_last_updates() {
+        let (sender, updates) = mpsc::channel();
+        let mut window = GameWindow {
+            actions: mpsc::channel().0,
+            updates,
+            lines: Vec::new(),
+            brief: None,
+            scoreboard: None,
+            counter: None,
+            answer: String::new(),
+        };
+        for line in 0..MAX_LINES + 5 {
+            sender.send(Update::Line(line.to_string())).unwrap();
+        }
+        sender.send(Update::Scoreboard(Some("Round 1".to_string()))).unwrap();
+        window.apply_updates();
+
+        assert_eq!(window.lines.len(), MAX_LINES);
+        assert_eq!(window.lines[0], "5");
+        assert_eq!(window.scoreboard.as_deref(), Some("Round 1"));
+    }
+}
@@ -0,0 +1,237 @@
+//! Help module for the keys and rules summary shown at the start of a turn.
+//!
+//! Typing `?` and ENTER at the start of a turn shows the summary over the
+//! game, and the turn carries on once it is dismissed. It is not offered
+//! while the counter runs, where every key press counts as a stop attempt.
+//! `--help-keys` prints it and exits. The summary follows the settings of
+//! the match: the stop keys, whether turns can be passed, and how the match
+//! is won.
+//!
+//! The `rules` subcommand prints the full rules reference instead: the scoring
+//! table, the damage model, the penalties and bonuses, and the win condition.
+//! It is read from the `RuleSet` of the match and from the scoring table the
+//! game scores with, so it cannot drift from what the game does.
+
+use crate::config::GameConfig;
+use crate::dial::Precision;
+use crate::game::{self, PASS_COMMAND, RESIGN_COMMAND, RISK_KEYS};
+use crate::history::TIMELINE_COMMAND;
+use crate::i18n::{Msg, tr};
+use crate::rules::{DRAFT_ITEMS, DamageModel, DraftItem, SpeedCurve, SpeedFloor, TargetDistribution, TurnOrder, WinCondition};
+use crate::scoring::{PREDICTION_BONUS, PREDICTION_MARGIN, SAFE_CAP, SCORING_TABLE};
+
+/// What a player types at the start of their turn to show the help.
+pub const HELP_COMMAND: &str = "?";
+
+/// The key a player presses while their counter runs to show the help.
+pub const HELP_KEY: char = '?';
+
+/// Builds the summary of the keys and rules of a match.
+///
+/// # Arguments
+///
+/// * `config` - The settings of the match
+///
+/// # Returns
+///
+/// The lines of the summary, in the current language
+pub fn help_lines(config: &GameConfig) -> Vec<String> {
+    let mut lines = vec![tr!(Msg::HelpKeysTitle), tr!(Msg::HelpStart)];
+    let names = [&config.player1_name, &config.player2_name];
+    if config.keys.player1.is_none() || config.keys.player2.is_none() {
+        lines.push(tr!(Msg::HelpStopEnter));
+    }
+    for (player_idx, name) in names.into_iter().enumerate() {
+        if let Some(key) = config.keys.get(player_idx) {
+            lines.push(tr!(Msg::HelpStopKey, key, name));
+        }
+    }
+    lines.push(tr!(Msg::HelpResign, RESIGN_COMMAND));
+    if let Some(cost) = config.pass_cost {
+        lines.push(tr!(Msg::HelpPass, PASS_COMMAND, cost));
+    }
+    lines.push(tr!(Msg::HelpMenus));
+    lines.push(tr!(Msg::HelpTimeline, TIMELINE_COMMAND));
+    lines.push(tr!(Msg::HelpHelp, HELP_COMMAND, HELP_KEY));
+
+    lines.push(String::new());
+    lines.push(tr!(Msg::HelpRulesTitle));
+    lines.push(tr!(Msg::HelpTargets, config.target_count, config.rules.counter_max));
+    if let Some(cap) = config.target_cap {
+        lines.push(tr!(Msg::HelpTargetsGrow, cap.max(config.target_count)));
+    }
+    if config.shared_pool && !config.duel {
+        lines.push(tr!(Msg::HelpSharedPool));
+    }
+    if config.predictions && !config.duel {
+        lines.push(tr!(Msg::HelpPredictions, PREDICTION_MARGIN, PREDICTION_BONUS));
+    }
+    if config.risk_tiers && !config.duel {
+        lines.push(tr!(Msg::HelpRisk, RISK_KEYS[0], SAFE_CAP, RISK_KEYS[1], RISK_KEYS[2]));
+    }
+    lines.push(tr!(Msg::HelpScoring));
+    lines.push(tr!(Msg::HelpRounds));
+    lines.push(win_goal(config.rules.win_condition));
+    lines
+}
+
+/// Describes how a match is won.
+///
+/// # Arguments
+///
+/// * `condition` - The win condition of the match
+///
+/// # Returns
+///
+/// The win condition, as a sentence in the current language
+fn win_goal(condition: WinCondition) -> String {
+    match condition {
+        WinCondition::Vitality => tr!(Msg::HelpWinVitality),
+        WinCondition::RoundWins(wins) => tr!(Msg::WinGoalRounds, wins),
+        WinCondition::HighScore(rounds) => tr!(Msg::WinGoalScore, rounds),
+        WinCondition::SuddenDeath => tr!(Msg::WinGoalSuddenDeath),
+    }
+}
+
+/// Lays rows out in two columns, the labels padded to the widest one.
+///
+/// # Arguments
+///
+/// * `rows` - The label and the value of each row
+///
+/// # Returns
+///
+/// One line per row
+fn table(rows: &[(String, String)]) -> Vec<String> {
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+    rows.iter().map(|(label, value)| format!("{:<width$}  {}", label, value)).collect()
+}
+
+/// Builds the reference of the rules a match is played with.
+///
+/// # Arguments
+///
+/// * `config` - The settings of the match
+///
+/// # Returns
+///
+/// The lines of the reference, in the current language
+pub fn rules_table(config: &GameConfig) -> Vec<String> {
+    let rules = &config.rules;
+    let dial = rules.dial();
+    let mut lines = vec![tr!(Msg::RulesScoringTitle)];
+    let mut rows = vec![(tr!(Msg::RulesDistance), tr!(Msg::RulesBaseScore))];
+    let mut low = 0;
+    for (max_diff, score) in SCORING_TABLE {
+        let distance = if low == max_diff {
+            dial.format(low)
+        } else {
+            format!("{}–{}", dial.format(low), dial.format(max_diff))
+        };
+        rows.push((distance, score.to_string()));
+        low = max_diff + 1;
+    }
+    rows.push((format!("{}+", dial.format(low)), 0.to_string()));
+    lines.extend(table(&rows));
+    lines.push(tr!(Msg::RulesFormula));
+
+    let items = |penalties: bool| -> String {
+        let items: Vec<_> = DRAFT_ITEMS.into_iter()
+            .filter(|&item| matches!(item, DraftItem::Penalty(_)) == penalties && rules.pool.allows(item))
+            .map(game::draft_label)
+            .collect();
+        items.join(", ")
+    };
+    let rows = [
+        (tr!(Msg::RulesCounter), tr!(Msg::RulesCounterRange, dial.format(dial.max()), match rules.precision {
+            Precision::Normal => tr!(Msg::RulesWholeUnits),
+            Precision::Fine => tr!(Msg::RulesTenths),
+        })),
+        (tr!(Msg::RulesTick), tr!(Msg::RulesTickValue, rules.base_tick_ms)),
+        (tr!(Msg::RulesSpeedCurve), match rules.speed_curve {
+            SpeedCurve::Flat => tr!(Msg::RulesCurveFlat),
+            SpeedCurve::Ramp(percent) => tr!(Msg::RulesCurveRamp, percent),
+        }),
+        (tr!(Msg::RulesTargets), match rules.distribution {
+            TargetDistribution::Uniform => tr!(Msg::RulesTargetsUniform),
+            TargetDistribution::Center => tr!(Msg::RulesTargetsCenter),
+            TargetDistribution::Edges => tr!(Msg::RulesTargetsEdges),
+            TargetDistribution::Sequence => tr!(Msg::RulesTargetsSequence),
+        }),
+        (tr!(Msg::RulesSpacing), match rules.target_spacing {
+            None => tr!(Msg::RulesSpacingNone),
+            Some(spacing) => tr!(Msg::RulesSpacingUnits, spacing),
+        }),
+        (tr!(Msg::RulesTurnOrder), match rules.turn_order {
+            TurnOrder::Fixed => tr!(Msg::RulesOrderFixed),
+            TurnOrder::Alternate => tr!(Msg::RulesOrderAlternate),
+            TurnOrder::LoserFirst => tr!(Msg::RulesOrderLoserFirst),
+        }),
+        (tr!(Msg::RulesDamage), match rules.damage {
+            DamageModel::Difference => tr!(Msg::RulesDamageDifference),
+            DamageModel::Capped(cap) => tr!(Msg::RulesDamageCapped, cap),
+            DamageModel::Percent(percent) => tr!(Msg::RulesDamagePercent, percent),
+            DamageModel::Fixed(amount) => tr!(Msg::RulesDamageFixed, amount),
+        }),
+        (tr!(Msg::RulesPenalties), items(true)),
+        (tr!(Msg::RulesBonuses), if config.consolation { items(false) } else { tr!(Msg::RulesNoBonuses) }),
+        (tr!(Msg::RulesSpeedFloor), match rules.bounds.speed_floor {
+            SpeedFloor::Knockout => tr!(Msg::RulesFloorKnockout, rules.bounds.speed.min),
+            SpeedFloor::Clamp => tr!(Msg::RulesFloorClamp, rules.bounds.speed.min),
+        }),
+        (tr!(Msg::RulesWin), win_goal(rules.win_condition)),
+    ];
+    lines.push(String::new());
+    lines.push(tr!(Msg::RulesTitle));
+    lines.extend(table(&rows));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KeyBindings;
+    use crate::player::Attribute;
+
+    #[test]
+    fn test_help_follows_the_settings() {
+        let lines = help_lines(&GameConfig::default());
+        assert!(lines.contains(&"ENTER: stop the counter".to_string()));
+        assert!(!lines.iter().any(|line| line.contains("pass it")));
+        assert!(lines.contains(&"A player with no vitality left loses the match.".to_string()));
+
+        let mut config = GameConfig { pass_cost: Some(10), keys: KeyBindings { player1: Some('f'), player2: Some('j') }, ..GameConfig::default() };
+        config.rules.win_condition = WinCondition::RoundWins(3);
+        let lines = help_lines(&config);
+        assert!(!lines.contains(&"ENTER: stop the counter".to_string()));
+        assert!(lines.contains(&format!("j: {} stops the counter", config.player2_name)));
+        assert!(lines.contains(&"p then ENTER, at the start of a turn: pass it for 10 vitality".to_string()));
+        assert!(lines.contains(&tr!(Msg::WinGoalRounds, 3)));
+    }
+
+    #[test]
+    fn test_rules_table_follows_the_scoring_table() {
+        let lines = rules_table(&GameConfig::default());
+        assert!(lines.contains(&"Distance  Base score".to_string()));
+        assert!(lines.contains(&"0         100".to_string()));
+        assert!(lines.contains(&"1–5       80".to_string()));
+        assert!(lines.contains(&"51+       0".to_string()));
+        assert!(lines.iter().any(|line| line.starts_with("Damage") && line.ends_with("The whole score difference")));
+        assert!(lines.iter().any(|line| line.starts_with("Penalties") && line.ends_with("-5 speed, -5 strength")));
+        assert!(lines.iter().any(|line| line.starts_with("Bonuses") && line.ends_with("None, without --consolation")));
+    }
+
+    #[test]
+    fn test_rules_table_follows_the_rules() {
+        let mut config = GameConfig { consolation: true, ..GameConfig::default() };
+        config.rules.damage = DamageModel::Capped(15);
+        config.rules.win_condition = WinCondition::SuddenDeath;
+        config.rules.pool.ban(DraftItem::Penalty(Attribute::Strength));
+        let lines = rules_table(&config);
+
+        assert!(lines.iter().any(|line| line.ends_with("The score difference, at most 15")));
+        assert!(lines.iter().any(|line| line.starts_with("Penalties") && line.ends_with("  -5 speed")));
+        assert!(lines.iter().any(|line| line.starts_with("Bonuses") && line.ends_with("+3 speed, +3 strength, +5 vitality")));
+        assert!(lines.iter().any(|line| line.starts_with("Win condition") && line.ends_with(&tr!(Msg::WinGoalSuddenDeath))));
+    }
+}
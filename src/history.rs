@@ -0,0 +1,198 @@
+//! History module for the timeline of a match.
+//!
+//! Every round played is kept as a `RoundRecord`: both players' scores, the
+//! vitality the loser lost, the penalty the winner chose and the players'
+//! attributes once the round was over. Typing `t` and ENTER at the start of a
+//! turn shows the timeline so far over the game, and it is printed in full
+//! when the match ends.
+//!
+//! The history is part of the game state, so a saved match keeps it and a
+//! resumed match shows its rounds from the first one. Matches saved before
+//! that resume with an empty history, and their timeline says which rounds
+//! are missing.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::game;
+#[cfg(feature = "native")]
+use crate::i18n::{Msg, tr};
+use crate::player::{Attribute, Player};
+use crate::state::{RoundOutcome, TurnOutcome};
+#[cfg(feature = "native")]
+use crate::ui;
+
+/// What a player types at the start of their turn to show the timeline of the match.
+pub const TIMELINE_COMMAND: &str = "t";
+
+/// A player's attributes at the end of a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The player's vitality
+    pub vitality: u32,
+    /// The player's speed
+    pub speed: u32,
+    /// The player's strength
+    pub strength: u32,
+}
+
+impl Snapshot {
+    /// Takes down a player's attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The player
+    ///
+    /// # Returns
+    ///
+    /// The player's vitality, speed and strength as they are now
+    pub fn of(player: &Player) -> Self {
+        Self { vitality: player.vitality(), speed: player.speed(), strength: player.strength() }
+    }
+}
+
+/// How a round of the match went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundRecord {
+    /// The round number
+    pub round: u32,
+    /// How each player's turn went
+    pub turns: [TurnOutcome; 2],
+    /// Who won the round, and the vitality the loser lost
+    pub outcome: RoundOutcome,
+    /// The penalty the winner chose, and how much it took
+    pub penalty: Option<(Attribute, u32)>,
+    /// Both players' attributes once the round was over
+    pub players: [Snapshot; 2],
+}
+
+/// The rounds of a match, in the order they were played.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MatchHistory {
+    /// The rounds played so far
+    rounds: Vec<RoundRecord>,
+}
+
+impl MatchHistory {
+    /// Adds a round to the end of the timeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - How the round went
+    pub fn record(&mut self, record: RoundRecord) {
+        self.rounds.push(record);
+    }
+
+    /// Returns the rounds played so far.
+    ///
+    /// # Returns
+    ///
+    /// The rounds, oldest first
+    pub fn rounds(&self) -> &[RoundRecord] {
+        &self.rounds
+    }
+
+    /// Returns whether no round was recorded yet, so the history can be left out of saves.
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+
+    /// Lays the timeline out as a table, one row per round.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The players' names
+    ///
+    /// # Returns
+    ///
+    /// The lines of the timeline, in the current language
+    #[cfg(feature = "native")]
+    pub fn timeline(&self, names: [&str; 2]) -> Vec<String> {
+        if self.rounds.is_empty() {
+            return vec![tr!(Msg::TimelineEmpty)];
+        }
+        let header = [
+            tr!(Msg::TimelineRound),
+            names[0].to_string(),
+            names[1].to_string(),
+            tr!(Msg::TimelineDamage),
+            tr!(Msg::TimelinePenalty),
+            names[0].to_string(),
+            names[1].to_string(),
+        ];
+        let rows: Vec<Vec<String>> = self.rounds.iter().map(|record| {
+            let scores = record.turns.map(|turn| match turn {
+                TurnOutcome::Played(score) => score.to_string(),
+                TurnOutcome::Passed => tr!(Msg::TimelinePassed),
+            });
+            let damage = match record.outcome {
+                RoundOutcome::Win { loser, damage, pot, .. } => tr!(Msg::TimelineDamageTo, damage + pot, names[loser]),
+                RoundOutcome::Draw => tr!(Msg::TimelineDraw),
+            };
+            let penalty = record.penalty
+                .map_or_else(|| "-".to_string(), |(attribute, amount)| game::penalty_label(attribute, amount));
+            let [first, second] = record.players
+                .map(|player| tr!(Msg::TimelineAttributes, player.vitality, player.speed, player.strength));
+            let [first_score, second_score] = scores;
+            vec![record.round.to_string(), first_score, second_score, damage, penalty, first, second]
+        }).collect();
+
+        let mut lines = vec![tr!(Msg::TimelineTitle)];
+        // A match saved before its history was resumes with the rounds played since
+        if let Some(first) = self.rounds.first().filter(|first| first.round > 1) {
+            lines.push(tr!(Msg::TimelineMissing, first.round - 1));
+        }
+        lines.extend(ui::table_lines(&header, &rows));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_lists_the_rounds_in_order() {
+        let mut history = MatchHistory::default();
+        assert_eq!(history.timeline(["Alice", "Bob"]), [tr!(Msg::TimelineEmpty)]);
+
+        history.record(RoundRecord {
+            round: 1,
+            turns: [TurnOutcome::Played(80), TurnOutcome::Played(50)],
+            outcome: RoundOutcome::Win { winner: 0, loser: 1, diff: 30, damage: 30, pot: 0 },
+            penalty: Some((Attribute::Speed, 5)),
+            players: [Snapshot { vitality: 100, speed: 50, strength: 50 }, Snapshot { vitality: 70, speed: 45, strength: 50 }],
+        });
+        history.record(RoundRecord {
+            round: 2,
+            turns: [TurnOutcome::Passed, TurnOutcome::Played(60)],
+            outcome: RoundOutcome::Draw,
+            penalty: None,
+            players: [Snapshot { vitality: 90, speed: 50, strength: 50 }, Snapshot { vitality: 70, speed: 45, strength: 50 }],
+        });
+        let lines = history.timeline(["Alice", "Bob"]);
+
+        assert_eq!(history.rounds().len(), 2);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[3].contains("30 to Bob") && lines[3].contains("-5 speed"));
+        assert!(lines[3].ends_with("HP:70 Sp:45 Str:50"));
+        assert!(lines[4].contains("passed") && lines[4].contains("draw"));
+    }
+
+    #[test]
+    fn test_timeline_of_a_resumed_match_names_the_missing_rounds() {
+        let mut history = MatchHistory::default();
+        history.record(RoundRecord {
+            round: 4,
+            turns: [TurnOutcome::Played(80), TurnOutcome::Played(80)],
+            outcome: RoundOutcome::Draw,
+            penalty: None,
+            players: [Snapshot { vitality: 50, speed: 50, strength: 50 }; 2],
+        });
+        let lines = history.timeline(["Alice", "Bob"]);
+
+        assert_eq!(lines[1], tr!(Msg::TimelineMissing, 3));
+        assert_eq!(lines.len(), 5);
+    }
+}
@@ -0,0 +1,225 @@
+//! HTTP module for the small HTTP/1.1 endpoints the game serves.
+//!
+//! The metrics of the matchmaking server and the `--api` mode each answer
+//! one request per connection: this module reads the request line, the
+//! headers and a bounded body, and writes a response with its length, then
+//! the connection is closed.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{GameError, GameResult};
+
+/// Longest request line or header read from a client, in bytes.
+const MAX_LINE: u64 = 8 * 1024;
+
+/// Largest request body read from a client, in bytes.
+pub const MAX_BODY: usize = 64 * 1024;
+
+/// How long a client has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A request read from a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The method, such as `GET` or `POST`
+    pub method: String,
+    /// The path, without the query string
+    pub path: String,
+    /// The body, empty if none was sent
+    pub body: String,
+}
+
+/// A response to send back to a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The status code and reason, such as `200 OK`
+    pub status: &'static str,
+    /// The media type of the body
+    pub content_type: &'static str,
+    /// The body
+    pub body: String,
+}
+
+impl Response {
+    /// Creates a plain text response.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status code and reason
+    /// * `body` - The text to send
+    ///
+    /// # Returns
+    ///
+    /// A new Response
+    pub fn text(status: &'static str, body: impl Into<String>) -> Self {
+        Self { status, content_type: "text/plain; charset=utf-8", body: body.into() }
+    }
+
+    /// Creates a JSON response.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status code and reason
+    /// * `body` - The JSON document to send
+    ///
+    /// # Returns
+    ///
+    /// A new Response
+    pub fn json(status: &'static str, body: impl Into<String>) -> Self {
+        Self { status, content_type: "application/json", body: body.into() }
+    }
+}
+
+/// Answers the clients connecting to a listener until it fails, each one on its own thread.
+///
+/// # Arguments
+///
+/// * `listener` - Where the clients connect
+/// * `handler` - What answers each request
+///
+/// # Returns
+///
+/// Result indicating why the listener stopped
+pub fn serve<H>(listener: TcpListener, handler: H) -> GameResult<()>
+where
+    H: Fn(&Request) -> Response + Clone + Send + 'static,
+{
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Could not accept a connection: {}", e);
+                continue;
+            },
+        };
+        let handler = handler.clone();
+        thread::spawn(move || {
+            if let Err(e) = answer(stream, handler) {
+                log::warn!("{}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Answers one request.
+///
+/// # Arguments
+///
+/// * `stream` - The client's connection
+/// * `handler` - What answers the request
+///
+/// # Returns
+///
+/// Result indicating whether the request could be read and answered
+fn answer(mut stream: TcpStream, handler: impl Fn(&Request) -> Response) -> GameResult<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let response = match read_request(&stream) {
+        Ok(request) => handler(&request),
+        Err(GameError::ParseError(reason)) => Response::text("400 Bad Request", reason + "\n"),
+        Err(e) => return Err(e),
+    };
+    write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+           response.status, response.content_type, response.body.len(), response.body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Reads a request from a client.
+///
+/// # Arguments
+///
+/// * `stream` - The client's connection
+///
+/// # Returns
+///
+/// Result containing the request, or `GameError::ParseError` if it is not valid HTTP
+pub fn read_request(stream: &TcpStream) -> GameResult<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut read_line = || -> GameResult<String> {
+        let mut line = String::new();
+        (&mut reader).take(MAX_LINE).read_line(&mut line)?;
+        Ok(line.trim_end().to_string())
+    };
+
+    let request_line = read_line()?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(GameError::ParseError(format!("Invalid request line: {}", request_line)));
+    };
+    let (method, path) = (method.to_string(), target.split('?').next().unwrap_or_default().to_string());
+
+    let mut length = 0;
+    loop {
+        let header = read_line()?;
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            length = value.trim().parse::<usize>()
+                .map_err(|_| GameError::ParseError(format!("Invalid content length: {}", value.trim())))?;
+        }
+    }
+    if length > MAX_BODY {
+        return Err(GameError::ParseError(format!("The body is longer than {} bytes", MAX_BODY)));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8(body).map_err(|_| GameError::ParseError("The body is not UTF-8".to_string()))?;
+    Ok(Request { method, path, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves a handler on a free port.
+    fn start(handler: impl Fn(&Request) -> Response + Clone + Send + 'static) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || serve(listener, handler));
+        address
+    }
+
+    /// Sends raw bytes to a server and returns its whole answer.
+    fn send(address: &str, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_request_is_handed_to_the_handler() {
+        let address = start(|request| Response::text("200 OK", format!("{} {} {}", request.method, request.path, request.body)));
+        let response = send(&address, "POST /games?debug=1 HTTP/1.1\r\nHost: x\r\ncontent-length: 5\r\n\r\nhello");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Length: 17\r\n"));
+        assert!(response.ends_with("\r\n\r\nPOST /games hello"));
+    }
+
+    #[test]
+    fn test_invalid_requests_are_refused() {
+        let address = start(|_| Response::text("200 OK", "unreachable"));
+
+        assert!(send(&address, "nonsense\r\n\r\n").starts_with("HTTP/1.1 400 Bad Request"));
+        let too_long = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        assert!(send(&address, &too_long).starts_with("HTTP/1.1 400 Bad Request"));
+    }
+
+    #[test]
+    fn test_json_response() {
+        let response = Response::json("201 Created", "{}");
+        assert_eq!(response.content_type, "application/json");
+        assert_eq!(response.body, "{}");
+    }
+}
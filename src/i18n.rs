@@ -0,0 +1,1442 @@
+//! Localization module for user-facing strings.
+//!
+//! Every message shown to the players is identified by a `Msg` key and looked up
+//! in a translation table for the selected language. Message templates use `{}`
+//! placeholders that are filled in order by `format_message` or the `tr!` macro.
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::error::GameError;
+
+/// The language currently used to render messages.
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::En as u8);
+
+/// The languages messages can be rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum Language {
+    /// English
+    #[default]
+    En,
+    /// French
+    Fr,
+    /// Spanish
+    Es,
+}
+
+impl FromStr for Language {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "en" => Ok(Language::En),
+            "fr" => Ok(Language::Fr),
+            "es" => Ok(Language::Es),
+            other => Err(GameError::ConfigError(format!("Unknown language: {}", other))),
+        }
+    }
+}
+
+/// Sets the language used to render messages.
+///
+/// # Arguments
+///
+/// * `language` - The language to switch to
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// Returns the language currently used to render messages.
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        x if x == Language::Fr as u8 => Language::Fr,
+        x if x == Language::Es as u8 => Language::Es,
+        _ => Language::En,
+    }
+}
+
+/// Declares the message keys, with `Msg::ALL` listing every one of them.
+macro_rules! message_keys {
+    ($($key:ident,)*) => {
+        /// Keys of all the user-facing messages.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $($key,)*
+        }
+
+        impl Msg {
+            /// Every message key, in the order they are declared.
+            pub const ALL: &'static [Msg] = &[$(Msg::$key,)*];
+        }
+    };
+}
+
+message_keys! {
+    GameError,
+    ReadError,
+    RematchMenu,
+    RematchSame,
+    RematchSwap,
+    RematchCarryOver,
+    RematchQuit,
+    YesKey,
+    GameStarted,
+    CounterRange,
+    RhythmMode,
+    RoundStart,
+    RoundTargets,
+    WeatherClear,
+    WeatherFog,
+    WeatherStorm,
+    WeatherCalm,
+    FirstPlayer,
+    BlindReveal,
+    ObjectiveHidden,
+    RunningAverage,
+    RunningAverageToBeat,
+    RunningAverageBeaten,
+    RoundEnd,
+    GameOver,
+    Winner,
+    TurnHeader,
+    Objectives,
+    ObjectivesHidden,
+    PressEnterStart,
+    PressEnterStartOrResign,
+    PressEnterStartOrPass,
+    TurnPassed,
+    PassedRoundWin,
+    Resigned,
+    PressEnterStop,
+    PressKeyStop,
+    DuelKeys,
+    DuelStop,
+    PlayerAverageScore,
+    NextStop,
+    MultiStopResult,
+    ObjectiveResult,
+    HandicapValue,
+    ImplausibleStop,
+    TargetVoided,
+    ObjectiveDetails,
+    TargetDifficulty,
+    DifficultyEasy,
+    DifficultyNormal,
+    DifficultyHard,
+    DifficultyExtreme,
+    StopDistance,
+    TurnEnd,
+    AverageScore,
+    RoundWin,
+    Draw,
+    ChoosePoison,
+    ChoosePenalty,
+    PenaltySpeed,
+    PenaltyStrength,
+    SpeedReduced,
+    SpeedAtFloor,
+    StrengthAtFloor,
+    SpeedKnockout,
+    MutatorLoss,
+    WinGoalRounds,
+    WinGoalScore,
+    WinGoalSuddenDeath,
+    WinRounds,
+    WinHighScore,
+    WinSuddenDeath,
+    StrengthReduced,
+    ChooseConsolation,
+    ConsolationSpeed,
+    ConsolationStrength,
+    ConsolationVitality,
+    ConsolationGained,
+    DraftStart,
+    DraftBan,
+    DraftBanned,
+    DraftPick,
+    DraftPicked,
+    CounterBar,
+    CounterCaption,
+    CounterCaptionShort,
+    InvalidChoice,
+    ChoiceTimer,
+    PenaltyTimeout,
+    UnparsableChoice,
+    MatchInterrupted,
+    PlayerStatus,
+    SavePrompt,
+    MatchSaved,
+    SaveFailed,
+    MatchResumed,
+    AutosaveFailed,
+    AutosaveFound,
+    AutosaveUnreadable,
+    ResultsExported,
+    ExportFailed,
+    SummaryHeader,
+    SummaryPlayer,
+    SummaryScores,
+    SummaryVitality,
+    SummaryTargets,
+    SummaryExactHits,
+    SummaryResigned,
+    ScoreboardRound,
+    ScoreboardPlayer,
+    VitalityBar,
+    Healed,
+    ReactionReady,
+    ReactionNow,
+    ReactionWin,
+    ReactionFalseStart,
+    CampaignStage,
+    QuirkNone,
+    QuirkFastCounter,
+    QuirkHeavyPenalty,
+    QuirkRelentless,
+    CampaignLost,
+    CampaignStageCleared,
+    CampaignContinue,
+    CampaignComplete,
+    SurvivalStart,
+    SurvivalWave,
+    SurvivalHit,
+    SurvivalMiss,
+    SurvivalWaveCleared,
+    SurvivalOver,
+    SurvivalRecord,
+    GhostStop,
+    GhostRace,
+    GhostMissing,
+    GhostRecorded,
+    PracticeStart,
+    TimeAttackStart,
+    PracticeStop,
+    TimeAttackStop,
+    TimeAttackMiss,
+    PracticeOver,
+    PracticeName,
+    TimeAttackName,
+    PracticePoints,
+    TimeAttackSeconds,
+    SimulationResult,
+    SimulationPlayer,
+    SimulationDraws,
+    StatsCampaign,
+    StatsSurvival,
+    StatsLatency,
+    CalibrationStart,
+    CalibrationReady,
+    CalibrationEarly,
+    CalibrationSample,
+    CalibrationResult,
+    CalibrationFailed,
+    NetQueued,
+    NetMatchFound,
+    NetConnectionLost,
+    NetPlayerLeft,
+    NetMatchStarted,
+    NetMatchEnded,
+    NetReconnecting,
+    NetResumed,
+    NetWaitingForRejoin,
+    NetChat,
+    NetChatHint,
+    PresenceStarting,
+    PresenceRound,
+    PresenceVitality,
+    PresenceOver,
+    PresenceUnavailable,
+    NetListening,
+    MetricsListening,
+    ApiListening,
+    ReplaySummary,
+    ReplayStop,
+    GamblePrompt,
+    GamblePromptHidden,
+    GambleWon,
+    GambleLost,
+    CriticalHit,
+    BeatBonus,
+    CounterCombo,
+    CounterBeat,
+    CounterGhost,
+    CounterWarmer,
+    CounterColder,
+    ComboBonus,
+    MomentumStolen,
+    WagerPrompt,
+    WagerTooHigh,
+    WagerUnparsable,
+    PotWon,
+    WagersReturned,
+    GuiStartStop,
+    GuiPass,
+    GuiResign,
+    GuiSend,
+    HelpKeysTitle,
+    HelpStart,
+    HelpStopEnter,
+    HelpStopKey,
+    HelpResign,
+    HelpPass,
+    HelpMenus,
+    HelpHelp,
+    HelpRulesTitle,
+    HelpTargets,
+    HelpTargetsGrow,
+    HelpScoring,
+    HelpRounds,
+    HelpWinVitality,
+    HelpDismiss,
+    HelpHint,
+    TutorialWelcome,
+    TutorialContinue,
+    TutorialTargets,
+    TutorialCounter,
+    TutorialStop,
+    TutorialEnterKey,
+    TutorialNoMiss,
+    TutorialMiss,
+    TutorialScore,
+    TutorialAverage,
+    TutorialTutor,
+    TutorialTutorTurn,
+    TutorialRoundWin,
+    TutorialRoundDraw,
+    TutorialPenalty,
+    TutorialEnd,
+    RulesScoringTitle,
+    RulesDistance,
+    RulesBaseScore,
+    RulesFormula,
+    RulesTitle,
+    RulesCounter,
+    RulesCounterRange,
+    RulesWholeUnits,
+    RulesTenths,
+    RulesTick,
+    RulesTickValue,
+    RulesSpeedCurve,
+    RulesCurveFlat,
+    RulesCurveRamp,
+    RulesTargets,
+    RulesTargetsUniform,
+    RulesTargetsCenter,
+    RulesTargetsEdges,
+    RulesTargetsSequence,
+    RulesSpacing,
+    RulesSpacingNone,
+    RulesSpacingUnits,
+    StealPrompt,
+    StealTarget,
+    StealNone,
+    StealBonus,
+    HelpSharedPool,
+    PredictionPrompt,
+    PredictionUnparsable,
+    PredictionHit,
+    PredictionMissed,
+    HelpPredictions,
+    RiskPrompt,
+    HelpRisk,
+    RulesTurnOrder,
+    RulesOrderFixed,
+    RulesOrderAlternate,
+    RulesOrderLoserFirst,
+    RulesDamage,
+    RulesDamageDifference,
+    RulesDamageCapped,
+    RulesDamagePercent,
+    RulesDamageFixed,
+    RulesPenalties,
+    RulesBonuses,
+    RulesSpeedFloor,
+    RulesFloorKnockout,
+    RulesFloorClamp,
+    RulesWin,
+    RulesNoBonuses,
+    CounterTimer,
+    TurnDuration,
+    TableTarget,
+    TableStop,
+    TableDistance,
+    TableMiss,
+    TableScore,
+    TableOwner,
+    RecapTitle,
+    RecapTarget,
+    RecapCell,
+    RecapCloser,
+    RecapTie,
+    RecapCount,
+    TimelineTitle,
+    TimelineMissing,
+    TimelineEmpty,
+    TimelineRound,
+    TimelineDamage,
+    TimelinePenalty,
+    TimelinePassed,
+    TimelineDamageTo,
+    TimelineDraw,
+    TimelineAttributes,
+    HelpTimeline,
+}
+
+/// Returns the message template in the current language.
+///
+/// # Arguments
+///
+/// * `msg` - The message key
+///
+/// # Returns
+///
+/// The message template, with `{}` placeholders for its arguments
+pub fn text(msg: Msg) -> &'static str {
+    text_in(language(), msg)
+}
+
+/// Returns the message template in the given language.
+///
+/// # Arguments
+///
+/// * `language` - The language to look the message up in
+/// * `msg` - The message key
+///
+/// # Returns
+///
+/// The message template, with `{}` placeholders for its arguments
+pub fn text_in(language: Language, msg: Msg) -> &'static str {
+    match language {
+        Language::En => english(msg),
+        Language::Fr => french(msg),
+        Language::Es => spanish(msg),
+    }
+}
+
+/// Fills the `{}` placeholders of a template with the given arguments, in order.
+///
+/// Placeholders without a matching argument are left untouched.
+///
+/// # Arguments
+///
+/// * `template` - The message template
+/// * `args` - The values to insert
+///
+/// # Returns
+///
+/// The formatted message
+pub fn format_message(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find("{}") {
+        output.push_str(&rest[..pos]);
+        match args.next() {
+            // Writing to a String cannot fail
+            Some(arg) => { let _ = write!(output, "{}", arg); },
+            None => output.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    output.push_str(rest);
+
+    output
+}
+
+/// Renders a message in the current language, filling in its arguments.
+///
+/// ```ignore
+/// log::info!("{}", tr!(Msg::RoundStart, round));
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($msg:expr) => {
+        $crate::i18n::format_message($crate::i18n::text($msg), &[])
+    };
+    ($msg:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format_message($crate::i18n::text($msg), &[$(&$arg),+])
+    };
+}
+pub use crate::tr;
+
+/// English translation table.
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::GameError => "Game error: {}",
+        Msg::ReadError => "Error reading input: {}",
+        Msg::RematchMenu => "The match is over. What next?",
+        Msg::RematchSame => "Rematch with the same settings",
+        Msg::RematchSwap => "Rematch, the other player goes first",
+        Msg::RematchCarryOver => "Rematch, keeping the speed, strength and stamina left",
+        Msg::RematchQuit => "Quit",
+        Msg::YesKey => "y",
+        Msg::GameStarted => "##### Game Started #####",
+        Msg::CounterRange => "Counter range: 0–{}",
+        Msg::RhythmMode => "Rhythm mode: a beat every {} units, stop on a beat for {} bonus points.",
+        Msg::RoundStart => "## Round {} ##",
+        Msg::RoundTargets => "{} targets per turn this round.",
+        Msg::WeatherClear => "Weather: clear skies, nothing changes this round.",
+        Msg::WeatherFog => "Weather: fog! The targets are hidden this round.",
+        Msg::WeatherStorm => "Weather: storm! The counter runs 20% faster this round.",
+        Msg::WeatherCalm => "Weather: calm. Misses are not counted this round.",
+        Msg::FirstPlayer => "{} goes first this round.",
+        Msg::BlindReveal => "Both turns are over, here are the results:",
+        Msg::ObjectiveHidden => "→ Objective {}: result hidden until the end of the round",
+        Msg::RunningAverage => "  Running average: {}",
+        Msg::RunningAverageToBeat => "  Running average: {} | {} per remaining target to beat {}",
+        Msg::RunningAverageBeaten => "  Running average: {} | {} is already beaten",
+        Msg::RoundEnd => "## End of round {} ##",
+        Msg::GameOver => "##### Game Over #####",
+        Msg::Winner => "Winner: {} \n",
+        Msg::TurnHeader => "{}'s turn (Vitality={}, Speed={}, Strength={}, Momentum={})",
+        Msg::Objectives => "→ Objectives: {}",
+        Msg::ObjectivesHidden => "→ Objectives: {} hidden targets, follow the hints",
+        Msg::PressEnterStart => "→ Press ENTER to start the turn..",
+        Msg::PressEnterStartOrResign => "→ Press ENTER to start the turn, or type {} and ENTER to resign..",
+        Msg::Resigned => "{} resigns the match.",
+        Msg::PressEnterStartOrPass => "→ Press ENTER to start the turn, type {} and ENTER to pass it for {} vitality, or {} to resign..",
+        Msg::TurnPassed => "{} passes the turn and pays {} vitality points.",
+        Msg::PassedRoundWin => "{} wins the round. {} passed and takes no damage.",
+        Msg::PressEnterStop => "Press ENTER to stop the counter.",
+        Msg::PressKeyStop => "Press {} to stop the counter.",
+        Msg::DuelKeys => "Duel! {} stops with {}, {} stops with {}.",
+        Msg::DuelStop => "→ #{} {}: Counter = {} ({} ms)",
+        Msg::PlayerAverageScore => "→ {}: average score {}",
+        Msg::NextStop => "Stop {} of {}: stop the counter again.",
+        Msg::MultiStopResult => "→ Stops: {}, combined value: {}",
+        Msg::ObjectiveResult => "→ Objective {}: Miss = {} | Counter = {} // Score = ({} + {}) / {} = {}",
+        Msg::HandicapValue => "{} ({} {} handicap)",
+        Msg::ImplausibleStop => "{} stopped the counter after only {} ms, faster than a human could",
+        Msg::TargetVoided => "{} made {} implausibly fast stops in a row, the target is voided",
+        Msg::StopDistance => "  Distance from target: {}",
+        Msg::ObjectiveDetails => "  Tick interval: {} ms | Reaction time: {} ms",
+        Msg::TargetDifficulty => "Counter +{} every {} ms, {} to {} in a lap ({} s): {}",
+        Msg::DifficultyEasy => "easy",
+        Msg::DifficultyNormal => "normal",
+        Msg::DifficultyHard => "hard",
+        Msg::DifficultyExtreme => "extreme",
+        Msg::TurnEnd => "# End of turn #",
+        Msg::AverageScore => "→ Average score: {} \n",
+        Msg::RoundWin => "{} wins the round. {} loses {} vitality points.",
+        Msg::Draw => "It's a draw! No penalties applied.",
+        Msg::ChoosePoison => "{}, you must choose which poison to apply to {}:",
+        Msg::ChoosePenalty => "Choose a penalty:",
+        Msg::PenaltySpeed => "-{} speed",
+        Msg::PenaltyStrength => "-{} strength",
+        Msg::SpeedReduced => "{}'s speed reduced by {}!",
+        Msg::SpeedAtFloor => "{}'s speed cannot go any lower.",
+        Msg::StrengthAtFloor => "{}'s strength cannot go any lower.",
+        Msg::SpeedKnockout => "Game Over! {} has lost because their speed reached {}!",
+        Msg::MutatorLoss => "Game Over! {} loses the match under the {} mutator!",
+        Msg::WinGoalRounds => "The first player to win {} rounds wins the match.",
+        Msg::WinGoalScore => "The highest total score after {} rounds wins the match.",
+        Msg::WinGoalSuddenDeath => "Sudden death: the first exact hit wins the match.",
+        Msg::WinRounds => "{} wins {} rounds and takes the match!",
+        Msg::WinHighScore => "{} wins the match with a total score of {} against {}!",
+        Msg::WinSuddenDeath => "{} hits a target exactly and wins the match!",
+        Msg::StrengthReduced => "{}'s strength reduced by {}!",
+        Msg::ChooseConsolation => "{}, pick a consolation bonus:",
+        Msg::ConsolationSpeed => "+{} speed",
+        Msg::ConsolationStrength => "+{} strength",
+        Msg::ConsolationVitality => "+{} vitality",
+        Msg::ConsolationGained => "{} gains {}.",
+        Msg::DraftStart => "Draft: each player bans a penalty or bonus from the match, then picks one only they can use.",
+        Msg::DraftBan => "{}, ban a penalty or bonus:",
+        Msg::DraftBanned => "{} bans {}.",
+        Msg::DraftPick => "{}, pick a penalty or bonus for yourself:",
+        Msg::DraftPicked => "{} picks {}.",
+        Msg::CounterBar => "→ Objective {}: {} Miss = {} | Counter = {}",
+        Msg::CounterCaption => "→ Objective {}: Miss = {} | Counter = {}",
+        Msg::CounterCaptionShort => "→ Objective {}: Miss = {}",
+        Msg::InvalidChoice => "Invalid choice. Selecting the first option by default.",
+        Msg::ChoiceTimer => "Press the number of your choice within {} seconds.",
+        Msg::PenaltyTimeout => "Time is up, a penalty is picked at random.",
+        Msg::UnparsableChoice => "Could not parse input. Selecting the first option by default.",
+        Msg::MatchInterrupted => "##### Match interrupted during round {} #####",
+        Msg::PlayerStatus => "→ {}: Vitality={}, Speed={}, Strength={}",
+        Msg::SavePrompt => "Save the match in progress? [Y/N]",
+        Msg::MatchSaved => "Match saved to {}. Resume it with --load {}",
+        Msg::SaveFailed => "Could not save the match: {}",
+        Msg::MatchResumed => "##### Resuming match at round {} #####",
+        Msg::AutosaveFailed => "Could not autosave the match: {}",
+        Msg::AutosaveFound => "An unfinished match was autosaved at round {}. Resume it? [Y/N]",
+        Msg::AutosaveUnreadable => "Could not load autosave from {}: {}",
+        Msg::ResultsExported => "Results exported to {}",
+        Msg::ExportFailed => "Could not export the results: {}",
+        Msg::SummaryHeader => "##### Match summary #####",
+        Msg::SummaryPlayer => "{}",
+        Msg::SummaryScores => "  Scores per round: {} ({})",
+        Msg::SummaryVitality => "  Vitality:         {} ({})",
+        Msg::SummaryTargets => "  Best target: {} ({} points) | Worst target: {} ({} points)",
+        Msg::SummaryExactHits => "  Exact hits: {}",
+        Msg::SummaryResigned => "  Resigned in round {}",
+        Msg::ScoreboardRound => "Round {}",
+        Msg::ScoreboardPlayer => "{} {} Sp:{}/{} Str:{}/{} Sta:{}/{}",
+        Msg::VitalityBar => "→ {} {}",
+        Msg::Healed => "{} made {} exact hits and recovers {} vitality points.",
+        Msg::ReactionReady => "Reaction game! {} presses {}, {} presses {} as soon as NOW! appears.",
+        Msg::ReactionNow => ">>>>> NOW! <<<<<",
+        Msg::ReactionWin => "{} reacted first ({} ms) and steals {} vitality points from {}.",
+        Msg::ReactionFalseStart => "{} pressed too early! {} steals {} vitality points.",
+        Msg::CampaignStage => "##### Campaign stage {} of {}: {} ({}) #####",
+        Msg::QuirkNone => "plays by the usual rules",
+        Msg::QuirkFastCounter => "your counter runs faster",
+        Msg::QuirkHeavyPenalty => "its penalties take {} points",
+        Msg::QuirkRelentless => "your counter runs faster and its penalties take {} points",
+        Msg::CampaignLost => "{} wins. Play the campaign again to retry stage {}.",
+        Msg::CampaignStageCleared => "{} is defeated! Progress saved to {}.",
+        Msg::CampaignContinue => "Face the next opponent?",
+        Msg::CampaignComplete => "You have beaten every opponent of the campaign!",
+        Msg::SurvivalStart => "Survival mode: stop within {} of each target, every miss costs {} vitality points. Best so far: {} waves.",
+        Msg::SurvivalWave => "## Wave {} (tick interval {} ms) ##",
+        Msg::SurvivalHit => "→ Objective {}: Counter = {}, safe.",
+        Msg::SurvivalMiss => "→ Objective {}: Counter = {}, missed! You lose {} vitality points.",
+        Msg::SurvivalWaveCleared => "Wave {} survived, the counter speeds up.",
+        Msg::SurvivalOver => "{} survived {} waves.",
+        Msg::SurvivalRecord => "New record! Saved to {}.",
+        Msg::GhostStop => "  Ghost: Counter = {}",
+        Msg::GhostRace => "Racing the ghost of a run of {}.",
+        Msg::GhostMissing => "No ghost recorded in {} yet, playing without one.",
+        Msg::GhostRecorded => "This run is the new ghost, recorded in {}.",
+        Msg::PracticeStart => "Practice: {} targets, each stop scored as in a match. The best total is recorded as a ghost.",
+        Msg::TimeAttackStart => "Time attack: {} targets, each stop timed from the start of the counter. A stop more than {} away from its target, or after the counter went around, adds {} s. The fastest run is recorded as a ghost.",
+        Msg::PracticeStop => "→ Objective {}: Counter = {}, {} points.",
+        Msg::TimeAttackStop => "→ Objective {}: Counter = {}, {} s.",
+        Msg::TimeAttackMiss => "→ Objective {}: Counter = {}, {} s, missed! {} s added.",
+        Msg::PracticeOver => "{} finished the run: {}.",
+        Msg::PracticeName => "practice",
+        Msg::TimeAttackName => "time attack",
+        Msg::PracticePoints => "{} points",
+        Msg::TimeAttackSeconds => "{} s",
+        Msg::SimulationResult => "{} simulated matches, {} rounds each on average.",
+        Msg::SimulationPlayer => "{} won {} matches ({}%).",
+        Msg::SimulationDraws => "{} matches reached {} rounds and were called a draw.",
+        Msg::StatsCampaign => "Campaign: {} of {} opponents beaten.",
+        Msg::StatsSurvival => "Survival: best run of {} waves.",
+        Msg::StatsLatency => "Latency offset: {} ms.",
+        Msg::CalibrationStart => "Calibration: press ENTER as soon as NOW! appears, {} times in a row.",
+        Msg::CalibrationReady => "Get ready... ({}/{})",
+        Msg::CalibrationEarly => "Too early, this press does not count.",
+        Msg::CalibrationSample => "{} ms",
+        Msg::CalibrationResult => "Latency offset: {} ms, saved to {}. Play with --latency-credit to have it credited back to each stop.",
+        Msg::CalibrationFailed => "No press could be measured, the profile is unchanged.",
+        Msg::NetQueued => "Waiting for an opponent to join...",
+        Msg::NetMatchFound => "Matched against {}, you are player {}.",
+        Msg::NetConnectionLost => "The connection to the server was lost.",
+        Msg::NetPlayerLeft => "{} left the match.",
+        Msg::NetMatchStarted => "Match started: {} against {}",
+        Msg::NetMatchEnded => "Match over: {} against {}",
+        Msg::NetReconnecting => "Connection lost, trying to rejoin the match...",
+        Msg::NetResumed => "Back in the match, round {} starts again.",
+        Msg::NetWaitingForRejoin => "{} lost the connection, the match waits {} seconds for them to come back.",
+        Msg::NetChat => "[{}] {}",
+        Msg::NetChatHint => "Type /say followed by a message to talk to your opponent.",
+        Msg::PresenceStarting => "Starting a match",
+        Msg::PresenceRound => "Round {} against {}",
+        Msg::PresenceVitality => "Vitality {} to {}",
+        Msg::PresenceOver => "{} won the match",
+        Msg::PresenceUnavailable => "Discord Rich Presence unavailable: {}",
+        Msg::NetListening => "Waiting for players on {}",
+        Msg::MetricsListening => "Serving metrics on http://{}/metrics",
+        Msg::ApiListening => "Serving the game API on http://{}",
+        Msg::ReplaySummary => "Recorded {} run: seed {}, {}, {} stops.",
+        Msg::ReplayStop => "  Target {}: Counter = {}, state hash {}",
+        Msg::GamblePrompt => "Double or nothing on target {}? Costs {} stamina ({} left) [Y/N]",
+        Msg::GamblePromptHidden => "Double or nothing on the next objective? Costs {} stamina ({} left) [Y/N]",
+        Msg::GambleWon => "  Double or nothing: won, the score is doubled to {}!",
+        Msg::GambleLost => "  Double or nothing: lost, the score is 0.",
+        Msg::CriticalHit => "  Critical hit! The score is raised to {}.",
+        Msg::BeatBonus => "  On the beat! +{} points.",
+        Msg::CounterCombo => " | Combo x{}",
+        Msg::CounterBeat => " | ♪",
+        Msg::CounterGhost => " | Ghost {}",
+        Msg::CounterWarmer => " | Warmer",
+        Msg::CounterColder => " | Colder",
+        Msg::ComboBonus => "  Combo x{}: base score {} → {}",
+        Msg::MomentumStolen => "  Exact hit! {} steals {} momentum from {}.",
+        Msg::WagerPrompt => "{}, how much vitality do you wager on this round? (0 to {}, ENTER for none)",
+        Msg::WagerTooHigh => "You cannot wager more than {} vitality points.",
+        Msg::WagerUnparsable => "Please enter a number between 0 and {}.",
+        Msg::PotWon => "{} takes the pot of {} vitality points: {} loses {} more vitality points.",
+        Msg::WagersReturned => "The wagers are returned to both players.",
+        Msg::GuiStartStop => "Start / Stop",
+        Msg::GuiPass => "Pass",
+        Msg::GuiResign => "Resign",
+        Msg::GuiSend => "Send",
+        Msg::HelpKeysTitle => "Keys",
+        Msg::HelpStart => "ENTER: start the counter",
+        Msg::HelpStopEnter => "ENTER: stop the counter",
+        Msg::HelpStopKey => "{}: {} stops the counter",
+        Msg::HelpResign => "{} then ENTER, at the start of a turn: resign the match",
+        Msg::HelpPass => "{} then ENTER, at the start of a turn: pass it for {} vitality",
+        Msg::HelpMenus => "Arrow keys and ENTER, or a number: choose in a menu",
+        Msg::HelpHelp => "{} then ENTER at the start of a turn, or {} alone while the counter runs (it pauses): show this help",
+        Msg::HelpRulesTitle => "Rules",
+        Msg::HelpTargets => "Stop the counter as close as you can to each of the {} targets of a turn, on a dial from 0 to {}.",
+        Msg::HelpTargetsGrow => "Each round adds a target to the turns, up to {}.",
+        Msg::HelpScoring => "Strength adds to the score of each target, and every time the counter goes around divides it.",
+        Msg::HelpRounds => "The best average score wins the round: the loser loses vitality and takes a penalty.",
+        Msg::HelpWinVitality => "A player with no vitality left loses the match.",
+        Msg::HelpDismiss => "Press ENTER to go back to the game.",
+        Msg::HelpHint => "Type {} and press ENTER at the start of a turn, or press it while your counter runs, to see the keys and rules.",
+        Msg::TutorialWelcome => "Welcome to the tutorial! You will play one turn against the tutor, one step at a time.",
+        Msg::TutorialContinue => "(Press ENTER to go on)",
+        Msg::TutorialTargets => "Each turn has targets between 0 and {}. Yours are {}. The closer you stop the counter to a target, the more it scores.",
+        Msg::TutorialCounter => "For each target a counter climbs from 0 to {}, then starts over from 0. Every time it starts over is a miss, and misses divide the score.",
+        Msg::TutorialStop => "Target {}: press ENTER to start the counter, then {} to stop it as close to the target as you can.",
+        Msg::TutorialEnterKey => "ENTER",
+        Msg::TutorialNoMiss => "You stopped the counter before it started over: no miss.",
+        Msg::TutorialMiss => "The counter went past {} and started over {} time(s) before you stopped it: the score is divided by {}.",
+        Msg::TutorialScore => "Your distance from the target gives a base score of {}. Your strength, {}, is added to it, and the sum is divided by the misses plus one: ({} + {}) / {} = {}.",
+        Msg::TutorialAverage => "Your turn scores the average of its targets: {}.",
+        Msg::TutorialTutor => "Tutor",
+        Msg::TutorialTutorTurn => "The tutor played its turn too, stopping at {}, for an average of {}.",
+        Msg::TutorialRoundWin => "{} scored more and wins the round. The difference, {} points, costs {} {} vitality.",
+        Msg::TutorialRoundDraw => "Both turns scored the same: the round is a draw and nobody loses vitality.",
+        Msg::TutorialPenalty => "The winner of a round also picks a penalty for the loser: -{} speed makes their counter run faster, -{} strength lowers their scores.",
+        Msg::TutorialEnd => "A match goes on round after round until a player has no vitality left. Type {} and press ENTER at the start of a turn to see the keys and rules again. Good luck!",
+        Msg::RulesScoringTitle => "Scoring table",
+        Msg::RulesDistance => "Distance",
+        Msg::RulesBaseScore => "Base score",
+        Msg::RulesFormula => "Score = (base score + strength) / (misses + 1)",
+        Msg::RulesTitle => "Rules",
+        Msg::RulesCounter => "Counter",
+        Msg::RulesCounterRange => "0 to {}, in {}",
+        Msg::RulesWholeUnits => "whole units",
+        Msg::RulesTenths => "tenths of a unit",
+        Msg::RulesTick => "Counter tick",
+        Msg::RulesTickValue => "{} ms at a speed of 100",
+        Msg::RulesSpeedCurve => "Counter speed",
+        Msg::RulesCurveFlat => "The same all match",
+        Msg::RulesCurveRamp => "{}% faster every round",
+        Msg::RulesTargets => "Targets",
+        Msg::RulesTargetsUniform => "Anywhere on the dial",
+        Msg::RulesTargetsCenter => "Mostly near the middle of the dial",
+        Msg::RulesTargetsEdges => "Near either end of the dial",
+        Msg::RulesTargetsSequence => "Evenly stepped around the dial",
+        Msg::RulesSpacing => "Target spacing",
+        Msg::RulesSpacingNone => "Targets may repeat",
+        Msg::RulesSpacingUnits => "At least {} apart, none repeated",
+        Msg::StealPrompt => "Steal one of {}'s targets? It scores {}% of its score as a bonus:",
+        Msg::StealTarget => "Target {}",
+        Msg::StealNone => "Keep to my own targets",
+        Msg::StealBonus => "Stolen target: +{} bonus points.",
+        Msg::HelpSharedPool => "Both players share the targets of a round; after your turn, you may steal one your opponent has not played.",
+        Msg::PredictionPrompt => "{}, what average will you score this turn? Within {} of it earns {} bonus points (ENTER for no prediction)",
+        Msg::PredictionUnparsable => "Please enter a whole number, or just ENTER.",
+        Msg::PredictionHit => "Predicted {}, scored {}: +{} bonus points for the round.",
+        Msg::PredictionMissed => "Predicted {}, scored {}: no bonus.",
+        Msg::HelpPredictions => "Before each turn, predict your average: landing within {} of it adds {} points to your score for the round.",
+        Msg::RiskPrompt => "Risk on this target? {}: safe (wider brackets, at most {}), {}: normal, {}: risky (narrower brackets, up to 150)",
+        Msg::HelpRisk => "Before each target, press {} to play it safe (wider brackets, at most {}), {} for normal, or {} to play it risky (narrower brackets, up to 150).",
+        Msg::RulesTurnOrder => "Turn order",
+        Msg::RulesOrderFixed => "Player 1 always goes first",
+        Msg::RulesOrderAlternate => "The players take turns going first",
+        Msg::RulesOrderLoserFirst => "The loser of a round goes first in the next one",
+        Msg::RulesDamage => "Damage",
+        Msg::RulesDamageDifference => "The whole score difference",
+        Msg::RulesDamageCapped => "The score difference, at most {}",
+        Msg::RulesDamagePercent => "{}% of the loser's vitality",
+        Msg::RulesDamageFixed => "{} whatever the difference",
+        Msg::RulesPenalties => "Penalties",
+        Msg::RulesBonuses => "Bonuses",
+        Msg::RulesSpeedFloor => "Speed floor",
+        Msg::RulesFloorKnockout => "Falling to a speed of {} loses the match",
+        Msg::RulesFloorClamp => "Speed stays at {} at least and the match goes on",
+        Msg::RulesWin => "Win condition",
+        Msg::RulesNoBonuses => "None, without --consolation",
+        Msg::CounterTimer => " | ⏱ {} s",
+        Msg::TurnDuration => "→ Turn time: {} s",
+        Msg::TableTarget => "Target",
+        Msg::TableStop => "Stop",
+        Msg::TableDistance => "Distance",
+        Msg::TableMiss => "Misses",
+        Msg::TableScore => "Score",
+        Msg::TableOwner => "{}:",
+        Msg::RecapTitle => "Round recap:",
+        Msg::RecapTarget => "#",
+        Msg::RecapCell => "{} for {} ({} off)",
+        Msg::RecapCloser => "Closer",
+        Msg::RecapTie => "tie",
+        Msg::RecapCount => "{} was closer on {} targets, {} on {}.",
+        Msg::TimelineTitle => "Match timeline:",
+        Msg::TimelineMissing => "Rounds 1 to {} are missing: the match was saved by a build that did not keep its timeline.",
+        Msg::TimelineEmpty => "No round has been played yet.",
+        Msg::TimelineRound => "Round",
+        Msg::TimelineDamage => "Damage",
+        Msg::TimelinePenalty => "Penalty",
+        Msg::TimelinePassed => "passed",
+        Msg::TimelineDamageTo => "{} to {}",
+        Msg::TimelineDraw => "draw",
+        Msg::TimelineAttributes => "HP:{} Sp:{} Str:{}",
+        Msg::HelpTimeline => "{} then ENTER, at the start of a turn: show the timeline of the match",
+    }
+}
+
+/// French translation table.
+fn french(msg: Msg) -> &'static str {
+    match msg {
+        Msg::GameError => "Erreur de jeu : {}",
+        Msg::ReadError => "Erreur de lecture de l'entrée : {}",
+        Msg::RematchMenu => "La partie est terminée. Et maintenant ?",
+        Msg::RematchSame => "Revanche avec les mêmes réglages",
+        Msg::RematchSwap => "Revanche, l'autre joueur commence",
+        Msg::RematchCarryOver => "Revanche, en gardant la vitesse, la force et l'endurance restantes",
+        Msg::RematchQuit => "Quitter",
+        Msg::YesKey => "o",
+        Msg::GameStarted => "##### Début de la partie #####",
+        Msg::CounterRange => "Plage du compteur : 0–{}",
+        Msg::RhythmMode => "Mode rythme : un temps toutes les {} unités, arrêtez sur un temps pour {} points de bonus.",
+        Msg::RoundStart => "## Manche {} ##",
+        Msg::RoundTargets => "{} objectifs par tour dans cette manche.",
+        Msg::WeatherClear => "Météo : ciel dégagé, rien ne change pour cette manche.",
+        Msg::WeatherFog => "Météo : brouillard ! Les objectifs sont cachés pour cette manche.",
+        Msg::WeatherStorm => "Météo : tempête ! Le compteur va 20 % plus vite pour cette manche.",
+        Msg::WeatherCalm => "Météo : calme. Les ratés ne comptent pas pour cette manche.",
+        Msg::FirstPlayer => "{} commence cette manche.",
+        Msg::BlindReveal => "Les deux tours sont terminés, voici les résultats :",
+        Msg::ObjectiveHidden => "→ Objectif {} : résultat caché jusqu'à la fin de la manche",
+        Msg::RunningAverage => "  Moyenne en cours : {}",
+        Msg::RunningAverageToBeat => "  Moyenne en cours : {} | {} par objectif restant pour battre {}",
+        Msg::RunningAverageBeaten => "  Moyenne en cours : {} | {} est déjà battu",
+        Msg::RoundEnd => "## Fin de la manche {} ##",
+        Msg::GameOver => "##### Fin de la partie #####",
+        Msg::Winner => "Vainqueur : {} \n",
+        Msg::TurnHeader => "Au tour de {} (Vitalité={}, Vitesse={}, Force={}, Élan={})",
+        Msg::Objectives => "→ Objectifs : {}",
+        Msg::ObjectivesHidden => "→ Objectifs : {} cibles cachées, suivez les indices",
+        Msg::PressEnterStart => "→ Appuyez sur ENTRÉE pour commencer le tour..",
+        Msg::PressEnterStartOrResign => "→ Appuyez sur ENTRÉE pour commencer le tour, ou tapez {} puis ENTRÉE pour abandonner..",
+        Msg::Resigned => "{} abandonne la partie.",
+        Msg::PressEnterStartOrPass => "→ Appuyez sur ENTRÉE pour commencer le tour, tapez {} puis ENTRÉE pour le passer contre {} points de vitalité, ou {} pour abandonner..",
+        Msg::TurnPassed => "{} passe son tour et paie {} points de vitalité.",
+        Msg::PassedRoundWin => "{} remporte la manche. {} a passé son tour et ne subit aucun dégât.",
+        Msg::PressEnterStop => "Appuyez sur ENTRÉE pour arrêter le compteur.",
+        Msg::PressKeyStop => "Appuyez sur {} pour arrêter le compteur.",
+        Msg::DuelKeys => "Duel ! {} arrête avec {}, {} arrête avec {}.",
+        Msg::DuelStop => "→ #{} {} : Compteur = {} ({} ms)",
+        Msg::PlayerAverageScore => "→ {} : score moyen {}",
+        Msg::NextStop => "Arrêt {} sur {} : arrêtez à nouveau le compteur.",
+        Msg::MultiStopResult => "→ Arrêts : {}, valeur combinée : {}",
+        Msg::ObjectiveResult => "→ Objectif {} : Raté = {} | Compteur = {} // Score = ({} + {}) / {} = {}",
+        Msg::HandicapValue => "{} ({} {} handicap)",
+        Msg::ImplausibleStop => "{} a arrêté le compteur après seulement {} ms, plus vite qu'un humain ne le peut",
+        Msg::TargetVoided => "{} a fait {} arrêts trop rapides d'affilée, la cible est annulée",
+        Msg::StopDistance => "  Distance à l'objectif : {}",
+        Msg::ObjectiveDetails => "  Intervalle : {} ms | Temps de réaction : {} ms",
+        Msg::TargetDifficulty => "Compteur +{} toutes les {} ms, de {} à {} en un tour ({} s) : {}",
+        Msg::DifficultyEasy => "facile",
+        Msg::DifficultyNormal => "normal",
+        Msg::DifficultyHard => "difficile",
+        Msg::DifficultyExtreme => "extrême",
+        Msg::TurnEnd => "# Fin du tour #",
+        Msg::AverageScore => "→ Score moyen : {} \n",
+        Msg::RoundWin => "{} remporte la manche. {} perd {} points de vitalité.",
+        Msg::Draw => "Égalité ! Aucune pénalité appliquée.",
+        Msg::ChoosePoison => "{}, vous devez choisir quel poison appliquer à {} :",
+        Msg::ChoosePenalty => "Choisissez une pénalité :",
+        Msg::PenaltySpeed => "-{} vitesse",
+        Msg::PenaltyStrength => "-{} force",
+        Msg::SpeedReduced => "La vitesse de {} est réduite de {} !",
+        Msg::SpeedAtFloor => "La vitesse de {} ne peut pas descendre plus bas.",
+        Msg::StrengthAtFloor => "La force de {} ne peut pas descendre plus bas.",
+        Msg::SpeedKnockout => "Fin de la partie ! {} a perdu car sa vitesse est tombée à {} !",
+        Msg::MutatorLoss => "Fin de la partie ! {} perd le match à cause du modificateur {} !",
+        Msg::WinGoalRounds => "Le premier joueur à gagner {} manches remporte le match.",
+        Msg::WinGoalScore => "Le meilleur score total après {} manches remporte le match.",
+        Msg::WinGoalSuddenDeath => "Mort subite : le premier coup parfait remporte le match.",
+        Msg::WinRounds => "{} gagne {} manches et remporte le match !",
+        Msg::WinHighScore => "{} remporte le match avec un score total de {} contre {} !",
+        Msg::WinSuddenDeath => "{} touche une cible exactement et remporte le match !",
+        Msg::StrengthReduced => "La force de {} est réduite de {} !",
+        Msg::ChooseConsolation => "{}, choisissez un bonus de consolation :",
+        Msg::ConsolationSpeed => "+{} vitesse",
+        Msg::ConsolationStrength => "+{} force",
+        Msg::ConsolationVitality => "+{} vitalité",
+        Msg::ConsolationGained => "{} gagne {}.",
+        Msg::DraftStart => "Draft : chaque joueur retire une pénalité ou un bonus du match, puis en choisit un que lui seul pourra utiliser.",
+        Msg::DraftBan => "{}, retirez une pénalité ou un bonus :",
+        Msg::DraftBanned => "{} retire {}.",
+        Msg::DraftPick => "{}, choisissez une pénalité ou un bonus pour vous :",
+        Msg::DraftPicked => "{} choisit {}.",
+        Msg::CounterBar => "→ Objectif {} : {} Raté = {} | Compteur = {}",
+        Msg::CounterCaption => "→ Objectif {} : Raté = {} | Compteur = {}",
+        Msg::CounterCaptionShort => "→ Objectif {} : Raté = {}",
+        Msg::InvalidChoice => "Choix invalide. La première option est sélectionnée par défaut.",
+        Msg::ChoiceTimer => "Appuyez sur le numéro de votre choix dans les {} secondes.",
+        Msg::PenaltyTimeout => "Temps écoulé, une pénalité est tirée au hasard.",
+        Msg::UnparsableChoice => "Saisie illisible. La première option est sélectionnée par défaut.",
+        Msg::MatchInterrupted => "##### Partie interrompue pendant la manche {} #####",
+        Msg::PlayerStatus => "→ {} : Vitalité={}, Vitesse={}, Force={}",
+        Msg::SavePrompt => "Sauvegarder la partie en cours ? [O/N]",
+        Msg::MatchSaved => "Partie sauvegardée dans {}. Reprenez-la avec --load {}",
+        Msg::SaveFailed => "Impossible de sauvegarder la partie : {}",
+        Msg::MatchResumed => "##### Reprise de la partie à la manche {} #####",
+        Msg::AutosaveFailed => "Impossible de sauvegarder automatiquement la partie : {}",
+        Msg::AutosaveFound => "Une partie inachevée a été sauvegardée automatiquement à la manche {}. La reprendre ? [O/N]",
+        Msg::AutosaveUnreadable => "Impossible de charger la sauvegarde automatique {} : {}",
+        Msg::ResultsExported => "Résultats exportés dans {}",
+        Msg::ExportFailed => "Impossible d'exporter les résultats : {}",
+        Msg::SummaryHeader => "##### Résumé de la partie #####",
+        Msg::SummaryPlayer => "{}",
+        Msg::SummaryScores => "  Scores par manche : {} ({})",
+        Msg::SummaryVitality => "  Vitalité :          {} ({})",
+        Msg::SummaryTargets => "  Meilleur objectif : {} ({} points) | Pire objectif : {} ({} points)",
+        Msg::SummaryExactHits => "  Coups parfaits : {}",
+        Msg::SummaryResigned => "  A abandonné à la manche {}",
+        Msg::ScoreboardRound => "Manche {}",
+        Msg::ScoreboardPlayer => "{} {} Vt:{}/{} F:{}/{} En:{}/{}",
+        Msg::VitalityBar => "→ {} {}",
+        Msg::Healed => "{} a réussi {} coups parfaits et récupère {} points de vitalité.",
+        Msg::ReactionReady => "Jeu de réflexes ! {} appuie sur {}, {} appuie sur {} dès que MAINTENANT ! apparaît.",
+        Msg::ReactionNow => ">>>>> MAINTENANT ! <<<<<",
+        Msg::ReactionWin => "{} a réagi en premier ({} ms) et vole {} points de vitalité à {}.",
+        Msg::ReactionFalseStart => "{} a appuyé trop tôt ! {} vole {} points de vitalité.",
+        Msg::CampaignStage => "##### Campagne, étape {} sur {} : {} ({}) #####",
+        Msg::QuirkNone => "joue selon les règles habituelles",
+        Msg::QuirkFastCounter => "votre compteur tourne plus vite",
+        Msg::QuirkHeavyPenalty => "ses pénalités retirent {} points",
+        Msg::QuirkRelentless => "votre compteur tourne plus vite et ses pénalités retirent {} points",
+        Msg::CampaignLost => "{} gagne. Relancez la campagne pour retenter l'étape {}.",
+        Msg::CampaignStageCleared => "{} est vaincu ! Progression enregistrée dans {}.",
+        Msg::CampaignContinue => "Affronter l'adversaire suivant ?",
+        Msg::CampaignComplete => "Vous avez battu tous les adversaires de la campagne !",
+        Msg::SurvivalStart => "Mode survie : arrêtez-vous à {} près de chaque cible, chaque raté coûte {} points de vitalité. Meilleur score : {} vagues.",
+        Msg::SurvivalWave => "## Vague {} (intervalle {} ms) ##",
+        Msg::SurvivalHit => "→ Objectif {} : Compteur = {}, réussi.",
+        Msg::SurvivalMiss => "→ Objectif {} : Compteur = {}, raté ! Vous perdez {} points de vitalité.",
+        Msg::SurvivalWaveCleared => "Vague {} terminée, le compteur accélère.",
+        Msg::SurvivalOver => "{} a survécu à {} vagues.",
+        Msg::SurvivalRecord => "Nouveau record ! Enregistré dans {}.",
+        Msg::GhostStop => "  Fantôme : Compteur = {}",
+        Msg::GhostRace => "Course contre le fantôme d'une partie à {}.",
+        Msg::GhostMissing => "Aucun fantôme enregistré dans {} pour l'instant, partie sans fantôme.",
+        Msg::GhostRecorded => "Cette partie est le nouveau fantôme, enregistrée dans {}.",
+        Msg::PracticeStart => "Entraînement : {} cibles, chaque arrêt compté comme en match. Le meilleur total est enregistré comme fantôme.",
+        Msg::TimeAttackStart => "Contre-la-montre : {} cibles, chaque arrêt chronométré depuis le départ du compteur. Un arrêt à plus de {} de sa cible, ou après un tour complet, ajoute {} s. La partie la plus rapide est enregistrée comme fantôme.",
+        Msg::PracticeStop => "→ Objectif {} : Compteur = {}, {} points.",
+        Msg::TimeAttackStop => "→ Objectif {} : Compteur = {}, {} s.",
+        Msg::TimeAttackMiss => "→ Objectif {} : Compteur = {}, {} s, raté ! {} s ajoutées.",
+        Msg::PracticeOver => "{} a terminé la partie : {}.",
+        Msg::PracticeName => "entraînement",
+        Msg::TimeAttackName => "contre-la-montre",
+        Msg::PracticePoints => "{} points",
+        Msg::TimeAttackSeconds => "{} s",
+        Msg::SimulationResult => "{} parties simulées, {} manches en moyenne.",
+        Msg::SimulationPlayer => "{} a gagné {} parties ({} %).",
+        Msg::SimulationDraws => "{} parties ont atteint {} manches et sont déclarées nulles.",
+        Msg::StatsCampaign => "Campagne : {} adversaires battus sur {}.",
+        Msg::StatsSurvival => "Survie : meilleure partie de {} vagues.",
+        Msg::StatsLatency => "Décalage de latence : {} ms.",
+        Msg::CalibrationStart => "Calibrage : appuyez sur ENTRÉE dès que MAINTENANT ! apparaît, {} fois de suite.",
+        Msg::CalibrationReady => "Préparez-vous... ({}/{})",
+        Msg::CalibrationEarly => "Trop tôt, cet appui ne compte pas.",
+        Msg::CalibrationSample => "{} ms",
+        Msg::CalibrationResult => "Décalage de latence : {} ms, enregistré dans {}. Jouez avec --latency-credit pour le récupérer à chaque arrêt.",
+        Msg::CalibrationFailed => "Aucun appui n'a pu être mesuré, le profil n'a pas changé.",
+        Msg::NetQueued => "En attente d'un adversaire...",
+        Msg::NetMatchFound => "Vous affrontez {}, vous êtes le joueur {}.",
+        Msg::NetConnectionLost => "La connexion au serveur a été perdue.",
+        Msg::NetPlayerLeft => "{} a quitté la partie.",
+        Msg::NetMatchStarted => "Partie commencée : {} contre {}",
+        Msg::NetMatchEnded => "Partie terminée : {} contre {}",
+        Msg::NetReconnecting => "Connexion perdue, tentative de retour dans la partie...",
+        Msg::NetResumed => "De retour dans la partie, la manche {} reprend depuis le début.",
+        Msg::NetWaitingForRejoin => "{} a perdu la connexion, la partie l'attend pendant {} secondes.",
+        Msg::NetChat => "[{}] {}",
+        Msg::NetChatHint => "Tapez /say suivi d'un message pour parler à votre adversaire.",
+        Msg::PresenceStarting => "Début d'un match",
+        Msg::PresenceRound => "Manche {} contre {}",
+        Msg::PresenceVitality => "Vitalité {} à {}",
+        Msg::PresenceOver => "{} a gagné le match",
+        Msg::PresenceUnavailable => "Rich Presence Discord indisponible : {}",
+        Msg::NetListening => "En attente de joueurs sur {}",
+        Msg::MetricsListening => "Métriques servies sur http://{}/metrics",
+        Msg::ApiListening => "API du jeu servie sur http://{}",
+        Msg::ReplaySummary => "Partie enregistrée ({}) : graine {}, {}, {} arrêts.",
+        Msg::ReplayStop => "  Objectif {} : Compteur = {}, empreinte de l'état {}",
+        Msg::GamblePrompt => "Quitte ou double sur l'objectif {} ? Coûte {} d'endurance ({} restante) [O/N]",
+        Msg::GamblePromptHidden => "Quitte ou double sur le prochain objectif ? Coûte {} d'endurance ({} restante) [O/N]",
+        Msg::GambleWon => "  Quitte ou double : gagné, le score est doublé à {} !",
+        Msg::GambleLost => "  Quitte ou double : perdu, le score est de 0.",
+        Msg::CriticalHit => "  Coup critique ! Le score passe à {}.",
+        Msg::BeatBonus => "  Dans le temps ! +{} points.",
+        Msg::CounterCombo => " | Combo x{}",
+        Msg::CounterBeat => " | ♪",
+        Msg::CounterGhost => " | Fantôme {}",
+        Msg::CounterWarmer => " | Plus chaud",
+        Msg::CounterColder => " | Plus froid",
+        Msg::ComboBonus => "  Combo x{} : score de base {} → {}",
+        Msg::MomentumStolen => "  Coup parfait ! {} vole {} d'élan à {}.",
+        Msg::WagerPrompt => "{}, combien de vitalité misez-vous sur cette manche ? (0 à {}, ENTRÉE pour rien)",
+        Msg::WagerTooHigh => "Vous ne pouvez pas miser plus de {} points de vitalité.",
+        Msg::WagerUnparsable => "Veuillez saisir un nombre entre 0 et {}.",
+        Msg::PotWon => "{} remporte la mise de {} points de vitalité : {} perd {} points de vitalité de plus.",
+        Msg::WagersReturned => "Les mises sont rendues aux deux joueurs.",
+        Msg::GuiStartStop => "Démarrer / Arrêter",
+        Msg::GuiPass => "Passer",
+        Msg::GuiResign => "Abandonner",
+        Msg::GuiSend => "Envoyer",
+        Msg::HelpKeysTitle => "Touches",
+        Msg::HelpStart => "ENTRÉE : démarrer le compteur",
+        Msg::HelpStopEnter => "ENTRÉE : arrêter le compteur",
+        Msg::HelpStopKey => "{} : {} arrête le compteur",
+        Msg::HelpResign => "{} puis ENTRÉE, au début d'un tour : abandonner le match",
+        Msg::HelpPass => "{} puis ENTRÉE, au début d'un tour : le passer pour {} points de vitalité",
+        Msg::HelpMenus => "Flèches et ENTRÉE, ou un numéro : choisir dans un menu",
+        Msg::HelpHelp => "{} puis ENTRÉE au début d'un tour, ou {} seul pendant que le compteur tourne (il est mis en pause) : afficher cette aide",
+        Msg::HelpRulesTitle => "Règles",
+        Msg::HelpTargets => "Arrêtez le compteur au plus près de chacun des {} objectifs du tour, sur un cadran de 0 à {}.",
+        Msg::HelpTargetsGrow => "Chaque manche ajoute un objectif aux tours, jusqu'à {}.",
+        Msg::HelpScoring => "La force s'ajoute au score de chaque objectif, et chaque tour complet du compteur le divise.",
+        Msg::HelpRounds => "Le meilleur score moyen remporte la manche : le perdant perd de la vitalité et subit une pénalité.",
+        Msg::HelpWinVitality => "Un joueur sans vitalité perd le match.",
+        Msg::HelpDismiss => "Appuyez sur ENTRÉE pour revenir au jeu.",
+        Msg::HelpHint => "Tapez {} puis ENTRÉE au début d'un tour, ou appuyez dessus pendant que votre compteur tourne, pour voir les touches et les règles.",
+        Msg::TutorialWelcome => "Bienvenue dans le tutoriel ! Vous allez jouer un tour contre le tuteur, une étape à la fois.",
+        Msg::TutorialContinue => "(Appuyez sur ENTRÉE pour continuer)",
+        Msg::TutorialTargets => "Chaque tour a des objectifs entre 0 et {}. Les vôtres sont {}. Plus vous arrêtez le compteur près d'un objectif, plus il rapporte.",
+        Msg::TutorialCounter => "Pour chaque objectif, un compteur monte de 0 à {}, puis repart de 0. Chaque fois qu'il repart est un raté, et les ratés divisent le score.",
+        Msg::TutorialStop => "Objectif {} : appuyez sur ENTRÉE pour lancer le compteur, puis sur {} pour l'arrêter au plus près de l'objectif.",
+        Msg::TutorialEnterKey => "ENTRÉE",
+        Msg::TutorialNoMiss => "Vous avez arrêté le compteur avant qu'il ne reparte : aucun raté.",
+        Msg::TutorialMiss => "Le compteur a dépassé {} et est reparti {} fois avant que vous ne l'arrêtiez : le score est divisé par {}.",
+        Msg::TutorialScore => "Votre distance à l'objectif donne un score de base de {}. Votre force, {}, s'y ajoute, et la somme est divisée par les ratés plus un : ({} + {}) / {} = {}.",
+        Msg::TutorialAverage => "Votre tour vaut la moyenne de ses objectifs : {}.",
+        Msg::TutorialTutor => "Tuteur",
+        Msg::TutorialTutorTurn => "Le tuteur a joué son tour aussi, en s'arrêtant à {}, pour une moyenne de {}.",
+        Msg::TutorialRoundWin => "{} a marqué plus et gagne la manche. La différence, {} points, fait perdre à {} {} de vitalité.",
+        Msg::TutorialRoundDraw => "Les deux tours ont marqué autant : la manche est nulle et personne ne perd de vitalité.",
+        Msg::TutorialPenalty => "Le gagnant d'une manche choisit aussi une pénalité pour le perdant : -{} de vitesse fait tourner son compteur plus vite, -{} de force baisse ses scores.",
+        Msg::TutorialEnd => "Un match se joue manche après manche jusqu'à ce qu'un joueur n'ait plus de vitalité. Tapez {} puis ENTRÉE au début d'un tour pour revoir les touches et les règles. Bonne chance !",
+        Msg::RulesScoringTitle => "Barème",
+        Msg::RulesDistance => "Distance",
+        Msg::RulesBaseScore => "Score de base",
+        Msg::RulesFormula => "Score = (score de base + force) / (ratés + 1)",
+        Msg::RulesTitle => "Règles",
+        Msg::RulesCounter => "Compteur",
+        Msg::RulesCounterRange => "0 à {}, en {}",
+        Msg::RulesWholeUnits => "unités entières",
+        Msg::RulesTenths => "dixièmes d'unité",
+        Msg::RulesTick => "Pas du compteur",
+        Msg::RulesTickValue => "{} ms à une vitesse de 100",
+        Msg::RulesSpeedCurve => "Vitesse du compteur",
+        Msg::RulesCurveFlat => "La même tout le match",
+        Msg::RulesCurveRamp => "{} % plus rapide à chaque manche",
+        Msg::RulesTargets => "Objectifs",
+        Msg::RulesTargetsUniform => "N'importe où sur le cadran",
+        Msg::RulesTargetsCenter => "Surtout vers le milieu du cadran",
+        Msg::RulesTargetsEdges => "Près de l'un ou l'autre bout du cadran",
+        Msg::RulesTargetsSequence => "À pas réguliers autour du cadran",
+        Msg::RulesSpacing => "Écart des objectifs",
+        Msg::RulesSpacingNone => "Les objectifs peuvent se répéter",
+        Msg::RulesSpacingUnits => "Au moins {} d'écart, aucun répété",
+        Msg::StealPrompt => "Voler un des objectifs de {} ? Il rapporte {} % de son score en bonus :",
+        Msg::StealTarget => "Objectif {}",
+        Msg::StealNone => "M'en tenir à mes objectifs",
+        Msg::StealBonus => "Objectif volé : +{} points de bonus.",
+        Msg::HelpSharedPool => "Les deux joueurs se partagent les objectifs de la manche ; après votre tour, vous pouvez en voler un que votre adversaire n'a pas joué.",
+        Msg::PredictionPrompt => "{}, quelle moyenne ferez-vous ce tour ? À {} près, elle rapporte {} points de bonus (ENTRÉE pour ne rien prédire)",
+        Msg::PredictionUnparsable => "Veuillez entrer un nombre entier, ou seulement ENTRÉE.",
+        Msg::PredictionHit => "Prédit {}, obtenu {} : +{} points de bonus pour la manche.",
+        Msg::PredictionMissed => "Prédit {}, obtenu {} : pas de bonus.",
+        Msg::HelpPredictions => "Avant chaque tour, prédisez votre moyenne : à {} près, elle ajoute {} points à votre score de la manche.",
+        Msg::RiskPrompt => "Risque sur cet objectif ? {} : prudent (tranches plus larges, au plus {}), {} : normal, {} : risqué (tranches plus étroites, jusqu'à 150)",
+        Msg::HelpRisk => "Avant chaque objectif, appuyez sur {} pour jouer prudent (tranches plus larges, au plus {}), {} pour normal, ou {} pour jouer risqué (tranches plus étroites, jusqu'à 150).",
+        Msg::RulesTurnOrder => "Ordre des tours",
+        Msg::RulesOrderFixed => "Le joueur 1 commence toujours",
+        Msg::RulesOrderAlternate => "Les joueurs commencent chacun leur tour",
+        Msg::RulesOrderLoserFirst => "Le perdant d'une manche commence la suivante",
+        Msg::RulesDamage => "Dégâts",
+        Msg::RulesDamageDifference => "Toute la différence de score",
+        Msg::RulesDamageCapped => "La différence de score, au plus {}",
+        Msg::RulesDamagePercent => "{} % de la vitalité du perdant",
+        Msg::RulesDamageFixed => "{} quelle que soit la différence",
+        Msg::RulesPenalties => "Pénalités",
+        Msg::RulesBonuses => "Bonus",
+        Msg::RulesSpeedFloor => "Vitesse minimale",
+        Msg::RulesFloorKnockout => "Tomber à une vitesse de {} fait perdre le match",
+        Msg::RulesFloorClamp => "La vitesse reste au moins à {} et le match continue",
+        Msg::RulesWin => "Condition de victoire",
+        Msg::RulesNoBonuses => "Aucun, sans --consolation",
+        Msg::CounterTimer => " | ⏱ {} s",
+        Msg::TurnDuration => "→ Durée du tour : {} s",
+        Msg::TableTarget => "Objectif",
+        Msg::TableStop => "Arrêt",
+        Msg::TableDistance => "Distance",
+        Msg::TableMiss => "Ratés",
+        Msg::TableScore => "Score",
+        Msg::TableOwner => "{} :",
+        Msg::RecapTitle => "Récapitulatif de la manche :",
+        Msg::RecapTarget => "#",
+        Msg::RecapCell => "{} pour {} (à {})",
+        Msg::RecapCloser => "Plus près",
+        Msg::RecapTie => "égalité",
+        Msg::RecapCount => "{} a été plus près sur {} objectifs, {} sur {}.",
+        Msg::TimelineTitle => "Déroulement du match :",
+        Msg::TimelineMissing => "Les manches 1 à {} manquent : le match a été sauvegardé par une version qui ne gardait pas son déroulement.",
+        Msg::TimelineEmpty => "Aucune manche n'a encore été jouée.",
+        Msg::TimelineRound => "Manche",
+        Msg::TimelineDamage => "Dégâts",
+        Msg::TimelinePenalty => "Pénalité",
+        Msg::TimelinePassed => "passé",
+        Msg::TimelineDamageTo => "{} à {}",
+        Msg::TimelineDraw => "égalité",
+        Msg::TimelineAttributes => "PV:{} Vt:{} F:{}",
+        Msg::HelpTimeline => "{} puis ENTRÉE, au début d'un tour : afficher le déroulement du match",
+    }
+}
+
+/// Spanish translation table.
+fn spanish(msg: Msg) -> &'static str {
+    match msg {
+        Msg::GameError => "Error del juego: {}",
+        Msg::ReadError => "Error al leer la entrada: {}",
+        Msg::RematchMenu => "La partida ha terminado. ¿Y ahora?",
+        Msg::RematchSame => "Revancha con la misma configuración",
+        Msg::RematchSwap => "Revancha, empieza el otro jugador",
+        Msg::RematchCarryOver => "Revancha, conservando la velocidad, la fuerza y la resistencia restantes",
+        Msg::RematchQuit => "Salir",
+        Msg::YesKey => "s",
+        Msg::GameStarted => "##### Partida iniciada #####",
+        Msg::CounterRange => "Rango del contador: 0–{}",
+        Msg::RhythmMode => "Modo ritmo: un pulso cada {} unidades, detén en un pulso para {} puntos extra.",
+        Msg::RoundStart => "## Ronda {} ##",
+        Msg::RoundTargets => "{} objetivos por turno en esta ronda.",
+        Msg::WeatherClear => "Tiempo: cielo despejado, nada cambia en esta ronda.",
+        Msg::WeatherFog => "Tiempo: ¡niebla! Los objetivos están ocultos en esta ronda.",
+        Msg::WeatherStorm => "Tiempo: ¡tormenta! El contador va un 20 % más rápido en esta ronda.",
+        Msg::WeatherCalm => "Tiempo: calma. Los fallos no cuentan en esta ronda.",
+        Msg::FirstPlayer => "{} empieza esta ronda.",
+        Msg::BlindReveal => "Los dos turnos han terminado, estos son los resultados:",
+        Msg::ObjectiveHidden => "→ Objetivo {}: resultado oculto hasta el final de la ronda",
+        Msg::RunningAverage => "  Promedio actual: {}",
+        Msg::RunningAverageToBeat => "  Promedio actual: {} | {} por objetivo restante para superar {}",
+        Msg::RunningAverageBeaten => "  Promedio actual: {} | {} ya está superado",
+        Msg::RoundEnd => "## Fin de la ronda {} ##",
+        Msg::GameOver => "##### Fin de la partida #####",
+        Msg::Winner => "Ganador: {} \n",
+        Msg::TurnHeader => "Turno de {} (Vitalidad={}, Velocidad={}, Fuerza={}, Impulso={})",
+        Msg::Objectives => "→ Objetivos: {}",
+        Msg::ObjectivesHidden => "→ Objetivos: {} objetivos ocultos, sigue las pistas",
+        Msg::PressEnterStart => "→ Pulsa ENTER para empezar el turno..",
+        Msg::PressEnterStartOrResign => "→ Pulsa ENTER para empezar el turno, o escribe {} y ENTER para rendirte..",
+        Msg::Resigned => "{} se rinde.",
+        Msg::PressEnterStartOrPass => "→ Pulsa ENTER para empezar el turno, escribe {} y ENTER para pasarlo por {} puntos de vitalidad, o {} para rendirte..",
+        Msg::TurnPassed => "{} pasa el turno y paga {} puntos de vitalidad.",
+        Msg::PassedRoundWin => "{} gana la ronda. {} pasó el turno y no recibe daño.",
+        Msg::PressEnterStop => "Pulsa ENTER para detener el contador.",
+        Msg::PressKeyStop => "Pulsa {} para detener el contador.",
+        Msg::DuelKeys => "¡Duelo! {} detiene con {}, {} detiene con {}.",
+        Msg::DuelStop => "→ #{} {}: Contador = {} ({} ms)",
+        Msg::PlayerAverageScore => "→ {}: puntuación media {}",
+        Msg::NextStop => "Parada {} de {}: detén el contador otra vez.",
+        Msg::MultiStopResult => "→ Paradas: {}, valor combinado: {}",
+        Msg::ObjectiveResult => "→ Objetivo {}: Fallos = {} | Contador = {} // Puntuación = ({} + {}) / {} = {}",
+        Msg::HandicapValue => "{} ({} {} de hándicap)",
+        Msg::ImplausibleStop => "{} detuvo el contador tras solo {} ms, más rápido de lo humanamente posible",
+        Msg::TargetVoided => "{} hizo {} paradas demasiado rápidas seguidas, el objetivo queda anulado",
+        Msg::StopDistance => "  Distancia al objetivo: {}",
+        Msg::ObjectiveDetails => "  Intervalo: {} ms | Tiempo de reacción: {} ms",
+        Msg::TargetDifficulty => "Contador +{} cada {} ms, de {} a {} en una vuelta ({} s): {}",
+        Msg::DifficultyEasy => "fácil",
+        Msg::DifficultyNormal => "normal",
+        Msg::DifficultyHard => "difícil",
+        Msg::DifficultyExtreme => "extremo",
+        Msg::TurnEnd => "# Fin del turno #",
+        Msg::AverageScore => "→ Puntuación media: {} \n",
+        Msg::RoundWin => "{} gana la ronda. {} pierde {} puntos de vitalidad.",
+        Msg::Draw => "¡Empate! No se aplican penalizaciones.",
+        Msg::ChoosePoison => "{}, debes elegir qué veneno aplicar a {}:",
+        Msg::ChoosePenalty => "Elige una penalización:",
+        Msg::PenaltySpeed => "-{} velocidad",
+        Msg::PenaltyStrength => "-{} fuerza",
+        Msg::SpeedReduced => "¡La velocidad de {} se reduce en {}!",
+        Msg::SpeedAtFloor => "La velocidad de {} no puede bajar más.",
+        Msg::StrengthAtFloor => "La fuerza de {} no puede bajar más.",
+        Msg::SpeedKnockout => "¡Fin de la partida! ¡{} ha perdido porque su velocidad llegó a {}!",
+        Msg::MutatorLoss => "¡Fin de la partida! ¡{} pierde la partida por el modificador {}!",
+        Msg::WinGoalRounds => "El primer jugador que gane {} rondas gana la partida.",
+        Msg::WinGoalScore => "La mayor puntuación total tras {} rondas gana la partida.",
+        Msg::WinGoalSuddenDeath => "Muerte súbita: el primer acierto exacto gana la partida.",
+        Msg::WinRounds => "¡{} gana {} rondas y se lleva la partida!",
+        Msg::WinHighScore => "¡{} gana la partida con una puntuación total de {} contra {}!",
+        Msg::WinSuddenDeath => "¡{} acierta un objetivo exactamente y gana la partida!",
+        Msg::StrengthReduced => "¡La fuerza de {} se reduce en {}!",
+        Msg::ChooseConsolation => "{}, elige una bonificación de consolación:",
+        Msg::ConsolationSpeed => "+{} velocidad",
+        Msg::ConsolationStrength => "+{} fuerza",
+        Msg::ConsolationVitality => "+{} vitalidad",
+        Msg::ConsolationGained => "{} gana {}.",
+        Msg::DraftStart => "Draft: cada jugador elimina una penalización o bonificación de la partida y luego elige una que solo él podrá usar.",
+        Msg::DraftBan => "{}, elimina una penalización o bonificación:",
+        Msg::DraftBanned => "{} elimina {}.",
+        Msg::DraftPick => "{}, elige una penalización o bonificación para ti:",
+        Msg::DraftPicked => "{} elige {}.",
+        Msg::CounterBar => "→ Objetivo {}: {} Fallos = {} | Contador = {}",
+        Msg::CounterCaption => "→ Objetivo {}: Fallos = {} | Contador = {}",
+        Msg::CounterCaptionShort => "→ Objetivo {}: Fallos = {}",
+        Msg::InvalidChoice => "Opción no válida. Se selecciona la primera opción por defecto.",
+        Msg::ChoiceTimer => "Pulse el número de su elección en menos de {} segundos.",
+        Msg::PenaltyTimeout => "Se acabó el tiempo, se elige una penalización al azar.",
+        Msg::UnparsableChoice => "No se pudo leer la entrada. Se selecciona la primera opción por defecto.",
+        Msg::MatchInterrupted => "##### Partida interrumpida durante la ronda {} #####",
+        Msg::PlayerStatus => "→ {}: Vitalidad={}, Velocidad={}, Fuerza={}",
+        Msg::SavePrompt => "¿Guardar la partida en curso? [S/N]",
+        Msg::MatchSaved => "Partida guardada en {}. Reanúdala con --load {}",
+        Msg::SaveFailed => "No se pudo guardar la partida: {}",
+        Msg::MatchResumed => "##### Reanudando la partida en la ronda {} #####",
+        Msg::AutosaveFailed => "No se pudo guardar automáticamente la partida: {}",
+        Msg::AutosaveFound => "Se guardó automáticamente una partida sin terminar en la ronda {}. ¿Reanudarla? [S/N]",
+        Msg::AutosaveUnreadable => "No se pudo cargar la partida guardada automáticamente {}: {}",
+        Msg::ResultsExported => "Resultados exportados a {}",
+        Msg::ExportFailed => "No se pudieron exportar los resultados: {}",
+        Msg::SummaryHeader => "##### Resumen de la partida #####",
+        Msg::SummaryPlayer => "{}",
+        Msg::SummaryScores => "  Puntuación por ronda: {} ({})",
+        Msg::SummaryVitality => "  Vitalidad:            {} ({})",
+        Msg::SummaryTargets => "  Mejor objetivo: {} ({} puntos) | Peor objetivo: {} ({} puntos)",
+        Msg::SummaryExactHits => "  Aciertos exactos: {}",
+        Msg::SummaryResigned => "  Se rindió en la ronda {}",
+        Msg::ScoreboardRound => "Ronda {}",
+        Msg::ScoreboardPlayer => "{} {} Ve:{}/{} F:{}/{} Re:{}/{}",
+        Msg::VitalityBar => "→ {} {}",
+        Msg::Healed => "{} ha logrado {} aciertos exactos y recupera {} puntos de vitalidad.",
+        Msg::ReactionReady => "¡Juego de reflejos! {} pulsa {}, {} pulsa {} en cuanto aparezca ¡YA!.",
+        Msg::ReactionNow => ">>>>> ¡YA! <<<<<",
+        Msg::ReactionWin => "{} reaccionó primero ({} ms) y roba {} puntos de vitalidad a {}.",
+        Msg::ReactionFalseStart => "¡{} pulsó demasiado pronto! {} roba {} puntos de vitalidad.",
+        Msg::CampaignStage => "##### Campaña, etapa {} de {}: {} ({}) #####",
+        Msg::QuirkNone => "juega con las reglas habituales",
+        Msg::QuirkFastCounter => "tu contador va más rápido",
+        Msg::QuirkHeavyPenalty => "sus penalizaciones quitan {} puntos",
+        Msg::QuirkRelentless => "tu contador va más rápido y sus penalizaciones quitan {} puntos",
+        Msg::CampaignLost => "{} gana. Vuelve a jugar la campaña para reintentar la etapa {}.",
+        Msg::CampaignStageCleared => "¡{} ha sido derrotado! Progreso guardado en {}.",
+        Msg::CampaignContinue => "¿Enfrentarse al siguiente rival?",
+        Msg::CampaignComplete => "¡Has vencido a todos los rivales de la campaña!",
+        Msg::SurvivalStart => "Modo supervivencia: detén el contador a {} o menos de cada objetivo, cada fallo cuesta {} puntos de vitalidad. Mejor marca: {} oleadas.",
+        Msg::SurvivalWave => "## Oleada {} (intervalo {} ms) ##",
+        Msg::SurvivalHit => "→ Objetivo {}: Contador = {}, a salvo.",
+        Msg::SurvivalMiss => "→ Objetivo {}: Contador = {}, ¡fallo! Pierdes {} puntos de vitalidad.",
+        Msg::SurvivalWaveCleared => "Oleada {} superada, el contador acelera.",
+        Msg::SurvivalOver => "{} sobrevivió {} oleadas.",
+        Msg::SurvivalRecord => "¡Nuevo récord! Guardado en {}.",
+        Msg::GhostStop => "  Fantasma: Contador = {}",
+        Msg::GhostRace => "Compitiendo contra el fantasma de una partida de {}.",
+        Msg::GhostMissing => "Aún no hay fantasma guardado en {}, se juega sin él.",
+        Msg::GhostRecorded => "Esta partida es el nuevo fantasma, guardada en {}.",
+        Msg::PracticeStart => "Práctica: {} objetivos, cada parada puntúa como en una partida. El mejor total se guarda como fantasma.",
+        Msg::TimeAttackStart => "Contrarreloj: {} objetivos, cada parada cronometrada desde que arranca el contador. Una parada a más de {} de su objetivo, o tras dar la vuelta, añade {} s. La partida más rápida se guarda como fantasma.",
+        Msg::PracticeStop => "→ Objetivo {}: Contador = {}, {} puntos.",
+        Msg::TimeAttackStop => "→ Objetivo {}: Contador = {}, {} s.",
+        Msg::TimeAttackMiss => "→ Objetivo {}: Contador = {}, {} s, ¡fallo! Se añaden {} s.",
+        Msg::PracticeOver => "{} terminó la partida: {}.",
+        Msg::PracticeName => "práctica",
+        Msg::TimeAttackName => "contrarreloj",
+        Msg::PracticePoints => "{} puntos",
+        Msg::TimeAttackSeconds => "{} s",
+        Msg::SimulationResult => "{} partidas simuladas, {} rondas de media.",
+        Msg::SimulationPlayer => "{} ganó {} partidas ({} %).",
+        Msg::SimulationDraws => "{} partidas llegaron a {} rondas y se declararon empate.",
+        Msg::StatsCampaign => "Campaña: {} de {} rivales vencidos.",
+        Msg::StatsSurvival => "Supervivencia: mejor partida de {} oleadas.",
+        Msg::StatsLatency => "Desfase de latencia: {} ms.",
+        Msg::CalibrationStart => "Calibración: pulsa ENTER en cuanto aparezca ¡YA!, {} veces seguidas.",
+        Msg::CalibrationReady => "Prepárate... ({}/{})",
+        Msg::CalibrationEarly => "Demasiado pronto, esta pulsación no cuenta.",
+        Msg::CalibrationSample => "{} ms",
+        Msg::CalibrationResult => "Desfase de latencia: {} ms, guardado en {}. Juega con --latency-credit para recuperarlo en cada parada.",
+        Msg::CalibrationFailed => "No se pudo medir ninguna pulsación, el perfil no ha cambiado.",
+        Msg::NetQueued => "Esperando a un rival...",
+        Msg::NetMatchFound => "Te enfrentas a {}, eres el jugador {}.",
+        Msg::NetConnectionLost => "Se perdió la conexión con el servidor.",
+        Msg::NetPlayerLeft => "{} abandonó la partida.",
+        Msg::NetMatchStarted => "Partida iniciada: {} contra {}",
+        Msg::NetMatchEnded => "Partida terminada: {} contra {}",
+        Msg::NetReconnecting => "Conexión perdida, intentando volver a la partida...",
+        Msg::NetResumed => "De vuelta en la partida, la ronda {} empieza de nuevo.",
+        Msg::NetWaitingForRejoin => "{} perdió la conexión, la partida espera {} segundos a que vuelva.",
+        Msg::NetChat => "[{}] {}",
+        Msg::NetChatHint => "Escribe /say seguido de un mensaje para hablar con tu rival.",
+        Msg::PresenceStarting => "Empezando una partida",
+        Msg::PresenceRound => "Ronda {} contra {}",
+        Msg::PresenceVitality => "Vitalidad {} a {}",
+        Msg::PresenceOver => "{} ganó la partida",
+        Msg::PresenceUnavailable => "Rich Presence de Discord no disponible: {}",
+        Msg::NetListening => "Esperando jugadores en {}",
+        Msg::MetricsListening => "Métricas servidas en http://{}/metrics",
+        Msg::ApiListening => "API del juego servida en http://{}",
+        Msg::ReplaySummary => "Partida grabada ({}): semilla {}, {}, {} paradas.",
+        Msg::ReplayStop => "  Objetivo {}: Contador = {}, huella del estado {}",
+        Msg::GamblePrompt => "¿Doble o nada en el objetivo {}? Cuesta {} de resistencia (quedan {}) [S/N]",
+        Msg::GamblePromptHidden => "¿Doble o nada en el próximo objetivo? Cuesta {} de resistencia (quedan {}) [S/N]",
+        Msg::GambleWon => "  Doble o nada: ¡ganado, la puntuación se duplica a {}!",
+        Msg::GambleLost => "  Doble o nada: perdido, la puntuación es 0.",
+        Msg::CriticalHit => "  ¡Golpe crítico! La puntuación sube a {}.",
+        Msg::BeatBonus => "  ¡Al compás! +{} puntos.",
+        Msg::CounterCombo => " | Combo x{}",
+        Msg::CounterBeat => " | ♪",
+        Msg::CounterGhost => " | Fantasma {}",
+        Msg::CounterWarmer => " | Caliente",
+        Msg::CounterColder => " | Frío",
+        Msg::ComboBonus => "  Combo x{}: puntuación base {} → {}",
+        Msg::MomentumStolen => "  ¡Acierto exacto! {} roba {} de impulso a {}.",
+        Msg::WagerPrompt => "{}, ¿cuánta vitalidad apuestas en esta ronda? (0 a {}, ENTER para nada)",
+        Msg::WagerTooHigh => "No puedes apostar más de {} puntos de vitalidad.",
+        Msg::WagerUnparsable => "Introduce un número entre 0 y {}.",
+        Msg::PotWon => "{} se lleva el bote de {} puntos de vitalidad: {} pierde {} puntos de vitalidad más.",
+        Msg::WagersReturned => "Las apuestas se devuelven a ambos jugadores.",
+        Msg::GuiStartStop => "Iniciar / Detener",
+        Msg::GuiPass => "Pasar",
+        Msg::GuiResign => "Rendirse",
+        Msg::GuiSend => "Enviar",
+        Msg::HelpKeysTitle => "Teclas",
+        Msg::HelpStart => "ENTER: iniciar el contador",
+        Msg::HelpStopEnter => "ENTER: detener el contador",
+        Msg::HelpStopKey => "{}: {} detiene el contador",
+        Msg::HelpResign => "{} y ENTER, al empezar un turno: rendirse",
+        Msg::HelpPass => "{} y ENTER, al empezar un turno: pasarlo por {} puntos de vitalidad",
+        Msg::HelpMenus => "Flechas y ENTER, o un número: elegir en un menú",
+        Msg::HelpHelp => "{} y ENTER al empezar un turno, o {} solo mientras el contador gira (se pone en pausa): mostrar esta ayuda",
+        Msg::HelpRulesTitle => "Reglas",
+        Msg::HelpTargets => "Detén el contador lo más cerca posible de cada uno de los {} objetivos del turno, en un dial de 0 a {}.",
+        Msg::HelpTargetsGrow => "Cada ronda añade un objetivo a los turnos, hasta {}.",
+        Msg::HelpScoring => "La fuerza se suma a la puntuación de cada objetivo, y cada vuelta del contador la divide.",
+        Msg::HelpRounds => "La mejor puntuación media gana la ronda: el perdedor pierde vitalidad y recibe una penalización.",
+        Msg::HelpWinVitality => "Un jugador sin vitalidad pierde la partida.",
+        Msg::HelpDismiss => "Pulsa ENTER para volver al juego.",
+        Msg::HelpHint => "Escribe {} y pulsa ENTER al empezar un turno, o púlsalo mientras tu contador gira, para ver las teclas y las reglas.",
+        Msg::TutorialWelcome => "¡Bienvenido al tutorial! Vas a jugar un turno contra el tutor, paso a paso.",
+        Msg::TutorialContinue => "(Pulsa ENTER para seguir)",
+        Msg::TutorialTargets => "Cada turno tiene objetivos entre 0 y {}. Los tuyos son {}. Cuanto más cerca de un objetivo pares el contador, más puntúa.",
+        Msg::TutorialCounter => "Para cada objetivo, un contador sube de 0 a {} y vuelve a empezar desde 0. Cada vez que vuelve a empezar es un fallo, y los fallos dividen la puntuación.",
+        Msg::TutorialStop => "Objetivo {}: pulsa ENTER para arrancar el contador, y luego {} para pararlo lo más cerca posible del objetivo.",
+        Msg::TutorialEnterKey => "ENTER",
+        Msg::TutorialNoMiss => "Paraste el contador antes de que volviera a empezar: ningún fallo.",
+        Msg::TutorialMiss => "El contador pasó de {} y volvió a empezar {} vez/veces antes de que lo pararas: la puntuación se divide entre {}.",
+        Msg::TutorialScore => "Tu distancia al objetivo da una puntuación base de {}. Tu fuerza, {}, se suma, y el total se divide entre los fallos más uno: ({} + {}) / {} = {}.",
+        Msg::TutorialAverage => "Tu turno vale la media de sus objetivos: {}.",
+        Msg::TutorialTutor => "Tutor",
+        Msg::TutorialTutorTurn => "El tutor también jugó su turno, parando en {}, con una media de {}.",
+        Msg::TutorialRoundWin => "{} puntuó más y gana la ronda. La diferencia, {} puntos, hace perder a {} {} de vitalidad.",
+        Msg::TutorialRoundDraw => "Los dos turnos puntuaron lo mismo: la ronda es un empate y nadie pierde vitalidad.",
+        Msg::TutorialPenalty => "El ganador de una ronda también elige una penalización para el perdedor: -{} de velocidad hace que su contador vaya más rápido, -{} de fuerza baja sus puntuaciones.",
+        Msg::TutorialEnd => "Un partido sigue ronda tras ronda hasta que un jugador se queda sin vitalidad. Escribe {} y pulsa ENTER al empezar un turno para volver a ver las teclas y las reglas. ¡Suerte!",
+        Msg::RulesScoringTitle => "Tabla de puntuación",
+        Msg::RulesDistance => "Distancia",
+        Msg::RulesBaseScore => "Puntuación base",
+        Msg::RulesFormula => "Puntuación = (puntuación base + fuerza) / (fallos + 1)",
+        Msg::RulesTitle => "Reglas",
+        Msg::RulesCounter => "Contador",
+        Msg::RulesCounterRange => "0 a {}, en {}",
+        Msg::RulesWholeUnits => "unidades enteras",
+        Msg::RulesTenths => "décimas de unidad",
+        Msg::RulesTick => "Paso del contador",
+        Msg::RulesTickValue => "{} ms con una velocidad de 100",
+        Msg::RulesSpeedCurve => "Velocidad del contador",
+        Msg::RulesCurveFlat => "La misma toda la partida",
+        Msg::RulesCurveRamp => "{} % más rápido cada ronda",
+        Msg::RulesTargets => "Objetivos",
+        Msg::RulesTargetsUniform => "En cualquier punto del dial",
+        Msg::RulesTargetsCenter => "Sobre todo hacia el centro del dial",
+        Msg::RulesTargetsEdges => "Cerca de uno u otro extremo del dial",
+        Msg::RulesTargetsSequence => "A pasos regulares alrededor del dial",
+        Msg::RulesSpacing => "Separación de objetivos",
+        Msg::RulesSpacingNone => "Los objetivos pueden repetirse",
+        Msg::RulesSpacingUnits => "Al menos {} de separación, ninguno repetido",
+        Msg::StealPrompt => "¿Robar uno de los objetivos de {}? Da el {} % de su puntuación como bonificación:",
+        Msg::StealTarget => "Objetivo {}",
+        Msg::StealNone => "Quedarme con mis objetivos",
+        Msg::StealBonus => "Objetivo robado: +{} puntos de bonificación.",
+        Msg::HelpSharedPool => "Ambos jugadores comparten los objetivos de la ronda; tras tu turno, puedes robar uno que tu rival no haya jugado.",
+        Msg::PredictionPrompt => "{}, ¿qué media harás en este turno? A {} o menos, da {} puntos de bonificación (ENTER para no predecir)",
+        Msg::PredictionUnparsable => "Introduce un número entero, o solo ENTER.",
+        Msg::PredictionHit => "Predicho {}, obtenido {}: +{} puntos de bonificación para la ronda.",
+        Msg::PredictionMissed => "Predicho {}, obtenido {}: sin bonificación.",
+        Msg::HelpPredictions => "Antes de cada turno, predice tu media: a {} o menos, suma {} puntos a tu puntuación de la ronda.",
+        Msg::RiskPrompt => "¿Riesgo en este objetivo? {}: prudente (tramos más anchos, como mucho {}), {}: normal, {}: arriesgado (tramos más estrechos, hasta 150)",
+        Msg::HelpRisk => "Antes de cada objetivo, pulsa {} para ir a lo seguro (tramos más anchos, como mucho {}), {} para normal, o {} para arriesgar (tramos más estrechos, hasta 150).",
+        Msg::RulesTurnOrder => "Orden de turnos",
+        Msg::RulesOrderFixed => "El jugador 1 siempre empieza",
+        Msg::RulesOrderAlternate => "Los jugadores empiezan por turnos",
+        Msg::RulesOrderLoserFirst => "El perdedor de una ronda empieza la siguiente",
+        Msg::RulesDamage => "Daño",
+        Msg::RulesDamageDifference => "Toda la diferencia de puntuación",
+        Msg::RulesDamageCapped => "La diferencia de puntuación, como mucho {}",
+        Msg::RulesDamagePercent => "{} % de la vitalidad del perdedor",
+        Msg::RulesDamageFixed => "{} sea cual sea la diferencia",
+        Msg::RulesPenalties => "Penalizaciones",
+        Msg::RulesBonuses => "Bonificaciones",
+        Msg::RulesSpeedFloor => "Velocidad mínima",
+        Msg::RulesFloorKnockout => "Bajar a una velocidad de {} hace perder la partida",
+        Msg::RulesFloorClamp => "La velocidad se queda en {} como mínimo y la partida sigue",
+        Msg::RulesWin => "Condición de victoria",
+        Msg::RulesNoBonuses => "Ninguna, sin --consolation",
+        Msg::CounterTimer => " | ⏱ {} s",
+        Msg::TurnDuration => "→ Duración del turno: {} s",
+        Msg::TableTarget => "Objetivo",
+        Msg::TableStop => "Parada",
+        Msg::TableDistance => "Distancia",
+        Msg::TableMiss => "Fallos",
+        Msg::TableScore => "Puntos",
+        Msg::TableOwner => "{}:",
+        Msg::RecapTitle => "Resumen de la ronda:",
+        Msg::RecapTarget => "#",
+        Msg::RecapCell => "{} para {} (a {})",
+        Msg::RecapCloser => "Más cerca",
+        Msg::RecapTie => "empate",
+        Msg::RecapCount => "{} estuvo más cerca en {} objetivos, {} en {}.",
+        Msg::TimelineTitle => "Desarrollo de la partida:",
+        Msg::TimelineMissing => "Faltan las rondas 1 a {}: la partida se guardó con una versión que no conservaba su desarrollo.",
+        Msg::TimelineEmpty => "Aún no se ha jugado ninguna ronda.",
+        Msg::TimelineRound => "Ronda",
+        Msg::TimelineDamage => "Daño",
+        Msg::TimelinePenalty => "Penalización",
+        Msg::TimelinePassed => "pasado",
+        Msg::TimelineDamageTo => "{} a {}",
+        Msg::TimelineDraw => "empate",
+        Msg::TimelineAttributes => "PV:{} Ve:{} F:{}",
+        Msg::HelpTimeline => "{} y ENTER, al empezar un turno: mostrar el desarrollo de la partida",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_str() {
+        assert_eq!("en".parse::<Language>().unwrap(), Language::En);
+        assert_eq!("FR".parse::<Language>().unwrap(), Language::Fr);
+        assert_eq!("es".parse::<Language>().unwrap(), Language::Es);
+        assert!("de".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn test_format_message() {
+        assert_eq!(format_message("## Round {} ##", &[&3]), "## Round 3 ##");
+        assert_eq!(format_message("{} vs {}", &[&"A", &"B"]), "A vs B");
+        assert_eq!(format_message("no placeholders", &[&1]), "no placeholders");
+        assert_eq!(format_message("{} and {}", &[&1]), "1 and {}");
+    }
+
+    #[test]
+    fn test_text_in_each_language() {
+        assert_eq!(text_in(Language::En, Msg::RoundStart), "## Round {} ##");
+        assert_eq!(text_in(Language::Fr, Msg::RoundStart), "## Manche {} ##");
+        assert_eq!(text_in(Language::Es, Msg::RoundStart), "## Ronda {} ##");
+    }
+
+    #[test]
+    fn test_translations_keep_placeholders() {
+        for &msg in Msg::ALL {
+            let expected = english(msg).matches("{}").count();
+            assert_eq!(french(msg).matches("{}").count(), expected, "{:?}", msg);
+            assert_eq!(spanish(msg).matches("{}").count(), expected, "{:?}", msg);
+        }
+    }
+}
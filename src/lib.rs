@@ -0,0 +1,90 @@
+//! # Turn-Based Terminal Game
+//!
+//! The game's modules, shared by the `rust_game` binary and the benchmarks.
+//! The binary in `main.rs` only reads the command line and starts the game.
+//!
+//! The rules, scoring and state of a match need nothing but `std`. Everything
+//! that needs a terminal, threads or the network is behind the default
+//! `native` feature, so the core also builds for `wasm32`, where the `wasm`
+//! feature exposes it to JavaScript. The `ffi` feature exposes it to C.
+
+#[cfg(feature = "native")]
+pub mod api;
+#[cfg(feature = "native")]
+pub mod audio;
+#[cfg(feature = "native")]
+pub mod bot;
+#[cfg(feature = "native")]
+pub mod calibration;
+#[cfg(feature = "native")]
+pub mod campaign;
+#[cfg(feature = "native")]
+pub mod config;
+pub mod player;
+#[cfg(feature = "native")]
+pub mod counter;
+pub mod dial;
+pub mod scoring;
+#[cfg(feature = "native")]
+pub mod game;
+#[cfg(feature = "native")]
+pub mod minigames;
+pub mod mutators;
+#[cfg(feature = "native")]
+pub mod net;
+#[cfg(feature = "native")]
+pub mod ui;
+pub mod error;
+#[cfg(feature = "native")]
+pub mod events;
+#[cfg(feature = "native")]
+pub mod export;
+#[cfg(feature = "native")]
+pub mod fairness;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "native")]
+pub mod gui;
+#[cfg(feature = "native")]
+pub mod help;
+pub mod history;
+#[cfg(feature = "native")]
+pub mod http;
+#[cfg(feature = "native")]
+pub mod i18n;
+#[cfg(feature = "native")]
+pub mod metrics;
+#[cfg(feature = "native")]
+pub mod observer;
+#[cfg(feature = "native")]
+pub mod persistence;
+pub mod plugins;
+pub mod pool;
+#[cfg(feature = "native")]
+pub mod practice;
+#[cfg(feature = "native")]
+pub mod presence;
+#[cfg(feature = "native")]
+pub mod profile;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "native")]
+pub mod replay;
+pub mod rules;
+#[cfg(feature = "native")]
+pub mod runtime;
+#[cfg(feature = "native")]
+pub mod scripting;
+#[cfg(feature = "native")]
+pub mod server;
+#[cfg(feature = "native")]
+pub mod simulation;
+pub mod stages;
+pub mod state;
+#[cfg(feature = "native")]
+pub mod survival;
+#[cfg(feature = "native")]
+pub mod tutorial;
+pub mod version;
+#[cfg(feature = "wasm")]
+pub mod wasm;
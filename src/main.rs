@@ -11,22 +11,56 @@
 //! 4. The player with the highest average score wins the round.
 //! 5. The game continues until one player's vitality reaches zero.
 
-mod player;
-mod counter;
-mod scoring;
-mod game;
-mod ui;
-mod error;
+mod cli;
 
-use clap::{Command, Arg};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use clap_complete::Shell;
+use rust_game::{api, audio, calibration, campaign, config, counter, game, gui, help, i18n, net, persistence, practice, rules, scoring, scripting, simulation, state, survival, tutorial, ui};
+use rust_game::plugins::{GamePlugin, PluginRegistry};
+use rust_game::stages::Stage;
+use audio::SoundMode;
+use config::{ConfigFile, GameConfig};
 use game::Game;
-use crate::error::GameResult;
+use rust_game::profile::Profile;
+use practice::PracticeMode;
+use rust_game::replay::Replay;
+use i18n::{Language, Msg, tr};
+use ui::term::{self, TerminalGuard};
+use counter::Precision;
+use rules::{DamageModel, RuleSet, TargetDistribution, TurnOrder, WinCondition};
+use scoring::StopRule;
+use state::GameState;
+use ui::{CounterView, Rematch, Verbosity};
+use ui::render::RenderMode;
+use ui::source::{ScriptedInput, TerminalInput};
+use rust_game::error::{GameError, GameResult};
+
+/// Reports an error that ends the game.
+///
+/// # Arguments
+///
+/// * `e` - The error
+///
+/// # Returns
+///
+/// The exit code of the error, see `GameError::exit_code`
+fn exit_with(e: GameError) -> ExitCode {
+    // Ctrl-C is what the user asked for, not a failure to report
+    if !matches!(e, GameError::Interrupted) {
+        log::error!("{}", e);
+    }
+    ExitCode::from(e.exit_code())
+}
 
 /// The entry point for the game application.
 ///
 /// Parses command line arguments and starts the game.
 /// Returns an exit code appropriate to the result.
-fn main() -> GameResult<()> {
+fn main() -> ExitCode {
     // Initialize logger with colors enabled
     env_logger::builder()
         .filter_level(log::LevelFilter::Info)
@@ -36,94 +70,400 @@ fn main() -> GameResult<()> {
         .write_style(env_logger::WriteStyle::Always) // Force color output
         .init();
 
-    // Set up command line argument parsing
-    let matches = Command::new("Turn-based Game")
-        .version("1.0")
-        .author("SEC3 Game Developer")
-        .about("A terminal-based turn-based game")
-        .arg(Arg::new("name1")
-            .long("name1")
-            .value_name("NAME")
-            .help("Name of player 1")
-            .default_value("Player 1"))
-        .arg(Arg::new("name2")
-            .long("name2")
-            .value_name("NAME")
-            .help("Name of player 2")
-            .default_value("Player 2"))
-        .arg(Arg::new("vitality")
-            .long("vitality")
-            .value_name("AMOUNT")
-            .help("Starting vitality for both players")
-            .default_value("50"))
-        .arg(Arg::new("speed")
-            .long("speed")
-            .value_name("AMOUNT")
-            .help("Starting speed for both players")
-            .default_value("50"))
-        .arg(Arg::new("strength")
-            .long("strength")
-            .value_name("AMOUNT")
-            .help("Starting strength for both players")
-            .default_value("50"))
-        .arg(Arg::new("objectives")
-            .long("objectives")
-            .value_name("COUNT")
-            .help("Number of targets per turn")
-            .default_value("5"))
-        .get_matches();
+    let matches = cli::command().get_matches();
+    
+    // Generated files go to the standard output untouched by the terminal setup
+    match matches.subcommand() {
+        Some(("completions", sub_matches)) => {
+            cli::print_completions(*sub_matches.get_one::<Shell>("shell").unwrap());
+            return ExitCode::SUCCESS;
+        },
+        Some(("man", _)) => {
+            return match cli::print_man_page() {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => exit_with(e.into()),
+            };
+        },
+        _ => {},
+    }
 
-    // Parse command line arguments
+    // Parse command line arguments, clap has already checked their values
+    i18n::set_language(*matches.get_one::<Language>("lang").unwrap());
+    
     let player1_name = matches.get_one::<String>("name1").unwrap().to_string();
     let player2_name = matches.get_one::<String>("name2").unwrap().to_string();
+    let vitality = *matches.get_one::<u32>("vitality").unwrap();
+    let speed = *matches.get_one::<u32>("speed").unwrap();
+    let strength = *matches.get_one::<u32>("strength").unwrap();
+    let stamina = *matches.get_one::<u32>("stamina").unwrap();
+    let target_count = *matches.get_one::<usize>("objectives").unwrap();
+    let target_cap = matches.get_one::<usize>("progressive-targets").copied();
+    let seed = matches.get_one::<u64>("seed").copied();
+    let heal_hits = *matches.get_one::<u32>("heal-hits").unwrap();
+    let heal_amount = *matches.get_one::<u32>("heal-amount").unwrap();
+    let memory_seconds = matches.get_one::<u64>("memory-mode").copied();
+    let penalty_seconds = matches.get_one::<u64>("penalty-timer").copied();
+    let pass_cost = matches.get_one::<u32>("pass").copied();
+    let drift_ticks = matches.get_one::<u32>("moving-target").copied();
+    let stops = *matches.get_one::<u32>("stops").unwrap();
+    let stop_rule = *matches.get_one::<StopRule>("stop-rule").unwrap();
+    let precision = *matches.get_one::<Precision>("precision").unwrap();
+    let counter_max = *matches.get_one::<u32>("range").unwrap();
+    let base_tick_ms = *matches.get_one::<u32>("base-tick").unwrap();
+    let turn_order = *matches.get_one::<TurnOrder>("turn-order").unwrap();
+    let distribution = *matches.get_one::<TargetDistribution>("targets").unwrap();
+    let target_spacing = matches.get_one::<u32>("unique-targets").copied();
+    let damage = *matches.get_one::<DamageModel>("damage").unwrap();
+    let win_condition = *matches.get_one::<WinCondition>("win").unwrap();
+    let mut rules = RuleSet {
+        counter_max, precision, turn_order, distribution, target_spacing, damage, win_condition, base_tick_ms, ..RuleSet::default()
+    };
+    // A stage sets the rules it covers, unless they were given on the command line
+    if let Some(stage) = matches.get_one::<Stage>("stage") {
+        let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        stage.apply(&mut rules);
+        if given("range") {
+            rules.counter_max = counter_max;
+        }
+        if given("damage") {
+            rules.damage = damage;
+        }
+    }
+    let mut plugins = PluginRegistry::new(matches.get_one::<Vec<Box<dyn GamePlugin>>>("mutators").cloned().unwrap_or_default());
+    if let Some(path) = matches.get_one::<String>("script") {
+        match scripting::load(Path::new(path)) {
+            Ok(script) => plugins.register(script),
+            Err(e) => {
+                log::error!("Could not load rules script from {}", path);
+                return exit_with(e);
+            }
+        }
+    }
+    let beat = matches.get_one::<u32>("rhythm").copied();
+    let counter_view = *matches.get_one::<CounterView>("counter-view").unwrap();
+    let sound = *matches.get_one::<SoundMode>("sound").unwrap();
+    let render_mode = *matches.get_one::<RenderMode>("output").unwrap();
+    
+    let config_file = match matches.get_one::<String>("config") {
+        Some(path) => match ConfigFile::load(Path::new(path)) {
+            Ok(file) => file,
+            Err(e) => {
+                log::error!("Could not read config file {}", path);
+                return exit_with(e);
+            }
+        },
+        None => ConfigFile::default(),
+    };
+    rules.bounds = config_file.bounds;
+    
+    let duel = matches.get_flag("duel") && {
+        let keys_bound = config_file.keys.is_complete();
+        if !keys_bound {
+            log::error!("Duel mode needs a stop key for each player in the config file, playing in turns");
+        }
+        keys_bound
+    };
+    
+    let reaction_game = matches.get_flag("reaction-game") && {
+        let keys_bound = config_file.keys.is_complete();
+        if !keys_bound {
+            log::error!("The reaction game needs a stop key for each player in the config file, skipping it");
+        }
+        keys_bound
+    };
+    
+    let latency_credit_ms = match matches.get_one::<String>("latency-credit") {
+        Some(path) => match Profile::load(Path::new(path)) {
+            Ok(profile) => profile.latency_offset_ms,
+            Err(e) => {
+                log::error!("Could not read the latency offset from {}", path);
+                return exit_with(e);
+            }
+        },
+        None => 0,
+    };
+    
+    // Matches without a subcommand are played as with `play`
+    let play_matches = matches.subcommand_matches("play");
+    let script = match play_matches.and_then(|m| m.get_one::<String>("input-script")) {
+        Some(path) => match ScriptedInput::load(Path::new(path)) {
+            Ok(script) => Some(script),
+            Err(e) => {
+                log::error!("Could not load input script from {}", path);
+                return exit_with(e);
+            }
+        },
+        None => None,
+    };
+    
+    let config = GameConfig {
+        player1_name,
+        player2_name,
+        vitality,
+        speed,
+        strength,
+        stamina,
+        target_count,
+        target_cap,
+        shared_pool: matches.get_flag("shared-pool"),
+        predictions: matches.get_flag("predict"),
+        risk_tiers: matches.get_flag("risk"),
+        seed,
+        betting: matches.get_flag("betting"),
+        heal_hits,
+        heal_amount,
+        memory_seconds,
+        penalty_seconds,
+        pass_cost,
+        hidden_target: matches.get_flag("hidden-target"),
+        blind: matches.get_flag("blind"),
+        weather: matches.get_flag("weather"),
+        drift_ticks,
+        stops,
+        stop_rule,
+        rules,
+        plugins,
+        beat,
+        counter_view,
+        sound,
+        discord_app_id: matches.get_one::<String>("discord").cloned(),
+        render_mode,
+        log_file: matches.get_one::<String>("log-file").map(PathBuf::from),
+        export: matches.get_one::<String>("export").map(PathBuf::from),
+        verbosity: Verbosity::from_flags(matches.get_flag("verbose"), matches.get_flag("quiet")),
+        keys: config_file.keys,
+        duel,
+        // The draft bans bonuses too, so they are in play
+        consolation: matches.get_flag("consolation") || matches.get_flag("draft"),
+        draft: matches.get_flag("draft"),
+        reaction_game,
+        void_implausible: matches.get_flag("void-implausible"),
+        handicap: ["handicap1", "handicap2"].map(|id| *matches.get_one::<i32>(id).unwrap()),
+        latency_credit_ms,
+        // A scripted match must not replace the autosave of a real one
+        autosave: script.is_none(),
+        bot_command: matches.get_one::<String>("bot-command").cloned(),
+    };
+    
+    // The settings go to the standard output untouched by the terminal setup
+    if let Some(config_matches) = matches.subcommand_matches("config") {
+        let path = config_matches.get_one::<String>("file").or(matches.get_one::<String>("config"));
+        return match check_config(path.map(Path::new)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => exit_with(e),
+        };
+    }
+    
+    // The API plays its matches without the terminal
+    if let Some(&port) = matches.get_one::<u16>("api") {
+        return match api::serve(port, config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => exit_with(e),
+        };
+    }
+    
+    // The help and the rules go to the standard output untouched by the terminal setup
+    if matches.get_flag("help-keys") {
+        for line in help::help_lines(&config) {
+            println!("{}", line);
+        }
+        return ExitCode::SUCCESS;
+    }
+    if matches.subcommand_matches("rules").is_some() {
+        for line in help::rules_table(&config) {
+            println!("{}", line);
+        }
+        return ExitCode::SUCCESS;
+    }
+    
+    // The window plays its matches without the terminal
+    if matches.get_flag("gui") {
+        return match gui::run(config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => exit_with(e),
+        };
+    }
+    
+    // Leave the terminal usable however the game ends
+    term::install_panic_hook();
+    let _terminal = TerminalGuard::new();
+    if let Err(e) = ui::input::install_interrupt_handler() {
+        log::error!("{}", e);
+    }
     
-    let vitality = matches.get_one::<String>("vitality")
-        .unwrap()
-        .parse::<u32>()
-        .unwrap_or_else(|_| {
-            log::error!("Invalid vitality value, using default of 50");
-            50
-        });
-    
-    let speed = matches.get_one::<String>("speed")
-        .unwrap()
-        .parse::<u32>()
-        .unwrap_or_else(|_| {
-            log::error!("Invalid speed value, using default of 50");
-            50
-        });
-    
-    let strength = matches.get_one::<String>("strength")
-        .unwrap()
-        .parse::<u32>()
-        .unwrap_or_else(|_| {
-            log::error!("Invalid strength value, using default of 50");
-            50
-        });
-    
-    let target_count = matches.get_one::<String>("objectives")
-        .unwrap()
-        .parse::<usize>()
-        .unwrap_or_else(|_| {
-            log::error!("Invalid target count, using default of 5");
-            5
-        });
+    // Every mode but `play` runs once and exits
+    let profile_path = |sub_matches: &ArgMatches| PathBuf::from(sub_matches.get_one::<String>("profile").unwrap());
+    let result = match matches.subcommand() {
+        Some(("campaign", sub_matches)) => campaign::play(&config, &profile_path(sub_matches)),
+        Some(("survival", sub_matches)) => survival::play(&config, &profile_path(sub_matches)),
+        Some((name @ ("practice" | "time-attack"), sub_matches)) => {
+            let mode = if name == "practice" { PracticeMode::Practice } else { PracticeMode::TimeAttack };
+            let replay_path = Path::new(sub_matches.get_one::<String>("replay").unwrap());
+            practice::play(&config, mode, replay_path, sub_matches.get_flag("ghost"))
+        },
+        Some(("simulate", sub_matches)) => {
+            simulation::play(&config, *sub_matches.get_one::<u64>("games").unwrap());
+            Ok(())
+        },
+        Some(("connect", sub_matches)) => net::play(&config, sub_matches.get_one::<String>("address").unwrap()),
+        Some(("calibrate", sub_matches)) => calibration::play(&config, &profile_path(sub_matches), &mut TerminalInput),
+        Some(("tutorial", _)) => tutorial::play(&config, &mut TerminalInput),
+        Some(("stats", sub_matches)) => show_stats(&config, &profile_path(sub_matches)),
+        Some(("replay", sub_matches)) => show_replay(&config, Path::new(sub_matches.get_one::<String>("file").unwrap())),
+        _ => return play(config, play_matches, script),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => exit_with(e),
+    }
+}
+
+/// Plays matches between two players until they do not want another one.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+/// * `play_matches` - The options of the `play` subcommand, None if it was left out
+/// * `script` - The script to play the matches from, None to read the keyboard
+///
+/// # Returns
+///
+/// The exit code of the program
+fn play(mut config: GameConfig, play_matches: Option<&ArgMatches>, script: Option<ScriptedInput>) -> ExitCode {
+    // Resume a saved match first, if requested
+    let mut saved_state = match play_matches.and_then(|m| m.get_one::<String>("load")) {
+        Some(path) => match persistence::load_game(Path::new(path)) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                log::error!("Could not load saved match from {}", path);
+                return exit_with(e);
+            }
+        },
+        None if script.is_none() => recover_autosave(config.render_mode),
+        None => None,
+    };
     
     // Create and run the game
     loop {
-        let mut game = Game::new(
-            player1_name.clone(), 
-            player2_name.clone(), 
-            vitality,
-            speed,
-            strength,
-            target_count
-        );
+        let mut game = match saved_state.take() {
+            Some(state) => Game::from_state(config.clone(), state),
+            None => Game::new(config.clone()),
+        };
+        if let Some(script) = &script {
+            game = game.with_input(script.clone());
+        }
         
-        if !game.run() {
-            break;
+        match game.run() {
+            Ok(Rematch::Quit) => break,
+            Ok(Rematch::Same) => {},
+            Ok(Rematch::SwapFirst) => config.swap_players(),
+            Ok(Rematch::CarryOver) => saved_state = Some(game.rematch_state()),
+            Err(e) => return exit_with(e),
         }
     }
     
+    ExitCode::SUCCESS
+}
+
+/// Shows the progress kept in a profile.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, for the output mode
+/// * `profile_path` - The file the progress is kept in
+///
+/// # Returns
+///
+/// Result indicating whether the profile could be read
+fn show_stats(config: &GameConfig, profile_path: &Path) -> GameResult<()> {
+    let profile = Profile::load(profile_path)?;
+    let mut renderer = config.render_mode.renderer();
+    renderer.info(&tr!(Msg::StatsCampaign, profile.campaign_stage, campaign::OPPONENTS.len()));
+    renderer.info(&tr!(Msg::StatsSurvival, profile.survival_best));
+    renderer.info(&tr!(Msg::StatsLatency, profile.latency_offset_ms));
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Inspects a recorded practice or time attack run.
+///
+/// Shows what the run achieved and, for each target, where it was stopped and
+/// the hash of the game state, then checks that the run is complete.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, for the output mode and the dial the stops are shown on
+/// * `path` - The file the run is recorded in
+///
+/// # Returns
+///
+/// Result indicating whether the run could be read and is complete
+fn show_replay(config: &GameConfig, path: &Path) -> GameResult<()> {
+    let replay = Replay::load(path)?;
+    let dial = config.rules.dial();
+    let mut renderer = config.render_mode.renderer();
+    renderer.info(&tr!(Msg::ReplaySummary,
+                       replay.mode.name(), replay.seed, replay.mode.describe(replay.result), replay.stops.len()));
+    for (idx, &stop) in replay.stops.iter().enumerate() {
+        let hash = replay.hashes.get(idx).map_or_else(|| "-".to_string(), |hash| format!("{:016x}", hash));
+        renderer.info(&tr!(Msg::ReplayStop, idx + 1, dial.format(stop), hash));
+    }
+    replay.check_complete().map_err(|e| e.in_save(path))
+}
+
+/// Checks a configuration file and prints the settings it gives.
+///
+/// # Arguments
+///
+/// * `path` - The file to check, None to print the default settings
+///
+/// # Returns
+///
+/// Result indicating whether the file is valid
+fn check_config(path: Option<&Path>) -> GameResult<()> {
+    let config_file = match path {
+        Some(path) => {
+            let config_file = ConfigFile::load(path)?;
+            log::info!("{} is a valid configuration file", path.display());
+            if !config_file.keys.is_complete() {
+                log::warn!("{} does not give each player a stop key, which --duel and --reaction-game need", path.display());
+            }
+            config_file
+        },
+        None => ConfigFile::default(),
+    };
+    print!("{}", config_file.to_toml()?);
+    Ok(())
+}
+
+/// Offers to resume the latest autosave of a match that did not end.
+///
+/// Declining deletes the autosaves, so that they are not offered again.
+///
+/// # Arguments
+///
+/// * `render_mode` - How the question is drawn
+///
+/// # Returns
+///
+/// The state of the match to resume, or None to start a new one
+fn recover_autosave(render_mode: RenderMode) -> Option<GameState> {
+    let dir = persistence::autosave_dir();
+    let path = persistence::recoverable_autosave(&dir)?;
+    let state = match persistence::load_game(&path) {
+        Ok(state) => state,
+        Err(e) => {
+            log::error!("{}", tr!(Msg::AutosaveUnreadable, path.display(), e));
+            return None;
+        }
+    };
+    
+    // A Ctrl-C or a read error is taken as a "no", without discarding the autosave
+    match render_mode.renderer().confirm(&mut TerminalInput, &tr!(Msg::AutosaveFound, state.round)) {
+        Ok(true) => Some(state),
+        Ok(false) => {
+            persistence::clear_autosaves(&dir);
+            None
+        },
+        Err(_) => None,
+    }
+}
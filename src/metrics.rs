@@ -0,0 +1,198 @@
+//! Metrics module for watching a matchmaking server from a monitoring system.
+//!
+//! The server counts the matches it started and finished, the matches in
+//! progress, how long their rounds took and the errors it ran into. With
+//! `--metrics`, the `rust-game-server` binary serves them over HTTP at
+//! `/metrics`, in the Prometheus text format.
+
+use std::fmt::Write as _;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::error::GameResult;
+use crate::http::{self, Request, Response};
+
+/// The counters of a server, shared by the threads running its matches.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Matches started since the server started
+    matches_started: AtomicU64,
+    /// Matches played to their end since the server started
+    matches_finished: AtomicU64,
+    /// Matches in progress
+    active_matches: AtomicU64,
+    /// Rounds played to their end
+    rounds: AtomicU64,
+    /// Time spent in those rounds, in microseconds
+    round_micros: AtomicU64,
+    /// Connections and matches that ended with an error
+    errors: AtomicU64,
+}
+
+impl Metrics {
+    /// Counts a match starting.
+    pub fn match_started(&self) {
+        self.matches_started.fetch_add(1, Ordering::Relaxed);
+        self.active_matches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a match ending.
+    ///
+    /// # Arguments
+    ///
+    /// * `finished` - Whether the match was played to its end
+    pub fn match_ended(&self, finished: bool) {
+        if finished {
+            self.matches_finished.fetch_add(1, Ordering::Relaxed);
+        }
+        self.active_matches.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Counts a round played to its end.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How long the round took
+    pub fn round_played(&self, duration: Duration) {
+        self.rounds.fetch_add(1, Ordering::Relaxed);
+        self.round_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Counts a connection or match that ended with an error.
+    pub fn error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the average duration of the rounds played so far, None before the first one ends.
+    pub fn average_round(&self) -> Option<Duration> {
+        let rounds = self.rounds.load(Ordering::Relaxed);
+        (rounds > 0).then(|| Duration::from_micros(self.round_micros.load(Ordering::Relaxed) / rounds))
+    }
+
+    /// Writes the metrics in the Prometheus text format.
+    ///
+    /// # Returns
+    ///
+    /// A `# HELP` and `# TYPE` header for each metric, followed by its samples
+    pub fn render(&self) -> String {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed).to_string();
+        let round_seconds = self.round_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let average = self.average_round().map_or(0.0, |average| average.as_secs_f64());
+
+        let mut text = String::new();
+        write_metric(&mut text, "rust_game_matches_started_total", "counter", "Matches started",
+                     &[("", load(&self.matches_started))]);
+        write_metric(&mut text, "rust_game_matches_finished_total", "counter", "Matches played to their end",
+                     &[("", load(&self.matches_finished))]);
+        write_metric(&mut text, "rust_game_active_matches", "gauge", "Matches in progress",
+                     &[("", load(&self.active_matches))]);
+        write_metric(&mut text, "rust_game_round_duration_seconds", "summary", "Duration of the rounds played to their end",
+                     &[("_sum", round_seconds.to_string()), ("_count", load(&self.rounds))]);
+        write_metric(&mut text, "rust_game_round_duration_average_seconds", "gauge", "Average duration of the rounds played so far",
+                     &[("", average.to_string())]);
+        write_metric(&mut text, "rust_game_errors_total", "counter", "Connections and matches that ended with an error",
+                     &[("", load(&self.errors))]);
+        text
+    }
+}
+
+/// Writes one metric in the Prometheus text format.
+///
+/// # Arguments
+///
+/// * `text` - Where the metric is written
+/// * `name` - The name of the metric
+/// * `kind` - The type of the metric, such as `counter` or `gauge`
+/// * `help` - What the metric measures
+/// * `samples` - The suffix of each sample's name, with its value
+fn write_metric(text: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, String)]) {
+    let _ = writeln!(text, "# HELP {} {}", name, help);
+    let _ = writeln!(text, "# TYPE {} {}", name, kind);
+    for (suffix, value) in samples {
+        let _ = writeln!(text, "{}{} {}", name, suffix, value);
+    }
+}
+
+/// Answers the scrapers connecting to a listener until it fails.
+///
+/// # Arguments
+///
+/// * `listener` - Where the scrapers connect
+/// * `metrics` - The counters to serve
+///
+/// # Returns
+///
+/// Result indicating why the listener stopped
+pub fn serve(listener: TcpListener, metrics: Arc<Metrics>) -> GameResult<()> {
+    http::serve(listener, move |request: &Request| match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/metrics") => Response { content_type: "text/plain; version=0.0.4", ..Response::text("200 OK", metrics.render()) },
+        ("GET", _) => Response::text("404 Not Found", "Not found\n"),
+        _ => Response::text("405 Method Not Allowed", "Only GET is supported\n"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread;
+
+    #[test]
+    fn test_counts_matches_and_rounds() {
+        let metrics = Metrics::default();
+        metrics.match_started();
+        metrics.match_started();
+        metrics.match_ended(true);
+        metrics.round_played(Duration::from_secs(2));
+        metrics.round_played(Duration::from_secs(4));
+        metrics.error();
+
+        let text = metrics.render();
+        assert!(text.contains("rust_game_matches_started_total 2\n"));
+        assert!(text.contains("rust_game_matches_finished_total 1\n"));
+        assert!(text.contains("rust_game_active_matches 1\n"));
+        assert!(text.contains("rust_game_round_duration_seconds_sum 6\n"));
+        assert!(text.contains("rust_game_round_duration_seconds_count 2\n"));
+        assert!(text.contains("rust_game_round_duration_average_seconds 3\n"));
+        assert!(text.contains("rust_game_errors_total 1\n"));
+    }
+
+    #[test]
+    fn test_no_average_before_the_first_round() {
+        let metrics = Metrics::default();
+        assert_eq!(metrics.average_round(), None);
+        assert!(metrics.render().contains("rust_game_round_duration_average_seconds 0\n"));
+    }
+
+    #[test]
+    fn test_every_metric_has_a_type() {
+        let text = Metrics::default().render();
+        let types = text.lines().filter(|line| line.starts_with("# TYPE")).count();
+        assert_eq!(types, text.lines().filter(|line| line.starts_with("# HELP")).count());
+        assert_eq!(types, 6);
+    }
+
+    #[test]
+    fn test_serves_metrics_over_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let metrics = Arc::new(Metrics::default());
+        metrics.match_started();
+        thread::spawn(move || serve(listener, metrics));
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("rust_game_active_matches 1\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,124 @@
+//! Minigames module for the bonus games played between rounds.
+//!
+//! The reaction game waits a random delay, then shows "NOW!". The first player
+//! to press their key steals vitality from the other one, but a player pressing
+//! before the signal hands the win to their opponent.
+
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::error::GameResult;
+use crate::i18n::{Msg, tr};
+use crate::ui::render::Renderer;
+use crate::ui::source::InputSource;
+
+/// Vitality the winner of the reaction game takes from the other player.
+pub const REACTION_STEAL: u32 = 3;
+
+/// Shortest and longest wait before the signal, in milliseconds.
+const REACTION_DELAY_MS: RangeInclusive<u64> = 1500..=5000;
+
+/// Outcome of a reaction game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReactionResult {
+    /// Index of the winning player
+    pub winner: usize,
+    /// Time between the signal and the winning key press, None if the loser pressed too early
+    pub reaction_ms: Option<u64>,
+}
+
+impl ReactionResult {
+    /// Returns the index of the losing player.
+    pub fn loser(&self) -> usize {
+        1 - self.winner
+    }
+}
+
+/// Draws how long the players wait for the signal.
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator of the game
+///
+/// # Returns
+///
+/// The delay before "NOW!" is shown
+pub fn reaction_delay(rng: &mut impl Rng) -> Duration {
+    Duration::from_millis(rng.random_range(REACTION_DELAY_MS))
+}
+
+/// Plays a reaction game between the two players.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the signal is drawn
+/// * `input` - Where the players' key presses are read from
+/// * `names` - The name of each player
+/// * `keys` - The key of each player
+/// * `delay` - How long to wait before showing the signal
+///
+/// # Returns
+///
+/// Result containing who won and how fast they were
+pub fn play_reaction(renderer: &mut dyn Renderer, input: &mut dyn InputSource, names: [&str; 2], keys: [char; 2],
+                     delay: Duration) -> GameResult<ReactionResult> {
+    renderer.info(&tr!(Msg::ReactionReady, names[0], keys[0], names[1], keys[1]));
+    if let Some(early) = input.wait_for_key_timeout(&keys, delay)? {
+        return Ok(ReactionResult { winner: 1 - early, reaction_ms: None });
+    }
+
+    renderer.info(&tr!(Msg::ReactionNow));
+    let shown = Instant::now();
+    let winner = input.wait_for_key(&keys)?;
+    Ok(ReactionResult { winner, reaction_ms: Some(shown.elapsed().as_millis() as u64) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::render::NullRenderer;
+    use crate::ui::source::ScriptedInput;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    /// Plays a reaction game with a 20 ms delay, a single key press coming after `press_ms`.
+    fn react(press_ms: u64, key: char) -> ReactionResult {
+        let mut input = ScriptedInput::new([format!("{} key {}", press_ms, key).parse().unwrap()]);
+        play_reaction(&mut NullRenderer, &mut input, ["Alice", "Bob"], ['f', 'j'], Duration::from_millis(20)).unwrap()
+    }
+
+    #[test]
+    fn test_reaction_delay_in_range() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let delay = reaction_delay(&mut rng).as_millis() as u64;
+            assert!(REACTION_DELAY_MS.contains(&delay));
+        }
+    }
+
+    #[test]
+    fn test_reaction_delay_is_reproducible() {
+        let mut first = StdRng::seed_from_u64(42);
+        let mut second = StdRng::seed_from_u64(42);
+        assert_eq!(reaction_delay(&mut first), reaction_delay(&mut second));
+    }
+
+    #[test]
+    fn test_reaction_loser() {
+        assert_eq!(ReactionResult { winner: 0, reaction_ms: Some(250) }.loser(), 1);
+        assert_eq!(ReactionResult { winner: 1, reaction_ms: None }.loser(), 0);
+    }
+
+    #[test]
+    fn test_play_reaction() {
+        let result = react(40, 'j');
+        assert_eq!(result.winner, 1);
+        assert!(result.reaction_ms.is_some_and(|ms| ms >= 15));
+    }
+
+    #[test]
+    fn test_play_reaction_false_start() {
+        assert_eq!(react(5, 'f'), ReactionResult { winner: 1, reaction_ms: None });
+    }
+}
@@ -0,0 +1,144 @@
+//! Mutators module for custom game variants.
+//!
+//! A mutator is a built-in plugin bending one rule of the match: how targets
+//! are scored, how much vitality a lost round costs, or when a player loses.
+//! Mutators are picked by name on the command line and can be combined
+//! freely, e.g. `--mutators no-strength,double-damage`. A Lua script given
+//! with `--script` is one more plugin, see `scripting.rs`.
+
+use crate::error::{GameError, GameResult};
+use crate::plugins::GamePlugin;
+use crate::scoring::ScoreBreakdown;
+use crate::state::Tally;
+
+/// Strength no longer adds to the score, only accuracy counts.
+#[derive(Debug, Clone, Copy)]
+pub struct NoStrength;
+
+impl GamePlugin for NoStrength {
+    fn name(&self) -> &'static str {
+        "no-strength"
+    }
+
+    fn score(&self, breakdown: &mut ScoreBreakdown) {
+        breakdown.strength = 0;
+    }
+
+    fn clone_box(&self) -> Box<dyn GamePlugin> {
+        Box::new(*self)
+    }
+}
+
+/// Every lost round costs twice as much vitality.
+#[derive(Debug, Clone, Copy)]
+pub struct DoubleDamage;
+
+impl GamePlugin for DoubleDamage {
+    fn name(&self) -> &'static str {
+        "double-damage"
+    }
+
+    fn damage(&self, damage: u32) -> u32 {
+        damage.saturating_mul(2)
+    }
+
+    fn clone_box(&self) -> Box<dyn GamePlugin> {
+        Box::new(*self)
+    }
+}
+
+/// Misses the counter may make over a match before its player loses.
+pub const MISS_LIMIT: u32 = 5;
+
+/// A player whose counter went around `MISS_LIMIT` times over the match loses.
+#[derive(Debug, Clone, Copy)]
+pub struct FiveMissesLose;
+
+impl GamePlugin for FiveMissesLose {
+    fn name(&self) -> &'static str {
+        "five-misses-lose"
+    }
+
+    fn loser(&self, tally: &Tally) -> Option<usize> {
+        // If both reached the limit in the same round, the one with more misses loses
+        let [misses1, misses2] = tally.misses;
+        match (misses1 >= MISS_LIMIT, misses2 >= MISS_LIMIT) {
+            (true, false) => Some(0),
+            (false, true) => Some(1),
+            (true, true) if misses1 != misses2 => Some(if misses1 > misses2 { 0 } else { 1 }),
+            _ => None,
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn GamePlugin> {
+        Box::new(*self)
+    }
+}
+
+/// Parses a comma-separated list of mutator names.
+///
+/// # Arguments
+///
+/// * `list` - The names, e.g. `no-strength,double-damage`
+///
+/// # Returns
+///
+/// Result containing the mutators in the order given, or an error naming
+/// the first unknown or repeated one
+pub fn parse(list: &str) -> GameResult<Vec<Box<dyn GamePlugin>>> {
+    let mut mutators: Vec<Box<dyn GamePlugin>> = Vec::new();
+    for name in list.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let mutator: Box<dyn GamePlugin> = match name.to_ascii_lowercase().as_str() {
+            "no-strength" => Box::new(NoStrength),
+            "double-damage" => Box::new(DoubleDamage),
+            "five-misses-lose" => Box::new(FiveMissesLose),
+            _ => return Err(GameError::ConfigError(format!("Unknown mutator: {}", name))),
+        };
+        if mutators.iter().any(|other| other.name() == mutator.name()) {
+            return Err(GameError::ConfigError(format!("Mutator listed twice: {}", name)));
+        }
+        mutators.push(mutator);
+    }
+    Ok(mutators)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dial::Dial;
+
+    #[test]
+    fn test_parse_mutators() {
+        let mutators = parse("no-strength, Double-Damage").unwrap();
+        let names: Vec<_> = mutators.iter().map(|mutator| mutator.name()).collect();
+        assert_eq!(names, ["no-strength", "double-damage"]);
+        assert!(parse("").unwrap().is_empty());
+        assert!(matches!(parse("no-gravity"), Err(GameError::ConfigError(_))));
+        assert!(parse("double-damage,double-damage").is_err());
+    }
+
+    #[test]
+    fn test_score_and_damage_hooks() {
+        let mut breakdown = ScoreBreakdown::new(50, 50, 70, 0, 0, Dial::default());
+        NoStrength.score(&mut breakdown);
+        assert_eq!(breakdown.total(), 100);
+        DoubleDamage.score(&mut breakdown);
+        assert_eq!(breakdown.total(), 100);
+
+        assert_eq!(DoubleDamage.damage(15), 30);
+        assert_eq!(NoStrength.damage(15), 15);
+        // The built-in mutators leave rounds and penalties alone
+        assert_eq!(NoStrength.round_start(1), None);
+        assert_eq!(DoubleDamage.penalty(5), 5);
+    }
+
+    #[test]
+    fn test_five_misses_lose() {
+        let tally = |misses| Tally { misses, ..Tally::default() };
+        assert_eq!(FiveMissesLose.loser(&tally([4, 2])), None);
+        assert_eq!(FiveMissesLose.loser(&tally([4, 5])), Some(1));
+        assert_eq!(FiveMissesLose.loser(&tally([7, 5])), Some(0));
+        assert_eq!(FiveMissesLose.loser(&tally([6, 6])), None);
+        assert_eq!(DoubleDamage.loser(&tally([9, 0])), None);
+    }
+}
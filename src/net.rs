@@ -0,0 +1,837 @@
+//! Net module for playing matches against players on other machines.
+//!
+//! Clients and the matchmaking server (`rust-game-server`) exchange JSON
+//! messages, either over raw TCP with one message per line, or over WebSocket
+//! with one message per text frame, so that browsers and mobile apps can join
+//! without a framing layer of their own. The server tells them apart by the
+//! first bytes a client sends. Both sides start with a `hello` giving the
+//! version of the protocol they speak, and the connection is closed if the
+//! versions are not compatible. A message added by a later minor version is
+//! read as `Unknown` and ignored. A client then joins with its player's name and
+//! is paired with the next player to join. The server plays the match: it draws the
+//! same lines the terminal would show, and each client sends back what its
+//! player types, ENTER to stop the counter included. Every game event is
+//! relayed to both clients as it is recorded.
+//!
+//! A client that loses its connection during a match connects again and
+//! rejoins it with the session it was given, along with the hash of the last
+//! game state it saw. The server holds the match for `REJOIN_GRACE` and, if
+//! that hash is one the match went through, resumes it from the start of the
+//! current round.
+//!
+//! Players can also talk to each other: a line typed as `/say <message>` is
+//! sent as a chat message, and shown to both players among the game output.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tungstenite::handshake::{HandshakeError, HandshakeRole};
+use tungstenite::http::Uri;
+use tungstenite::protocol::WebSocketConfig;
+use tungstenite::{Message, WebSocket};
+
+use crate::config::GameConfig;
+use crate::error::{GameError, GameResult};
+use crate::events::GameEvent;
+use crate::i18n::{Msg, tr};
+use crate::ui::{input, render};
+use crate::version::PROTOCOL;
+
+/// Address the server listens on and clients connect to unless another one is given.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// Longest message accepted, in bytes, so that a peer cannot fill the memory.
+pub const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// How long a match is held for a player who lost their connection.
+pub const REJOIN_GRACE: Duration = Duration::from_secs(30);
+
+/// Time between two attempts at connecting again to rejoin a match.
+const REJOIN_RETRY: Duration = Duration::from_secs(1);
+
+/// Longest chat message passed on, in characters.
+pub const MAX_CHAT_LEN: usize = 200;
+
+/// Longest player name kept, in characters.
+pub const MAX_NAME_LEN: usize = 32;
+
+/// Command a line starts with to be sent as a chat message.
+const SAY_COMMAND: &str = "/say";
+
+/// Scheme of the addresses reached over WebSocket rather than raw TCP.
+const WEBSOCKET_SCHEME: &str = "ws://";
+
+/// How long the other end has to open a connection, WebSocket handshake included, before it is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a WebSocket read holds the connection before letting a send through.
+const WEBSOCKET_POLL: Duration = Duration::from_millis(20);
+
+/// First byte of a WebSocket close frame: the final frame bit and the close opcode.
+const WEBSOCKET_CLOSE: u8 = 0x88;
+
+/// A message from a client to the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Gives the protocol version of the client, first thing after connecting
+    Hello {
+        magic: String,
+        version: String,
+    },
+    /// Asks for an opponent, once the server said hello
+    Join {
+        name: String,
+    },
+    /// Goes back to a match after losing the connection, in place of a join,
+    /// with the last state hash seen, or round 0 if no round started yet
+    Rejoin {
+        session: String,
+        round: u32,
+        hash: u64,
+    },
+    /// A line the player typed, empty for ENTER alone
+    Line {
+        text: String,
+    },
+    /// A message for the opponent, typed after `/say`
+    Chat {
+        text: String,
+    },
+    /// A message added by a later minor version of the protocol, ignored
+    #[serde(other)]
+    Unknown,
+}
+
+/// A message from the server to a client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Gives the protocol version of the server, if the client's is compatible with it
+    Hello {
+        magic: String,
+        version: String,
+    },
+    /// The player waits for an opponent to join
+    Queued,
+    /// The player was paired with an opponent, the match starts, and can be rejoined with the session
+    MatchFound {
+        player: usize,
+        opponent: String,
+        session: String,
+    },
+    /// A player rejoined the match, which starts again from the round with this state hash
+    Resumed {
+        round: u32,
+        hash: u64,
+    },
+    /// A line of game output
+    Text {
+        text: String,
+    },
+    /// An answer is expected
+    Prompt,
+    /// The live counter moved, shown in place of the previous caption
+    Counter {
+        caption: String,
+    },
+    /// Something happened in the match
+    Event {
+        event: GameEvent,
+    },
+    /// A player said something, shown to both players
+    Chat {
+        player: usize,
+        text: String,
+    },
+    /// The match ended, without a winner if it was a draw
+    MatchOver {
+        winner: Option<usize>,
+    },
+    /// The match could not go on
+    Error {
+        message: String,
+    },
+    /// A message added by a later minor version of the protocol, ignored
+    #[serde(other)]
+    Unknown,
+}
+
+impl ClientMessage {
+    /// Returns the hello of this build.
+    pub fn hello() -> Self {
+        ClientMessage::Hello { magic: PROTOCOL.magic.to_string(), version: PROTOCOL.version.to_string() }
+    }
+
+    /// Turns a line the player typed into a message, a chat message if it starts with `/say`.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The line the player typed
+    ///
+    /// # Returns
+    ///
+    /// The message to send to the server
+    pub fn typed(line: String) -> Self {
+        match line.trim_start().strip_prefix(SAY_COMMAND) {
+            Some(text) if text.is_empty() || text.starts_with(char::is_whitespace) => {
+                ClientMessage::Chat { text: text.trim().to_string() }
+            },
+            _ => ClientMessage::Line { text: line },
+        }
+    }
+}
+
+impl ServerMessage {
+    /// Returns the hello of this build.
+    pub fn hello() -> Self {
+        ServerMessage::Hello { magic: PROTOCOL.magic.to_string(), version: PROTOCOL.version.to_string() }
+    }
+}
+
+/// Writes a message as one line.
+///
+/// # Arguments
+///
+/// * `writer` - Where the message is written
+/// * `message` - The message to write
+///
+/// # Returns
+///
+/// Result indicating whether the message was written
+pub fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> GameResult<()> {
+    let mut line = serde_json::to_vec(message).map_err(io::Error::from)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the next message.
+///
+/// # Arguments
+///
+/// * `reader` - Where the message is read from
+///
+/// # Returns
+///
+/// Result containing the message, None once the connection is closed, or
+/// `GameError::ParseError` if the line is too long or not a valid message
+pub fn receive<T: DeserializeOwned>(reader: &mut impl BufRead) -> GameResult<Option<T>> {
+    let mut line = String::new();
+    if reader.take(MAX_MESSAGE_LEN as u64 + 1).read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.len() > MAX_MESSAGE_LEN {
+        return Err(GameError::ParseError(format!("A message is longer than {} bytes", MAX_MESSAGE_LEN)));
+    }
+    Ok(Some(serde_json::from_str(line.trim_end())?))
+}
+
+/// How the messages are framed on a connection.
+#[derive(Debug)]
+enum Link {
+    /// One message per line on a raw TCP connection, read and written through separate handles
+    Lines {
+        reader: Mutex<BufReader<TcpStream>>,
+        writer: Mutex<TcpStream>,
+    },
+    /// One message per text frame on a WebSocket, read with a short timeout so that sends get through
+    WebSocket(Mutex<Box<WebSocket<TcpStream>>>),
+}
+
+/// A connection to the other end, shared by the threads that read and write it.
+#[derive(Debug, Clone)]
+pub struct Peer {
+    /// The connection and its framing
+    link: Arc<Link>,
+}
+
+impl Peer {
+    /// Creates a connection sending one message per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The raw TCP connection
+    ///
+    /// # Returns
+    ///
+    /// Result containing the connection, or an error if it could not be shared between threads
+    pub fn new(stream: TcpStream) -> GameResult<Self> {
+        let reader = Mutex::new(BufReader::new(stream.try_clone()?));
+        Ok(Self { link: Arc::new(Link::Lines { reader, writer: Mutex::new(stream) }) })
+    }
+
+    /// Accepts a new connection, over WebSocket if it opens with an HTTP request.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The connection, as accepted by the listener
+    ///
+    /// # Returns
+    ///
+    /// Result containing the connection, or an error if the WebSocket handshake failed
+    /// or the other end sent nothing within `HANDSHAKE_TIMEOUT`
+    pub fn accept(stream: TcpStream) -> GameResult<Self> {
+        Self::accept_within(stream, HANDSHAKE_TIMEOUT)
+    }
+
+    /// Accepts a new connection, giving up on one that does not open in time.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The connection, as accepted by the listener
+    /// * `timeout` - How long each read of the opening may wait
+    ///
+    /// # Returns
+    ///
+    /// Result containing the connection, or an error if the WebSocket handshake failed or timed out
+    fn accept_within(stream: TcpStream, timeout: Duration) -> GameResult<Self> {
+        // The reads block until the opening arrives, but not for ever
+        stream.set_read_timeout(Some(timeout))?;
+        let mut start = [0; 4];
+        let read = stream.peek(&mut start).map_err(GameError::from).map_err(network_error)?;
+        if &start[..read] != b"GET " {
+            stream.set_read_timeout(None)?;
+            return Self::new(stream);
+        }
+        let websocket = tungstenite::accept_with_config(stream, Some(websocket_config())).map_err(handshake_error)?;
+        Self::over_websocket(websocket)
+    }
+
+    /// Connects to a server, over WebSocket if the address starts with `ws://`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The server, as `host:port` or `ws://host:port/`
+    ///
+    /// # Returns
+    ///
+    /// Result containing the connection, or an error if the server could not be reached
+    /// or did not answer the WebSocket handshake within `HANDSHAKE_TIMEOUT`
+    pub fn connect(address: &str) -> GameResult<Self> {
+        if !address.starts_with(WEBSOCKET_SCHEME) {
+            return Self::new(TcpStream::connect(address).map_err(GameError::from).map_err(network_error)?);
+        }
+        let uri: Uri = address.parse()
+            .map_err(|e| GameError::ConfigError(format!("Invalid server address {}: {}", address, e)))?;
+        let host = uri.host().unwrap_or_default();
+        let stream = TcpStream::connect((host, uri.port_u16().unwrap_or(80))).map_err(GameError::from).map_err(network_error)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let (websocket, _) = tungstenite::client::client_with_config(uri, stream, Some(websocket_config()))
+            .map_err(handshake_error)?;
+        Self::over_websocket(websocket)
+    }
+
+    /// Shares a WebSocket between the threads that read and write it.
+    ///
+    /// # Arguments
+    ///
+    /// * `websocket` - The WebSocket, after the handshake
+    ///
+    /// # Returns
+    ///
+    /// Result containing the connection, or an error if the read timeout could not be set
+    fn over_websocket(websocket: WebSocket<TcpStream>) -> GameResult<Self> {
+        websocket.get_ref().set_read_timeout(Some(WEBSOCKET_POLL))?;
+        Ok(Self { link: Arc::new(Link::WebSocket(Mutex::new(Box::new(websocket)))) })
+    }
+
+    /// Sends a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to send
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the message was sent
+    pub fn send<T: Serialize>(&self, message: &T) -> GameResult<()> {
+        match &*self.link {
+            Link::Lines { writer, .. } => send(&mut *writer.lock().unwrap(), message).map_err(network_error),
+            Link::WebSocket(websocket) => {
+                let text = serde_json::to_string(message).map_err(io::Error::from)?;
+                websocket.lock().unwrap().send(Message::text(text)).map_err(websocket_error)
+            },
+        }
+    }
+
+    /// Waits for the next message.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the message, None once the connection is closed, or
+    /// `GameError::ParseError` if it is not a valid message
+    pub fn receive<T: DeserializeOwned>(&self) -> GameResult<Option<T>> {
+        let websocket = match &*self.link {
+            Link::Lines { reader, .. } => return receive(&mut *reader.lock().unwrap()).map_err(network_error),
+            Link::WebSocket(websocket) => websocket,
+        };
+        loop {
+            // The lock is given up between reads, for the messages waiting to be sent
+            let read = websocket.lock().unwrap().read();
+            match read {
+                Ok(Message::Text(text)) => return Ok(Some(serde_json::from_str(&text)?)),
+                Ok(Message::Close(_)) | Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    return Ok(None);
+                },
+                // Pings are answered by the WebSocket itself, other frames carry no message
+                Ok(_) => {},
+                Err(tungstenite::Error::Io(e)) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                    thread::yield_now();
+                },
+                Err(e) => return Err(websocket_error(e)),
+            }
+        }
+    }
+
+    /// Checks whether the other end is still there, without waiting or taking any message.
+    ///
+    /// # Returns
+    ///
+    /// False if the other end closed the connection or the connection failed
+    pub fn is_connected(&self) -> bool {
+        let mut start = [0; 1];
+        let peeked = match &*self.link {
+            Link::Lines { reader, .. } => peek_now(reader.lock().unwrap().get_ref(), &mut start),
+            Link::WebSocket(websocket) => peek_now(websocket.lock().unwrap().get_ref(), &mut start),
+        };
+        match peeked {
+            // Nothing was sent since, the other end is waiting
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => true,
+            Err(_) | Ok(0) => false,
+            // A WebSocket that starts closing is as good as closed
+            Ok(_) => !matches!(&*self.link, Link::WebSocket(_)) || start[0] != WEBSOCKET_CLOSE,
+        }
+    }
+
+    /// Closes the connection both ways, which ends the reads waiting on it.
+    pub fn close(&self) {
+        match &*self.link {
+            Link::Lines { writer, .. } => {
+                let _ = writer.lock().unwrap().shutdown(Shutdown::Both);
+            },
+            Link::WebSocket(websocket) => {
+                let mut websocket = websocket.lock().unwrap();
+                let _ = websocket.close(None);
+                let _ = websocket.flush();
+                let _ = websocket.get_ref().shutdown(Shutdown::Both);
+            },
+        }
+    }
+}
+
+/// Peeks at the bytes waiting on a connection, without waiting for any.
+///
+/// # Arguments
+///
+/// * `stream` - The connection
+/// * `buffer` - Where the bytes are copied
+///
+/// # Returns
+///
+/// Result containing the number of bytes copied, 0 if the connection is closed,
+/// or a `WouldBlock` error if nothing is waiting
+fn peek_now(stream: &TcpStream, buffer: &mut [u8]) -> io::Result<usize> {
+    stream.set_nonblocking(true)?;
+    let peeked = stream.peek(buffer);
+    stream.set_nonblocking(false)?;
+    peeked
+}
+
+/// Returns the limits of a WebSocket, the same as for raw TCP.
+fn websocket_config() -> WebSocketConfig {
+    WebSocketConfig::default()
+        .max_message_size(Some(MAX_MESSAGE_LEN))
+        .max_frame_size(Some(MAX_MESSAGE_LEN))
+}
+
+/// Converts a WebSocket error to a game error.
+///
+/// # Arguments
+///
+/// * `error` - The WebSocket error
+///
+/// # Returns
+///
+/// A `GameError::NetworkError` for the I/O error underneath, or a `GameError::ParseError` for a protocol error
+fn websocket_error(error: tungstenite::Error) -> GameError {
+    match error {
+        tungstenite::Error::Io(e) => network_error(GameError::IoError(e)),
+        e => GameError::ParseError(e.to_string()),
+    }
+}
+
+/// Converts a failed WebSocket handshake to a game error.
+///
+/// # Arguments
+///
+/// * `error` - The handshake error
+///
+/// # Returns
+///
+/// A `GameError::NetworkError` if the other end stopped answering, or a `GameError::ParseError` if it did not
+/// speak WebSocket
+fn handshake_error<Role: HandshakeRole>(error: HandshakeError<Role>) -> GameError {
+    match error {
+        // A read timed out with the handshake half done
+        HandshakeError::Interrupted(_) => GameError::NetworkError("The WebSocket handshake timed out".to_string()),
+        HandshakeError::Failure(tungstenite::Error::Io(e)) => network_error(GameError::IoError(e)),
+        HandshakeError::Failure(e) => GameError::ParseError(format!("WebSocket handshake failed: {}", e)),
+    }
+}
+
+/// Reports an I/O error on a connection as a network error, so that it is not taken for a file error.
+///
+/// # Arguments
+///
+/// * `error` - The error from reading or writing the connection
+///
+/// # Returns
+///
+/// A `GameError::NetworkError` for an I/O error, any other error unchanged
+fn network_error(error: GameError) -> GameError {
+    match error {
+        GameError::IoError(e) => GameError::NetworkError(e.to_string()),
+        other => other,
+    }
+}
+
+/// Cleans up a chat message before it is passed on to the players.
+///
+/// # Arguments
+///
+/// * `text` - The message as the player sent it
+///
+/// # Returns
+///
+/// The message without control characters, which could take over the other player's terminal,
+/// and cut to `MAX_CHAT_LEN` characters, or None if nothing is left to say
+pub fn chat_text(text: &str) -> Option<String> {
+    clean_text(text, MAX_CHAT_LEN)
+}
+
+/// Cleans up the name a player joins with, before it is shown to anyone or logged.
+///
+/// # Arguments
+///
+/// * `name` - The name as the player sent it
+///
+/// # Returns
+///
+/// The name without control characters, cut to `MAX_NAME_LEN` characters,
+/// or None if nothing is left of it
+pub fn player_name(name: &str) -> Option<String> {
+    clean_text(name, MAX_NAME_LEN)
+}
+
+/// Drops the control characters of a text, cuts it to a length and trims it.
+///
+/// # Arguments
+///
+/// * `text` - The text as a player sent it
+/// * `max_len` - The most characters kept
+///
+/// # Returns
+///
+/// The cleaned up text, or None if nothing is left of it
+fn clean_text(text: &str, max_len: usize) -> Option<String> {
+    let text: String = text.chars().filter(|c| !c.is_control()).take(max_len).collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Builds the error returned when the other end of a connection is gone.
+///
+/// # Arguments
+///
+/// * `message` - What was lost
+///
+/// # Returns
+///
+/// A `GameError::NetworkError`
+pub fn connection_lost(message: String) -> GameError {
+    GameError::NetworkError(message)
+}
+
+/// Joins a server and plays a match through it.
+///
+/// # Arguments
+///
+/// * `config` - The game settings: the player's name and the output mode
+/// * `address` - The server to connect to, as `host:port`, or `ws://host:port/` over WebSocket
+///
+/// # Returns
+///
+/// Result indicating whether the match was played to its end
+pub fn play(config: &GameConfig, address: &str) -> GameResult<()> {
+    let peer = Peer::connect(address)?;
+    peer.send(&ClientMessage::hello())?;
+    expect_hello(&peer)?;
+    peer.send(&ClientMessage::Join { name: config.player1_name.clone() })?;
+    let current = Arc::new(Mutex::new(peer));
+
+    // The keyboard is read on its own thread, so that the server's messages show up as they come
+    let keyboard = Arc::clone(&current);
+    let quit = Arc::new(AtomicBool::new(false));
+    let quitting = Arc::clone(&quit);
+    thread::spawn(move || {
+        while let Ok(text) = input::read_line() {
+            // A line typed while the connection is lost goes nowhere
+            let _ = keyboard.lock().unwrap().send(&ClientMessage::typed(text));
+        }
+        quitting.store(true, Ordering::SeqCst);
+        keyboard.lock().unwrap().close();
+    });
+
+    let mut renderer = config.render_mode.renderer();
+    let mut caption_shown = false;
+    let mut session = None;
+    let mut last_hash = (0, 0);
+    let mut names = [config.player1_name.clone(), String::new()];
+    loop {
+        let peer = current.lock().unwrap().clone();
+        let message = match peer.receive() {
+            Ok(Some(message)) => message,
+            Ok(None) | Err(GameError::NetworkError(_)) if session.is_some() && !quit.load(Ordering::SeqCst) => {
+                if caption_shown {
+                    renderer.new_line()?;
+                    caption_shown = false;
+                }
+                renderer.info(&tr!(Msg::NetReconnecting));
+                let session = session.clone().unwrap_or_default();
+                *current.lock().unwrap() = rejoin(address, session, last_hash)?;
+                continue;
+            },
+            Ok(None) => return Err(connection_lost(tr!(Msg::NetConnectionLost))),
+            Err(e) => return Err(e),
+        };
+        // A counter caption stays on its line until something else is shown
+        if caption_shown && !matches!(message, ServerMessage::Counter { .. } | ServerMessage::Event { .. }) {
+            renderer.new_line()?;
+            caption_shown = false;
+        }
+        match message {
+            ServerMessage::Hello { magic, version } => {
+                if let Err(e) = PROTOCOL.check(&magic, &version) {
+                    peer.close();
+                    return Err(e);
+                }
+            },
+            ServerMessage::Queued => renderer.info(&tr!(Msg::NetQueued)),
+            ServerMessage::MatchFound { player, opponent, session: id } => {
+                session = Some(id);
+                renderer.info(&tr!(Msg::NetMatchFound, opponent, player + 1));
+                renderer.info(&tr!(Msg::NetChatHint));
+                names = [config.player1_name.clone(), opponent];
+                if player == 1 {
+                    names.swap(0, 1);
+                }
+            },
+            ServerMessage::Chat { player, text } => {
+                let name = names.get(player).map_or("?", String::as_str);
+                renderer.info(&tr!(Msg::NetChat, name, text));
+            },
+            ServerMessage::Resumed { round, hash } => {
+                if last_hash.0 == round && last_hash.1 != hash {
+                    peer.close();
+                    return Err(GameError::Desync { round, expected: last_hash.1, actual: hash });
+                }
+                renderer.info(&tr!(Msg::NetResumed, round));
+            },
+            ServerMessage::Text { text } => renderer.info(&text),
+            ServerMessage::Prompt => renderer.prompt()?,
+            ServerMessage::Counter { caption } => {
+                render::write_plain_caption(&caption)?;
+                caption_shown = true;
+            },
+            ServerMessage::Event { event: GameEvent::StateHash { round, hash } } => last_hash = (round, hash),
+            ServerMessage::Event { .. } | ServerMessage::Unknown => {},
+            ServerMessage::MatchOver { .. } => {
+                peer.close();
+                return Ok(());
+            },
+            ServerMessage::Error { message } => {
+                peer.close();
+                return Err(connection_lost(message));
+            },
+        }
+    }
+}
+
+/// Waits for the server's hello and checks that both sides speak compatible versions.
+///
+/// # Arguments
+///
+/// * `peer` - The connection to the server, once the client said hello
+///
+/// # Returns
+///
+/// Result with `GameError::IncompatibleVersion` if the server speaks a version this build
+/// cannot talk to, or an error if the server went away or answered anything else
+fn expect_hello(peer: &Peer) -> GameResult<()> {
+    let checked = match peer.receive()? {
+        Some(ServerMessage::Hello { magic, version }) => PROTOCOL.check(&magic, &version),
+        Some(ServerMessage::Error { message }) => Err(connection_lost(message)),
+        Some(_) => Err(GameError::ParseError("The server must start with a hello".to_string())),
+        None => Err(connection_lost(tr!(Msg::NetConnectionLost))),
+    };
+    if checked.is_err() {
+        peer.close();
+    }
+    checked
+}
+
+/// Connects again to a server to rejoin a match, until the match is no longer held.
+///
+/// # Arguments
+///
+/// * `address` - The server to connect to
+/// * `session` - The session the match was found with
+/// * `(round, hash)` - The last state hash seen and its round, or round 0 if none was seen
+///
+/// # Returns
+///
+/// Result containing the new connection, or an error if the server could not be reached in time
+fn rejoin(address: &str, session: String, (round, hash): (u32, u64)) -> GameResult<Peer> {
+    let start = Instant::now();
+    let peer = loop {
+        match Peer::connect(address) {
+            Ok(peer) => break peer,
+            Err(e) if start.elapsed() >= REJOIN_GRACE => return Err(e),
+            Err(_) => thread::sleep(REJOIN_RETRY),
+        }
+    };
+    peer.send(&ClientMessage::hello())?;
+    peer.send(&ClientMessage::Rejoin { session, round, hash })?;
+    Ok(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_messages_round_trip() {
+        let mut buffer = Vec::new();
+        send(&mut buffer, &ClientMessage::Join { name: "Alice".to_string() }).unwrap();
+        send(&mut buffer, &ClientMessage::Line { text: String::new() }).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        assert_eq!(receive(&mut reader).unwrap(), Some(ClientMessage::Join { name: "Alice".to_string() }));
+        assert_eq!(receive(&mut reader).unwrap(), Some(ClientMessage::Line { text: String::new() }));
+        assert_eq!(receive::<ClientMessage>(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_message_format() {
+        let mut buffer = Vec::new();
+        send(&mut buffer, &ServerMessage::MatchFound { player: 1, opponent: "Bob".to_string(), session: "ab".to_string() }).unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(),
+                   "{\"type\":\"match_found\",\"player\":1,\"opponent\":\"Bob\",\"session\":\"ab\"}\n");
+    }
+
+    #[test]
+    fn test_receive_rejects_invalid_messages() {
+        let mut reader = Cursor::new("{\"type\":\"join\"}\n");
+        assert!(matches!(receive::<ClientMessage>(&mut reader), Err(GameError::ParseError(_))));
+
+        let mut reader = Cursor::new(format!("\"{}\"\n", "x".repeat(MAX_MESSAGE_LEN)));
+        assert!(matches!(receive::<ClientMessage>(&mut reader), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_messages_from_a_later_minor_version_are_ignored() {
+        let mut reader = Cursor::new("{\"type\":\"shout\",\"text\":\"hi\"}\n{\"type\":\"emote\"}\n");
+        assert_eq!(receive::<ClientMessage>(&mut reader).unwrap(), Some(ClientMessage::Unknown));
+        assert_eq!(receive::<ServerMessage>(&mut reader).unwrap(), Some(ServerMessage::Unknown));
+    }
+
+    #[test]
+    fn test_refused_version_exits_as_incompatible() {
+        // A server that only speaks an older version of the protocol
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let peer = Peer::new(stream).unwrap();
+            let _ = peer.receive::<ClientMessage>();
+            let _ = peer.send(&ServerMessage::Hello { magic: PROTOCOL.magic.to_string(), version: "1.0.0".to_string() });
+            let _ = peer.send(&ServerMessage::Error { message: "Incompatible network protocol version".to_string() });
+        });
+
+        let error = play(&GameConfig::default(), &address).unwrap_err();
+        assert!(matches!(&error, GameError::IncompatibleVersion { found, .. } if found == "1.0.0"));
+        assert_eq!(error.exit_code(), 8);
+    }
+
+    #[test]
+    fn test_say_command_sends_a_chat_message() {
+        let typed = |line: &str| ClientMessage::typed(line.to_string());
+        assert_eq!(typed("/say good luck "), ClientMessage::Chat { text: "good luck".to_string() });
+        assert_eq!(typed("/say"), ClientMessage::Chat { text: String::new() });
+        assert_eq!(typed("/sayonara"), ClientMessage::Line { text: "/sayonara".to_string() });
+        assert_eq!(typed(""), ClientMessage::Line { text: String::new() });
+    }
+
+    #[test]
+    fn test_chat_text_is_cleaned_up() {
+        assert_eq!(chat_text(" gg\u{1b}[2J "), Some("gg[2J".to_string()));
+        assert_eq!(chat_text(&"x".repeat(MAX_CHAT_LEN + 10)).map(|text| text.len()), Some(MAX_CHAT_LEN));
+        assert_eq!(chat_text(" \n "), None);
+    }
+
+    #[test]
+    fn test_player_name_is_cleaned_up() {
+        assert_eq!(player_name("Bob\u{1b}]0;owned\u{7}"), Some("Bob]0;owned".to_string()));
+        assert_eq!(player_name(&"x".repeat(64 * 1024)).map(|name| name.len()), Some(MAX_NAME_LEN));
+        assert_eq!(player_name("\u{1b}\r\n"), None);
+    }
+
+    #[test]
+    fn test_accept_tells_websocket_from_lines() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            for _ in 0..2 {
+                let peer = Peer::accept(listener.accept().unwrap().0).unwrap();
+                let message: ClientMessage = peer.receive().unwrap().unwrap();
+                peer.send(&ServerMessage::Text { text: format!("{:?}", message) }).unwrap();
+                peer.close();
+            }
+        });
+
+        for address in [address.to_string(), format!("ws://{}/", address)] {
+            let peer = Peer::connect(&address).unwrap();
+            peer.send(&ClientMessage::Join { name: "Alice".to_string() }).unwrap();
+            let reply = ServerMessage::Text { text: "Join { name: \"Alice\" }".to_string() };
+            assert_eq!(peer.receive().unwrap(), Some(reply));
+            assert_eq!(peer.receive::<ServerMessage>().unwrap(), None);
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_accept_gives_up_on_a_silent_connection() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let silent = TcpStream::connect(address).unwrap();
+        let error = Peer::accept_within(listener.accept().unwrap().0, Duration::from_millis(50)).unwrap_err();
+        assert!(matches!(error, GameError::NetworkError(_)));
+
+        // A WebSocket handshake left half done times out as well
+        let mut stalled = TcpStream::connect(address).unwrap();
+        stalled.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n").unwrap();
+        let error = Peer::accept_within(listener.accept().unwrap().0, Duration::from_millis(50)).unwrap_err();
+        assert!(matches!(error, GameError::NetworkError(_)));
+        drop((silent, stalled));
+    }
+}
@@ -0,0 +1,150 @@
+//! Observer module for following a match from a program embedding the game.
+//!
+//! An observer registered on a `Game` with `Game::with_observer` is told when
+//! a turn starts, when the counter ticks, when a target is scored and when the
+//! match ends, so that a GUI can draw the match its own way while the game
+//! runs the rules. Observers only watch: to change the rules, register a
+//! plugin instead, see `plugins.rs`.
+
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::counter::{CounterEvent, CounterEvents};
+use crate::error::GameResult;
+use crate::runtime;
+use crate::state::GameState;
+
+/// Callbacks for what happens in a match.
+///
+/// Every callback does nothing unless the observer overrides it. The counter
+/// ticks are reported from a worker thread, while the counter runs, so an
+/// observer shares its state behind a lock or sends it over a channel.
+pub trait Observer: Send + Sync {
+    /// Called when a player's turn starts, before the counter runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - The current round
+    /// * `player_idx` - The index of the player taking the turn
+    /// * `targets` - The targets of the turn, in whole units
+    fn on_turn_start(&self, _round: u32, _player_idx: usize, _targets: &[u32]) {}
+
+    /// Called every time the running counter moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The counter value, in ticks
+    fn on_counter_tick(&self, _value: u32) {}
+
+    /// Called when a stopped target is scored.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player who stopped the counter
+    /// * `target` - The target, in ticks
+    /// * `value` - Where the counter was stopped, in ticks
+    /// * `score` - The score of the target
+    fn on_score(&self, _player_idx: usize, _target: u32, _value: u32, _score: u32) {}
+
+    /// Called once the match is over.
+    ///
+    /// # Arguments
+    ///
+    /// * `winner_idx` - The index of the winner
+    /// * `state` - The state the match ended in
+    fn on_game_over(&self, _winner_idx: usize, _state: &GameState) {}
+}
+
+/// A shared observer, so the program registering it can keep a handle on it.
+impl<T: Observer + ?Sized> Observer for Arc<T> {
+    fn on_turn_start(&self, round: u32, player_idx: usize, targets: &[u32]) {
+        (**self).on_turn_start(round, player_idx, targets);
+    }
+
+    fn on_counter_tick(&self, value: u32) {
+        (**self).on_counter_tick(value);
+    }
+
+    fn on_score(&self, player_idx: usize, target: u32, value: u32, score: u32) {
+        (**self).on_score(player_idx, target, value, score);
+    }
+
+    fn on_game_over(&self, winner_idx: usize, state: &GameState) {
+        (**self).on_game_over(winner_idx, state);
+    }
+}
+
+/// Reports the ticks of a counter to observers on their way to the display.
+///
+/// The observers are called before the display sees each tick, so every tick
+/// is reported by the time the display ends.
+///
+/// # Arguments
+///
+/// * `events` - The counter's events
+/// * `observers` - The observers to report the ticks to
+///
+/// # Returns
+///
+/// Result containing the events for the display, or an error if the runtime could not be started
+pub fn relay_ticks(mut events: CounterEvents, observers: Vec<Arc<dyn Observer>>) -> GameResult<CounterEvents> {
+    let (relay, receiver) = mpsc::unbounded_channel();
+    runtime::get()?.spawn(async move {
+        while let Some(event) = events.recv().await {
+            if let CounterEvent::Tick(value) = event {
+                for observer in &observers {
+                    observer.on_counter_tick(value);
+                }
+            }
+            if relay.send(event).is_err() || matches!(event, CounterEvent::Stopped(..)) {
+                break;
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Keeps every tick it is told about.
+    #[derive(Default)]
+    struct Ticks(Mutex<Vec<u32>>);
+
+    impl Observer for Ticks {
+        fn on_counter_tick(&self, value: u32) {
+            self.0.lock().unwrap().push(value);
+        }
+    }
+
+    #[test]
+    fn test_ticks_are_reported_before_the_display_sees_them() {
+        let ticks = Arc::new(Ticks::default());
+        let (counter, events) = mpsc::unbounded_channel();
+        let mut display = relay_ticks(events, vec![ticks.clone()]).unwrap();
+        for event in [CounterEvent::Tick(1), CounterEvent::Wrapped, CounterEvent::Tick(0), CounterEvent::Stopped(0, 1)] {
+            counter.send(event).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        while let Some(event) = display.blocking_recv() {
+            seen.push(event);
+        }
+        assert_eq!(seen.len(), 4);
+        assert_eq!(*ticks.0.lock().unwrap(), [1, 0]);
+    }
+
+    #[test]
+    fn test_relay_ends_with_the_counter() {
+        let (counter, events) = mpsc::unbounded_channel();
+        let mut display = relay_ticks(events, Vec::new()).unwrap();
+        counter.send(CounterEvent::Tick(3)).unwrap();
+        drop(counter);
+
+        assert_eq!(display.blocking_recv(), Some(CounterEvent::Tick(3)));
+        assert_eq!(display.blocking_recv(), None);
+    }
+}
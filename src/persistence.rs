@@ -0,0 +1,423 @@
+//! Persistence module for saving and resuming matches.
+//!
+//! Matches are stored as JSON so that they can be inspected by hand, after a
+//! header line with the version of the save format. Since
+//! they can be edited by hand too, every file the game reads back is bounded
+//! in size and checked before use.
+//!
+//! The match in progress is also autosaved at the end of each round, in turn
+//! to one of a few slots, so that a crash while writing one leaves the others
+//! intact. A marker file is touched whenever a match ends: only autosaves
+//! newer than it belong to an unfinished match.
+
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::{GameError, GameResult};
+use crate::state::GameState;
+use crate::version::SAVE_FORMAT;
+
+/// File a match in progress is saved to when the game is interrupted.
+pub const DEFAULT_SAVE_PATH: &str = "rust_game_save.json";
+
+/// Directory of the player's data the autosaves are kept in, under their data directory.
+const DATA_DIR_NAME: &str = "rust_game";
+
+/// Number of autosave slots written in turn.
+pub const AUTOSAVE_SLOTS: u32 = 3;
+
+/// File touched whenever a match ends.
+const COMPLETED_MARKER: &str = "rust_game_completed";
+
+/// Largest file the game reads back, in bytes.
+pub const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Reads a text file, refusing files larger than `MAX_FILE_SIZE`.
+///
+/// # Arguments
+///
+/// * `path` - The file to read
+///
+/// # Returns
+///
+/// Result containing the contents, or `GameError::ParseError` if the file is too large
+pub fn read_file(path: &Path) -> GameResult<String> {
+    let mut contents = String::new();
+    File::open(path)?.take(MAX_FILE_SIZE + 1).read_to_string(&mut contents)?;
+    if contents.len() as u64 > MAX_FILE_SIZE {
+        return Err(GameError::ParseError(format!("{} is larger than {} bytes", path.display(), MAX_FILE_SIZE)));
+    }
+    Ok(contents)
+}
+
+/// Saves the state of a match to a file.
+///
+/// # Arguments
+///
+/// * `path` - The file to write, replaced if it already exists
+/// * `state` - The state of the match
+///
+/// # Returns
+///
+/// Result indicating whether the match was saved
+pub fn save_game(path: &Path, state: &GameState) -> GameResult<()> {
+    let json = serde_json::to_string_pretty(state).map_err(io::Error::from)?;
+    fs::write(path, SAVE_FORMAT.write(&json))?;
+    Ok(())
+}
+
+/// Loads the state of a match from a file.
+///
+/// # Arguments
+///
+/// * `path` - The file to read
+///
+/// # Returns
+///
+/// Result containing the saved state of the match, or `GameError::SaveCorrupted` if it is not valid
+pub fn load_game(path: &Path) -> GameResult<GameState> {
+    read_file(path).and_then(|json| parse_game(&json)).map_err(|e| e.in_save(path))
+}
+
+/// Parses the state of a saved match.
+///
+/// # Arguments
+///
+/// * `contents` - The contents of the save file
+///
+/// # Returns
+///
+/// Result containing the state, `GameError::IncompatibleVersion` if it was saved in another
+/// version of the format, or `GameError::ParseError` if it is not a valid match
+pub fn parse_game(contents: &str) -> GameResult<GameState> {
+    let state: GameState = serde_json::from_str(SAVE_FORMAT.read(contents)?)?;
+    state.validate()?;
+    Ok(state)
+}
+
+/// Returns the directory the autosaves and the completed match marker are kept in.
+///
+/// It is the game's directory in the player's data directory, so that the
+/// autosaves do not end up wherever the game was launched from.
+pub fn autosave_dir() -> PathBuf {
+    data_dir(|name| std::env::var_os(name))
+}
+
+/// Works out the game's data directory from the environment.
+///
+/// # Arguments
+///
+/// * `var` - Looks up an environment variable
+///
+/// # Returns
+///
+/// `$XDG_DATA_HOME/rust_game` or `~/.local/share/rust_game` on Linux and the BSDs,
+/// `~/Library/Application Support/rust_game` on macOS, `%LOCALAPPDATA%\rust_game` on
+/// Windows, and a directory in the temporary directory if none of these is set
+fn data_dir(var: impl Fn(&str) -> Option<OsString>) -> PathBuf {
+    let set = |name: &str| var(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        set("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        set("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        set("XDG_DATA_HOME").or_else(|| set("HOME").map(|home| home.join(".local").join("share")))
+    };
+    base.unwrap_or_else(std::env::temp_dir).join(DATA_DIR_NAME)
+}
+
+/// Returns the file of an autosave slot.
+fn autosave_path(dir: &Path, slot: u32) -> PathBuf {
+    dir.join(format!("rust_game_autosave_{}.json", slot))
+}
+
+/// Returns when a file was last written, or None if it does not exist.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Autosaves the state of a match, to the slot of its current round.
+///
+/// # Arguments
+///
+/// * `dir` - The directory the autosaves are kept in
+/// * `state` - The state of the match
+///
+/// # Returns
+///
+/// Result indicating whether the match was saved
+pub fn autosave(dir: &Path, state: &GameState) -> GameResult<()> {
+    fs::create_dir_all(dir)?;
+    save_game(&autosave_path(dir, state.round % AUTOSAVE_SLOTS), state)
+}
+
+/// Records that a match ended, so that its autosaves are no longer offered.
+///
+/// # Arguments
+///
+/// * `dir` - The directory the autosaves are kept in
+///
+/// # Returns
+///
+/// Result indicating whether the marker was written
+pub fn mark_match_completed(dir: &Path) -> GameResult<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(COMPLETED_MARKER), "")?;
+    Ok(())
+}
+
+/// Finds the latest autosave of a match that did not end.
+///
+/// # Arguments
+///
+/// * `dir` - The directory the autosaves are kept in
+///
+/// # Returns
+///
+/// The newest autosave written after the last completed match, if any
+pub fn recoverable_autosave(dir: &Path) -> Option<PathBuf> {
+    let completed = modified(&dir.join(COMPLETED_MARKER));
+    (0..AUTOSAVE_SLOTS)
+        .map(|slot| autosave_path(dir, slot))
+        .filter_map(|path| modified(&path).map(|time| (time, path)))
+        .filter(|(time, _)| completed.is_none_or(|completed| *time > completed))
+        .max_by_key(|(time, _)| *time)
+        .map(|(_, path)| path)
+}
+
+/// Deletes every autosave, once the player declined to recover them.
+///
+/// # Arguments
+///
+/// * `dir` - The directory the autosaves are kept in
+pub fn clear_autosaves(dir: &Path) {
+    for slot in 0..AUTOSAVE_SLOTS {
+        let _ = fs::remove_file(autosave_path(dir, slot));
+    }
+}
+
+/// Helpers for the tests of the modules that keep files.
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::path::PathBuf;
+
+    /// Returns a path in the temporary directory, unique to the test process.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - What the file is for, unique among the tests
+    pub(crate) fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rust_game_{}_{}.json", name, std::process::id()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::testing::temp_path;
+    use crate::history::{RoundRecord, Snapshot};
+    use crate::player::{Attribute, Player};
+    use crate::state::{RoundOutcome, TurnOutcome};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust_game_autosave_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn state_at_round(round: u32) -> GameState {
+        let mut state = GameState::new([
+            Player::new("Alice".to_string(), 100, 60, 70, 3),
+            Player::new("Bob".to_string(), 100, 60, 70, 3),
+        ]);
+        state.round = round;
+        state
+    }
+
+    /// Leaves enough time between two writes for their timestamps to differ.
+    fn tick() {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let mut state = GameState::new([
+            Player::new("Alice".to_string(), 100, 60, 70, 3),
+            Player::new("Bob".to_string(), 100, 60, 70, 3),
+        ]);
+        state.round = 4;
+        state.players[1].decrease_vitality(30);
+
+        let path = temp_path("round_trip");
+        save_game(&path, &state).unwrap();
+        let loaded = load_game(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded, state);
+    }
+
+    #[test]
+    fn test_saved_match_keeps_its_history() {
+        let mut state = state_at_round(3);
+        let before = state.hash();
+        for round in 1..3 {
+            state.history.record(RoundRecord {
+                round,
+                turns: [TurnOutcome::Played(80), TurnOutcome::Played(50)],
+                outcome: RoundOutcome::Win { winner: 0, loser: 1, diff: 30, damage: 30, pot: 0 },
+                penalty: Some((Attribute::Speed, 5)),
+                players: state.players.each_ref().map(Snapshot::of),
+            });
+        }
+
+        let path = temp_path("history");
+        save_game(&path, &state).unwrap();
+        let loaded = load_game(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.history.rounds().len(), 2);
+        assert_eq!(loaded, state);
+        // The history is left out of the hash, which follows the course of the match
+        assert_eq!(state.hash(), before);
+        // A history ahead of the match could not have been played
+        state.round = 2;
+        assert!(matches!(parse_game(&serde_json::to_string(&state).unwrap()), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_save_from_before_momentum_loads() {
+        let player = |name: &str| format!(
+            r#"{{"name":"{}","vitality":80,"max_vitality":100,"speed":60,"max_speed":60,"strength":70,"max_strength":70,"stamina":3,"max_stamina":3}}"#,
+            name);
+        let json = format!(r#"{{"players":[{},{}],"round":3,"game_over":false,"winner_idx":null}}"#, player("Alice"), player("Bob"));
+        let state = parse_game(&json).unwrap();
+
+        assert_eq!(state.round, 3);
+        assert_eq!(state.players[0].vitality(), 80);
+        assert_eq!(state.players.iter().map(Player::momentum).collect::<Vec<_>>(), [0, 0]);
+    }
+
+    #[test]
+    fn test_save_starts_with_the_format_header() {
+        let state = state_at_round(1);
+        let path = temp_path("header");
+        save_game(&path, &state).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, contents.replace("RUSTGAME-SAVE 1.", "RUSTGAME-SAVE 2.")).unwrap();
+        let result = load_game(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(contents.starts_with("RUSTGAME-SAVE 1.1.0\n{"));
+        assert!(matches!(result, Err(GameError::IncompatibleVersion { .. })));
+        // Saves written before the header are still read
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(parse_game(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let result = load_game(&temp_path("missing"));
+        assert!(matches!(result, Err(GameError::IoError(_))));
+    }
+
+    #[test]
+    fn test_load_corrupted_file() {
+        let path = temp_path("corrupted");
+        fs::write(&path, "{ not json").unwrap();
+        let result = load_game(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(matches!(result, Err(GameError::SaveCorrupted { .. })));
+    }
+
+    #[test]
+    fn test_parse_game_rejects_impossible_states() {
+        let mut state = GameState::new([
+            Player::new("Alice".to_string(), 100, 60, 70, 3),
+            Player::new("Bob".to_string(), 100, 60, 70, 3),
+        ]);
+        state.winner_idx = Some(2);
+        let json = serde_json::to_string(&state).unwrap();
+
+        assert!(matches!(parse_game(&json), Err(GameError::ParseError(_))));
+        assert!(matches!(parse_game(&json.replace("\"round\":1", "\"round\":0")), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_read_file_rejects_large_files() {
+        let path = temp_path("large");
+        fs::write(&path, vec![b' '; MAX_FILE_SIZE as usize + 1]).unwrap();
+        let result = read_file(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(matches!(result, Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_autosave_rotates_slots() {
+        let dir = temp_dir("rotates");
+        for round in 1..=AUTOSAVE_SLOTS + 1 {
+            autosave(&dir, &state_at_round(round)).unwrap();
+            tick();
+        }
+        let slots = (0..AUTOSAVE_SLOTS).filter(|&slot| autosave_path(&dir, slot).exists()).count();
+        let latest = recoverable_autosave(&dir).map(|path| load_game(&path).unwrap());
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(slots, AUTOSAVE_SLOTS as usize);
+        assert_eq!(latest, Some(state_at_round(AUTOSAVE_SLOTS + 1)));
+    }
+
+    #[test]
+    fn test_completed_match_hides_older_autosaves() {
+        let dir = temp_dir("completed");
+        autosave(&dir, &state_at_round(2)).unwrap();
+        tick();
+        mark_match_completed(&dir).unwrap();
+        let after_completion = recoverable_autosave(&dir);
+        tick();
+        autosave(&dir, &state_at_round(3)).unwrap();
+        let after_new_match = recoverable_autosave(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(after_completion, None);
+        assert_eq!(after_new_match, Some(autosave_path(&dir, 0)));
+    }
+
+    #[test]
+    fn test_clear_autosaves() {
+        let dir = temp_dir("clear");
+        autosave(&dir, &state_at_round(1)).unwrap();
+        clear_autosaves(&dir);
+        let recovered = recoverable_autosave(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(recovered, None);
+    }
+
+    #[test]
+    fn test_autosave_creates_its_directory() {
+        let dir = temp_dir("created").join("nested");
+        autosave(&dir, &state_at_round(1)).unwrap();
+        let recovered = recoverable_autosave(&dir);
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+
+        assert_eq!(recovered, Some(autosave_path(&dir, 1)));
+    }
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn test_data_dir_follows_xdg() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| OsString::from(value))
+        };
+
+        assert_eq!(data_dir(env(&[("XDG_DATA_HOME", "/data"), ("HOME", "/home/ada")])), PathBuf::from("/data/rust_game"));
+        assert_eq!(data_dir(env(&[("XDG_DATA_HOME", ""), ("HOME", "/home/ada")])),
+                   PathBuf::from("/home/ada/.local/share/rust_game"));
+        assert_eq!(data_dir(env(&[])), std::env::temp_dir().join("rust_game"));
+    }
+}
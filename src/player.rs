@@ -3,17 +3,63 @@
 //! This module defines the Player struct and its associated methods for
 //! managing player characteristics during gameplay.
 
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GameError, GameResult};
+use crate::rules::AttributeBounds;
+
+/// The player attributes that penalties and effects can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Attribute {
+    /// Counter speed
+    Speed,
+    /// Scoring power
+    Strength,
+}
+
+/// The bonuses the loser of a round can pick as a consolation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Consolation {
+    /// Counter speed, up to the starting speed
+    Speed,
+    /// Scoring power, up to the starting strength
+    Strength,
+    /// Health points, up to the starting vitality
+    Vitality,
+}
+
+/// Highest momentum a player can build up.
+pub const MAX_MOMENTUM: u32 = 5;
+
 /// Represents a player in the game with their characteristics.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
     /// The player's name
     name: String,
     /// The player's health points
     vitality: u32,
+    /// The player's health points at full health
+    max_vitality: u32,
     /// The player's movement speed (affects counter speed)
     speed: u32,
+    /// The highest speed the player can reach
+    max_speed: u32,
     /// The player's power (affects scoring)
     strength: u32,
+    /// The highest strength the player can reach
+    max_strength: u32,
+    /// The player's reserve for risky actions
+    stamina: u32,
+    /// The player's stamina when fully rested
+    max_stamina: u32,
+    /// Built up by winning rounds, each point slows the counter down by 1 ms per tick
+    #[serde(default)]
+    momentum: u32,
+    /// The bounds of the attributes, set again from the rules when a match starts or resumes
+    #[serde(skip)]
+    bounds: AttributeBounds,
 }
 
 impl Player {
@@ -25,19 +71,79 @@ impl Player {
     /// * `vitality` - The initial vitality of the player
     /// * `speed` - The initial speed of the player
     /// * `strength` - The initial strength of the player
+    /// * `stamina` - The initial stamina of the player
     ///
     /// # Returns
     ///
     /// A new Player instance
-    pub fn new(name: String, vitality: u32, speed: u32, strength: u32) -> Self {
+    pub fn new(name: String, vitality: u32, speed: u32, strength: u32, stamina: u32) -> Self {
         Self {
             name,
             vitality,
+            max_vitality: vitality,
             speed,
+            max_speed: speed,
             strength,
+            max_strength: strength,
+            stamina,
+            max_stamina: stamina,
+            momentum: 0,
+            bounds: AttributeBounds::default(),
+        }
+    }
+    
+    /// Keeps the player's attributes within the bounds of the rules.
+    ///
+    /// The current and highest values of each attribute are brought within its bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds of the rules the match is played with
+    ///
+    /// # Returns
+    ///
+    /// The player, with their attributes within the bounds
+    pub fn with_bounds(self, bounds: AttributeBounds) -> Self {
+        Self {
+            vitality: bounds.vitality.clamp(self.vitality),
+            max_vitality: bounds.vitality.clamp(self.max_vitality),
+            speed: bounds.speed.clamp(self.speed),
+            max_speed: bounds.speed.clamp(self.max_speed),
+            strength: bounds.strength.clamp(self.strength),
+            max_strength: bounds.strength.clamp(self.max_strength),
+            stamina: bounds.stamina.clamp(self.stamina),
+            max_stamina: bounds.stamina.clamp(self.max_stamina),
+            bounds,
+            ..self
         }
     }
     
+    /// Checks that the attributes are within their bounds, e.g. after loading a save.
+    ///
+    /// # Returns
+    ///
+    /// Result with `GameError::ParseError` naming the first attribute out of bounds
+    pub fn validate(&self) -> GameResult<()> {
+        let out_of_bounds = |attribute: &str| Err(GameError::ParseError(format!("{} of {} is out of bounds", attribute, self.name)));
+        let bounds = self.bounds;
+        if self.vitality > self.max_vitality || !bounds.vitality.contains(self.vitality) {
+            return out_of_bounds("Vitality");
+        }
+        if self.speed > self.max_speed || !bounds.speed.contains(self.speed) {
+            return out_of_bounds("Speed");
+        }
+        if self.strength > self.max_strength || !bounds.strength.contains(self.strength) {
+            return out_of_bounds("Strength");
+        }
+        if self.stamina > self.max_stamina || !bounds.stamina.contains(self.stamina) {
+            return out_of_bounds("Stamina");
+        }
+        if self.momentum > MAX_MOMENTUM {
+            return out_of_bounds("Momentum");
+        }
+        Ok(())
+    }
+    
     /// Returns the player's name.
     ///
     /// # Returns
@@ -56,6 +162,15 @@ impl Player {
         self.vitality
     }
     
+    /// Returns the player's maximum vitality.
+    ///
+    /// # Returns
+    ///
+    /// The player's vitality at full health
+    pub fn max_vitality(&self) -> u32 {
+        self.max_vitality
+    }
+    
     /// Returns the player's current speed.
     ///
     /// # Returns
@@ -65,6 +180,15 @@ impl Player {
         self.speed
     }
     
+    /// Returns the player's maximum speed.
+    ///
+    /// # Returns
+    ///
+    /// The highest speed the player can reach
+    pub fn max_speed(&self) -> u32 {
+        self.max_speed
+    }
+    
     /// Returns the player's current strength.
     ///
     /// # Returns
@@ -74,55 +198,157 @@ impl Player {
         self.strength
     }
     
+    /// Returns the player's maximum strength.
+    ///
+    /// # Returns
+    ///
+    /// The highest strength the player can reach
+    pub fn max_strength(&self) -> u32 {
+        self.max_strength
+    }
+    
+    /// Returns the player's current stamina.
+    ///
+    /// # Returns
+    ///
+    /// The player's stamina value
+    pub fn stamina(&self) -> u32 {
+        self.stamina
+    }
+    
+    /// Returns the player's maximum stamina.
+    ///
+    /// # Returns
+    ///
+    /// The player's stamina when fully rested
+    pub fn max_stamina(&self) -> u32 {
+        self.max_stamina
+    }
+    
+    /// Returns the player's current momentum.
+    ///
+    /// # Returns
+    ///
+    /// The player's momentum value
+    pub fn momentum(&self) -> u32 {
+        self.momentum
+    }
+    
     /// Decreases the player's vitality by the given amount.
-    /// Vitality will not go below zero.
+    /// Vitality will not go below its floor, zero by default.
     ///
     /// # Arguments
     ///
     /// * `amount` - The amount to decrease
     pub fn decrease_vitality(&mut self, amount: u32) {
-        self.vitality = self.vitality.saturating_sub(amount);
+        self.vitality = self.vitality.saturating_sub(amount).max(self.bounds.vitality.min);
     }
     
     /// Decreases the player's speed by the given amount.
-    /// Speed will not go below zero.
+    /// Speed will not go below its floor, zero by default.
     ///
     /// # Arguments
     ///
     /// * `amount` - The amount to decrease
     pub fn decrease_speed(&mut self, amount: u32) {
-        self.speed = self.speed.saturating_sub(amount);
+        self.speed = self.speed.saturating_sub(amount).max(self.bounds.speed.min);
     }
     
     /// Decreases the player's strength by the given amount.
-    /// Strength will not go below zero.
+    /// Strength will not go below its floor, zero by default.
     ///
     /// # Arguments
     ///
     /// * `amount` - The amount to decrease
     pub fn decrease_strength(&mut self, amount: u32) {
-        self.strength = self.strength.saturating_sub(amount);
+        self.strength = self.strength.saturating_sub(amount).max(self.bounds.strength.min);
+    }
+    
+    /// Decreases the player's stamina by the given amount.
+    /// Stamina will not go below its floor, zero by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to decrease
+    pub fn decrease_stamina(&mut self, amount: u32) {
+        self.stamina = self.stamina.saturating_sub(amount).max(self.bounds.stamina.min);
+    }
+    
+    /// Increases the player's vitality by the given amount.
+    /// Vitality will not go above the maximum vitality.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to increase
+    pub fn increase_vitality(&mut self, amount: u32) {
+        self.vitality = self.vitality.saturating_add(amount).min(self.max_vitality);
+    }
+    
+    /// Increases the player's momentum by the given amount.
+    /// Momentum will not go above `MAX_MOMENTUM`.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to increase
+    pub fn increase_momentum(&mut self, amount: u32) {
+        self.momentum = self.momentum.saturating_add(amount).min(MAX_MOMENTUM);
+    }
+    
+    /// Removes all of the player's momentum.
+    ///
+    /// # Returns
+    ///
+    /// The momentum the player had
+    pub fn take_momentum(&mut self) -> u32 {
+        std::mem::take(&mut self.momentum)
+    }
+    
+    /// Increases the player's speed by the given amount.
+    /// Speed will not go above the maximum speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to increase
+    pub fn increase_speed(&mut self, amount: u32) {
+        self.speed = self.speed.saturating_add(amount).min(self.max_speed);
+    }
+    
+    /// Increases the player's strength by the given amount.
+    /// Strength will not go above the maximum strength.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to increase
+    pub fn increase_strength(&mut self, amount: u32) {
+        self.strength = self.strength.saturating_add(amount).min(self.max_strength);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::Bounds;
 
     #[test]
     fn test_new_player() {
-        let player = Player::new("TestPlayer".to_string(), 100, 60, 70);
+        let player = Player::new("TestPlayer".to_string(), 100, 60, 70, 3);
         assert_eq!(player.name(), "TestPlayer");
         assert_eq!(player.vitality(), 100);
+        assert_eq!(player.max_vitality(), 100);
         assert_eq!(player.speed(), 60);
+        assert_eq!(player.max_speed(), 60);
         assert_eq!(player.strength(), 70);
+        assert_eq!(player.max_strength(), 70);
+        assert_eq!(player.stamina(), 3);
+        assert_eq!(player.max_stamina(), 3);
     }
 
     #[test]
     fn test_decrease_vitality() {
-        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50);
+        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50, 3);
         player.decrease_vitality(30);
         assert_eq!(player.vitality(), 70);
+        assert_eq!(player.max_vitality(), 100);
         
         // Test that vitality doesn't go below 0
         player.decrease_vitality(100);
@@ -131,7 +357,7 @@ mod tests {
 
     #[test]
     fn test_decrease_speed() {
-        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50);
+        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50, 3);
         player.decrease_speed(20);
         assert_eq!(player.speed(), 30);
         
@@ -142,7 +368,7 @@ mod tests {
 
     #[test]
     fn test_decrease_strength() {
-        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50);
+        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50, 3);
         player.decrease_strength(10);
         assert_eq!(player.strength(), 40);
         
@@ -151,9 +377,101 @@ mod tests {
         assert_eq!(player.strength(), 0);
     }
     
+    #[test]
+    fn test_decrease_stamina() {
+        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50, 3);
+        player.decrease_stamina(1);
+        assert_eq!(player.stamina(), 2);
+        assert_eq!(player.max_stamina(), 3);
+        
+        // Test that stamina doesn't go below 0
+        player.decrease_stamina(5);
+        assert_eq!(player.stamina(), 0);
+    }
+
+    #[test]
+    fn test_increase_vitality() {
+        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50, 3);
+        player.decrease_vitality(30);
+        player.increase_vitality(10);
+        assert_eq!(player.vitality(), 80);
+        
+        // Test that vitality doesn't go above the maximum
+        player.increase_vitality(50);
+        assert_eq!(player.vitality(), 100);
+    }
+
+    #[test]
+    fn test_increase_speed_and_strength() {
+        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 40, 3);
+        player.decrease_speed(20);
+        player.decrease_strength(20);
+        player.increase_speed(5);
+        player.increase_strength(5);
+        assert_eq!(player.speed(), 35);
+        assert_eq!(player.strength(), 25);
+        
+        // Test that neither goes above its maximum
+        player.increase_speed(100);
+        player.increase_strength(u32::MAX);
+        assert_eq!(player.speed(), 50);
+        assert_eq!(player.strength(), 40);
+    }
+    
+    #[test]
+    fn test_momentum() {
+        let mut player = Player::new("TestPlayer".to_string(), 100, 50, 50, 3);
+        assert_eq!(player.momentum(), 0);
+        
+        player.increase_momentum(2);
+        assert_eq!(player.momentum(), 2);
+        
+        // Test that momentum doesn't go above the maximum
+        player.increase_momentum(10);
+        assert_eq!(player.momentum(), MAX_MOMENTUM);
+        
+        assert_eq!(player.take_momentum(), MAX_MOMENTUM);
+        assert_eq!(player.momentum(), 0);
+    }
+    
+    #[test]
+    fn test_validate() {
+        let mut player = Player::new("TestPlayer".to_string(), 100, 60, 70, 3);
+        player.decrease_vitality(40);
+        assert!(player.validate().is_ok());
+        
+        player.vitality = 101;
+        assert!(matches!(player.validate(), Err(GameError::ParseError(_))));
+        player.vitality = 100;
+        player.momentum = MAX_MOMENTUM + 1;
+        assert!(player.validate().is_err());
+    }
+    
+    #[test]
+    fn test_bounds() {
+        let bounds = AttributeBounds {
+            speed: Bounds { min: 40, max: Some(55) },
+            strength: Bounds { min: 0, max: Some(60) },
+            ..AttributeBounds::default()
+        };
+        let mut player = Player::new("TestPlayer".to_string(), 100, 60, 70, 3).with_bounds(bounds);
+        assert_eq!(player.speed(), 55);
+        assert_eq!(player.max_strength(), 60);
+        
+        player.decrease_speed(10);
+        player.decrease_speed(10);
+        assert_eq!(player.speed(), 40);
+        player.increase_speed(30);
+        assert_eq!(player.speed(), 55);
+        assert!(player.validate().is_ok());
+        
+        player.speed = 30;
+        assert!(player.validate().is_err());
+    }
+    
     #[test]
     fn test_player_clone() {
-        let player1 = Player::new("TestPlayer".to_string(), 100, 60, 70);
+        let player1 = Player::new("TestPlayer".to_string(), 100, 60, 70, 3);
         let player2 = player1.clone();
         
         assert_eq!(player1.name(), player2.name());
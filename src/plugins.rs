@@ -0,0 +1,301 @@
+//! Plugins module for composing variants of the match.
+//!
+//! A plugin hooks into the match wherever a variant needs to: the targets of
+//! a turn, the score of a stop, the damage of a lost round, the resolution
+//! of the round, the penalties, and when a player loses or wins. The
+//! built-in mutators of `mutators.rs`, the win conditions of `rules.rs` and
+//! the Lua scripts of `scripting.rs` are plugins, and programs embedding the
+//! game can register their own on a `Game` with `Game::with_plugin`.
+//!
+//! Plugins apply in the order they were registered, each one seeing what the
+//! previous ones did.
+
+use std::fmt;
+use std::ops::Deref;
+
+use crate::scoring::ScoreBreakdown;
+use crate::state::{GameState, RoundOutcome, Tally};
+
+/// A rule change that hooks into the match.
+///
+/// Every hook leaves the match unchanged unless the plugin overrides it.
+pub trait GamePlugin: fmt::Debug + Send + Sync {
+    /// Returns the name of the plugin, shown when it decides the match.
+    fn name(&self) -> &'static str;
+
+    /// Takes the start of a round into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - The round starting
+    ///
+    /// # Returns
+    ///
+    /// A message to show the players, if any
+    fn round_start(&self, _round: u32) -> Option<String> {
+        None
+    }
+
+    /// Adjusts the targets drawn for a turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - The targets, in whole units, to change, add to or remove from
+    /// * `counter_max` - The highest counter value, which no target may exceed
+    fn targets(&self, _targets: &mut Vec<u32>, _counter_max: u32) {}
+
+    /// Adjusts how a stopped target is scored, before its total is worked out.
+    ///
+    /// # Arguments
+    ///
+    /// * `breakdown` - The parts of the score
+    fn score(&self, _breakdown: &mut ScoreBreakdown) {}
+
+    /// Adjusts the vitality the loser of a round loses.
+    ///
+    /// # Arguments
+    ///
+    /// * `damage` - The damage dealt by the score difference, without wagers
+    ///
+    /// # Returns
+    ///
+    /// The damage to deal
+    fn damage(&self, damage: u32) -> u32 {
+        damage
+    }
+
+    /// Adjusts the state once a round is resolved, e.g. to heal or reward a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state after the round
+    /// * `outcome` - What the round did to the players
+    fn resolve_round(&self, _state: &mut GameState, _outcome: &RoundOutcome) {}
+
+    /// Adjusts the attribute points a penalty takes.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The points the penalty would take
+    ///
+    /// # Returns
+    ///
+    /// The points to take
+    fn penalty(&self, amount: u32) -> u32 {
+        amount
+    }
+
+    /// Tells whether a player lost the match, on top of running out of vitality.
+    ///
+    /// # Arguments
+    ///
+    /// * `tally` - What both players did so far in the match
+    ///
+    /// # Returns
+    ///
+    /// The index of the player who lost, if any
+    fn loser(&self, _tally: &Tally) -> Option<usize> {
+        None
+    }
+
+    /// Tells whether a player won the match, on top of the opponent running out of vitality.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state at the end of a round, with the round not counted yet
+    ///
+    /// # Returns
+    ///
+    /// The index of the winner and the message announcing the win, if any
+    fn winner(&self, _state: &GameState) -> Option<(usize, String)> {
+        None
+    }
+
+    /// Returns a copy of the plugin, so the settings holding it can be cloned.
+    fn clone_box(&self) -> Box<dyn GamePlugin>;
+}
+
+impl Clone for Box<dyn GamePlugin> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// The plugins of a match, in the order they apply.
+#[derive(Debug, Clone, Default)]
+pub struct PluginRegistry {
+    /// The plugins registered
+    plugins: Vec<Box<dyn GamePlugin>>,
+}
+
+impl PluginRegistry {
+    /// Creates a registry holding some plugins.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugins` - The plugins, in the order they apply
+    ///
+    /// # Returns
+    ///
+    /// A new PluginRegistry
+    pub fn new(plugins: Vec<Box<dyn GamePlugin>>) -> Self {
+        Self { plugins }
+    }
+
+    /// Adds a plugin, applied after those already registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin` - The plugin to add
+    pub fn register(&mut self, plugin: Box<dyn GamePlugin>) {
+        self.plugins.push(plugin);
+    }
+
+    /// Collects the messages of the plugins at the start of a round.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - The round starting
+    ///
+    /// # Returns
+    ///
+    /// The messages to show, in the order of the plugins
+    pub fn round_start(&self, round: u32) -> Vec<String> {
+        self.plugins.iter().filter_map(|plugin| plugin.round_start(round)).collect()
+    }
+
+    /// Lets every plugin adjust the targets of a turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - The targets drawn, in whole units
+    /// * `counter_max` - The highest counter value
+    pub fn targets(&self, targets: &mut Vec<u32>, counter_max: u32) {
+        for plugin in &self.plugins {
+            plugin.targets(targets, counter_max);
+        }
+        // A plugin must not push a target out of the counter's reach
+        for target in targets.iter_mut() {
+            *target = (*target).min(counter_max);
+        }
+    }
+
+    /// Lets every plugin adjust the score of a stop.
+    ///
+    /// # Arguments
+    ///
+    /// * `breakdown` - The parts of the score
+    pub fn score(&self, breakdown: &mut ScoreBreakdown) {
+        for plugin in &self.plugins {
+            plugin.score(breakdown);
+        }
+    }
+
+    /// Returns the points a penalty takes once every plugin adjusted them.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The points the penalty would take
+    pub fn penalty(&self, amount: u32) -> u32 {
+        self.plugins.iter().fold(amount, |amount, plugin| plugin.penalty(amount))
+    }
+
+    /// Returns the first plugin deciding that a player lost the match.
+    ///
+    /// # Arguments
+    ///
+    /// * `tally` - What both players did so far in the match
+    ///
+    /// # Returns
+    ///
+    /// The index of the player who lost and the name of the plugin, if any
+    pub fn loser(&self, tally: &Tally) -> Option<(usize, &'static str)> {
+        self.plugins.iter().find_map(|plugin| plugin.loser(tally).map(|loser_idx| (loser_idx, plugin.name())))
+    }
+
+    /// Returns the first plugin deciding that a player won the match.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state at the end of a round, with the round not counted yet
+    ///
+    /// # Returns
+    ///
+    /// The index of the winner and the message announcing the win, if any
+    pub fn winner(&self, state: &GameState) -> Option<(usize, String)> {
+        self.plugins.iter().find_map(|plugin| plugin.winner(state))
+    }
+}
+
+impl Deref for PluginRegistry {
+    type Target = [Box<dyn GamePlugin>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.plugins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutators::{self, DoubleDamage, NoStrength};
+    use crate::player::Player;
+
+    /// Adds a target past the end of the counter, and always takes 7 points.
+    #[derive(Debug, Clone, Copy)]
+    struct Greedy;
+
+    impl GamePlugin for Greedy {
+        fn name(&self) -> &'static str {
+            "greedy"
+        }
+
+        fn targets(&self, targets: &mut Vec<u32>, counter_max: u32) {
+            targets.push(counter_max + 50);
+        }
+
+        fn penalty(&self, _amount: u32) -> u32 {
+            7
+        }
+
+        fn clone_box(&self) -> Box<dyn GamePlugin> {
+            Box::new(*self)
+        }
+    }
+
+    #[test]
+    fn test_plugins_apply_in_order() {
+        let mut registry = PluginRegistry::new(mutators::parse("double-damage").unwrap());
+        registry.register(Box::new(Greedy));
+
+        let mut targets = vec![10, 20];
+        registry.targets(&mut targets, 100);
+        assert_eq!(targets, [10, 20, 100]);
+        assert_eq!(registry.penalty(5), 7);
+        assert_eq!(registry.len(), 2);
+        assert!(registry.round_start(1).is_empty());
+    }
+
+    #[test]
+    fn test_default_hooks_change_nothing() {
+        let registry = PluginRegistry::new(vec![Box::new(NoStrength), Box::new(DoubleDamage)]);
+        let state = GameState::new(["Alice", "Bob"].map(|name| Player::new(name.to_string(), 50, 50, 50, 3)));
+        let mut after = state.clone();
+        registry[1].resolve_round(&mut after, &RoundOutcome::Draw);
+
+        assert_eq!(after, state);
+        assert_eq!(registry.penalty(5), 5);
+        assert_eq!(registry.loser(&Tally::default()), None);
+        assert_eq!(registry.winner(&state), None);
+    }
+
+    #[test]
+    fn test_empty_registry() {
+        let registry = PluginRegistry::default();
+        let mut targets = vec![42];
+        registry.targets(&mut targets, 100);
+
+        assert_eq!(targets, [42]);
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! Pool module for the targets both players share in a round.
+//!
+//! With a shared pool, the targets of a round are drawn once and dealt out
+//! one by one between the two players. A player who finished their turn may
+//! then steal one of the targets their opponent has not played yet: they play
+//! it for bonus points, and the opponent's turn is one target shorter. Only
+//! the first player of a round finds targets left to steal, which makes up
+//! for not knowing the score to beat.
+
+/// Share of a stolen target's score added to the thief's turn average, in percent.
+pub const STEAL_BONUS_PERCENT: u32 = 50;
+
+/// A target of the pool, and who it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PoolTarget {
+    /// The target, in whole units
+    target: u32,
+    /// Index of the player who plays it
+    owner: usize,
+    /// Whether it was played, or handed to its owner to play
+    played: bool,
+}
+
+/// The targets of a round, each owned by one of the players.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TargetPool {
+    /// The targets, in the order they were drawn
+    targets: Vec<PoolTarget>,
+}
+
+impl TargetPool {
+    /// Deals the targets of a round out, one to each player in turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - The targets drawn for the round
+    /// * `first` - The index of the player dealt the first target
+    ///
+    /// # Returns
+    ///
+    /// A new TargetPool, with no target played
+    pub fn deal(targets: Vec<u32>, first: usize) -> Self {
+        let targets = targets.into_iter().enumerate()
+            .map(|(idx, target)| PoolTarget { target, owner: (first + idx) % 2, played: false })
+            .collect();
+        Self { targets }
+    }
+
+    /// Hands a player the targets they own and have not played yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player
+    ///
+    /// # Returns
+    ///
+    /// The player's targets, marked as played so they cannot be stolen
+    pub fn take_turn(&mut self, player_idx: usize) -> Vec<u32> {
+        self.targets.iter_mut()
+            .filter(|pool_target| pool_target.owner == player_idx && !pool_target.played)
+            .map(|pool_target| {
+                pool_target.played = true;
+                pool_target.target
+            })
+            .collect()
+    }
+
+    /// Lists the targets a player may steal from their opponent.
+    ///
+    /// The opponent keeps at least one target for their own turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `thief` - The index of the player stealing
+    ///
+    /// # Returns
+    ///
+    /// The position in the pool and the value of each target that can be stolen
+    pub fn stealable(&self, thief: usize) -> Vec<(usize, u32)> {
+        let remaining: Vec<(usize, u32)> = self.targets.iter().enumerate()
+            .filter(|(_, pool_target)| pool_target.owner != thief && !pool_target.played)
+            .map(|(idx, pool_target)| (idx, pool_target.target))
+            .collect();
+        if remaining.len() < 2 {
+            return Vec::new();
+        }
+        remaining
+    }
+
+    /// Gives a target of the opponent to the thief, who plays it right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `idx` - The position of the target in the pool, from `stealable`
+    /// * `thief` - The index of the player stealing
+    ///
+    /// # Returns
+    ///
+    /// The stolen target
+    pub fn steal(&mut self, idx: usize, thief: usize) -> u32 {
+        let pool_target = &mut self.targets[idx];
+        pool_target.owner = thief;
+        pool_target.played = true;
+        pool_target.target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stolen_targets_change_hands() {
+        let mut pool = TargetPool::deal(vec![10, 20, 30, 40, 50, 60], 1);
+        assert_eq!(pool.take_turn(1), [10, 30, 50]);
+        // Nothing is left to play once the turn was handed out
+        assert!(pool.take_turn(1).is_empty());
+        assert!(pool.stealable(0).is_empty());
+
+        let stealable = pool.stealable(1);
+        assert_eq!(stealable, [(1, 20), (3, 40), (5, 60)]);
+        assert_eq!(pool.steal(3, 1), 40);
+        assert_eq!(pool.stealable(1), [(1, 20), (5, 60)]);
+        assert_eq!(pool.take_turn(0), [20, 60]);
+    }
+
+    #[test]
+    fn test_the_last_target_cannot_be_stolen() {
+        let mut pool = TargetPool::deal(vec![10, 20], 0);
+        pool.take_turn(0);
+        assert!(pool.stealable(0).is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+//! Practice module for the solo runs raced against a ghost.
+//!
+//! A run is a fixed number of targets drawn from a seed, which player 1 plays
+//! alone, one target per round. In practice each stop scores as in a match and
+//! the run is worth its total score. In time attack each stop is timed from the
+//! start of the counter, and a stop too far from its target adds a penalty, so
+//! the fastest run wins. The best run of each mode is recorded as a replay:
+//! with `--ghost`, a later run gets the same targets and sees where the
+//! recorded one stopped, next to its own counter.
+
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::error::{GameError, GameResult};
+use crate::game::Game;
+use crate::i18n::{Msg, tr};
+use crate::replay::Replay;
+
+/// Number of targets in a run.
+pub const RUN_TARGETS: usize = 10;
+
+/// Largest distance from the target, in whole units, that is not penalized in time attack.
+pub const TOLERANCE: u32 = 10;
+
+/// Time added in time attack for each stop further than `TOLERANCE` from its target, in milliseconds.
+pub const MISS_PENALTY_MS: u64 = 5000;
+
+/// The solo modes raced against a ghost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PracticeMode {
+    /// The run is worth the total score of its stops
+    Practice,
+    /// The run is worth the time its stops took, plus the penalties
+    TimeAttack,
+}
+
+impl PracticeMode {
+    /// Returns the file the best run is recorded in unless another one is given.
+    pub fn default_replay_path(self) -> &'static str {
+        match self {
+            PracticeMode::Practice => "rust_game_practice_ghost.json",
+            PracticeMode::TimeAttack => "rust_game_time_attack_ghost.json",
+        }
+    }
+
+    /// Tells whether a run did better than the best one so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - What the run achieved
+    /// * `best` - What the best run achieved
+    ///
+    /// # Returns
+    ///
+    /// True for a higher score in practice, or a shorter time in time attack
+    pub fn beats(self, result: u64, best: u64) -> bool {
+        match self {
+            PracticeMode::Practice => result > best,
+            PracticeMode::TimeAttack => result < best,
+        }
+    }
+
+    /// Returns the name of the mode, as shown to the player.
+    pub fn name(self) -> String {
+        match self {
+            PracticeMode::Practice => tr!(Msg::PracticeName),
+            PracticeMode::TimeAttack => tr!(Msg::TimeAttackName),
+        }
+    }
+
+    /// Describes what a run achieved.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The total score in practice, or the time in milliseconds in time attack
+    ///
+    /// # Returns
+    ///
+    /// The points or the seconds, as shown to the player
+    pub fn describe(self, result: u64) -> String {
+        match self {
+            PracticeMode::Practice => tr!(Msg::PracticePoints, result),
+            PracticeMode::TimeAttack => tr!(Msg::TimeAttackSeconds, seconds(result)),
+        }
+    }
+}
+
+/// Formats a duration in milliseconds as seconds, to a tenth.
+///
+/// # Arguments
+///
+/// * `ms` - The duration in milliseconds
+///
+/// # Returns
+///
+/// The seconds, such as `12.3`
+pub fn seconds(ms: u64) -> String {
+    format!("{:.1}", ms as f64 / 1000.0)
+}
+
+/// Plays one run and records it if it beats the best one.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, player 1 plays alone
+/// * `mode` - Practice or time attack
+/// * `replay_path` - The file the best run is recorded in
+/// * `race_ghost` - Whether to race the run recorded in `replay_path`
+///
+/// # Returns
+///
+/// Result indicating whether the run could be played and recorded
+pub fn play(config: &GameConfig, mode: PracticeMode, replay_path: &Path, race_ghost: bool) -> GameResult<()> {
+    let mut renderer = config.render_mode.renderer();
+    match mode {
+        PracticeMode::Practice => renderer.info_wrapped(&tr!(Msg::PracticeStart, RUN_TARGETS)),
+        PracticeMode::TimeAttack => renderer.info_wrapped(&tr!(Msg::TimeAttackStart,
+                                                               RUN_TARGETS, TOLERANCE, seconds(MISS_PENALTY_MS))),
+    }
+
+    let best = load_best(mode, replay_path)?;
+    let ghost = best.as_ref().filter(|_| race_ghost);
+    match (race_ghost, ghost) {
+        (true, Some(ghost)) => renderer.info_wrapped(&tr!(Msg::GhostRace, mode.describe(ghost.result))),
+        (true, None) => renderer.info_wrapped(&tr!(Msg::GhostMissing, replay_path.display())),
+        (false, _) => {},
+    }
+    // The ghost's seed brings back its targets, and a new run needs a known seed to become a ghost
+    let seed = ghost.map(|ghost| ghost.seed).or(config.seed).unwrap_or_else(rand::random);
+    // Both of these need a second player, and every round of a run has one target
+    let config = GameConfig {
+        duel: false,
+        reaction_game: false,
+        seed: Some(seed),
+        target_count: 1,
+        target_cap: None,
+        ..config.clone()
+    };
+
+    let name = config.player1_name.clone();
+    let run = Game::new(config).play_practice(mode, ghost)?;
+    renderer.info_wrapped(&tr!(Msg::PracticeOver, name, mode.describe(run.result)));
+
+    if best.is_none_or(|best| mode.beats(run.result, best.result)) {
+        run.save(replay_path)?;
+        renderer.info_wrapped(&tr!(Msg::GhostRecorded, replay_path.display()));
+    }
+    Ok(())
+}
+
+/// Loads the best run recorded so far.
+///
+/// # Arguments
+///
+/// * `mode` - The mode the run must have been played in
+/// * `path` - The file the run was recorded in
+///
+/// # Returns
+///
+/// Result containing the recorded run, None if no run was recorded yet, or
+/// `GameError::ConfigError` if the file holds a run of the other mode
+fn load_best(mode: PracticeMode, path: &Path) -> GameResult<Option<Replay>> {
+    match Replay::load(path) {
+        Ok(replay) if replay.mode != mode => Err(GameError::ConfigError(
+            format!("{} holds a {} run, not a {} run", path.display(), replay.mode.name(), mode.name()))),
+        Ok(replay) => Ok(Some(replay)),
+        Err(GameError::IoError(e)) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::testing::temp_path;
+
+    #[test]
+    fn test_better_runs() {
+        assert!(PracticeMode::Practice.beats(400, 380));
+        assert!(!PracticeMode::Practice.beats(380, 380));
+        assert!(PracticeMode::TimeAttack.beats(9_000, 12_500));
+        assert!(!PracticeMode::TimeAttack.beats(12_500, 9_000));
+    }
+
+    #[test]
+    fn test_describe_results() {
+        assert_eq!(PracticeMode::Practice.describe(380), "380 points");
+        assert_eq!(PracticeMode::TimeAttack.describe(12_345), "12.3 s");
+    }
+
+    #[test]
+    fn test_best_run_of_the_other_mode_is_refused() {
+        let path = temp_path("practice_other_mode");
+        let replay = Replay { seed: 1, mode: PracticeMode::TimeAttack, result: 9_000, stops: vec![5], hashes: vec![1] };
+        replay.save(&path).unwrap();
+        let practice = load_best(PracticeMode::Practice, &path);
+        let time_attack = load_best(PracticeMode::TimeAttack, &path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(practice, Err(GameError::ConfigError(_))));
+        assert_eq!(time_attack.unwrap(), Some(replay));
+        assert_eq!(load_best(PracticeMode::Practice, &temp_path("practice_missing")).unwrap(), None);
+    }
+}
@@ -0,0 +1,180 @@
+//! Presence module for showing the match in progress on the player's Discord profile.
+//!
+//! The round, both players' vitality and the opponent's name are worked out
+//! from the game events as they are recorded. When the `discord` feature is
+//! enabled and `--discord` gives the application id, they are published as
+//! Discord Rich Presence from a thread of their own, so a slow or missing
+//! Discord client never holds up the game.
+//!
+//! The presence goes through the `discord-rich-presence` crate rather than
+//! `discord-sdk`: only the activity is ever set, which the former does with a
+//! blocking IPC client and few dependencies, while the latter brings the whole
+//! SDK surface (relationships, lobbies, overlay) and its own event loop.
+
+use std::sync::mpsc::Sender;
+
+#[cfg(feature = "discord")]
+use std::{sync::mpsc, thread, time::{SystemTime, UNIX_EPOCH}};
+
+use crate::events::GameEvent;
+use crate::i18n::{Msg, tr};
+
+/// What the presence shows of a match, from the first player's point of view.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchPresence {
+    /// The current round, 0 before the first one starts
+    round: u32,
+    /// The name of each player, once their first turn started
+    names: [String; 2],
+    /// The vitality of each player, once their first turn started
+    vitality: [Option<u32>; 2],
+    /// The index of the winner, once the match is over
+    winner: Option<usize>,
+}
+
+impl MatchPresence {
+    /// Takes a game event into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event just recorded
+    ///
+    /// # Returns
+    ///
+    /// True if what the presence shows changed
+    pub fn update(&mut self, event: &GameEvent) -> bool {
+        let before = self.clone();
+        match event {
+            GameEvent::StateHash { round, .. } => self.round = *round,
+            GameEvent::TurnStart { round, player, name, vitality, .. } => {
+                self.round = *round;
+                self.names[*player].clone_from(name);
+                self.vitality[*player] = Some(*vitality);
+            },
+            GameEvent::VitalityChange { player, vitality, .. } | GameEvent::Heal { player, vitality, .. } => {
+                self.vitality[*player] = Some(*vitality);
+            },
+            GameEvent::GameOver { winner, .. } => self.winner = Some(*winner),
+            _ => {},
+        }
+        *self != before
+    }
+
+    /// Returns the first line of the presence: the round and the opponent, or the winner.
+    pub fn details(&self) -> String {
+        match self.winner {
+            Some(winner) => tr!(Msg::PresenceOver, self.names[winner]),
+            None if self.names[1].is_empty() => tr!(Msg::PresenceStarting),
+            None => tr!(Msg::PresenceRound, self.round, self.names[1]),
+        }
+    }
+
+    /// Returns the second line of the presence: the vitality of both players.
+    pub fn state(&self) -> String {
+        let vitality = |player: usize| self.vitality[player].map_or_else(|| "?".to_string(), |v| v.to_string());
+        tr!(Msg::PresenceVitality, vitality(0), vitality(1))
+    }
+}
+
+/// Starts publishing the presence of a match.
+///
+/// # Arguments
+///
+/// * `app_id` - The Discord application the presence is shown under
+///
+/// # Returns
+///
+/// The channel to send the game events to, or None if the presence cannot be published
+#[cfg(feature = "discord")]
+pub fn publish(app_id: &str) -> Option<Sender<GameEvent>> {
+    use discord_rich_presence::activity::{Activity, Timestamps};
+    use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+
+    let (sender, receiver) = mpsc::channel::<GameEvent>();
+    let mut client = DiscordIpcClient::new(app_id);
+    thread::spawn(move || {
+        if let Err(e) = client.connect() {
+            log::warn!("{}", tr!(Msg::PresenceUnavailable, e));
+            // The events are drained all the same, until the match is over
+            receiver.into_iter().for_each(drop);
+            return;
+        }
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs() as i64);
+        let mut presence = MatchPresence::default();
+        for event in receiver {
+            if !presence.update(&event) {
+                continue;
+            }
+            let (details, state) = (presence.details(), presence.state());
+            let activity = Activity::new()
+                .details(details.as_str())
+                .state(state.as_str())
+                .timestamps(Timestamps::new().start(started));
+            if let Err(e) = client.set_activity(activity) {
+                log::warn!("{}", tr!(Msg::PresenceUnavailable, e));
+                break;
+            }
+        }
+        let _ = client.clear_activity();
+        let _ = client.close();
+    });
+    Some(sender)
+}
+
+/// Starts publishing the presence of a match, which this build cannot do.
+///
+/// # Arguments
+///
+/// * `_app_id` - The Discord application the presence would be shown under
+///
+/// # Returns
+///
+/// None, the game was built without the `discord` feature
+#[cfg(not(feature = "discord"))]
+pub fn publish(_app_id: &str) -> Option<Sender<GameEvent>> {
+    log::warn!("{}", tr!(Msg::PresenceUnavailable, "built without the discord feature"));
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn_start(round: u32, player: usize, name: &str, vitality: u32) -> GameEvent {
+        GameEvent::TurnStart {
+            round,
+            player,
+            name: name.to_string(),
+            vitality,
+            speed: 50,
+            strength: 50,
+            targets: vec![10],
+        }
+    }
+
+    #[test]
+    fn test_presence_follows_the_match() {
+        let mut presence = MatchPresence::default();
+        assert_eq!(presence.details(), "Starting a match");
+
+        assert!(presence.update(&turn_start(1, 0, "Alice", 50)));
+        assert!(presence.update(&turn_start(1, 1, "Bob", 50)));
+        assert_eq!(presence.details(), "Round 1 against Bob");
+
+        assert!(presence.update(&GameEvent::VitalityChange { round: 1, player: 1, amount: 20, vitality: 30 }));
+        assert_eq!(presence.state(), "Vitality 50 to 30");
+
+        assert!(presence.update(&GameEvent::GameOver { round: 3, winner: 0 }));
+        assert_eq!(presence.details(), "Alice won the match");
+    }
+
+    #[test]
+    fn test_presence_ignores_other_events() {
+        let mut presence = MatchPresence::default();
+        presence.update(&turn_start(1, 0, "Alice", 50));
+
+        assert!(!presence.update(&GameEvent::Score { round: 1, player: 0, target: 10, score: 90 }));
+        // The same round and vitality again change nothing either
+        assert!(!presence.update(&turn_start(1, 0, "Alice", 50)));
+    }
+}
@@ -0,0 +1,142 @@
+//! Profile module for the progress kept between matches.
+//!
+//! The profile is stored as JSON next to the saved matches. A missing profile
+//! file is not an error: the player simply starts from scratch.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::calibration::MAX_LATENCY_OFFSET_MS;
+use crate::campaign::OPPONENTS;
+use crate::error::{GameError, GameResult};
+use crate::persistence;
+
+/// File the profile is kept in unless another one is given.
+pub const DEFAULT_PROFILE_PATH: &str = "rust_game_profile.json";
+
+/// Progress of the player across matches.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Number of campaign opponents already beaten
+    pub campaign_stage: usize,
+    /// Most waves survived in survival mode
+    pub survival_best: u32,
+    /// Time the terminal and keyboard add to each key press, in milliseconds, from the last calibration
+    pub latency_offset_ms: u32,
+}
+
+impl Profile {
+    /// Loads the profile from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read
+    ///
+    /// # Returns
+    ///
+    /// Result containing the saved profile, or a new one if the file does not exist, or
+    /// `GameError::SaveCorrupted` if it is not valid
+    pub fn load(path: &Path) -> GameResult<Self> {
+        let json = match persistence::read_file(path) {
+            Ok(json) => json,
+            Err(GameError::IoError(e)) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.in_save(path)),
+        };
+        Self::parse(&json).map_err(|e| e.in_save(path))
+    }
+
+    /// Parses a profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The contents of the profile file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the profile, or `GameError::ParseError` if it is not valid
+    pub fn parse(json: &str) -> GameResult<Self> {
+        let profile: Self = serde_json::from_str(json)?;
+        if profile.campaign_stage > OPPONENTS.len() {
+            return Err(GameError::ParseError(format!("The campaign has only {} stages", OPPONENTS.len())));
+        }
+        if profile.latency_offset_ms > MAX_LATENCY_OFFSET_MS {
+            return Err(GameError::ParseError(format!("The latency offset is at most {} ms", MAX_LATENCY_OFFSET_MS)));
+        }
+        Ok(profile)
+    }
+
+    /// Saves the profile to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write, replaced if it already exists
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the profile was saved
+    pub fn save(&self, path: &Path) -> GameResult<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::GameError;
+    use crate::persistence::testing::temp_path;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let profile = Profile { campaign_stage: 2, survival_best: 7, latency_offset_ms: 40 };
+
+        let path = temp_path("profile_round_trip");
+        profile.save(&path).unwrap();
+        let loaded = Profile::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded, profile);
+    }
+
+    #[test]
+    fn test_load_missing_file_starts_fresh() {
+        assert_eq!(Profile::load(&temp_path("profile_missing")).unwrap(), Profile::default());
+    }
+
+    #[test]
+    fn test_load_fills_missing_fields() {
+        let path = temp_path("profile_partial");
+        fs::write(&path, r#"{ "campaign_stage": 1 }"#).unwrap();
+        let loaded = Profile::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded, Profile { campaign_stage: 1, ..Profile::default() });
+    }
+
+    #[test]
+    fn test_load_corrupted_file() {
+        let path = temp_path("profile_corrupted");
+        fs::write(&path, "{ not json").unwrap();
+        let result = Profile::load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(matches!(result, Err(GameError::SaveCorrupted { .. })));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_stage() {
+        let json = format!(r#"{{ "campaign_stage": {} }}"#, OPPONENTS.len() + 1);
+        assert!(matches!(Profile::parse(&json), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_parse_rejects_large_latency_offset() {
+        let json = format!(r#"{{ "latency_offset_ms": {} }}"#, MAX_LATENCY_OFFSET_MS + 1);
+        assert!(matches!(Profile::parse(&json), Err(GameError::ParseError(_))));
+    }
+}
@@ -0,0 +1,381 @@
+//! Python module for running balance experiments from a notebook, with the `python` feature.
+//!
+//! The bindings expose the players, a match played round by round with
+//! random stops, and the simulation runner, all with the same code as the
+//! `simulate` subcommand. Build and install them with maturin:
+//!
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! ```python
+//! import rust_game
+//! summary = rust_game.simulate(1000, vitality=80, seed=7)
+//! print(summary.win_rate(0), summary.average_rounds)
+//! ```
+//!
+//! The settings are keyword arguments named after the command line flags,
+//! and fall back to those of the terminal game. They are held to the same
+//! ranges as the flags, and a `ValueError` is raised for one outside them.
+
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::config::{GameConfig, SPEED_RANGE, STAMINA_RANGE, STRENGTH_RANGE, TARGET_COUNT_RANGE, VITALITY_RANGE};
+use crate::player::Player;
+use crate::simulation::{self, MAX_ROUNDS, SimulationSummary};
+use crate::state::GameState;
+
+/// A player, with their attributes at some point of a match.
+#[pyclass(name = "Player", module = "rust_game", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyPlayer {
+    /// The player
+    player: Player,
+}
+
+#[pymethods]
+impl PyPlayer {
+    /// Creates a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The player's name
+    /// * `vitality` - The starting vitality
+    /// * `speed` - The starting speed
+    /// * `strength` - The starting strength
+    /// * `stamina` - The starting stamina
+    ///
+    /// # Returns
+    ///
+    /// A new PyPlayer
+    #[new]
+    #[pyo3(signature = (name, vitality = 50, speed = 50, strength = 50, stamina = 3))]
+    fn new(name: String, vitality: u32, speed: u32, strength: u32, stamina: u32) -> Self {
+        Self { player: Player::new(name, vitality, speed, strength, stamina) }
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.player.name()
+    }
+
+    #[getter]
+    fn vitality(&self) -> u32 {
+        self.player.vitality()
+    }
+
+    #[getter]
+    fn speed(&self) -> u32 {
+        self.player.speed()
+    }
+
+    #[getter]
+    fn strength(&self) -> u32 {
+        self.player.strength()
+    }
+
+    #[getter]
+    fn stamina(&self) -> u32 {
+        self.player.stamina()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Player(name={:?}, vitality={}, speed={}, strength={}, stamina={})",
+                self.player.name(), self.player.vitality(), self.player.speed(), self.player.strength(), self.player.stamina())
+    }
+}
+
+/// A match played round by round, both players stopping the counter at random.
+#[pyclass(name = "Game", module = "rust_game")]
+pub struct PyGame {
+    /// The settings of the match
+    config: GameConfig,
+    /// The progress of the match
+    state: GameState,
+    /// The random number generator for the targets and stops
+    rng: StdRng,
+}
+
+#[pymethods]
+impl PyGame {
+    /// Creates a match at round 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The keyword arguments, see `config_from`
+    ///
+    /// # Returns
+    ///
+    /// PyResult containing the match, or a TypeError for an unknown setting
+    #[new]
+    #[pyo3(signature = (**settings))]
+    fn new(settings: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = config_from(settings)?;
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        Ok(Self { state: simulation::new_match(&config), config, rng })
+    }
+
+    /// Plays one round.
+    ///
+    /// # Returns
+    ///
+    /// True if the match goes on after it, False once it is over
+    fn step(&mut self) -> bool {
+        if self.state.game_over {
+            return false;
+        }
+        self.state = simulation::simulate_round(&self.config, &self.state, &mut self.rng);
+        if !self.state.in_progress() || self.state.round > MAX_ROUNDS {
+            self.state = simulation::finish_match(self.state.clone());
+        }
+        !self.state.game_over
+    }
+
+    /// Plays the rounds left.
+    ///
+    /// # Returns
+    ///
+    /// The index of the winner, or None if the match was called a draw
+    fn play(&mut self) -> Option<usize> {
+        while self.step() {}
+        self.state.winner_idx
+    }
+
+    /// The current round, or the number of rounds played plus one once the match is over.
+    #[getter]
+    fn round(&self) -> u32 {
+        self.state.round
+    }
+
+    #[getter]
+    fn players(&self) -> Vec<PyPlayer> {
+        self.state.players.iter().map(|player| PyPlayer { player: player.clone() }).collect()
+    }
+
+    #[getter]
+    fn winner(&self) -> Option<usize> {
+        self.state.winner_idx
+    }
+
+    #[getter]
+    fn game_over(&self) -> bool {
+        self.state.game_over
+    }
+
+    /// Rounds each player won so far.
+    #[getter]
+    fn round_wins(&self) -> [u32; 2] {
+        self.state.tally.round_wins
+    }
+
+    /// Sum of each player's round scores so far.
+    #[getter]
+    fn scores(&self) -> [u32; 2] {
+        self.state.tally.scores
+    }
+
+    /// Returns the state of the match as JSON, in the format of the saved matches.
+    fn to_json(&self) -> String {
+        serde_json::to_string(&self.state).expect("a game state always serializes")
+    }
+}
+
+/// The outcome of a batch of simulated matches.
+#[pyclass(name = "Summary", module = "rust_game", frozen)]
+#[derive(Debug, Clone, Copy)]
+pub struct PySummary {
+    /// The outcome
+    summary: SimulationSummary,
+}
+
+#[pymethods]
+impl PySummary {
+    #[getter]
+    fn games(&self) -> u64 {
+        self.summary.games
+    }
+
+    #[getter]
+    fn wins(&self) -> [u64; 2] {
+        self.summary.wins
+    }
+
+    #[getter]
+    fn draws(&self) -> u64 {
+        self.summary.draws()
+    }
+
+    #[getter]
+    fn average_rounds(&self) -> f64 {
+        self.summary.average_rounds()
+    }
+
+    /// Returns the percentage of matches a player won.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The index of the player (0 or 1)
+    ///
+    /// # Returns
+    ///
+    /// PyResult containing the percentage, or a ValueError for another index
+    fn win_rate(&self, player: usize) -> PyResult<f64> {
+        if player > 1 {
+            return Err(PyValueError::new_err(format!("No player {}, only 0 and 1", player)));
+        }
+        Ok(self.summary.win_rate(player))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Summary(games={}, wins={:?}, draws={}, average_rounds={:.1})",
+                self.summary.games, self.summary.wins, self.summary.draws(), self.summary.average_rounds())
+    }
+}
+
+/// Plays a batch of matches with random stops.
+///
+/// # Arguments
+///
+/// * `games` - How many matches to play
+/// * `settings` - The keyword arguments, see `config_from`
+///
+/// # Returns
+///
+/// PyResult containing the outcome of the matches
+#[pyfunction]
+#[pyo3(signature = (games, **settings))]
+fn simulate(py: Python<'_>, games: u64, settings: Option<&Bound<'_, PyDict>>) -> PyResult<PySummary> {
+    let config = config_from(settings)?;
+    // The matches need no Python object, so other threads can run meanwhile
+    let summary = py.detach(|| simulation::run(&config, games));
+    Ok(PySummary { summary })
+}
+
+/// Reads the settings of a match from keyword arguments.
+///
+/// The arguments are `player1`, `player2`, `vitality`, `speed`, `strength`,
+/// `stamina`, `targets` and `seed`, like the command line flags.
+///
+/// # Arguments
+///
+/// * `settings` - The keyword arguments, None if none were given
+///
+/// # Returns
+///
+/// PyResult containing the settings, a TypeError for an unknown setting or a value of the wrong type,
+/// or a ValueError for a number out of range
+fn config_from(settings: Option<&Bound<'_, PyDict>>) -> PyResult<GameConfig> {
+    let mut config = GameConfig::default();
+    for (key, value) in settings.into_iter().flat_map(|settings| settings.iter()) {
+        let key: String = key.extract()?;
+        match key.as_str() {
+            "player1" => config.player1_name = value.extract()?,
+            "player2" => config.player2_name = value.extract()?,
+            "vitality" => config.vitality = in_range(&key, &value, VITALITY_RANGE)?,
+            "speed" => config.speed = in_range(&key, &value, SPEED_RANGE)?,
+            "strength" => config.strength = in_range(&key, &value, STRENGTH_RANGE)?,
+            "stamina" => config.stamina = in_range(&key, &value, STAMINA_RANGE)?,
+            "targets" => config.target_count = in_range(&key, &value, TARGET_COUNT_RANGE)?,
+            "seed" => config.seed = value.extract()?,
+            _ => return Err(PyTypeError::new_err(format!("Unknown setting: {}", key))),
+        }
+    }
+    Ok(config)
+}
+
+/// Reads a whole number setting, within the range the command line accepts.
+///
+/// # Arguments
+///
+/// * `key` - The name of the setting
+/// * `value` - The value given
+/// * `range` - The values accepted
+///
+/// # Returns
+///
+/// PyResult containing the number, a TypeError if it is not a whole number, or a ValueError out of range
+fn in_range<T, R>(key: &str, value: &Bound<'_, PyAny>, range: RangeInclusive<R>) -> PyResult<T>
+where
+    R: TryFrom<i64> + PartialOrd + Display + Copy,
+    T: TryFrom<R>,
+{
+    let number: i64 = value.extract()?;
+    R::try_from(number).ok()
+        .filter(|number| range.contains(number))
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or_else(|| PyValueError::new_err(format!("{} must be from {} to {}, not {}", key, range.start(), range.end(), number)))
+}
+
+/// The `rust_game` Python module.
+#[pymodule]
+fn rust_game(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyPlayer>()?;
+    module.add_class::<PyGame>()?;
+    module.add_class::<PySummary>()?;
+    module.add_function(wrap_pyfunction!(simulate, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_game() -> PyGame {
+        Python::initialize();
+        Python::attach(|py| {
+            let settings = PyDict::new(py);
+            settings.set_item("vitality", 100).unwrap();
+            settings.set_item("seed", 7).unwrap();
+            PyGame::new(Some(&settings)).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_game_plays_like_the_simulation() {
+        let mut game = seeded_game();
+        let winner = game.play().unwrap();
+
+        let config = GameConfig { vitality: 100, seed: Some(7), ..GameConfig::default() };
+        let state = simulation::simulate_match(&config, &mut StdRng::seed_from_u64(7));
+        assert_eq!(Some(winner), state.winner_idx);
+        assert_eq!(game.to_json(), serde_json::to_string(&state).unwrap());
+        assert!(!game.step());
+    }
+
+    #[test]
+    fn test_unknown_and_mistyped_settings_are_refused() {
+        Python::initialize();
+        Python::attach(|py| {
+            let settings = PyDict::new(py);
+            settings.set_item("colour", "red").unwrap();
+            assert!(config_from(Some(&settings)).unwrap_err().is_instance_of::<PyTypeError>(py));
+
+            let settings = PyDict::new(py);
+            settings.set_item("vitality", "lots").unwrap();
+            assert!(config_from(Some(&settings)).unwrap_err().is_instance_of::<PyTypeError>(py));
+
+            for (key, value) in [("vitality", 0), ("targets", 0), ("targets", 1_000_000), ("stamina", -1)] {
+                let settings = PyDict::new(py);
+                settings.set_item(key, value).unwrap();
+                assert!(config_from(Some(&settings)).unwrap_err().is_instance_of::<PyValueError>(py), "{} = {}", key, value);
+            }
+        });
+    }
+
+    #[test]
+    fn test_summary_of_a_batch() {
+        let summary = PySummary { summary: simulation::run(&GameConfig { seed: Some(3), ..GameConfig::default() }, 20) };
+        assert_eq!(summary.games(), 20);
+        assert_eq!(summary.wins().iter().sum::<u64>() + summary.draws(), 20);
+        assert!(summary.win_rate(2).is_err());
+    }
+}
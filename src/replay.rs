@@ -0,0 +1,213 @@
+//! Replay module for the recorded runs raced as ghosts.
+//!
+//! A replay keeps the seed of a practice or time attack run, what the run
+//! achieved, and where each target was stopped. Playing with the same seed
+//! brings back the same targets, so a new run can be compared stop by stop
+//! with the recorded one.
+//!
+//! The hash of the game state at the start of each target is recorded too. As
+//! long as a new run makes the same stops as the recorded one, its states must
+//! hash the same, otherwise the two runs have diverged.
+//!
+//! Replays are shared between players, so a replay file is checked before it
+//! is raced against, starting with the version of its format.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GameError, GameResult};
+use crate::persistence;
+use crate::practice::{self, PracticeMode};
+use crate::version::REPLAY_FORMAT;
+
+/// A recorded practice or time attack run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replay {
+    /// Seed the run was played with
+    pub seed: u64,
+    /// The mode the run was played in
+    pub mode: PracticeMode,
+    /// What the run achieved: its total score in practice, its time in milliseconds in time attack
+    pub result: u64,
+    /// Counter value of each stop, in ticks, in the order they were made
+    pub stops: Vec<u32>,
+    /// Hash of the game state at the start of each target
+    pub hashes: Vec<u64>,
+}
+
+impl Replay {
+    /// Loads a replay from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read
+    ///
+    /// # Returns
+    ///
+    /// Result containing the recorded run, or `GameError::SaveCorrupted` if it is not valid
+    pub fn load(path: &Path) -> GameResult<Self> {
+        persistence::read_file(path).and_then(|json| Self::parse(&json)).map_err(|e| e.in_save(path))
+    }
+
+    /// Parses a recorded run.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The contents of the replay file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the recorded run, `GameError::IncompatibleVersion` if it was recorded
+    /// in another version of the format, or `GameError::ParseError` if it is not valid
+    pub fn parse(contents: &str) -> GameResult<Self> {
+        let replay: Self = serde_json::from_str(REPLAY_FORMAT.read(contents)?)?;
+        // A hash is recorded at the start of every target, before it is stopped
+        if replay.hashes.len() > replay.stops.len() {
+            return Err(GameError::ParseError("The replay has more hashes than stops".to_string()));
+        }
+        Ok(replay)
+    }
+
+    /// Checks that a run making the same stops as this replay reached the same state.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - The round starting, from 1, one for each target of the run
+    /// * `stops` - The stops of the run so far
+    /// * `hash` - The hash of the run's state at the start of the round
+    ///
+    /// # Returns
+    ///
+    /// Result with `GameError::Desync` if the run should match the replay but does not
+    pub fn check(&self, round: u32, stops: &[u32], hash: u64) -> GameResult<()> {
+        // Once the run made a different stop, its states have no reason to match
+        if !self.stops.starts_with(stops) {
+            return Ok(());
+        }
+        match (round as usize).checked_sub(1).and_then(|idx| self.hashes.get(idx)) {
+            Some(&expected) if expected != hash => Err(GameError::Desync { round, expected, actual: hash }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the replay holds a whole run, with a stop and a hash for each of its targets.
+    ///
+    /// A shorter replay can still be raced, its ghost stopping before the run
+    /// ends, so only inspecting a replay asks for this.
+    ///
+    /// # Returns
+    ///
+    /// Result with `GameError::ParseError` naming what is missing if the run is incomplete
+    pub fn check_complete(&self) -> GameResult<()> {
+        if self.stops.len() != practice::RUN_TARGETS {
+            return Err(GameError::ParseError(format!("The replay has {} stops, a run has {}",
+                                                     self.stops.len(), practice::RUN_TARGETS)));
+        }
+        if self.hashes.len() != self.stops.len() {
+            return Err(GameError::ParseError(format!("The replay has {} state hashes for {} stops",
+                                                     self.hashes.len(), self.stops.len())));
+        }
+        Ok(())
+    }
+
+    /// Saves the replay to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write, replaced if it already exists
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether the replay was saved
+    pub fn save(&self, path: &Path) -> GameResult<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        fs::write(path, REPLAY_FORMAT.write(&json))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::testing::temp_path;
+
+    fn replay(stops: Vec<u32>, hashes: Vec<u64>) -> Replay {
+        Replay { seed: 42, mode: PracticeMode::Practice, result: 380, stops, hashes }
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let replay = Replay { mode: PracticeMode::TimeAttack, ..replay(vec![12, 87, 40, 3], vec![7, 8, 9]) };
+
+        let path = temp_path("replay_round_trip");
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded, replay);
+    }
+
+    #[test]
+    fn test_check_complete() {
+        let whole = replay(vec![10; practice::RUN_TARGETS], vec![1; practice::RUN_TARGETS]);
+        assert!(whole.check_complete().is_ok());
+        assert!(matches!(replay(vec![12, 87], vec![7, 8]).check_complete(), Err(GameError::ParseError(_))));
+        let missing_hash = replay(vec![10; practice::RUN_TARGETS], vec![1; practice::RUN_TARGETS - 1]);
+        assert!(matches!(missing_hash.check_complete(), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        assert!(matches!(Replay::load(&temp_path("replay_missing")), Err(GameError::IoError(_))));
+    }
+
+    #[test]
+    fn test_survival_replays_are_no_longer_read() {
+        // Replays from before the header were survival runs
+        let path = temp_path("replay_survival");
+        fs::write(&path, r#"{ "seed": 1, "waves": 1, "stops": [5] }"#).unwrap();
+        let loaded = Replay::load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(matches!(loaded, Err(GameError::SaveCorrupted { .. })));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_replays() {
+        let parse = |json: &str| Replay::parse(&REPLAY_FORMAT.write(json));
+        assert!(matches!(parse("{ not json"), Err(GameError::ParseError(_))));
+        assert!(matches!(parse(r#"{ "seed": 1, "mode": "practice", "result": -1, "stops": [], "hashes": [] }"#),
+                         Err(GameError::ParseError(_))));
+        assert!(matches!(parse(r#"{ "seed": 1, "mode": "marathon", "result": 0, "stops": [], "hashes": [] }"#),
+                         Err(GameError::ParseError(_))));
+        assert!(matches!(parse(r#"{ "seed": 1, "mode": "practice", "result": 0, "stops": [4], "hashes": [1, 2] }"#),
+                         Err(GameError::ParseError(_))));
+        assert!(parse(r#"{ "seed": 1, "mode": "time-attack", "result": 0, "stops": [4], "hashes": [1] }"#).is_ok());
+    }
+
+    #[test]
+    fn test_parse_checks_the_format_version() {
+        let replay = replay(vec![], vec![]);
+        let json = serde_json::to_string(&replay).unwrap();
+
+        assert_eq!(Replay::parse(&REPLAY_FORMAT.write(&json)).unwrap(), replay);
+        assert!(matches!(Replay::parse(&format!("RUSTGAME-REPLAY 1.0.0\n{}", json)),
+                         Err(GameError::IncompatibleVersion { .. })));
+        assert!(matches!(Replay::parse(&format!("RUSTGAME-SAVE 2.0.0\n{}", json)), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_check_detects_divergence() {
+        let replay = replay(vec![12, 87], vec![100, 200]);
+
+        assert!(replay.check(1, &[], 100).is_ok());
+        assert!(replay.check(2, &[12], 200).is_ok());
+        assert!(matches!(replay.check(2, &[12], 201),
+                         Err(GameError::Desync { round: 2, expected: 200, actual: 201 })));
+        // A run that stopped elsewhere is expected to differ
+        assert!(replay.check(2, &[11], 201).is_ok());
+    }
+}
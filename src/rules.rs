@@ -0,0 +1,991 @@
+//! Rules module for the settings that change how a match is played.
+//!
+//! This module groups the rules of the counter and scoring into a single
+//! structure, so the game can be played with other variants than the classic one.
+
+use std::str::FromStr;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::dial::{DEFAULT_COUNTER_MAX, Dial, Precision};
+use crate::error::{GameError, GameResult};
+#[cfg(feature = "native")]
+use crate::i18n::{Msg, tr};
+use crate::player::{Attribute, Consolation, Player};
+#[cfg(feature = "native")]
+use crate::plugins::GamePlugin;
+use crate::scoring;
+use crate::state::{GameState, RoundOutcome};
+
+/// A penalty or consolation bonus the players can draft out of a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftItem {
+    /// A penalty the round winner applies to the loser
+    Penalty(Attribute),
+    /// A bonus the round loser picks
+    Bonus(Consolation),
+}
+
+impl DraftItem {
+    /// Tells whether two items are of the same kind, both penalties or both bonuses.
+    fn same_kind(self, other: DraftItem) -> bool {
+        matches!((self, other), (DraftItem::Penalty(_), DraftItem::Penalty(_)) | (DraftItem::Bonus(_), DraftItem::Bonus(_)))
+    }
+}
+
+/// Attribute points taken by a penalty.
+pub const PENALTY_AMOUNT: u32 = 5;
+
+/// Every penalty and bonus, in the order the draft offers them.
+pub const DRAFT_ITEMS: [DraftItem; 5] = [
+    DraftItem::Penalty(Attribute::Speed),
+    DraftItem::Penalty(Attribute::Strength),
+    DraftItem::Bonus(Consolation::Speed),
+    DraftItem::Bonus(Consolation::Strength),
+    DraftItem::Bonus(Consolation::Vitality),
+];
+
+/// The penalties and bonuses left in a match after the draft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DraftPool {
+    /// Whether each of `DRAFT_ITEMS` was banned
+    banned: [bool; DRAFT_ITEMS.len()],
+    /// The player who picked each of `DRAFT_ITEMS` for themselves, if any
+    #[serde(default)]
+    picked: [Option<usize>; DRAFT_ITEMS.len()],
+}
+
+impl DraftPool {
+    /// Returns the position of an item in `DRAFT_ITEMS`.
+    fn index(item: DraftItem) -> usize {
+        DRAFT_ITEMS.iter().position(|&other| other == item).expect("every item is in the draft")
+    }
+
+    /// Tells whether an item can be used in the match.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The penalty or bonus
+    ///
+    /// # Returns
+    ///
+    /// True unless the item was banned
+    pub fn allows(&self, item: DraftItem) -> bool {
+        !self.banned[Self::index(item)]
+    }
+
+    /// Tells whether a player can use an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The penalty or bonus
+    /// * `player_idx` - The index of the player
+    ///
+    /// # Returns
+    ///
+    /// True unless the item was banned or picked by the other player
+    pub fn allows_for(&self, item: DraftItem, player_idx: usize) -> bool {
+        self.allows(item) && self.picked[Self::index(item)].is_none_or(|picker| picker == player_idx)
+    }
+
+    /// Tells whether a player could still use another item of the same kind.
+    fn has_other(&self, item: DraftItem, player_idx: usize) -> bool {
+        DRAFT_ITEMS.iter().any(|&other| other != item && other.same_kind(item) && self.allows_for(other, player_idx))
+    }
+
+    /// Tells whether an item can still be banned.
+    ///
+    /// A round always needs a penalty to apply and a bonus to pick, so an
+    /// item cannot be banned if it is the last of its kind either player can
+    /// use. Picked items cannot be banned.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The penalty or bonus
+    ///
+    /// # Returns
+    ///
+    /// True if the item is allowed, nobody picked it, and both players can use another item of its kind
+    pub fn can_ban(&self, item: DraftItem) -> bool {
+        self.allows(item) && self.picked[Self::index(item)].is_none() && (0..2).all(|player_idx| self.has_other(item, player_idx))
+    }
+
+    /// Takes an item out of the match.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The penalty or bonus to ban
+    pub fn ban(&mut self, item: DraftItem) {
+        self.banned[Self::index(item)] = true;
+    }
+
+    /// Tells whether a player can pick an item for themselves.
+    ///
+    /// The other player must keep another item of the same kind, so that
+    /// they still have a penalty to apply and a bonus to pick.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The penalty or bonus
+    /// * `player_idx` - The index of the player picking
+    ///
+    /// # Returns
+    ///
+    /// True if the item is allowed, nobody picked it, and the other player can use another item of its kind
+    pub fn can_pick(&self, item: DraftItem, player_idx: usize) -> bool {
+        self.allows(item) && self.picked[Self::index(item)].is_none() && self.has_other(item, 1 - player_idx)
+    }
+
+    /// Keeps an item for one player, the other can no longer use it.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The penalty or bonus to pick
+    /// * `player_idx` - The index of the player picking
+    pub fn pick(&mut self, item: DraftItem, player_idx: usize) {
+        self.picked[Self::index(item)] = Some(player_idx);
+    }
+}
+
+/// Who takes the first turn of each round.
+///
+/// The player going second knows the score to beat, so the classic order
+/// favours player 2; the other orders share that advantage out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TurnOrder {
+    /// Player 1 always goes first
+    #[default]
+    Fixed,
+    /// The players take turns going first, one round each
+    Alternate,
+    /// The loser of a round goes first in the next one
+    LoserFirst,
+}
+
+impl TurnOrder {
+    /// Works out who goes first in the next round.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The index of the player who went first this round
+    /// * `outcome` - How this round ended
+    ///
+    /// # Returns
+    ///
+    /// The index of the player who goes first next round. After a draw,
+    /// `LoserFirst` keeps the same order.
+    pub fn next_first(self, first: usize, outcome: &RoundOutcome) -> usize {
+        match (self, outcome) {
+            (TurnOrder::Fixed, _) => 0,
+            (TurnOrder::Alternate, _) => 1 - first,
+            (TurnOrder::LoserFirst, RoundOutcome::Win { loser, .. }) => *loser,
+            (TurnOrder::LoserFirst, RoundOutcome::Draw) => first,
+        }
+    }
+}
+
+/// How the score difference of a round turns into vitality lost by the loser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DamageModel {
+    /// The loser loses the whole score difference
+    #[default]
+    Difference,
+    /// The loser loses the score difference, up to a maximum
+    Capped(u32),
+    /// The loser loses a percentage of their vitality, rounded up
+    Percent(u32),
+    /// The loser loses the same amount whatever the difference
+    Fixed(u32),
+}
+
+impl DamageModel {
+    /// Works out the vitality a round's loser loses.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - The difference between the two scores, more than 0
+    /// * `vitality` - The loser's vitality before the round
+    ///
+    /// # Returns
+    ///
+    /// The vitality lost, not counting any wagers
+    pub fn damage(self, diff: u32, vitality: u32) -> u32 {
+        match self {
+            DamageModel::Difference => diff,
+            DamageModel::Capped(cap) => diff.min(cap),
+            // At most 100 percent, so the damage fits back into a u32 once divided
+            DamageModel::Percent(percent) => (u64::from(vitality) * u64::from(percent)).div_ceil(100) as u32,
+            DamageModel::Fixed(amount) => amount,
+        }
+    }
+}
+
+/// How the counter speeds up as a match goes on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpeedCurve {
+    /// The counter keeps the players' speed all match
+    #[default]
+    Flat,
+    /// The counter gets faster every round, by the given percentage of its starting speed
+    Ramp(u32),
+}
+
+impl SpeedCurve {
+    /// Adjusts the delay between two counter ticks to the round.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The delay in milliseconds
+    /// * `round` - The current round, starting at 1
+    ///
+    /// # Returns
+    ///
+    /// The delay for that round, at least 1 ms
+    pub fn counter_delay(self, delay: u32, round: u32) -> u32 {
+        match self {
+            SpeedCurve::Flat => delay,
+            SpeedCurve::Ramp(percent) => {
+                let speed_up = 100 + percent.saturating_mul(round.saturating_sub(1));
+                (delay * 100 / speed_up).max(1)
+            },
+        }
+    }
+}
+
+/// How the targets of a turn are spread over the dial.
+///
+/// The scoring counts distances around the dial, so targets near both ends
+/// are close to each other across 0 even though they look far apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetDistribution {
+    /// Every value is as likely to be drawn
+    #[default]
+    Uniform,
+    /// Values near the middle of the dial are drawn more often
+    Center,
+    /// Values are drawn from the outer quarter of the dial at either end
+    Edges,
+    /// The targets follow each other by the same step, going around the dial
+    Sequence,
+}
+
+impl TargetDistribution {
+    /// Draws the targets of a turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to draw from
+    /// * `count` - How many targets to draw
+    /// * `max` - The highest counter value, in whole units
+    ///
+    /// # Returns
+    ///
+    /// The targets, each between 0 and `max`
+    pub fn draw<R: Rng>(self, rng: &mut R, count: usize, max: u32) -> Vec<u32> {
+        match self {
+            TargetDistribution::Uniform => (0..count).map(|_| rng.random_range(0..=max)).collect(),
+            // The mean of two draws falls near the middle more often than near the ends
+            TargetDistribution::Center => (0..count)
+                .map(|_| ((u64::from(rng.random_range(0..=max)) + u64::from(rng.random_range(0..=max))) / 2) as u32)
+                .collect(),
+            TargetDistribution::Edges => {
+                let width = max / 4;
+                (0..count)
+                    .map(|_| if rng.random_bool(0.5) {
+                        rng.random_range(0..=width)
+                    } else {
+                        rng.random_range(max - width..=max)
+                    })
+                    .collect()
+            },
+            // Worked out in u64, as the steps add up past the largest counter ranges
+            TargetDistribution::Sequence => {
+                let start = u64::from(rng.random_range(0..=max));
+                let step = u64::from(rng.random_range(1..=(max / 2).max(1)));
+                let values = u64::from(max) + 1;
+                (0..count as u64).map(|idx| ((start + idx * step) % values) as u32).collect()
+            },
+        }
+    }
+}
+
+/// The weather of a round, drawn at random when weather is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundModifier {
+    /// Nothing changes
+    #[default]
+    Clear,
+    /// The targets are hidden, the counter gives warmer/colder hints instead
+    Fog,
+    /// The counter runs 20% faster
+    Storm,
+    /// Misses are not counted in the score
+    Calm,
+}
+
+impl RoundModifier {
+    /// Every kind of weather, each as likely to be drawn.
+    pub const ALL: [RoundModifier; 4] = [RoundModifier::Clear, RoundModifier::Fog, RoundModifier::Storm, RoundModifier::Calm];
+
+    /// Draws the weather of a round.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to draw from
+    ///
+    /// # Returns
+    ///
+    /// One of the kinds of weather
+    pub fn draw<R: Rng>(rng: &mut R) -> Self {
+        Self::ALL[rng.random_range(0..Self::ALL.len())]
+    }
+
+    /// Returns whether the targets are hidden this round.
+    pub fn hides_targets(self) -> bool {
+        self == RoundModifier::Fog
+    }
+
+    /// Returns whether misses lower the score this round.
+    pub fn counts_misses(self) -> bool {
+        self != RoundModifier::Calm
+    }
+
+    /// Adjusts the delay between two counter ticks to the weather.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - The delay in milliseconds
+    ///
+    /// # Returns
+    ///
+    /// The delay under this weather, at least 1 ms
+    pub fn counter_delay(self, delay: u32) -> u32 {
+        match self {
+            RoundModifier::Storm => (delay * 5 / 6).max(1),
+            RoundModifier::Clear | RoundModifier::Fog | RoundModifier::Calm => delay,
+        }
+    }
+}
+
+/// Splits a rule given as `name` or `name:amount`.
+///
+/// # Arguments
+///
+/// * `s` - The rule, in any case
+///
+/// # Returns
+///
+/// The name in lowercase and the amount if one was given, or None if the amount is not a number
+fn split_amount(s: &str) -> Option<(String, Option<u32>)> {
+    let lower = s.to_ascii_lowercase();
+    match lower.split_once(':') {
+        Some((name, amount)) => Some((name.to_string(), Some(amount.parse().ok()?))),
+        None => Some((lower, None)),
+    }
+}
+
+impl FromStr for DamageModel {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || GameError::ConfigError(format!("Unknown damage model: {}", s));
+        let (name, amount) = split_amount(s).ok_or_else(unknown)?;
+        match (name.as_str(), amount) {
+            ("difference", None) => Ok(DamageModel::Difference),
+            ("capped", Some(cap)) if cap > 0 => Ok(DamageModel::Capped(cap)),
+            ("percent", Some(percent)) if (1..=100).contains(&percent) => Ok(DamageModel::Percent(percent)),
+            ("fixed", Some(amount)) if amount > 0 => Ok(DamageModel::Fixed(amount)),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+/// How a match is won, besides the opponent running out of vitality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WinCondition {
+    /// Only running out of vitality ends the match
+    #[default]
+    Vitality,
+    /// The first player to win the given number of rounds wins
+    RoundWins(u32),
+    /// The player with the highest total of round scores after the given number of rounds wins
+    HighScore(u32),
+    /// The first player to make more exact hits than the other wins
+    SuddenDeath,
+}
+
+impl WinCondition {
+    /// Works out whether the match is decided, at the end of a round.
+    ///
+    /// Ties go on to another round, until one player is ahead.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state after the round, with the round not counted yet
+    ///
+    /// # Returns
+    ///
+    /// The index of the winner, or None if the match goes on
+    pub fn winner(self, state: &GameState) -> Option<usize> {
+        let tally = &state.tally;
+        let leader = |counts: [u32; 2]| match counts[0].cmp(&counts[1]) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        };
+        match self {
+            WinCondition::Vitality => None,
+            WinCondition::RoundWins(wins) => tally.round_wins.iter().position(|&won| won >= wins),
+            WinCondition::HighScore(rounds) if state.round >= rounds => leader(tally.scores),
+            WinCondition::HighScore(_) => None,
+            WinCondition::SuddenDeath => leader(tally.exact_hits),
+        }
+    }
+}
+
+/// A win condition is the plugin ending the match once it decides a winner.
+#[cfg(feature = "native")]
+impl GamePlugin for WinCondition {
+    fn name(&self) -> &'static str {
+        match self {
+            WinCondition::Vitality => "vitality",
+            WinCondition::RoundWins(_) => "rounds",
+            WinCondition::HighScore(_) => "score",
+            WinCondition::SuddenDeath => "sudden-death",
+        }
+    }
+
+    fn winner(&self, state: &GameState) -> Option<(usize, String)> {
+        let winner_idx = WinCondition::winner(*self, state)?;
+        let name = state.players[winner_idx].name();
+        let tally = &state.tally;
+        let message = match self {
+            WinCondition::RoundWins(_) => tr!(Msg::WinRounds, name, tally.round_wins[winner_idx]),
+            WinCondition::HighScore(_) => tr!(Msg::WinHighScore, name, tally.scores[winner_idx], tally.scores[1 - winner_idx]),
+            WinCondition::SuddenDeath | WinCondition::Vitality => tr!(Msg::WinSuddenDeath, name),
+        };
+        Some((winner_idx, message))
+    }
+
+    fn clone_box(&self) -> Box<dyn GamePlugin> {
+        Box::new(*self)
+    }
+}
+
+impl FromStr for WinCondition {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || GameError::ConfigError(format!("Unknown win condition: {}", s));
+        let (name, amount) = split_amount(s).ok_or_else(unknown)?;
+        match (name.as_str(), amount) {
+            ("vitality", None) => Ok(WinCondition::Vitality),
+            ("rounds", Some(wins)) if wins > 0 => Ok(WinCondition::RoundWins(wins)),
+            ("score", Some(rounds)) if rounds > 0 => Ok(WinCondition::HighScore(rounds)),
+            ("sudden-death", None) => Ok(WinCondition::SuddenDeath),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+impl FromStr for TurnOrder {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fixed" => Ok(TurnOrder::Fixed),
+            "alternate" => Ok(TurnOrder::Alternate),
+            "loser-first" => Ok(TurnOrder::LoserFirst),
+            other => Err(GameError::ConfigError(format!("Unknown turn order: {}", other))),
+        }
+    }
+}
+
+impl FromStr for TargetDistribution {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "uniform" => Ok(TargetDistribution::Uniform),
+            "center" => Ok(TargetDistribution::Center),
+            "edges" => Ok(TargetDistribution::Edges),
+            "sequence" => Ok(TargetDistribution::Sequence),
+            other => Err(GameError::ConfigError(format!("Unknown target distribution: {}", other))),
+        }
+    }
+}
+
+/// The lowest and highest value an attribute may take during a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Bounds {
+    /// The floor the attribute cannot go under
+    pub min: u32,
+    /// The ceiling the attribute cannot go over, if any
+    pub max: Option<u32>,
+}
+
+impl Bounds {
+    /// Keeps a value within the bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to keep within the bounds
+    ///
+    /// # Returns
+    ///
+    /// The value, raised to the floor or lowered to the ceiling if needed
+    pub fn clamp(self, value: u32) -> u32 {
+        value.min(self.max.unwrap_or(u32::MAX)).max(self.min)
+    }
+
+    /// Returns whether a value is within the bounds.
+    pub fn contains(self, value: u32) -> bool {
+        self.clamp(value) == value
+    }
+}
+
+/// What happens when a penalty brings a player's speed down to its floor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedFloor {
+    /// The player loses the match
+    #[default]
+    Knockout,
+    /// The player's speed stays at the floor and the match goes on
+    Clamp,
+}
+
+/// The bounds of every player attribute, and what happens at the speed floor.
+///
+/// Strength needs no rule of its own: at 0 it adds nothing to the score and
+/// never makes a critical hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AttributeBounds {
+    /// Bounds of the player's health points
+    pub vitality: Bounds,
+    /// Bounds of the counter speed
+    pub speed: Bounds,
+    /// Bounds of the scoring power
+    pub strength: Bounds,
+    /// Bounds of the reserve for risky actions
+    pub stamina: Bounds,
+    /// What happens when a penalty brings speed down to its floor
+    pub speed_floor: SpeedFloor,
+}
+
+impl AttributeBounds {
+    /// Checks that the bounds can be played with.
+    ///
+    /// # Returns
+    ///
+    /// Result with `GameError::ConfigError` if a minimum is above its maximum, if the
+    /// vitality floor would keep players from being knocked out, or if a clamped speed
+    /// floor would let the counter run with no delay at all
+    pub fn validate(&self) -> GameResult<()> {
+        // A match ends when a player's vitality reaches 0, which a floor above it would prevent
+        if self.vitality.min > 0 {
+            return Err(GameError::ConfigError("The minimum vitality must be 0".to_string()));
+        }
+        let attributes = [("vitality", self.vitality), ("speed", self.speed), ("strength", self.strength), ("stamina", self.stamina)];
+        if let Some((name, _)) = attributes.iter().find(|(_, bounds)| bounds.max.is_some_and(|max| max < bounds.min)) {
+            return Err(GameError::ConfigError(format!("The minimum {} is above the maximum", name)));
+        }
+        if self.speed_floor == SpeedFloor::Clamp && self.speed.min == 0 {
+            return Err(GameError::ConfigError("A clamped speed floor needs a minimum speed above 0".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Batches of targets drawn before giving up on spacing the rest of a turn's targets apart.
+const MAX_TARGET_DRAWS: usize = 100;
+
+/// Delay between two counter ticks, in milliseconds, for a player with a speed of 100.
+pub const DEFAULT_BASE_TICK_MS: u32 = 25;
+
+/// The rules a match is played with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleSet {
+    /// Highest counter value, in whole units, before it wraps around to 0
+    pub counter_max: u32,
+    /// How finely the counter counts
+    pub precision: Precision,
+    /// Who takes the first turn of each round
+    pub turn_order: TurnOrder,
+    /// How the loser of a round loses vitality
+    pub damage: DamageModel,
+    /// How the counter speeds up from round to round
+    pub speed_curve: SpeedCurve,
+    /// How the targets are spread over the dial
+    pub distribution: TargetDistribution,
+    /// Least distance between two targets of a turn, in whole units, if they must differ
+    pub target_spacing: Option<u32>,
+    /// How the match is won, besides the opponent running out of vitality
+    pub win_condition: WinCondition,
+    /// The penalties and bonuses the players did not ban
+    pub pool: DraftPool,
+    /// The lowest and highest value of each player attribute
+    pub bounds: AttributeBounds,
+    /// Delay between two counter ticks, in milliseconds, for a player with a speed of 100
+    pub base_tick_ms: u32,
+}
+
+impl RuleSet {
+    /// Returns the dial the counter runs through under these rules.
+    ///
+    /// # Returns
+    ///
+    /// The dial for the counter range and precision
+    pub fn dial(&self) -> Dial {
+        Dial::new(self.counter_max, self.precision)
+    }
+
+    /// Draws the targets of a turn.
+    ///
+    /// With a target spacing, draws that fall too close to a target already
+    /// kept, going around the dial, are drawn again. If the dial has no room
+    /// left for the spacing, the last targets are kept wherever they fall.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to draw from
+    /// * `count` - How many targets to draw
+    ///
+    /// # Returns
+    ///
+    /// The targets, each between 0 and the highest counter value, spread as the distribution says
+    pub fn targets<R: Rng>(&self, rng: &mut R, count: usize) -> Vec<u32> {
+        let Some(spacing) = self.target_spacing else {
+            return self.distribution.draw(rng, count, self.counter_max);
+        };
+        // Targets are drawn in whole units, whatever the precision
+        let dial = Dial::new(self.counter_max, Precision::Normal);
+        let mut targets: Vec<u32> = Vec::with_capacity(count);
+        for _ in 0..MAX_TARGET_DRAWS {
+            for candidate in self.distribution.draw(rng, count, self.counter_max) {
+                if targets.len() < count && targets.iter().all(|&target| scoring::difference(target, candidate, dial) >= spacing) {
+                    targets.push(candidate);
+                }
+            }
+            if targets.len() == count {
+                return targets;
+            }
+        }
+        let missing = count - targets.len();
+        targets.extend(self.distribution.draw(rng, missing, self.counter_max));
+        targets
+    }
+
+    /// Returns the delay between two counter ticks on a player's turn.
+    ///
+    /// The delay is `base_tick_ms * 100 / speed`, so that a change of speed
+    /// changes the pace of the counter in proportion. Each point of momentum
+    /// then slows it down by 1 ms.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The player taking the turn
+    ///
+    /// # Returns
+    ///
+    /// The delay in milliseconds, at least 1 ms
+    pub fn tick_interval(&self, player: &Player) -> u32 {
+        // Without speed the counter crawls as at a speed of 1, it does not stop altogether
+        let interval = match player.speed() {
+            0 => self.base_tick_ms * 100,
+            speed => self.base_tick_ms * 100 / speed,
+        };
+        interval.max(1) + player.momentum()
+    }
+}
+
+impl Default for RuleSet {
+    /// Creates the rules of the classic game.
+    fn default() -> Self {
+        Self {
+            counter_max: DEFAULT_COUNTER_MAX,
+            precision: Precision::Normal,
+            turn_order: TurnOrder::Fixed,
+            damage: DamageModel::Difference,
+            speed_curve: SpeedCurve::Flat,
+            distribution: TargetDistribution::Uniform,
+            target_spacing: None,
+            win_condition: WinCondition::Vitality,
+            pool: DraftPool::default(),
+            bounds: AttributeBounds::default(),
+            base_tick_ms: DEFAULT_BASE_TICK_MS,
+        }
+    }
+}
+
+/// Parses a counter range given by name or as the highest counter value.
+///
+/// # Arguments
+///
+/// * `range` - `percent` (0–100), `clock` (0–60), `degrees` (0–360), or a number
+///
+/// # Returns
+///
+/// Result containing the highest counter value, or an error if the range is not valid
+pub fn parse_counter_max(range: &str) -> GameResult<u32> {
+    match range.to_ascii_lowercase().as_str() {
+        "percent" => Ok(100),
+        "clock" => Ok(60),
+        "degrees" => Ok(360),
+        other => match other.parse::<u32>() {
+            Ok(max) if max >= 10 => Ok(max),
+            _ => Err(GameError::ConfigError(format!("Unknown counter range: {}", other))),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn test_default_rules() {
+        let rules = RuleSet::default();
+        assert_eq!(rules.counter_max, 100);
+        assert_eq!(rules.precision, Precision::Normal);
+        assert_eq!(rules.dial().max(), 100);
+        assert_eq!(rules.turn_order, TurnOrder::Fixed);
+    }
+
+    #[test]
+    fn test_damage_models() {
+        assert_eq!(DamageModel::Difference.damage(70, 40), 70);
+        assert_eq!(DamageModel::Capped(25).damage(70, 40), 25);
+        assert_eq!(DamageModel::Capped(25).damage(10, 40), 10);
+        assert_eq!(DamageModel::Percent(25).damage(70, 40), 10);
+        // Rounded up, so a player on 1 vitality can still lose
+        assert_eq!(DamageModel::Percent(25).damage(70, 1), 1);
+        assert_eq!(DamageModel::Percent(100).damage(70, u32::MAX), u32::MAX);
+        assert_eq!(DamageModel::Percent(50).damage(70, u32::MAX), u32::MAX / 2 + 1);
+        assert_eq!(DamageModel::Fixed(15).damage(70, 40), 15);
+    }
+
+    #[test]
+    fn test_parse_damage_model() {
+        assert_eq!("difference".parse::<DamageModel>().unwrap(), DamageModel::Difference);
+        assert_eq!("Capped:30".parse::<DamageModel>().unwrap(), DamageModel::Capped(30));
+        assert_eq!("percent:20".parse::<DamageModel>().unwrap(), DamageModel::Percent(20));
+        assert_eq!("fixed:10".parse::<DamageModel>().unwrap(), DamageModel::Fixed(10));
+        assert!("percent:150".parse::<DamageModel>().is_err());
+        assert!("capped".parse::<DamageModel>().is_err());
+        assert!("fixed:0".parse::<DamageModel>().is_err());
+        assert!("difference:5".parse::<DamageModel>().is_err());
+        assert!("difference:x".parse::<DamageModel>().is_err());
+    }
+
+    #[test]
+    fn test_win_conditions() {
+        let mut state = GameState::new([
+            Player::new("A".to_string(), 100, 50, 50, 3),
+            Player::new("B".to_string(), 100, 50, 50, 3),
+        ]);
+        state.tally.round_wins = [2, 3];
+        state.tally.scores = [240, 200];
+        state.round = 3;
+
+        assert_eq!(WinCondition::Vitality.winner(&state), None);
+        assert_eq!(WinCondition::RoundWins(3).winner(&state), Some(1));
+        assert_eq!(WinCondition::RoundWins(4).winner(&state), None);
+        assert_eq!(WinCondition::HighScore(3).winner(&state), Some(0));
+        assert_eq!(WinCondition::HighScore(4).winner(&state), None);
+        assert_eq!(WinCondition::SuddenDeath.winner(&state), None);
+        state.tally.exact_hits = [0, 1];
+        assert_eq!(WinCondition::SuddenDeath.winner(&state), Some(1));
+
+        // As a plugin, the win condition also announces the winner
+        let (winner_idx, message) = GamePlugin::winner(&WinCondition::RoundWins(3), &state).unwrap();
+        assert_eq!(winner_idx, 1);
+        assert!(message.contains('B'));
+        assert_eq!(GamePlugin::winner(&WinCondition::Vitality, &state), None);
+    }
+
+    #[test]
+    fn test_parse_win_condition() {
+        assert_eq!("vitality".parse::<WinCondition>().unwrap(), WinCondition::Vitality);
+        assert_eq!("Rounds:3".parse::<WinCondition>().unwrap(), WinCondition::RoundWins(3));
+        assert_eq!("score:5".parse::<WinCondition>().unwrap(), WinCondition::HighScore(5));
+        assert_eq!("sudden-death".parse::<WinCondition>().unwrap(), WinCondition::SuddenDeath);
+        assert!("rounds".parse::<WinCondition>().is_err());
+        assert!("score:0".parse::<WinCondition>().is_err());
+        assert!("knockout".parse::<WinCondition>().is_err());
+    }
+
+    #[test]
+    fn test_draft_keeps_one_item_of_each_kind() {
+        let mut pool = DraftPool::default();
+        assert!(DRAFT_ITEMS.iter().all(|&item| pool.allows(item) && pool.can_ban(item)));
+
+        pool.ban(DraftItem::Penalty(Attribute::Speed));
+        assert!(!pool.allows(DraftItem::Penalty(Attribute::Speed)));
+        assert!(!pool.can_ban(DraftItem::Penalty(Attribute::Strength)));
+        assert!(pool.can_ban(DraftItem::Bonus(Consolation::Vitality)));
+
+        pool.ban(DraftItem::Bonus(Consolation::Speed));
+        pool.ban(DraftItem::Bonus(Consolation::Strength));
+        assert!(!pool.can_ban(DraftItem::Bonus(Consolation::Vitality)));
+    }
+
+    #[test]
+    fn test_picked_items_belong_to_the_picker() {
+        let mut pool = DraftPool::default();
+        let speed = DraftItem::Penalty(Attribute::Speed);
+        let strength = DraftItem::Penalty(Attribute::Strength);
+        assert!(pool.can_pick(speed, 0));
+
+        pool.pick(speed, 0);
+        assert!(pool.allows_for(speed, 0));
+        assert!(!pool.allows_for(speed, 1));
+        assert!(pool.allows(speed));
+        assert!(!pool.can_ban(speed));
+        assert!(!pool.can_pick(speed, 1));
+        // Player 2 would have no penalty left to apply
+        assert!(!pool.can_ban(strength));
+        // Player 1 keeps their own pick, so player 2 may take the other one
+        assert!(pool.can_pick(strength, 1));
+        assert!(!pool.can_pick(strength, 0));
+
+        let saved = serde_json::to_string(&pool).unwrap();
+        assert_eq!(serde_json::from_str::<DraftPool>(&saved).unwrap(), pool);
+    }
+
+    #[test]
+    fn test_speed_curve() {
+        assert_eq!(SpeedCurve::Flat.counter_delay(60, 9), 60);
+        assert_eq!(SpeedCurve::Ramp(10).counter_delay(60, 1), 60);
+        // Round 3 runs 20% faster than round 1
+        assert_eq!(SpeedCurve::Ramp(10).counter_delay(60, 3), 50);
+        assert_eq!(SpeedCurve::Ramp(50).counter_delay(2, 100), 1);
+    }
+
+    #[test]
+    fn test_round_modifiers() {
+        assert_eq!(RoundModifier::default(), RoundModifier::Clear);
+        assert!(RoundModifier::Fog.hides_targets());
+        assert!(!RoundModifier::Storm.hides_targets());
+        assert!(!RoundModifier::Calm.counts_misses());
+        assert!(RoundModifier::Fog.counts_misses());
+        // 20% faster: 6 ticks take the time of 5
+        assert_eq!(RoundModifier::Storm.counter_delay(60), 50);
+        assert_eq!(RoundModifier::Storm.counter_delay(1), 1);
+        assert_eq!(RoundModifier::Calm.counter_delay(60), 60);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let drawn: Vec<_> = (0..100).map(|_| RoundModifier::draw(&mut rng)).collect();
+        assert!(RoundModifier::ALL.iter().all(|modifier| drawn.contains(modifier)));
+    }
+
+    #[test]
+    fn test_turn_order() {
+        let win = RoundOutcome::Win { winner: 0, loser: 1, diff: 10, damage: 10, pot: 0 };
+        assert_eq!(TurnOrder::Fixed.next_first(0, &win), 0);
+        assert_eq!(TurnOrder::Alternate.next_first(0, &win), 1);
+        assert_eq!(TurnOrder::Alternate.next_first(1, &RoundOutcome::Draw), 0);
+        assert_eq!(TurnOrder::LoserFirst.next_first(0, &win), 1);
+        assert_eq!(TurnOrder::LoserFirst.next_first(1, &RoundOutcome::Draw), 1);
+        assert_eq!("Loser-First".parse::<TurnOrder>().unwrap(), TurnOrder::LoserFirst);
+        assert!("random".parse::<TurnOrder>().is_err());
+    }
+
+    #[test]
+    fn test_dial_follows_rules() {
+        let rules = RuleSet { counter_max: 60, precision: Precision::Fine, ..RuleSet::default() };
+        assert_eq!(rules.dial().max(), 600);
+    }
+
+    #[test]
+    fn test_parse_counter_max() {
+        assert_eq!(parse_counter_max("clock").unwrap(), 60);
+        assert_eq!(parse_counter_max("Degrees").unwrap(), 360);
+        assert_eq!(parse_counter_max("percent").unwrap(), 100);
+        assert_eq!(parse_counter_max("250").unwrap(), 250);
+        assert!(parse_counter_max("5").is_err());
+        assert!(parse_counter_max("hours").is_err());
+    }
+
+    #[test]
+    fn test_tick_interval() {
+        let rules = RuleSet::default();
+        let mut player = Player::new("A".to_string(), 100, 50, 50, 3);
+        assert_eq!(rules.tick_interval(&player), 50);
+
+        // Losing a tenth of the speed makes the counter a tenth slower, give or take rounding
+        player.decrease_speed(5);
+        assert_eq!(rules.tick_interval(&player), 55);
+        player.increase_momentum(2);
+        assert_eq!(rules.tick_interval(&player), 57);
+
+        let slow = RuleSet { base_tick_ms: 40, ..RuleSet::default() };
+        assert_eq!(slow.tick_interval(&Player::new("B".to_string(), 100, 100, 50, 3)), 40);
+        // No speed left crawls as at speed 1, rather than a 0 ms tick
+        assert_eq!(slow.tick_interval(&Player::new("C".to_string(), 100, 0, 50, 3)), 4000);
+        assert_eq!(rules.tick_interval(&Player::new("D".to_string(), 100, 999, 50, 3)), 2);
+    }
+
+    #[test]
+    fn test_targets_stay_on_the_dial() {
+        let rules = RuleSet { counter_max: 60, ..RuleSet::default() };
+        let mut rng = StdRng::seed_from_u64(7);
+        let targets = rules.targets(&mut rng, 50);
+
+        assert_eq!(targets.len(), 50);
+        assert!(targets.iter().all(|&t| t <= 60));
+        assert_eq!(targets, rules.targets(&mut StdRng::seed_from_u64(7), 50));
+    }
+
+    #[test]
+    fn test_target_distributions() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let center = TargetDistribution::Center.draw(&mut rng, 200, 100);
+        let middle = center.iter().filter(|&&t| (25..=75).contains(&t)).count();
+        // A uniform draw lands in the middle half about 100 times out of 200
+        assert!(middle > 130, "{} targets in the middle", middle);
+
+        let edges = TargetDistribution::Edges.draw(&mut rng, 200, 100);
+        assert!(edges.iter().all(|&t| t <= 25 || t >= 75));
+        assert!(edges.iter().any(|&t| t <= 25) && edges.iter().any(|&t| t >= 75));
+
+        let sequence = TargetDistribution::Sequence.draw(&mut rng, 10, 60);
+        let step = (sequence[1] + 61 - sequence[0]) % 61;
+        assert!(step > 0);
+        assert!(sequence.windows(2).all(|pair| (pair[1] + 61 - pair[0]) % 61 == step));
+        assert!(sequence.iter().all(|&t| t <= 60));
+
+        // The largest counter range does not overflow
+        for distribution in [TargetDistribution::Center, TargetDistribution::Sequence] {
+            assert_eq!(distribution.draw(&mut rng, 10, u32::MAX).len(), 10);
+        }
+
+        assert_eq!("Edges".parse::<TargetDistribution>().unwrap(), TargetDistribution::Edges);
+        assert!("gaussian".parse::<TargetDistribution>().is_err());
+    }
+
+    #[test]
+    fn test_spaced_targets() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let rules = RuleSet { counter_max: 20, target_spacing: Some(1), ..RuleSet::default() };
+        let mut targets = rules.targets(&mut rng, 21);
+        targets.sort();
+        // The whole dial, each value once
+        assert_eq!(targets, (0..=20).collect::<Vec<_>>());
+
+        let rules = RuleSet { target_spacing: Some(15), ..RuleSet::default() };
+        let targets = rules.targets(&mut rng, 5);
+        let dial = Dial::default();
+        for (idx, &target) in targets.iter().enumerate() {
+            assert!(targets[idx + 1..].iter().all(|&other| scoring::difference(target, other, dial) >= 15), "{:?}", targets);
+        }
+
+        // No room for the spacing: the turn still gets all its targets
+        let rules = RuleSet { counter_max: 10, target_spacing: Some(6), ..RuleSet::default() };
+        assert_eq!(rules.targets(&mut rng, 5).len(), 5);
+    }
+}
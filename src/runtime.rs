@@ -0,0 +1,67 @@
+//! Runtime module for the tasks running alongside the game.
+//!
+//! The counter and its display run as tasks on one async runtime shared by the
+//! whole process, instead of a new pair of threads for every target. Key
+//! presses are read on it too, and waited for alongside their deadline. The
+//! runtime is started the first time a task needs it.
+
+use std::sync::OnceLock;
+
+use tokio::runtime::{Builder, Runtime};
+use tokio::task::JoinHandle;
+
+use crate::error::{GameError, GameResult};
+
+/// Number of worker threads, enough for the counter and its display to run side by side.
+const WORKER_THREADS: usize = 2;
+
+/// The runtime shared by every task of the game.
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// Returns the shared runtime, starting it on first use.
+///
+/// # Returns
+///
+/// Result containing the runtime, or an error if its threads could not be started
+pub fn get() -> GameResult<&'static Runtime> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = Builder::new_multi_thread()
+        .worker_threads(WORKER_THREADS)
+        .thread_name("rust_game-worker")
+        .enable_time()
+        .build()?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Waits for a task to finish.
+///
+/// # Arguments
+///
+/// * `handle` - The handle returned when the task was spawned
+///
+/// # Returns
+///
+/// Result indicating whether the task ran to completion without panicking
+pub fn join(handle: JoinHandle<()>) -> GameResult<()> {
+    get()?.block_on(handle)
+        .map_err(|e| GameError::LogicError(format!("A background task failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_is_shared() {
+        assert!(std::ptr::eq(get().unwrap(), get().unwrap()));
+    }
+
+    #[test]
+    fn test_join() {
+        let runtime = get().unwrap();
+        assert!(join(runtime.spawn(async {})).is_ok());
+        assert!(join(runtime.spawn(async { panic!("task failure") })).is_err());
+    }
+}
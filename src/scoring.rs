@@ -3,12 +3,172 @@
 //! This module provides functions for determining player scores based on how
 //! close they get to target numbers, considering player attributes.
 
-/// Calculates the score for a single target based on the counter value.
+use std::str::FromStr;
+
+use rand::Rng;
+
+use crate::dial::Dial;
+use crate::error::GameError;
+
+/// Largest difference from the target, in ticks, that can produce a critical hit.
+pub const CRITICAL_MAX_DIFFERENCE: u32 = 2;
+
+/// Accurate stops in a row raise the base score by this many tenths each.
+const COMBO_STEP_TENTHS: u32 = 1;
+
+/// Largest difference from the target, in ticks, that keeps a combo going.
+pub const COMBO_MAX_DIFFERENCE: u32 = 5;
+
+/// Points added to a stop that lands exactly on a beat in rhythm mode.
+pub const BEAT_BONUS: u32 = 10;
+
+/// Points added to a turn average that lands close enough to the player's prediction.
+pub const PREDICTION_BONUS: u32 = 10;
+
+/// Largest gap between a prediction and the turn average that still earns the bonus.
+pub const PREDICTION_MARGIN: u32 = 5;
+
+/// The scoring table: the base score of a stop at most the given number of ticks from its target.
+///
+/// Stops farther than the last bracket score nothing.
+pub const SCORING_TABLE: [(u32, u32); 5] = [(0, 100), (5, 80), (10, 60), (20, 40), (50, 20)];
+
+/// Highest base score of a safe target.
+pub const SAFE_CAP: u32 = 60;
+
+/// How much a player risks on a target, chosen before it is played.
+///
+/// A safe target scores within brackets twice as wide, but never more than
+/// `SAFE_CAP`. A risky one needs a stop within brackets half as wide, and
+/// scores half as much again, up to 150 for an exact hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RiskTier {
+    /// Wider brackets, lower scores
+    Safe,
+    /// The scoring table as it is
+    #[default]
+    Normal,
+    /// Narrower brackets, higher scores
+    Risky,
+}
+
+impl RiskTier {
+    /// Every risk tier, from the safest.
+    pub const ALL: [RiskTier; 3] = [RiskTier::Safe, RiskTier::Normal, RiskTier::Risky];
+
+    /// Returns the base score of a stop at this risk.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - The distance between the stop and the target, in ticks
+    ///
+    /// # Returns
+    ///
+    /// The base score, before strength and misses are taken into account
+    pub fn base_score(self, diff: u32) -> u32 {
+        let bracket = |width: fn(u32) -> u32| {
+            SCORING_TABLE.iter().find(|&&(max_diff, _)| diff <= width(max_diff)).map_or(0, |&(_, score)| score)
+        };
+        match self {
+            RiskTier::Safe => bracket(|max_diff| max_diff * 2).min(SAFE_CAP),
+            RiskTier::Normal => bracket(|max_diff| max_diff),
+            RiskTier::Risky => bracket(|max_diff| max_diff / 2) * 3 / 2,
+        }
+    }
+}
+
+/// How the score of a single target was obtained.
 ///
 /// The score is calculated using the formula:
-/// (base_score + strength) / (miss + 1)
+/// (base_score * combo multiplier + strength) / (miss + 1)
 ///
-/// Where base_score is determined by the difference between target and counter_value.
+/// Where base_score is determined by the difference between target and counter_value,
+/// and the combo multiplier is x1.1, x1.2, ... after 1, 2, ... accurate stops in a row.
+/// A stop on a beat adds `BEAT_BONUS` points, a gamble then doubles the score on a hit, a stop within
+/// `COMBO_MAX_DIFFERENCE` of the target, or zeroes it otherwise, and a critical hit multiplies it by 1.5.
+/// The hit is kept apart from the base score, which depends on the risk taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    /// Base score from the scoring table
+    pub base: u32,
+    /// Accurate stops in a row before this one
+    pub combo: u32,
+    /// The player's strength attribute
+    pub strength: u32,
+    /// The number of times the counter looped back to zero
+    pub miss: u32,
+    /// Whether the stop landed within `COMBO_MAX_DIFFERENCE` of the target
+    pub hit: bool,
+    /// Whether the player gambled double or nothing on the target
+    pub gamble: bool,
+    /// Whether the stop was a critical hit
+    pub critical: bool,
+    /// Whether the stop landed exactly on a beat
+    pub on_beat: bool,
+}
+
+impl ScoreBreakdown {
+    /// Creates the breakdown of a stop, without gamble, critical hit, or beat.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target number to match
+    /// * `counter_value` - The counter value when stopped
+    /// * `strength` - The player's strength attribute
+    /// * `miss` - The number of times the counter looped back to zero
+    /// * `combo` - Accurate stops in a row before this one
+    /// * `dial` - The values the counter runs through
+    ///
+    /// # Returns
+    ///
+    /// A new ScoreBreakdown
+    pub fn new(target: u32, counter_value: u32, strength: u32, miss: u32, combo: u32, dial: Dial) -> Self {
+        Self {
+            base: base_score(target, counter_value, dial),
+            combo,
+            strength,
+            miss,
+            hit: is_accurate(target, counter_value, dial),
+            gamble: false,
+            critical: false,
+            on_beat: false,
+        }
+    }
+
+    /// Returns the base score raised by the combo multiplier, rounded up.
+    ///
+    /// Like `total`, it saturates at `u32::MAX` rather than overflow.
+    pub fn combo_base(&self) -> u32 {
+        let tenths = 10 + u64::from(self.combo) * u64::from(COMBO_STEP_TENTHS);
+        saturate(u64::from(self.base).saturating_mul(tenths).div_ceil(10))
+    }
+
+    /// Returns the final score of the target.
+    ///
+    /// Out of range inputs, e.g. from a script or the C API, saturate at `u32::MAX`.
+    pub fn total(&self) -> u32 {
+        let numerator = self.combo_base().saturating_add(self.strength);
+        // Calculate final score as a float and round up.
+        let score = (numerator as f64 / (self.miss as f64 + 1.0)).ceil() as u32;
+        let score = if self.on_beat { score.saturating_add(BEAT_BONUS) } else { score };
+
+        let score = match (self.gamble, self.hit) {
+            (false, _) => score,
+            (true, true) => score.saturating_mul(2),
+            (true, false) => 0,
+        };
+        if self.critical {
+            apply_critical(score)
+        } else {
+            score
+        }
+    }
+}
+
+/// Calculates the score for a single target based on the counter value.
+///
+/// Shorthand for a stop without combo, gamble, or critical hit, on the default
+/// dial. It is the scoring the C API exposes as `rg_calculate_score`.
 ///
 /// # Arguments
 ///
@@ -21,35 +181,226 @@
 ///
 /// The calculated score according to the scoring formula
 pub fn calculate_score(target: u32, counter_value: u32, strength: u32, miss: u32) -> u32 {
-    // Calculate the distance considering the circular nature (0-100)
-    let direct_distance = if target > counter_value {
-        target - counter_value
-    } else {
-        counter_value - target
-    };
-
-    // Handle circular difference on a dial of 0..100.
-    // If the direct distance is 100, treat it as 1; otherwise, use the minimum of the direct distance
-    // and the “wrap–around” distance (100 - direct_distance).
-    let difference = if direct_distance == 100 {
-        1
-    } else {
-        std::cmp::min(direct_distance, 100 - direct_distance)
-    };
-
-    // Determine base score from the scoring table.
-    let base_score = match difference {
-        0 => 100,
-        1..=5 => 80,
-        6..=10 => 60,
-        11..=20 => 40,
-        21..=50 => 20,
-        _ => 0,
-    };
-
-    let numerator = base_score + strength;
-    // Calculate final score as a float and round up.
-    (numerator as f64 / (miss as f64 + 1.0)).ceil() as u32
+    ScoreBreakdown::new(target, counter_value, strength, miss, 0, Dial::default()).total()
+}
+
+/// Returns the multiplier applied to the base score after a number of accurate stops.
+///
+/// # Arguments
+///
+/// * `combo` - Accurate stops in a row
+///
+/// # Returns
+///
+/// The multiplier, e.g. 1.2 after two accurate stops
+pub fn combo_multiplier(combo: u32) -> f64 {
+    f64::from(10 + combo * COMBO_STEP_TENTHS) / 10.0
+}
+
+/// Returns whether a stop is close enough to the target to keep a combo going.
+///
+/// # Arguments
+///
+/// * `target` - The target number to match
+/// * `counter_value` - The counter value when stopped
+/// * `dial` - The values the counter runs through
+///
+/// # Returns
+///
+/// True if the difference is at most `COMBO_MAX_DIFFERENCE` ticks
+pub fn is_accurate(target: u32, counter_value: u32, dial: Dial) -> bool {
+    difference(target, counter_value, dial) <= COMBO_MAX_DIFFERENCE
+}
+
+/// Returns whether a counter value falls exactly on a beat.
+///
+/// # Arguments
+///
+/// * `counter_value` - The counter value when stopped
+/// * `beat` - Number of ticks between two beats
+///
+/// # Returns
+///
+/// True if the value is a multiple of the beat
+pub fn is_on_beat(counter_value: u32, beat: u32) -> bool {
+    counter_value.is_multiple_of(beat)
+}
+
+/// Works out the bonus a player earns for predicting their turn average.
+///
+/// # Arguments
+///
+/// * `prediction` - The average the player predicted before the turn
+/// * `average` - The average the turn scored
+///
+/// # Returns
+///
+/// `PREDICTION_BONUS` if the average is within `PREDICTION_MARGIN` of the prediction, 0 otherwise
+pub fn prediction_bonus(prediction: u32, average: u32) -> u32 {
+    if prediction.abs_diff(average) <= PREDICTION_MARGIN { PREDICTION_BONUS } else { 0 }
+}
+
+/// Determines the base score from the scoring table for a single target.
+///
+/// The table is applied to the difference in ticks, so its brackets are
+/// ten times narrower in fine precision.
+///
+/// # Arguments
+///
+/// * `target` - The target number to match
+/// * `counter_value` - The counter value when stopped
+/// * `dial` - The values the counter runs through
+///
+/// # Returns
+///
+/// The base score, before strength and misses are taken into account
+pub fn base_score(target: u32, counter_value: u32, dial: Dial) -> u32 {
+    RiskTier::Normal.base_score(difference(target, counter_value, dial))
+}
+
+/// Calculates the distance between two values on a circle of `modulus` values.
+///
+/// # Arguments
+///
+/// * `a` - The first value
+/// * `b` - The second value
+/// * `modulus` - The number of values on the circle, must not be zero
+///
+/// # Returns
+///
+/// The shortest distance between the two values, going around the circle if needed
+pub fn circular_distance(a: u32, b: u32, modulus: u64) -> u32 {
+    let direct_distance = u64::from(a.abs_diff(b)) % modulus;
+    // The shorter way round is at most half the circle, so it fits back in a u32
+    std::cmp::min(direct_distance, modulus - direct_distance) as u32
+}
+
+/// Calculates the difference between the target and the counter value on the dial.
+///
+/// # Arguments
+///
+/// * `target` - The target number to match
+/// * `counter_value` - The counter value when stopped
+/// * `dial` - The values the counter runs through
+///
+/// # Returns
+///
+/// The shortest distance between the two values in ticks, going around the dial if needed
+pub fn difference(target: u32, counter_value: u32, dial: Dial) -> u32 {
+    // The dial runs from 0 to its maximum, both included
+    circular_distance(target, counter_value, u64::from(dial.max()) + 1)
+}
+
+/// How the stops of a multi-stop target are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StopRule {
+    /// The stops must add up to the target, going around the dial
+    #[default]
+    Sum,
+    /// The stops must enclose the target as tightly as possible
+    Bracket,
+}
+
+impl FromStr for StopRule {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sum" => Ok(StopRule::Sum),
+            "bracket" => Ok(StopRule::Bracket),
+            other => Err(GameError::ConfigError(format!("Unknown stop rule: {}", other))),
+        }
+    }
+}
+
+/// Combines the stops of a multi-stop target into a single counter value.
+///
+/// With `StopRule::Sum`, the value is the sum of the stops going around the dial.
+/// With `StopRule::Bracket`, the value is the single stop that would score the same:
+/// as far from the target as half the width of the bracket when the stops enclose
+/// the target, or as the farthest stop around the dial otherwise.
+///
+/// # Arguments
+///
+/// * `rule` - How the stops are combined
+/// * `target` - The target number to match
+/// * `stops` - The counter values the player stopped at, at least one
+/// * `dial` - The values the counter runs through
+///
+/// # Returns
+///
+/// The counter value the target is scored against
+pub fn composite_value(rule: StopRule, target: u32, stops: &[u32], dial: Dial) -> u32 {
+    let max = dial.max();
+    match rule {
+        StopRule::Sum => stops.iter().sum::<u32>() % (max + 1),
+        StopRule::Bracket => {
+            let low = stops.iter().copied().min().unwrap_or(target);
+            let high = stops.iter().copied().max().unwrap_or(target);
+            let distance = if (low..=high).contains(&target) {
+                (high - low).div_ceil(2)
+            } else {
+                difference(target, low, dial).max(difference(target, high, dial))
+            };
+            // No difference on the dial is larger than half of it
+            let distance = distance.min(max / 2);
+            if target + distance <= max { target + distance } else { target - distance }
+        },
+    }
+}
+
+/// Returns the probability that a close stop is a critical hit.
+///
+/// The chance is `strength / 200`, capped at 50% from a strength of 100.
+///
+/// # Arguments
+///
+/// * `strength` - The player's strength attribute
+///
+/// # Returns
+///
+/// The probability of a critical hit, between 0.0 and 0.5
+pub fn critical_chance(strength: u32) -> f64 {
+    f64::from(strength.min(100)) / 200.0
+}
+
+/// Decides whether a stop is a critical hit.
+///
+/// Only stops within `CRITICAL_MAX_DIFFERENCE` of the target can be critical,
+/// with the probability given by `critical_chance`. The RNG is only drawn
+/// from for such stops, so replays with the same seed stay in sync.
+///
+/// # Arguments
+///
+/// * `target` - The target number to match
+/// * `counter_value` - The counter value when stopped
+/// * `strength` - The player's strength attribute
+/// * `dial` - The values the counter runs through
+/// * `rng` - The game's random number generator
+///
+/// # Returns
+///
+/// True if the stop is a critical hit
+pub fn roll_critical<R: Rng>(target: u32, counter_value: u32, strength: u32, dial: Dial, rng: &mut R) -> bool {
+    difference(target, counter_value, dial) <= CRITICAL_MAX_DIFFERENCE && rng.random_bool(critical_chance(strength))
+}
+
+/// Applies the critical hit bonus to a score.
+///
+/// # Arguments
+///
+/// * `score` - The score of the target
+///
+/// # Returns
+///
+/// The score multiplied by 1.5, rounded up
+pub fn apply_critical(score: u32) -> u32 {
+    saturate((u64::from(score) * 3).div_ceil(2))
+}
+
+/// Narrows a score worked out on 64 bits, saturating at `u32::MAX`.
+fn saturate(score: u64) -> u32 {
+    u32::try_from(score).unwrap_or(u32::MAX)
 }
 
 /// Calculates the average score from a collection of individual scores.
@@ -66,14 +417,53 @@ pub fn calculate_average_score(scores: &[u32]) -> u32 {
         return 0;
     }
 
-    let sum: u32 = scores.iter().sum();
-    let avg = (sum as f64) / (scores.len() as f64);
+    average_of(scores.iter().copied().map(u64::from).sum(), scores.len())
+}
+
+/// Averages scores already summed, rounding up like `calculate_average_score`.
+///
+/// # Arguments
+///
+/// * `sum` - The sum of the scores
+/// * `count` - The number of scores
+///
+/// # Returns
+///
+/// The average score, rounded up, 0 for no score
+pub fn average_of(sum: u64, count: usize) -> u32 {
+    if count == 0 {
+        return 0;
+    }
+    let avg = (sum as f64) / (count as f64);
     avg.ceil() as u32 // Round up to nearest integer
 }
 
+/// Works out what a turn still needs to average more than the opponent.
+///
+/// Turn averages are rounded up, so the turn must score more than `to_beat`
+/// times the number of targets in total to win the round.
+///
+/// # Arguments
+///
+/// * `to_beat` - The opponent's average score for the round
+/// * `sum` - The points scored so far this turn
+/// * `played` - The number of targets played so far
+/// * `total` - The number of targets in the turn
+///
+/// # Returns
+///
+/// The points needed on each remaining target, 0 if the opponent is already
+/// beaten, or None if no target remains
+pub fn needed_per_target(to_beat: u32, sum: u32, played: usize, total: usize) -> Option<u32> {
+    let remaining = total.checked_sub(played).filter(|&remaining| remaining > 0)? as u64;
+    let needed = (u64::from(to_beat) * total as u64 + 1).saturating_sub(u64::from(sum));
+    Some(needed.div_ceil(remaining).try_into().unwrap_or(u32::MAX))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dial::Precision;
 
     #[test]
     fn test_score_calculation() {
@@ -96,9 +486,9 @@ mod tests {
         assert_eq!(calculate_score(50, 80, 50, 0), 70);
         assert_eq!(calculate_score(50, 25, 50, 0), 70);
 
-        // Test circular difference (95 to 15)
-        assert_eq!(calculate_score(15, 95, 50, 0), 90); // Difference is 20 (going around the circle)
-        assert_eq!(calculate_score(95, 15, 50, 0), 90); // Difference is 20 (going around the circle)
+        // Test circular difference (95 to 14)
+        assert_eq!(calculate_score(14, 95, 50, 0), 90); // Difference is 20 (going around the circle)
+        assert_eq!(calculate_score(95, 14, 50, 0), 90); // Difference is 20 (going around the circle)
 
         // Test circular edge cases
         assert_eq!(calculate_score(0, 100, 50, 0), 130); // Difference is 1 (going around the circle)
@@ -109,6 +499,187 @@ mod tests {
         assert_eq!(calculate_score(50, 50, 50, 2), 50); // (100 + 50) / (2 + 1)
     }
 
+    #[test]
+    fn test_base_score() {
+        assert_eq!(base_score(50, 50, Dial::default()), 100);
+        assert_eq!(base_score(50, 55, Dial::default()), 80);
+        assert_eq!(base_score(50, 60, Dial::default()), 60);
+        assert_eq!(base_score(50, 70, Dial::default()), 40);
+        assert_eq!(base_score(50, 100, Dial::default()), 20);
+        assert_eq!(base_score(0, 50, Dial::default()), 20);
+        assert_eq!(base_score(0, 100, Dial::default()), 80);
+    }
+
+    #[test]
+    fn test_gamble_score() {
+        let gamble = |target, value, miss| {
+            ScoreBreakdown { gamble: true, ..ScoreBreakdown::new(target, value, 50, miss, 0, Dial::default()) }.total()
+        };
+
+        // Hits within 5 double the usual score
+        assert_eq!(gamble(50, 50, 0), 300);
+        assert_eq!(gamble(50, 55, 0), 260);
+        assert_eq!(gamble(0, 98, 1), 130);
+
+        // Anything further away scores nothing
+        assert_eq!(gamble(50, 56, 0), 0);
+        assert_eq!(gamble(50, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_combo_score() {
+        // x1.1 then x1.2 on the base score, before strength is added
+        assert_eq!(ScoreBreakdown::new(50, 50, 50, 0, 1, Dial::default()).combo_base(), 110);
+        assert_eq!(ScoreBreakdown::new(50, 52, 50, 0, 2, Dial::default()).total(), 96 + 50);
+        assert_eq!(ScoreBreakdown::new(50, 52, 50, 1, 2, Dial::default()).total(), 73);
+        assert_eq!(combo_multiplier(0), 1.0);
+        assert_eq!(combo_multiplier(3), 1.3);
+
+        assert!(is_accurate(50, 55, Dial::default()));
+        assert!(is_accurate(98, 2, Dial::default()));
+        assert!(!is_accurate(50, 56, Dial::default()));
+    }
+
+    #[test]
+    fn test_breakdown_saturates() {
+        let breakdown = ScoreBreakdown { base: u32::MAX, combo: u32::MAX, strength: u32::MAX, gamble: true, critical: true,
+                                         on_beat: true, ..ScoreBreakdown::new(40, 40, 0, 0, 0, Dial::default()) };
+        assert_eq!(breakdown.combo_base(), u32::MAX);
+        assert_eq!(breakdown.total(), u32::MAX);
+        assert_eq!(calculate_average_score(&[u32::MAX, u32::MAX]), u32::MAX);
+    }
+
+    #[test]
+    fn test_breakdown_critical() {
+        let breakdown = ScoreBreakdown { critical: true, ..ScoreBreakdown::new(50, 51, 50, 0, 1, Dial::default()) };
+        // (88 + 50) * 1.5
+        assert_eq!(breakdown.total(), 207);
+    }
+
+    #[test]
+    fn test_risk_tiers() {
+        let scores = |risk: RiskTier| [0, 5, 8, 20, 40, 60].map(|diff| risk.base_score(diff));
+        assert_eq!(scores(RiskTier::Normal), [100, 80, 60, 40, 20, 0]);
+        assert_eq!(scores(RiskTier::Safe), [60, 60, 60, 60, 40, 20]);
+        assert_eq!(scores(RiskTier::Risky), [150, 90, 60, 30, 0, 0]);
+
+        // A safe stop 4 away scores less than 80, but still wins a gamble
+        let breakdown = ScoreBreakdown { base: RiskTier::Safe.base_score(4), gamble: true, ..ScoreBreakdown::new(40, 44, 50, 0, 0, Dial::default()) };
+        assert_eq!(breakdown.base, 60);
+        assert_eq!(breakdown.total(), 2 * (60 + 50));
+    }
+
+    #[test]
+    fn test_prediction_bonus() {
+        assert_eq!(prediction_bonus(80, 80), PREDICTION_BONUS);
+        assert_eq!(prediction_bonus(80, 75), PREDICTION_BONUS);
+        assert_eq!(prediction_bonus(80, 85), PREDICTION_BONUS);
+        assert_eq!(prediction_bonus(80, 86), 0);
+        assert_eq!(prediction_bonus(0, 120), 0);
+    }
+
+    #[test]
+    fn test_beat_bonus() {
+        let on_beat = |value, miss| {
+            ScoreBreakdown { on_beat: true, ..ScoreBreakdown::new(50, value, 50, miss, 0, Dial::default()) }.total()
+        };
+        assert_eq!(on_beat(50, 0), 100 + 50 + BEAT_BONUS);
+        // The bonus is added after the miss penalty
+        assert_eq!(on_beat(50, 1), 75 + BEAT_BONUS);
+
+        assert!(is_on_beat(40, 10));
+        assert!(is_on_beat(0, 10));
+        assert!(!is_on_beat(45, 10));
+        assert!(is_on_beat(450, 50));
+    }
+
+    #[test]
+    fn test_composite_value() {
+        // Sums go around the dial
+        assert_eq!(composite_value(StopRule::Sum, 60, &[20, 40], Dial::default()), 60);
+        assert_eq!(composite_value(StopRule::Sum, 60, &[80, 40, 41], Dial::default()), 60);
+        
+        // A tight bracket scores like a close stop
+        assert_eq!(composite_value(StopRule::Bracket, 50, &[49, 51], Dial::default()), 51);
+        assert_eq!(composite_value(StopRule::Bracket, 50, &[50, 50], Dial::default()), 50);
+        assert_eq!(composite_value(StopRule::Bracket, 95, &[80, 100], Dial::default()), 85);
+        // Missing the target counts the farthest stop
+        assert_eq!(composite_value(StopRule::Bracket, 50, &[55, 60], Dial::default()), 60);
+        // 0 is 11 away from 90 going around the dial, and 10 is 21 away
+        assert_eq!(composite_value(StopRule::Bracket, 90, &[0, 10], Dial::default()), 69);
+        
+        assert_eq!("Bracket".parse::<StopRule>().unwrap(), StopRule::Bracket);
+        assert!("product".parse::<StopRule>().is_err());
+    }
+
+    #[test]
+    fn test_fine_precision() {
+        let fine = Dial::new(100, Precision::Fine);
+        
+        // The scoring brackets are measured in tenths
+        assert_eq!(base_score(500, 500, fine), 100);
+        assert_eq!(base_score(500, 505, fine), 80);
+        assert_eq!(base_score(500, 510, fine), 60);
+        assert_eq!(base_score(500, 550, fine), 20);
+        assert_eq!(base_score(0, 1000, fine), 80);
+        assert!(is_accurate(500, 495, fine));
+        assert!(!is_accurate(500, 506, fine));
+        assert_eq!(composite_value(StopRule::Sum, 100, &[600, 501], fine), 100);
+    }
+
+    #[test]
+    fn test_circular_distance() {
+        assert_eq!(circular_distance(3, 3, 10), 0);
+        assert_eq!(circular_distance(2, 7, 10), 5);
+        assert_eq!(circular_distance(1, 9, 10), 2);
+        assert_eq!(circular_distance(9, 1, 10), 2);
+        assert_eq!(circular_distance(0, 10, 10), 0);
+        assert_eq!(circular_distance(0, 1, 1), 0);
+        assert_eq!(circular_distance(0, u32::MAX, 1 << 32), 1);
+    }
+
+    #[test]
+    fn test_counter_range() {
+        let degrees = Dial::new(360, Precision::Normal);
+        
+        // Differences go around the whole range
+        assert_eq!(difference(10, 350, degrees), 21);
+        assert_eq!(difference(0, 360, degrees), 1);
+        assert_eq!(difference(0, 359, degrees), 2);
+        // The scoring table is unchanged, so far stops can now score nothing
+        assert_eq!(base_score(0, 180, degrees), 0);
+        assert_eq!(composite_value(StopRule::Sum, 20, &[300, 81], degrees), 20);
+    }
+
+    #[test]
+    fn test_critical_chance() {
+        assert_eq!(critical_chance(0), 0.0);
+        assert_eq!(critical_chance(50), 0.25);
+        assert_eq!(critical_chance(100), 0.5);
+        assert_eq!(critical_chance(250), 0.5);
+    }
+
+    #[test]
+    fn test_roll_critical() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(7);
+        // Never critical beyond the maximum difference, or without strength
+        assert!(!roll_critical(50, 53, 100, Dial::default(), &mut rng));
+        assert!(!roll_critical(50, 50, 0, Dial::default(), &mut rng));
+
+        // Close stops are critical about as often as the documented chance
+        let hits = (0..1000).filter(|_| roll_critical(50, 51, 100, Dial::default(), &mut rng)).count();
+        assert!((400..600).contains(&hits));
+    }
+
+    #[test]
+    fn test_apply_critical() {
+        assert_eq!(apply_critical(100), 150);
+        assert_eq!(apply_critical(75), 113);
+        assert_eq!(apply_critical(0), 0);
+    }
+
     #[test]
     fn test_average_score_calculation() {
         // Test with normal values
@@ -124,6 +695,20 @@ mod tests {
         assert_eq!(calculate_average_score(&[10, 11]), 11); // 10.5 rounds up to 11
     }
 
+    #[test]
+    fn test_needed_per_target() {
+        // 5 targets against 80: 401 points are needed, 161 are left after 240
+        assert_eq!(needed_per_target(80, 240, 3, 5), Some(81));
+        assert_eq!(needed_per_target(80, 100, 1, 5), Some(76));
+        // Already beaten
+        assert_eq!(needed_per_target(80, 450, 4, 5), Some(0));
+        // Nothing left to play
+        assert_eq!(needed_per_target(80, 240, 5, 5), None);
+        // The answer matches the rounded-up average
+        assert!(calculate_average_score(&[100, 100, 40, 81, 80]) > 80);
+        assert_eq!(calculate_average_score(&[100, 100, 40, 80, 80]), 80);
+    }
+
     #[test]
     fn test_score_zero_miss_zero_strength() {
         // Edge case: zero strength
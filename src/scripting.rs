@@ -0,0 +1,247 @@
+//! Scripting module for custom rules written in Lua, with the `lua` feature.
+//!
+//! `--script rules.lua` loads a script whose global functions hook into the
+//! match, without recompiling the game. Each hook is optional:
+//!
+//! ```lua
+//! -- Called when a round starts, may return a message shown to the players
+//! function on_round_start(round)
+//!   if round % 3 == 0 then return "Every third round, strength counts twice" end
+//! end
+//!
+//! -- Called for each stopped target with the parts of its score
+//! -- (base, combo, strength, miss, gamble, critical, on_beat), changed in place
+//! function modify_score(score)
+//!   score.strength = score.strength * 2
+//! end
+//!
+//! -- Called before a penalty is chosen, returns the points it takes
+//! function on_penalty(amount)
+//!   return amount + 5
+//! end
+//! ```
+//!
+//! The script runs as one more plugin, after the `--mutators`. A hook that
+//! fails is logged and leaves the match unchanged, and the numbers of a score
+//! are kept to at most `MAX_SCORE_PART`.
+
+use std::path::Path;
+
+use crate::error::GameResult;
+use crate::plugins::GamePlugin;
+
+#[cfg(feature = "lua")]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "lua")]
+use mlua::{Function, Lua, Value};
+
+#[cfg(feature = "lua")]
+use crate::error::GameError;
+#[cfg(feature = "lua")]
+use crate::scoring::ScoreBreakdown;
+
+/// Highest value a script may give the base, combo, strength or misses of a score.
+pub const MAX_SCORE_PART: u32 = 10_000;
+
+/// The rules of a Lua script.
+#[cfg(feature = "lua")]
+#[derive(Clone)]
+pub struct LuaRules {
+    /// The interpreter holding the script, shared by the copies of the settings
+    lua: Arc<Mutex<Lua>>,
+}
+
+#[cfg(feature = "lua")]
+impl std::fmt::Debug for LuaRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LuaRules")
+    }
+}
+
+#[cfg(feature = "lua")]
+impl LuaRules {
+    /// Runs a script, which defines its hooks.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The Lua source of the script
+    /// * `name` - The name of the script in error messages, usually its path
+    ///
+    /// # Returns
+    ///
+    /// Result containing the rules, or `GameError::ConfigError` if the script does not run
+    pub fn new(source: &str, name: &str) -> GameResult<Self> {
+        let lua = Lua::new();
+        lua.load(source).set_name(name).exec()
+            .map_err(|e| GameError::ConfigError(format!("Invalid rules script {}: {}", name, e)))?;
+        Ok(Self { lua: Arc::new(Mutex::new(lua)) })
+    }
+
+    /// Calls a hook of the script, if it defines it.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The name of the global function
+    /// * `call` - What to do with the function
+    ///
+    /// # Returns
+    ///
+    /// What the call returned, or None if the script does not define the hook or it failed
+    fn with_hook<T>(&self, hook: &str, call: impl for<'lua> FnOnce(&'lua Lua, Function<'lua>) -> mlua::Result<T>) -> Option<T> {
+        let lua = self.lua.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let result = match lua.globals().get::<_, Option<Function>>(hook) {
+            Ok(Some(function)) => call(&lua, function),
+            Ok(None) => return None,
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| log::warn!("The {} hook of the rules script failed: {}", hook, e)).ok()
+    }
+}
+
+#[cfg(feature = "lua")]
+impl GamePlugin for LuaRules {
+    fn name(&self) -> &'static str {
+        "script"
+    }
+
+    fn round_start(&self, round: u32) -> Option<String> {
+        self.with_hook("on_round_start", |_, function| function.call::<_, Option<String>>(round)).flatten()
+    }
+
+    fn score(&self, breakdown: &mut ScoreBreakdown) {
+        let changed = self.with_hook("modify_score", |lua, function| {
+            let score = lua.create_table()?;
+            score.set("base", breakdown.base)?;
+            score.set("combo", breakdown.combo)?;
+            score.set("strength", breakdown.strength)?;
+            score.set("miss", breakdown.miss)?;
+            score.set("gamble", breakdown.gamble)?;
+            score.set("critical", breakdown.critical)?;
+            score.set("on_beat", breakdown.on_beat)?;
+            // The hook may change the table in place or return another one
+            let score = match function.call::<_, Value>(score.clone())? {
+                Value::Table(returned) => returned,
+                _ => score,
+            };
+            let part = |key: &str| score.get::<_, u32>(key).map(|value| value.min(MAX_SCORE_PART));
+            Ok(ScoreBreakdown {
+                base: part("base")?,
+                combo: part("combo")?,
+                strength: part("strength")?,
+                miss: part("miss")?,
+                // Where the stop landed is not for a script to change
+                hit: breakdown.hit,
+                gamble: score.get("gamble")?,
+                critical: score.get("critical")?,
+                on_beat: score.get("on_beat")?,
+            })
+        });
+        if let Some(changed) = changed {
+            *breakdown = changed;
+        }
+    }
+
+    fn penalty(&self, amount: u32) -> u32 {
+        self.with_hook("on_penalty", |_, function| function.call::<_, Option<u32>>(amount))
+            .flatten()
+            .unwrap_or(amount)
+    }
+
+    fn clone_box(&self) -> Box<dyn GamePlugin> {
+        Box::new(self.clone())
+    }
+}
+
+/// Loads the rules of a Lua script.
+///
+/// # Arguments
+///
+/// * `path` - The script file
+///
+/// # Returns
+///
+/// Result containing the script as a plugin, or an error if it cannot be read or does not run
+#[cfg(feature = "lua")]
+pub fn load(path: &Path) -> GameResult<Box<dyn GamePlugin>> {
+    let source = crate::persistence::read_file(path)?;
+    Ok(Box::new(LuaRules::new(&source, &path.display().to_string())?))
+}
+
+/// Loads the rules of a Lua script, which this build cannot do.
+///
+/// # Arguments
+///
+/// * `_path` - The script file
+///
+/// # Returns
+///
+/// `GameError::ConfigError`, the game was built without the `lua` feature
+#[cfg(not(feature = "lua"))]
+pub fn load(_path: &Path) -> GameResult<Box<dyn GamePlugin>> {
+    Err(crate::error::GameError::ConfigError("Rules scripts need a build with the lua feature".to_string()))
+}
+
+#[cfg(all(test, feature = "lua"))]
+mod tests {
+    use super::*;
+    use crate::dial::Dial;
+
+    #[test]
+    fn test_hooks_change_the_rules() {
+        let rules = LuaRules::new(r#"
+            function on_round_start(round) return "Round " .. round .. " is scripted" end
+            function modify_score(score) score.strength = score.strength * 2 end
+            function on_penalty(amount) return amount + 5 end
+        "#, "test").unwrap();
+
+        assert_eq!(rules.round_start(2).as_deref(), Some("Round 2 is scripted"));
+        let mut breakdown = ScoreBreakdown::new(40, 40, 30, 0, 0, Dial::default());
+        rules.score(&mut breakdown);
+        assert_eq!(breakdown.strength, 60);
+        assert_eq!(rules.penalty(5), 10);
+    }
+
+    #[test]
+    fn test_missing_hooks_change_nothing() {
+        let rules = LuaRules::new("x = 1", "test").unwrap();
+        let mut breakdown = ScoreBreakdown::new(40, 42, 30, 1, 0, Dial::default());
+        let before = breakdown;
+        rules.score(&mut breakdown);
+
+        assert_eq!(breakdown, before);
+        assert_eq!(rules.round_start(1), None);
+        assert_eq!(rules.penalty(5), 5);
+    }
+
+    #[test]
+    fn test_failing_hook_is_ignored() {
+        let rules = LuaRules::new(r#"
+            function modify_score(score) score.base = -1 end
+            function on_penalty(amount) error("no penalty today") end
+        "#, "test").unwrap();
+        let mut breakdown = ScoreBreakdown::new(40, 40, 30, 0, 0, Dial::default());
+        let before = breakdown;
+        rules.score(&mut breakdown);
+
+        assert_eq!(breakdown, before);
+        assert_eq!(rules.penalty(5), 5);
+    }
+
+    #[test]
+    fn test_huge_scores_are_kept_in_range() {
+        let rules = LuaRules::new(r#"
+            function modify_score(score) score.base = 4294967295 score.combo = 4294967295 score.strength = 4294967295 end
+        "#, "test").unwrap();
+        let mut breakdown = ScoreBreakdown::new(40, 40, 30, 0, 0, Dial::default());
+        rules.score(&mut breakdown);
+
+        assert_eq!((breakdown.base, breakdown.combo, breakdown.strength), (MAX_SCORE_PART, MAX_SCORE_PART, MAX_SCORE_PART));
+        assert!(breakdown.total() < u32::MAX);
+    }
+
+    #[test]
+    fn test_invalid_script_is_refused() {
+        assert!(matches!(LuaRules::new("function (", "broken.lua"), Err(GameError::ConfigError(_))));
+    }
+}
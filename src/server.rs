@@ -0,0 +1,895 @@
+//! Server module for the matchmaking server, run by the `rust-game-server` binary.
+//!
+//! Players connect with `rust_game connect`, or over WebSocket on the same
+//! port, and each one is paired with the
+//! next player to join. Every match runs on its own thread with the same
+//! `Game` as a local match: the server holds the authoritative state and
+//! counters, reads each player's actions from their own connection only, and
+//! sends the output and events of the match to both players.
+//!
+//! When a player's connection is lost, the match is held for a grace period.
+//! If the player rejoins with their session in time, and the last state hash
+//! they saw is one the match went through, the round in progress is played
+//! again from its start. Otherwise the match ends.
+
+use std::collections::HashMap;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+
+use crate::audio::Audio;
+use crate::config::{GameConfig, KeyBindings};
+use crate::counter::CounterEvents;
+use crate::error::{GameError, GameResult};
+use crate::events::GameEvent;
+use crate::game::Game;
+use crate::i18n::{Msg, tr};
+use crate::metrics::Metrics;
+use crate::net::{self, ClientMessage, Peer, ServerMessage};
+use crate::ui::render::{self, Renderer};
+use crate::ui::source::InputSource;
+use crate::ui::{CounterFrame, CounterView};
+use crate::version::PROTOCOL;
+
+/// Something a player did, as the match sees it.
+#[derive(Debug)]
+enum Incoming {
+    /// The player typed a line
+    Line(usize, String),
+    /// The player's connection was closed
+    Left(usize),
+    /// The player came back on a new connection
+    Rejoined(Rejoin),
+}
+
+/// A player asking to go back to their match.
+#[derive(Debug)]
+struct Rejoin {
+    /// The index of the player in the match
+    player: usize,
+    /// The player's new connection
+    peer: Peer,
+    /// The round of the last state hash the player saw, 0 if none
+    round: u32,
+    /// The last state hash the player saw
+    hash: u64,
+}
+
+/// A player's place in a match in progress, found by their session.
+#[derive(Debug, Clone)]
+struct Seat {
+    /// The index of the player in the match
+    player: usize,
+    /// Where the match reads the players' actions
+    inbox: Sender<Incoming>,
+}
+
+/// The players' actions waiting to be read, kept from one attempt at a match to the next.
+struct Inbox {
+    /// The actions of both players, in the order they came
+    receiver: Receiver<Incoming>,
+    /// The player who left during the attempt, with their new connection if they are already back
+    left: Option<(usize, Option<Rejoin>)>,
+}
+
+/// A player connected to the server.
+struct Client {
+    /// The name the player joined with
+    name: String,
+    /// The player's connection
+    peer: Peer,
+}
+
+/// Reads the players' actions from their connections.
+///
+/// Only the player the game waits for is listened to: the other one cannot
+/// stop the counter or answer a prompt in their place.
+pub struct NetworkInput {
+    /// The actions of both players, shared with the match
+    inbox: Arc<Mutex<Inbox>>,
+    /// The name of each player, for the error when one leaves
+    names: [String; 2],
+    /// The index of the player the game waits for
+    player: usize,
+}
+
+impl NetworkInput {
+    /// Takes the next line of the expected player, before a deadline if one is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - When to stop waiting, None to wait as long as it takes
+    ///
+    /// # Returns
+    ///
+    /// Result containing the line, None if the deadline passed, or an error if a player left
+    fn next_line(&mut self, deadline: Option<Instant>) -> GameResult<Option<String>> {
+        let mut inbox = self.inbox.lock().unwrap();
+        loop {
+            let incoming = match deadline {
+                Some(deadline) => match inbox.receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(incoming) => incoming,
+                    Err(RecvTimeoutError::Timeout) => return Ok(None),
+                    Err(RecvTimeoutError::Disconnected) => Incoming::Left(self.player),
+                },
+                None => inbox.receiver.recv().unwrap_or(Incoming::Left(self.player)),
+            };
+            let (player, rejoin) = match incoming {
+                Incoming::Line(player, text) if player == self.player => return Ok(Some(text)),
+                // Lines typed out of turn are dropped
+                Incoming::Line(..) => continue,
+                Incoming::Left(player) => (player, None),
+                // The old connection is not closed yet, but the player is already back
+                Incoming::Rejoined(rejoin) => (rejoin.player, Some(rejoin)),
+            };
+            inbox.left = Some((player, rejoin));
+            return Err(net::connection_lost(tr!(Msg::NetPlayerLeft, self.names[player])));
+        }
+    }
+
+    /// Takes lines until one starts with one of the given keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to wait for
+    /// * `deadline` - When to stop waiting, None to wait as long as it takes
+    ///
+    /// # Returns
+    ///
+    /// Result containing the index of the key, None if the deadline passed
+    fn next_key(&mut self, keys: &[char], deadline: Option<Instant>) -> GameResult<Option<usize>> {
+        while let Some(line) = self.next_line(deadline)? {
+            let pressed = line.trim().chars().next()
+                .and_then(|key| keys.iter().position(|k| k.eq_ignore_ascii_case(&key)));
+            if pressed.is_some() {
+                return Ok(pressed);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl InputSource for NetworkInput {
+    fn read_line(&mut self) -> GameResult<String> {
+        Ok(self.next_line(None)?.unwrap_or_default())
+    }
+
+    fn wait_for_key(&mut self, keys: &[char]) -> GameResult<usize> {
+        Ok(self.next_key(keys, None)?.unwrap_or_default())
+    }
+
+    fn wait_for_key_timeout(&mut self, keys: &[char], timeout: Duration) -> GameResult<Option<usize>> {
+        self.next_key(keys, Some(Instant::now() + timeout))
+    }
+
+    fn expect_player(&mut self, player_idx: usize) {
+        self.player = player_idx;
+    }
+}
+
+/// Draws the game output on both players' screens.
+pub struct NetRenderer {
+    /// The connection of each player
+    peers: [Peer; 2],
+}
+
+impl NetRenderer {
+    /// Sends a message to both players.
+    ///
+    /// A player who left is noticed when the game next waits for them, so
+    /// failing to send is not an error here.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to send
+    fn broadcast(&self, message: &ServerMessage) {
+        broadcast(&self.peers, message);
+    }
+}
+
+impl Renderer for NetRenderer {
+    fn info(&mut self, text: &str) {
+        self.broadcast(&ServerMessage::Text { text: text.to_string() });
+    }
+
+    fn info_wrapped(&mut self, text: &str) {
+        self.info(text);
+    }
+
+    fn show_briefly(&mut self, text: &str, duration: Duration) -> GameResult<()> {
+        // What was sent cannot be taken back, the message only scrolls on
+        self.info(text);
+        thread::sleep(duration);
+        Ok(())
+    }
+
+    fn show_scoreboard(&mut self, line: String) -> GameResult<()> {
+        self.info(&line);
+        Ok(())
+    }
+
+    fn hide_scoreboard(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn display_counter(&mut self, events: CounterEvents, frame: CounterFrame, _audio: Audio) -> GameResult<JoinHandle<()>> {
+        let peers = self.peers.clone();
+        render::show_captions(events, frame, move |caption| broadcast(&peers, &ServerMessage::Counter { caption }))
+    }
+
+    fn clear_counter(&mut self, _view: CounterView) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn new_line(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn prompt(&mut self) -> GameResult<()> {
+        self.broadcast(&ServerMessage::Prompt);
+        Ok(())
+    }
+}
+
+/// Sends a message to both players, whether or not they are still connected.
+///
+/// # Arguments
+///
+/// * `peers` - The connection of each player
+/// * `message` - The message to send
+fn broadcast(peers: &[Peer; 2], message: &ServerMessage) {
+    for peer in peers {
+        let _ = peer.send(message);
+    }
+}
+
+/// What every new connection needs to join or rejoin a match.
+#[derive(Clone)]
+struct Lobby {
+    /// The settings every match is played with
+    config: GameConfig,
+    /// The player waiting for an opponent, if any
+    waiting: Arc<Mutex<Option<Client>>>,
+    /// The seats of the matches in progress, by session
+    seats: Arc<Mutex<HashMap<String, Seat>>>,
+    /// How long a match is held for a player who lost their connection
+    rejoin_grace: Duration,
+    /// What the matches and connections are counted in
+    metrics: Arc<Metrics>,
+}
+
+/// Pairs the players connecting to it and runs their matches.
+pub struct Server {
+    /// Where the players connect
+    listener: TcpListener,
+    /// What the connections share
+    lobby: Lobby,
+}
+
+impl Server {
+    /// Starts listening for players.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address to listen on, as `host:port`
+    /// * `config` - The settings every match is played with
+    ///
+    /// # Returns
+    ///
+    /// Result containing the server, or an error if the address cannot be listened on
+    pub fn bind(address: &str, config: GameConfig) -> GameResult<Self> {
+        // Each player has their own connection, so neither needs a key of their own
+        let config = GameConfig {
+            keys: KeyBindings::default(),
+            duel: false,
+            reaction_game: false,
+            autosave: false,
+            ..config
+        };
+        let lobby = Lobby {
+            config,
+            waiting: Arc::new(Mutex::new(None)),
+            seats: Arc::new(Mutex::new(HashMap::new())),
+            rejoin_grace: net::REJOIN_GRACE,
+            metrics: Arc::new(Metrics::default()),
+        };
+        Ok(Self { listener: TcpListener::bind(address)?, lobby })
+    }
+
+    /// Holds the matches for another time than `net::REJOIN_GRACE` when a player loses their connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - How long a match waits for the player to rejoin
+    ///
+    /// # Returns
+    ///
+    /// The server, holding its matches for the given time
+    pub fn with_rejoin_grace(mut self, grace: Duration) -> Self {
+        self.lobby.rejoin_grace = grace;
+        self
+    }
+
+    /// Returns the counters of the matches and connections, shared with the running server.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        Arc::clone(&self.lobby.metrics)
+    }
+
+    /// Returns the address the server listens on.
+    pub fn local_addr(&self) -> GameResult<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Welcomes players until the server is stopped.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating why the server stopped
+    pub fn run(&self) -> GameResult<()> {
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("Could not accept a connection: {}", e);
+                    self.lobby.metrics.error();
+                    continue;
+                },
+            };
+            let lobby = self.lobby.clone();
+            thread::spawn(move || {
+                let metrics = Arc::clone(&lobby.metrics);
+                if let Err(e) = welcome(stream, lobby) {
+                    log::warn!("{}", e);
+                    metrics.error();
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Reads a new player's name, then pairs them or lets them wait for an opponent.
+///
+/// A player who lost their connection is sent back to their match instead.
+///
+/// # Arguments
+///
+/// * `stream` - The player's connection
+/// * `lobby` - What the connections share
+///
+/// # Returns
+///
+/// Result indicating whether the player joined and, if paired, how their match went
+fn welcome(stream: TcpStream, lobby: Lobby) -> GameResult<()> {
+    let peer = Peer::accept(stream)?;
+    let handshake = match peer.receive()? {
+        Some(ClientMessage::Hello { magic, version }) => PROTOCOL.check(&magic, &version),
+        _ => Err(GameError::ParseError("The first message must be a hello".to_string())),
+    };
+    if let Err(e) = handshake {
+        // The client learns which version the server speaks, to report the mismatch itself
+        if matches!(e, GameError::IncompatibleVersion { .. }) {
+            peer.send(&ServerMessage::hello())?;
+        }
+        return refuse(&peer, e);
+    }
+    peer.send(&ServerMessage::hello())?;
+    let name = match peer.receive()? {
+        Some(ClientMessage::Join { name }) => match net::player_name(&name) {
+            Some(name) => name,
+            None => return refuse(&peer, GameError::ParseError("A player name must not be empty".to_string())),
+        },
+        Some(ClientMessage::Rejoin { session, round, hash }) => {
+            let seat = lobby.seats.lock().unwrap().get(&session).cloned();
+            return match seat {
+                Some(seat) => {
+                    let rejoin = Rejoin { player: seat.player, peer: peer.clone(), round, hash };
+                    seat.inbox.send(Incoming::Rejoined(rejoin))
+                        .or_else(|_| refuse(&peer, GameError::LogicError("The match is over".to_string())))
+                },
+                None => refuse(&peer, GameError::LogicError("No match in progress for this session".to_string())),
+            };
+        },
+        _ => return refuse(&peer, GameError::ParseError("A hello must be followed by a join".to_string())),
+    };
+    let client = Client { name, peer };
+
+    let opponent = {
+        let mut waiting = lobby.waiting.lock().unwrap();
+        match waiting.take() {
+            Some(opponent) if opponent.peer.is_connected() => opponent,
+            // A player who left while waiting is forgotten, and the new one waits instead
+            gone => {
+                if let Some(gone) = gone {
+                    gone.peer.close();
+                }
+                client.peer.send(&ServerMessage::Queued)?;
+                *waiting = Some(client);
+                return Ok(());
+            },
+        }
+    };
+    play_match(&lobby, [opponent, client])
+}
+
+/// Tells a player why they cannot join, and closes their connection.
+///
+/// # Arguments
+///
+/// * `peer` - The player's connection
+/// * `error` - Why the player cannot join
+///
+/// # Returns
+///
+/// The error, once the player was told about it
+fn refuse(peer: &Peer, error: GameError) -> GameResult<()> {
+    peer.send(&ServerMessage::Error { message: error.to_string() })?;
+    peer.close();
+    Err(error)
+}
+
+/// Plays a match between two players, the one who waited first going first.
+///
+/// # Arguments
+///
+/// * `lobby` - What the connections share
+/// * `clients` - The two players
+///
+/// # Returns
+///
+/// Result indicating whether the match was played to its end
+fn play_match(lobby: &Lobby, clients: [Client; 2]) -> GameResult<()> {
+    let names = [clients[0].name.clone(), clients[1].name.clone()];
+    log::info!("{}", tr!(Msg::NetMatchStarted, names[0], names[1]));
+    lobby.metrics.match_started();
+
+    // Each player gets a session to rejoin the match with, forgotten once it is over
+    let (inbox_sender, receiver) = mpsc::channel();
+    let sessions = [0, 1].map(|player| {
+        let session = format!("{:016x}", rand::random::<u64>());
+        let seat = Seat { player, inbox: inbox_sender.clone() };
+        lobby.seats.lock().unwrap().insert(session.clone(), seat);
+        session
+    });
+    let result = run_match(lobby, clients, &sessions, inbox_sender, receiver);
+    let mut seats = lobby.seats.lock().unwrap();
+    for session in &sessions {
+        seats.remove(session);
+    }
+
+    log::info!("{}", tr!(Msg::NetMatchEnded, names[0], names[1]));
+    lobby.metrics.match_ended(result.is_ok());
+    result
+}
+
+/// Plays a match until it ends or a player who left does not come back in time.
+///
+/// # Arguments
+///
+/// * `lobby` - What the connections share
+/// * `clients` - The two players
+/// * `sessions` - The session of each player
+/// * `inbox_sender` - Where the players' actions are sent
+/// * `receiver` - Where the players' actions are read
+///
+/// # Returns
+///
+/// Result indicating whether the match was played to its end
+fn run_match(lobby: &Lobby, clients: [Client; 2], sessions: &[String; 2], inbox_sender: Sender<Incoming>,
+             receiver: Receiver<Incoming>) -> GameResult<()> {
+    let names = [clients[0].name.clone(), clients[1].name.clone()];
+    // Chat messages are sent from the players' threads, to whichever connection each player has now
+    let room = Arc::new(Mutex::new([clients[0].peer.clone(), clients[1].peer.clone()]));
+    let mut forwards = [None, None];
+    for (player_idx, client) in clients.into_iter().enumerate() {
+        let opponent = names[1 - player_idx].clone();
+        client.peer.send(&ServerMessage::MatchFound { player: player_idx, opponent, session: sessions[player_idx].clone() })?;
+        let (inbox_sender, room) = (inbox_sender.clone(), Arc::clone(&room));
+        forwards[player_idx] = Some(thread::spawn(move || forward(player_idx, client.peer, inbox_sender, room)));
+    }
+
+    let inbox = Arc::new(Mutex::new(Inbox { receiver, left: None }));
+    let mut hashes = HashMap::new();
+    let mut resumed = None;
+    let result = loop {
+        let peers = room.lock().unwrap().clone();
+        // Events go out on their own thread, which ends when the game is dropped
+        let (relay, relayed) = mpsc::channel();
+        let (relay_peers, metrics) = (peers.clone(), Arc::clone(&lobby.metrics));
+        let relay_thread = thread::spawn(move || {
+            let mut hashes = HashMap::new();
+            // A round runs from its state hash to the next one, or to the end of the match
+            let mut round_started = None;
+            for event in relayed {
+                match event {
+                    GameEvent::StateHash { round, hash } => {
+                        hashes.insert(round, hash);
+                        if let Some(started) = round_started.replace(Instant::now()) {
+                            metrics.round_played(started.elapsed());
+                        }
+                    },
+                    GameEvent::GameOver { .. } => {
+                        if let Some(started) = round_started.take() {
+                            metrics.round_played(started.elapsed());
+                        }
+                    },
+                    _ => {},
+                }
+                broadcast(&relay_peers, &ServerMessage::Event { event });
+            }
+            hashes
+        });
+
+        let config = GameConfig { player1_name: names[0].clone(), player2_name: names[1].clone(), ..lobby.config.clone() };
+        let input = NetworkInput { inbox: Arc::clone(&inbox), names: names.clone(), player: 0 };
+        let game = match resumed.take() {
+            Some(state) => Game::from_state(config, state),
+            None => Game::new(config),
+        };
+        let mut game = game
+            .with_input(input)
+            .with_renderer(NetRenderer { peers: peers.clone() })
+            .with_event_relay(relay);
+        let result = game.play_match();
+        let round_start = game.round_start().clone();
+        drop(game);
+        hashes.extend(relay_thread.join().unwrap_or_default());
+
+        let mut held = inbox.lock().unwrap();
+        let (Err(_), Some((player_idx, rejoin))) = (&result, held.left.take()) else {
+            break result;
+        };
+        let seconds = lobby.rejoin_grace.as_secs();
+        broadcast(&peers, &ServerMessage::Text { text: tr!(Msg::NetWaitingForRejoin, names[player_idx], seconds) });
+        let Some(peer) = wait_for_rejoin(&held.receiver, player_idx, rejoin, &hashes, lobby.rejoin_grace) else {
+            break Err(net::connection_lost(tr!(Msg::NetPlayerLeft, names[player_idx])));
+        };
+
+        // The old connection is closed for good, and whatever came before the new one is dropped
+        peers[player_idx].close();
+        if let Some(forward) = forwards[player_idx].take() {
+            let _ = forward.join();
+        }
+        if held.receiver.try_iter().any(|incoming| matches!(incoming, Incoming::Left(other) if other != player_idx)) {
+            break Err(net::connection_lost(tr!(Msg::NetPlayerLeft, names[1 - player_idx])));
+        }
+        room.lock().unwrap()[player_idx] = peer.clone();
+        let (inbox_sender, forward_room) = (inbox_sender.clone(), Arc::clone(&room));
+        forwards[player_idx] = Some(thread::spawn(move || forward(player_idx, peer, inbox_sender, forward_room)));
+
+        broadcast(&room.lock().unwrap(), &ServerMessage::Resumed { round: round_start.round, hash: round_start.hash() });
+        resumed = Some(round_start);
+    };
+
+    let peers = room.lock().unwrap().clone();
+    match &result {
+        Ok(winner) => broadcast(&peers, &ServerMessage::MatchOver { winner: Some(*winner) }),
+        Err(e) => broadcast(&peers, &ServerMessage::Error { message: e.to_string() }),
+    }
+    for peer in &peers {
+        peer.close();
+    }
+    result.map(|_| ())
+}
+
+/// Waits for a player who lost their connection to rejoin their match.
+///
+/// # Arguments
+///
+/// * `receiver` - Where the players' actions are read
+/// * `player_idx` - The index of the player who left
+/// * `rejoin` - The player's new connection, if they are already back
+/// * `hashes` - The state hash of each round played so far
+/// * `grace` - How long to wait for the player
+///
+/// # Returns
+///
+/// The player's new connection, or None if they did not come back in time or the other player left too
+fn wait_for_rejoin(receiver: &Receiver<Incoming>, player_idx: usize, mut rejoin: Option<Rejoin>,
+                   hashes: &HashMap<u32, u64>, grace: Duration) -> Option<Peer> {
+    let deadline = Instant::now() + grace;
+    loop {
+        let rejoin = match rejoin.take() {
+            Some(rejoin) => rejoin,
+            None => match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(Incoming::Rejoined(rejoin)) => rejoin,
+                Ok(Incoming::Left(other)) if other != player_idx => return None,
+                // The other player's lines are dropped while the match is held
+                Ok(_) => continue,
+                Err(_) => return None,
+            },
+        };
+        if rejoin.player != player_idx {
+            let _ = refuse(&rejoin.peer, GameError::LogicError("The player is still connected".to_string()));
+            continue;
+        }
+        // The player must have seen the same states as the match, unless no round started before they left
+        let expected = hashes.get(&rejoin.round).copied();
+        if rejoin.round > 0 && expected != Some(rejoin.hash) {
+            let desync = GameError::Desync { round: rejoin.round, expected: expected.unwrap_or_default(), actual: rejoin.hash };
+            let _ = refuse(&rejoin.peer, desync);
+            continue;
+        }
+        return Some(rejoin.peer);
+    }
+}
+
+/// Passes a player's lines on to their match until they leave, and their chat messages on to both players.
+///
+/// # Arguments
+///
+/// * `player_idx` - The index of the player in the match
+/// * `peer` - The player's connection
+/// * `inbox` - Where the match reads the players' actions
+/// * `room` - The connection of each player
+fn forward(player_idx: usize, peer: Peer, inbox: Sender<Incoming>, room: Arc<Mutex<[Peer; 2]>>) {
+    loop {
+        match peer.receive() {
+            Ok(Some(ClientMessage::Line { text })) => {
+                if inbox.send(Incoming::Line(player_idx, text)).is_err() {
+                    return;
+                }
+            },
+            Ok(Some(ClientMessage::Chat { text })) => {
+                if let Some(text) = net::chat_text(&text) {
+                    broadcast(&room.lock().unwrap(), &ServerMessage::Chat { player: player_idx, text });
+                }
+            },
+            // Saying hello, joining or rejoining once in the match changes nothing, and
+            // messages from a later minor version are not understood
+            Ok(Some(ClientMessage::Hello { .. } | ClientMessage::Join { .. } | ClientMessage::Rejoin { .. }
+                    | ClientMessage::Unknown)) => {},
+            Ok(None) | Err(_) => {
+                let _ = inbox.send(Incoming::Left(player_idx));
+                return;
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::render::RenderMode;
+
+    /// Builds an input reading from a channel, expecting player 1.
+    fn input() -> (Sender<Incoming>, NetworkInput) {
+        let (sender, receiver) = mpsc::channel();
+        let inbox = Arc::new(Mutex::new(Inbox { receiver, left: None }));
+        (sender, NetworkInput { inbox, names: ["Alice".to_string(), "Bob".to_string()], player: 0 })
+    }
+
+    /// Starts a server for one-target matches between players with 1 vitality.
+    fn start_server() -> SocketAddr {
+        let config = GameConfig {
+            vitality: 1,
+            stamina: 0,
+            target_count: 1,
+            seed: Some(1),
+            render_mode: RenderMode::Null,
+            ..GameConfig::default()
+        };
+        let server = Server::bind("127.0.0.1:0", config).unwrap().with_rejoin_grace(Duration::from_secs(10));
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+        address
+    }
+
+    /// Connects to a server and says hello, then sends a first message.
+    fn connect(address: &str, message: ClientMessage) -> Peer {
+        let peer = Peer::connect(address).unwrap();
+        peer.send(&ClientMessage::hello()).unwrap();
+        peer.send(&message).unwrap();
+        assert_eq!(peer.receive().unwrap(), Some(ServerMessage::hello()));
+        peer
+    }
+
+    /// Tells whether a message waits for the player to press ENTER.
+    ///
+    /// Other texts mentioning ENTER, such as the help hint, do not wait for anything.
+    fn asks_for_enter(message: &ServerMessage) -> bool {
+        match message {
+            ServerMessage::Prompt => true,
+            ServerMessage::Text { text } => text.starts_with("→ Press ENTER") || *text == tr!(Msg::PressEnterStop),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_input_only_takes_the_expected_player() {
+        let (sender, mut input) = input();
+        sender.send(Incoming::Line(1, "1".to_string())).unwrap();
+        sender.send(Incoming::Line(0, "2".to_string())).unwrap();
+        sender.send(Incoming::Line(1, "3".to_string())).unwrap();
+
+        assert_eq!(input.read_line().unwrap(), "2");
+        input.expect_player(1);
+        assert_eq!(input.read_line().unwrap(), "3");
+    }
+
+    #[test]
+    fn test_input_fails_when_a_player_leaves() {
+        let (sender, mut input) = input();
+        sender.send(Incoming::Left(1)).unwrap();
+
+        let error = input.read_line().unwrap_err();
+        assert!(matches!(&error, GameError::NetworkError(_)));
+        assert_eq!(error.exit_code(), 9);
+        assert!(error.to_string().contains("Bob"));
+        assert!(matches!(input.inbox.lock().unwrap().left, Some((1, None))));
+    }
+
+    #[test]
+    fn test_input_keys_and_timeout() {
+        let (sender, mut input) = input();
+        sender.send(Incoming::Line(0, "x".to_string())).unwrap();
+        sender.send(Incoming::Line(0, "2".to_string())).unwrap();
+
+        assert_eq!(input.wait_for_key_timeout(&['1', '2'], Duration::from_secs(1)).unwrap(), Some(1));
+        assert_eq!(input.wait_for_key_timeout(&['1', '2'], Duration::from_millis(10)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_incompatible_client_is_refused() {
+        let server = Server::bind("127.0.0.1:0", GameConfig::default()).unwrap();
+        let (address, metrics) = (server.local_addr().unwrap(), server.metrics());
+        thread::spawn(move || server.run());
+
+        // A 1.x client cannot read the chat and rejoin messages
+        let peer = Peer::connect(&address.to_string()).unwrap();
+        peer.send(&ClientMessage::Hello { magic: PROTOCOL.magic.to_string(), version: "1.2.0".to_string() }).unwrap();
+
+        assert_eq!(peer.receive().unwrap(), Some(ServerMessage::hello()));
+        match peer.receive().unwrap() {
+            Some(ServerMessage::Error { message }) => assert!(message.contains("Incompatible network protocol version 1.2.0")),
+            other => panic!("expected an error, got {:?}", other),
+        }
+        assert_eq!(peer.receive::<ServerMessage>().unwrap(), None);
+
+        // The refusal is counted once the connection is closed
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !metrics.render().contains("rust_game_errors_total 1\n") {
+            assert!(Instant::now() < deadline, "the refused client was not counted");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_match_between_tcp_and_websocket_clients() {
+        let address = start_server();
+        let join = |name: &str| ClientMessage::Join { name: name.to_string() };
+        let alice = connect(&address.to_string(), join("Alice"));
+        assert_eq!(alice.receive().unwrap(), Some(ServerMessage::Queued));
+        let bob = connect(&format!("ws://{}/", address), join("Bob"));
+
+        // Whatever either one reads, both keep pressing ENTER until the match is over
+        let press = |peer: Peer| thread::spawn(move || {
+            let mut messages = Vec::new();
+            while let Ok(Some(message)) = peer.receive::<ServerMessage>() {
+                if asks_for_enter(&message) {
+                    let _ = peer.send(&ClientMessage::Line { text: String::new() });
+                }
+                messages.push(message);
+            }
+            messages
+        });
+        let (alice, bob) = (press(alice), press(bob));
+        let (alice, bob) = (alice.join().unwrap(), bob.join().unwrap());
+
+        assert!(alice.iter().any(|message| matches!(message, ServerMessage::MatchFound { player: 0, opponent, .. } if opponent == "Bob")));
+        assert!(bob.iter().any(|message| matches!(message, ServerMessage::MatchFound { player: 1, opponent, .. } if opponent == "Alice")));
+        for messages in [&alice, &bob] {
+            assert!(matches!(messages.last(), Some(ServerMessage::MatchOver { winner: Some(_) })));
+            assert!(messages.iter().any(|message| matches!(message, ServerMessage::Event { .. })));
+        }
+    }
+
+    #[test]
+    fn test_player_rejoins_after_losing_the_connection() {
+        let address = start_server().to_string();
+        let alice = connect(&address, ClientMessage::Join { name: "Alice".to_string() });
+        assert_eq!(alice.receive().unwrap(), Some(ServerMessage::Queued));
+        let bob = connect(&address, ClientMessage::Join { name: "Bob".to_string() });
+
+        let alice = thread::spawn(move || {
+            let mut messages = Vec::new();
+            while let Ok(Some(message)) = alice.receive::<ServerMessage>() {
+                if asks_for_enter(&message) {
+                    let _ = alice.send(&ClientMessage::Line { text: String::new() });
+                }
+                messages.push(message);
+            }
+            messages
+        });
+
+        // Bob's connection drops as soon as the first round starts
+        let (mut peer, mut session, mut first_hash) = (bob, String::new(), None);
+        let mut messages = Vec::new();
+        while let Ok(Some(message)) = peer.receive::<ServerMessage>() {
+            match &message {
+                ServerMessage::MatchFound { session: id, .. } => session = id.clone(),
+                ServerMessage::Event { event: GameEvent::StateHash { round, hash } } if first_hash.is_none() => {
+                    first_hash = Some(*hash);
+                    peer.close();
+                    let rejoin = |hash| ClientMessage::Rejoin { session: session.clone(), round: *round, hash };
+
+                    // A player who saw another state cannot rejoin
+                    let desynced = connect(&address, rejoin(hash + 1));
+                    assert!(matches!(desynced.receive().unwrap(), Some(ServerMessage::Error { message }) if message.contains("diverged")));
+                    peer = connect(&address, rejoin(*hash));
+                },
+                message if asks_for_enter(message) => {
+                    let _ = peer.send(&ClientMessage::Line { text: String::new() });
+                },
+                _ => {},
+            }
+            messages.push(message);
+        }
+        let alice = alice.join().unwrap();
+
+        assert!(messages.contains(&ServerMessage::Resumed { round: 1, hash: first_hash.unwrap() }));
+        assert!(alice.iter().any(|message| matches!(message, ServerMessage::Text { text } if text.contains("Bob lost the connection"))));
+        for messages in [&alice, &messages] {
+            assert!(matches!(messages.last(), Some(ServerMessage::MatchOver { winner: Some(_) })));
+        }
+    }
+
+    #[test]
+    fn test_chat_reaches_both_players() {
+        let address = start_server().to_string();
+        let alice = connect(&address, ClientMessage::Join { name: "Alice".to_string() });
+        assert_eq!(alice.receive().unwrap(), Some(ServerMessage::Queued));
+        let bob = connect(&address, ClientMessage::Join { name: "Bob".to_string() });
+        bob.send(&ClientMessage::typed("/say good luck".to_string())).unwrap();
+
+        let chat = ServerMessage::Chat { player: 1, text: "good luck".to_string() };
+        for peer in [&alice, &bob] {
+            let mut messages = std::iter::from_fn(|| peer.receive::<ServerMessage>().unwrap());
+            assert!(messages.any(|message| message == chat));
+        }
+        alice.close();
+        bob.close();
+    }
+
+    #[test]
+    fn test_player_who_left_the_queue_is_not_paired() {
+        let address = start_server();
+        let join = |name: &str| ClientMessage::Join { name: name.to_string() };
+        for gone_address in [address.to_string(), format!("ws://{}/", address)] {
+            let gone = connect(&gone_address, join("Alice"));
+            assert_eq!(gone.receive().unwrap(), Some(ServerMessage::Queued));
+            gone.close();
+            thread::sleep(Duration::from_millis(50));
+
+            // Bob waits for someone else instead of joining a match nobody plays
+            let bob = connect(&address.to_string(), join("Bob"));
+            assert_eq!(bob.receive().unwrap(), Some(ServerMessage::Queued));
+            let carol = connect(&address.to_string(), join("Carol"));
+            assert!(matches!(bob.receive().unwrap(), Some(ServerMessage::MatchFound { opponent, .. }) if opponent == "Carol"));
+            bob.close();
+            carol.close();
+        }
+    }
+
+    #[test]
+    fn test_rejoin_without_a_match_is_refused() {
+        let address = start_server().to_string();
+        let peer = connect(&address, ClientMessage::Rejoin { session: "unknown".to_string(), round: 0, hash: 0 });
+
+        assert!(matches!(peer.receive().unwrap(), Some(ServerMessage::Error { .. })));
+        assert_eq!(peer.receive::<ServerMessage>().unwrap(), None);
+    }
+
+    #[test]
+    fn test_empty_name_is_refused() {
+        let address = start_server().to_string();
+        let peer = connect(&address, ClientMessage::Join { name: " \u{1b}\u{7} ".to_string() });
+
+        assert!(matches!(peer.receive().unwrap(), Some(ServerMessage::Error { message }) if message.contains("name")));
+        assert_eq!(peer.receive::<ServerMessage>().unwrap(), None);
+    }
+}
@@ -0,0 +1,294 @@
+//! Simulation module for playing many matches without any input or output.
+//!
+//! Both players stop the counter at random, so a simulation shows how the
+//! settings alone weigh on a match: how long it lasts, and how often a player
+//! with better attributes wins. Rounds are resolved with the same pure
+//! transition as a real match, `GameState::resolve_round`.
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::config::GameConfig;
+use crate::i18n::{Msg, tr};
+use crate::player::Player;
+use crate::scoring::{self, ScoreBreakdown};
+use crate::state::{GameState, TurnOutcome};
+
+/// Rounds after which a simulated match is called a draw, in case nobody can be hurt.
+pub const MAX_ROUNDS: u32 = 1000;
+
+/// The outcome of a batch of simulated matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SimulationSummary {
+    /// Number of matches played
+    pub games: u64,
+    /// Matches won by each player
+    pub wins: [u64; 2],
+    /// Rounds played over all the matches
+    pub rounds: u64,
+}
+
+impl SimulationSummary {
+    /// Returns the number of matches stopped after `MAX_ROUNDS` without a winner.
+    pub fn draws(&self) -> u64 {
+        self.games - self.wins[0] - self.wins[1]
+    }
+
+    /// Returns the share of matches a player won.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player (0 or 1)
+    ///
+    /// # Returns
+    ///
+    /// The percentage of matches won, 0 if no match was played
+    pub fn win_rate(&self, player_idx: usize) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins[player_idx] as f64 * 100.0 / self.games as f64
+    }
+
+    /// Returns how many rounds a match lasted on average.
+    pub fn average_rounds(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.rounds as f64 / self.games as f64
+    }
+}
+
+/// Plays a turn with a random stop for each target.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+/// * `round` - The round the turn is played in, for the number of targets
+/// * `player` - The player taking the turn
+/// * `rng` - The random number generator for the targets and stops
+///
+/// # Returns
+///
+/// The player's average score over the turn and their number of exact hits
+pub fn play_turn<R: Rng>(config: &GameConfig, round: u32, player: &Player, rng: &mut R) -> (u32, u32) {
+    let dial = config.rules.dial();
+    let mut exact_hits = 0;
+    let mut targets = config.rules.targets(rng, config.targets_in_round(round));
+    config.plugins.targets(&mut targets, config.rules.counter_max);
+    // Summed as they come rather than collected, this runs for every turn of a batch
+    let mut sum = 0;
+    for &target in &targets {
+        let stop = rng.random_range(0..=dial.max());
+        if stop == dial.ticks(target) {
+            exact_hits += 1;
+        }
+        let mut breakdown = ScoreBreakdown::new(dial.ticks(target), stop, player.strength(), 0, 0, dial);
+        config.plugins.score(&mut breakdown);
+        sum += u64::from(breakdown.total());
+    }
+    (scoring::average_of(sum, targets.len()), exact_hits)
+}
+
+/// Creates the state of a simulated match, at round 1.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, for the players' names and attributes
+///
+/// # Returns
+///
+/// A new GameState
+pub fn new_match(config: &GameConfig) -> GameState {
+    GameState::new([
+        Player::new(config.player1_name.clone(), config.vitality, config.speed, config.strength, config.stamina),
+        Player::new(config.player2_name.clone(), config.vitality, config.speed, config.strength, config.stamina),
+    ].map(|player| player.with_bounds(config.rules.bounds)))
+}
+
+/// Plays one round of a match with random stops.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+/// * `state` - The state before the round
+/// * `rng` - The random number generator for the targets and stops
+///
+/// # Returns
+///
+/// The state after the round, at the next round, with the winner set if the match is over
+pub fn simulate_round<R: Rng>(config: &GameConfig, state: &GameState, rng: &mut R) -> GameState {
+    let mut next = state.clone();
+    advance(config, &mut next, rng);
+    next
+}
+
+/// Plays one round of a match with random stops, on the state itself.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+/// * `state` - The state before the round, left at the next round
+/// * `rng` - The random number generator for the targets and stops
+fn advance<R: Rng>(config: &GameConfig, state: &mut GameState, rng: &mut R) {
+    let turns = [
+        play_turn(config, state.round, &state.players[0], rng),
+        play_turn(config, state.round, &state.players[1], rng),
+    ];
+    state.apply_round(turns.map(|(score, _)| TurnOutcome::Played(score)), 0, config.rules.damage, &config.plugins);
+    for (total, (_, hits)) in state.tally.exact_hits.iter_mut().zip(turns) {
+        *total += hits;
+    }
+    state.end_round(config.rules.win_condition);
+}
+
+/// Ends a simulated match, naming the player left standing as the winner if the win condition did not.
+///
+/// # Arguments
+///
+/// * `state` - The state after the last round played
+///
+/// # Returns
+///
+/// The state of the finished match
+pub fn finish_match(mut state: GameState) -> GameState {
+    if state.winner_idx.is_none() && !state.in_progress() {
+        state.winner_idx = state.players.iter().position(|p| p.vitality() > 0);
+    }
+    state.game_over = true;
+    state
+}
+
+/// Plays a whole match with random stops.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+/// * `rng` - The random number generator for the targets and stops
+///
+/// # Returns
+///
+/// The state at the end of the match, with the winner set unless it was a draw
+pub fn simulate_match<R: Rng>(config: &GameConfig, rng: &mut R) -> GameState {
+    let mut state = new_match(config);
+    while state.in_progress() && state.round <= MAX_ROUNDS {
+        advance(config, &mut state, rng);
+    }
+    finish_match(state)
+}
+
+/// Plays a batch of matches with random stops.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, the seed makes the batch repeatable
+/// * `games` - How many matches to play
+///
+/// # Returns
+///
+/// The outcome of the matches
+pub fn run(config: &GameConfig, games: u64) -> SimulationSummary {
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    let mut summary = SimulationSummary { games, ..SimulationSummary::default() };
+    for _ in 0..games {
+        let state = simulate_match(config, &mut rng);
+        if let Some(winner) = state.winner_idx {
+            summary.wins[winner] += 1;
+        }
+        summary.rounds += u64::from(state.round - 1);
+    }
+    summary
+}
+
+/// Plays a batch of matches with random stops and reports how they went.
+///
+/// # Arguments
+///
+/// * `config` - The game settings
+/// * `games` - How many matches to play
+pub fn play(config: &GameConfig, games: u64) {
+    let summary = run(config, games);
+    let mut renderer = config.render_mode.renderer();
+    renderer.info(&tr!(Msg::SimulationResult, summary.games, format!("{:.1}", summary.average_rounds())));
+    for (player_idx, name) in [&config.player1_name, &config.player2_name].into_iter().enumerate() {
+        renderer.info(&tr!(Msg::SimulationPlayer,
+                   name, summary.wins[player_idx], format!("{:.1}", summary.win_rate(player_idx))));
+    }
+    if summary.draws() > 0 {
+        renderer.info(&tr!(Msg::SimulationDraws, summary.draws(), MAX_ROUNDS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::WinCondition;
+
+    fn test_config() -> GameConfig {
+        GameConfig { vitality: 100, seed: Some(7), ..GameConfig::default() }
+    }
+
+    #[test]
+    fn test_simulated_match_has_a_winner() {
+        let state = simulate_match(&test_config(), &mut StdRng::seed_from_u64(1));
+        let winner = state.winner_idx.unwrap();
+
+        assert!(state.game_over);
+        assert!(state.players[winner].vitality() > 0);
+        assert_eq!(state.players[1 - winner].vitality(), 0);
+    }
+
+    #[test]
+    fn test_match_nobody_can_lose_is_a_draw() {
+        // Without targets every turn scores 0, so no round is ever won
+        let config = GameConfig { target_count: 0, ..test_config() };
+        let state = simulate_match(&config, &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(state.winner_idx, None);
+        assert_eq!(state.round, MAX_ROUNDS + 1);
+    }
+
+    #[test]
+    fn test_turns_follow_progressive_targets() {
+        // The first round has no target, the third one two, which always score
+        let config = GameConfig { target_count: 0, target_cap: Some(5), ..test_config() };
+        let player = &new_match(&config).players[0];
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert_eq!(play_turn(&config, 1, player, &mut rng).0, 0);
+        assert!(play_turn(&config, 3, player, &mut rng).0 > 0);
+    }
+
+    #[test]
+    fn test_run_is_repeatable_with_a_seed() {
+        let summary = run(&test_config(), 50);
+
+        assert_eq!(summary.games, 50);
+        assert_eq!(summary.wins[0] + summary.wins[1] + summary.draws(), 50);
+        assert!(summary.average_rounds() >= 1.0);
+        assert_eq!(summary, run(&test_config(), 50));
+    }
+
+    #[test]
+    fn test_match_ends_on_the_win_condition() {
+        let mut config = test_config();
+        config.vitality = 10_000;
+        config.rules.win_condition = WinCondition::RoundWins(3);
+        let state = simulate_match(&config, &mut StdRng::seed_from_u64(1));
+        let winner = state.winner_idx.unwrap();
+
+        assert_eq!(state.tally.round_wins[winner], 3);
+        assert!(state.tally.round_wins[1 - winner] < 3);
+        assert!(state.players.iter().all(|p| p.vitality() > 0));
+    }
+
+    #[test]
+    fn test_summary_rates() {
+        let summary = SimulationSummary { games: 4, wins: [3, 1], rounds: 10 };
+        assert_eq!(summary.win_rate(0), 75.0);
+        assert_eq!(summary.average_rounds(), 2.5);
+        assert_eq!(SimulationSummary::default().win_rate(1), 0.0);
+    }
+}
@@ -0,0 +1,93 @@
+//! Stages module for the arenas a match can be played in.
+//!
+//! A stage is a named preset of rules: how far the counter goes, how it
+//! speeds up as the match goes on, and how much vitality a lost round costs.
+//! Picking a stage gives a different match without setting each rule by hand.
+
+use crate::error::{GameError, GameResult};
+use crate::rules::{DamageModel, RuleSet, SpeedCurve};
+
+/// An arena with its own rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stage {
+    /// Name the stage is picked by
+    pub name: &'static str,
+    /// Highest counter value, in whole units
+    pub counter_max: u32,
+    /// How the counter speeds up from round to round
+    pub speed_curve: SpeedCurve,
+    /// How the loser of a round loses vitality
+    pub damage: DamageModel,
+}
+
+impl Stage {
+    /// Applies the stage's rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The rules to change, the ones the stage does not set are kept
+    pub fn apply(&self, rules: &mut RuleSet) {
+        rules.counter_max = self.counter_max;
+        rules.speed_curve = self.speed_curve;
+        rules.damage = self.damage;
+    }
+}
+
+/// The stages a match can be played in.
+pub const STAGES: [Stage; 4] = [
+    Stage { name: "classic", counter_max: 100, speed_curve: SpeedCurve::Flat, damage: DamageModel::Difference },
+    Stage { name: "clocktower", counter_max: 60, speed_curve: SpeedCurve::Ramp(5), damage: DamageModel::Capped(30) },
+    Stage { name: "colosseum", counter_max: 360, speed_curve: SpeedCurve::Flat, damage: DamageModel::Percent(25) },
+    Stage { name: "dojo", counter_max: 100, speed_curve: SpeedCurve::Ramp(10), damage: DamageModel::Fixed(15) },
+];
+
+/// Finds a stage by name.
+///
+/// # Arguments
+///
+/// * `name` - The name of the stage, in any case
+///
+/// # Returns
+///
+/// Result containing the stage, or an error if no stage has that name
+pub fn find(name: &str) -> GameResult<Stage> {
+    STAGES.iter()
+        .find(|stage| stage.name.eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| GameError::ConfigError(format!("Unknown stage: {}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_stage() {
+        assert_eq!(find("Dojo").unwrap().damage, DamageModel::Fixed(15));
+        assert!(matches!(find("moon"), Err(GameError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_stage_names_are_unique() {
+        for (i, stage) in STAGES.iter().enumerate() {
+            assert!(STAGES[i + 1..].iter().all(|other| other.name != stage.name));
+        }
+    }
+
+    #[test]
+    fn test_classic_stage_keeps_the_default_rules() {
+        let mut rules = RuleSet::default();
+        find("classic").unwrap().apply(&mut rules);
+        assert_eq!(rules, RuleSet::default());
+    }
+
+    #[test]
+    fn test_apply_stage() {
+        let mut rules = RuleSet::default();
+        find("clocktower").unwrap().apply(&mut rules);
+        assert_eq!(rules.counter_max, 60);
+        assert_eq!(rules.speed_curve, SpeedCurve::Ramp(5));
+        assert_eq!(rules.damage, DamageModel::Capped(30));
+        assert_eq!(rules.precision, RuleSet::default().precision);
+    }
+}
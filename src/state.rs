@@ -0,0 +1,498 @@
+//! State module for the progress of a match.
+//!
+//! This module defines the part of a game that changes from round to round,
+//! kept separate from the settings so it can be saved and restored.
+//!
+//! Rounds are resolved by a pure function of the state and the scores, so a
+//! match can be replayed or simulated without any input or output.
+//!
+//! The state can also be reduced to a hash, so that two runs of the same match
+//! can be compared round by round to spot where they diverge.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GameError, GameResult};
+use crate::history::MatchHistory;
+use crate::plugins::GamePlugin;
+use crate::player::Player;
+use crate::rules::{DamageModel, DraftPool, WinCondition};
+
+/// The progress of a match: players' attributes, round, and outcome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameState {
+    /// The two players
+    pub players: [Player; 2],
+    /// The current round number, starting at 1
+    pub round: u32,
+    /// Flag indicating if the game is over
+    pub game_over: bool,
+    /// Index of the winner (if game is over)
+    pub winner_idx: Option<usize>,
+    /// Index of the player taking the first turn of the current round
+    #[serde(default, skip_serializing_if = "is_player_one")]
+    pub first_player: usize,
+    /// What both players did so far, for the rules that end a match early
+    #[serde(default, skip_serializing_if = "Tally::is_empty")]
+    pub tally: Tally,
+    /// The penalties and bonuses left after the draft, once it was played
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<DraftPool>,
+    /// The rounds played so far, for the timeline of the match
+    #[serde(default, skip_serializing_if = "MatchHistory::is_empty")]
+    pub history: MatchHistory,
+}
+
+/// Running totals of a match, kept for each player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Tally {
+    /// Times each player's counter went around before they stopped it
+    pub misses: [u32; 2],
+    /// Rounds each player won
+    #[serde(default)]
+    pub round_wins: [u32; 2],
+    /// Sum of each player's round scores
+    #[serde(default)]
+    pub scores: [u32; 2],
+    /// Stops each player made right on the target
+    #[serde(default)]
+    pub exact_hits: [u32; 2],
+}
+
+impl Tally {
+    /// Returns whether nothing was counted yet, so the tally can be left out of saves.
+    pub fn is_empty(&self) -> bool {
+        *self == Tally::default()
+    }
+}
+
+/// Tells whether a player index is player 1's, so classic saves and hashes stay unchanged.
+fn is_player_one(idx: &usize) -> bool {
+    *idx == 0
+}
+
+/// How a player's turn went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnOutcome {
+    /// The player played their turn, for the given average score
+    Played(u32),
+    /// The player paid vitality to skip their turn
+    Passed,
+}
+
+impl TurnOutcome {
+    /// Returns the score of the turn, 0 for a passed turn.
+    pub fn score(self) -> u32 {
+        match self {
+            TurnOutcome::Played(score) => score,
+            TurnOutcome::Passed => 0,
+        }
+    }
+
+    /// Adds bonus points to the score of a played turn, a passed turn stays passed.
+    pub fn with_bonus(self, bonus: u32) -> Self {
+        match self {
+            TurnOutcome::Played(score) => TurnOutcome::Played(score + bonus),
+            TurnOutcome::Passed => TurnOutcome::Passed,
+        }
+    }
+}
+
+/// What a round did to the players.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundOutcome {
+    /// One player scored more and the other one lost vitality
+    Win {
+        /// Index of the player who scored more
+        winner: usize,
+        /// Index of the player who lost vitality
+        loser: usize,
+        /// Difference between the two scores
+        diff: u32,
+        /// Vitality the loser lost for the difference, under the damage model
+        damage: u32,
+        /// Vitality wagered by both players, lost by the loser on top of the difference
+        pot: u32,
+    },
+    /// Both players scored the same and nobody was hurt
+    Draw,
+}
+
+impl GameState {
+    /// Creates the state of a match that has not started yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `players` - The two players with their starting attributes
+    ///
+    /// # Returns
+    ///
+    /// A new GameState at round 1
+    pub fn new(players: [Player; 2]) -> Self {
+        Self {
+            players,
+            round: 1,
+            game_over: false,
+            winner_idx: None,
+            first_player: 0,
+            tally: Tally::default(),
+            draft: None,
+            history: MatchHistory::default(),
+        }
+    }
+
+    /// Returns whether the match should continue with another round.
+    pub fn in_progress(&self) -> bool {
+        !self.game_over && self.players.iter().all(|p| p.vitality() > 0)
+    }
+
+    /// Computes a hash of the state that is the same on every machine and every run.
+    ///
+    /// The hash is FNV-1a over the state serialized as JSON, with sorted keys.
+    /// Player names and the history are left out: they do not change the course of the match.
+    ///
+    /// # Returns
+    ///
+    /// The 64-bit hash of the state
+    pub fn hash(&self) -> u64 {
+        let mut value = serde_json::to_value(self).expect("a game state always serializes");
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("history");
+        }
+        if let Some(players) = value["players"].as_array_mut() {
+            for player in players {
+                player["name"].take();
+            }
+        }
+        let bytes = serde_json::to_vec(&value).expect("a JSON value always serializes");
+        fnv1a(&bytes)
+    }
+
+    /// Checks that the state could have been reached by playing, e.g. after loading it.
+    ///
+    /// # Returns
+    ///
+    /// Result with `GameError::ParseError` naming the first impossible value
+    pub fn validate(&self) -> GameResult<()> {
+        if self.round == 0 {
+            return Err(GameError::ParseError("Rounds start at 1".to_string()));
+        }
+        if self.winner_idx.is_some_and(|idx| idx >= self.players.len()) {
+            return Err(GameError::ParseError("The winner is not one of the players".to_string()));
+        }
+        if self.first_player >= self.players.len() {
+            return Err(GameError::ParseError("The first player is not one of the players".to_string()));
+        }
+        if self.history.rounds().last().is_some_and(|record| record.round >= self.round) {
+            return Err(GameError::ParseError("The history holds a round not played yet".to_string()));
+        }
+        self.players.iter().try_for_each(Player::validate)
+    }
+
+    /// Works out the state after a round from both players' turns.
+    ///
+    /// The winner gains one point of momentum and the loser loses the damage
+    /// the score difference deals, plus the pot. On a draw only the tally of
+    /// scores changes. A player who passed loses the round to a player who
+    /// did not, but takes no damage: they already paid for passing.
+    ///
+    /// # Arguments
+    ///
+    /// * `turns` - How each player's turn went
+    /// * `pot` - The vitality wagered by both players
+    /// * `damage_model` - How the score difference turns into lost vitality
+    /// * `plugins` - The plugins that may change the damage and the state after the round
+    ///
+    /// # Returns
+    ///
+    /// The state after the round, and what the round did to the players
+    pub fn resolve_round(&self, turns: [TurnOutcome; 2], pot: u32, damage_model: DamageModel, plugins: &[Box<dyn GamePlugin>])
+        -> (GameState, RoundOutcome) {
+        let mut next = self.clone();
+        let outcome = next.apply_round(turns, pot, damage_model, plugins);
+        (next, outcome)
+    }
+
+    /// Plays out a round on this state, as `resolve_round` does on a copy.
+    ///
+    /// Batches of simulated matches go through here, so that no round clones the players.
+    ///
+    /// # Arguments
+    ///
+    /// * `turns` - How each player's turn went
+    /// * `pot` - The vitality wagered by both players
+    /// * `damage_model` - How the score difference turns into lost vitality
+    /// * `plugins` - The plugins that may change the damage and the state after the round
+    ///
+    /// # Returns
+    ///
+    /// What the round did to the players
+    pub fn apply_round(&mut self, turns: [TurnOutcome; 2], pot: u32, damage_model: DamageModel, plugins: &[Box<dyn GamePlugin>])
+        -> RoundOutcome {
+        let outcome = self.settle_round(turns, pot, damage_model, plugins);
+        for plugin in plugins {
+            plugin.resolve_round(self, &outcome);
+        }
+        outcome
+    }
+
+    /// Closes a resolved round and moves on to the next one.
+    ///
+    /// The match is over once the win condition decides a winner or a player
+    /// runs out of vitality, the player left standing winning it then.
+    ///
+    /// # Arguments
+    ///
+    /// * `win_condition` - How the match is won, besides the opponent running out of vitality
+    pub fn end_round(&mut self, win_condition: WinCondition) {
+        if let Some(winner) = win_condition.winner(self).filter(|_| self.in_progress()) {
+            self.winner_idx = Some(winner);
+            self.game_over = true;
+        }
+        if !self.in_progress() {
+            self.game_over = true;
+            self.winner_idx = self.winner_idx.or_else(|| self.players.iter().position(|p| p.vitality() > 0));
+        }
+        self.round += 1;
+    }
+
+    /// Works out who won a round and deals the damage, before the plugins adjust the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `turns` - How each player's turn went
+    /// * `pot` - The vitality wagered by both players
+    /// * `damage_model` - How the score difference turns into lost vitality
+    /// * `plugins` - The plugins that may change the damage
+    ///
+    /// # Returns
+    ///
+    /// What the round did to the players
+    fn settle_round(&mut self, turns: [TurnOutcome; 2], pot: u32, damage_model: DamageModel, plugins: &[Box<dyn GamePlugin>])
+        -> RoundOutcome {
+        let scores = turns.map(TurnOutcome::score);
+        for (total, score) in self.tally.scores.iter_mut().zip(scores) {
+            *total += score;
+        }
+        let (winner, loser) = match turns {
+            [TurnOutcome::Passed, TurnOutcome::Passed] => return RoundOutcome::Draw,
+            [TurnOutcome::Passed, _] => (1, 0),
+            [_, TurnOutcome::Passed] => (0, 1),
+            _ => match scores[0].cmp(&scores[1]) {
+                Ordering::Greater => (0, 1),
+                Ordering::Less => (1, 0),
+                Ordering::Equal => return RoundOutcome::Draw,
+            },
+        };
+        let (diff, damage) = if turns[loser] == TurnOutcome::Passed {
+            (0, 0)
+        } else {
+            let diff = scores[winner] - scores[loser];
+            (diff, plugins.iter()
+                .fold(damage_model.damage(diff, self.players[loser].vitality()), |damage, plugin| plugin.damage(damage)))
+        };
+
+        self.tally.round_wins[winner] += 1;
+        self.players[winner].increase_momentum(1);
+        self.players[loser].decrease_vitality(damage + pot);
+        RoundOutcome::Win { winner, loser, diff, damage, pot }
+    }
+}
+
+/// Offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes bytes with 64-bit FNV-1a.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME))
+}
+
+/// Helpers for the tests of the modules that resolve rounds.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::TurnOutcome;
+
+    /// Builds the turns of two players who played for the given scores.
+    pub(crate) fn played(scores: [u32; 2]) -> [TurnOutcome; 2] {
+        scores.map(TurnOutcome::Played)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::testing::played;
+
+    fn test_players() -> [Player; 2] {
+        [
+            Player::new("Player1".to_string(), 100, 60, 70, 3),
+            Player::new("Player2".to_string(), 100, 60, 70, 3),
+        ]
+    }
+
+    #[test]
+    fn test_new_state() {
+        let state = GameState::new(test_players());
+        assert_eq!(state.round, 1);
+        assert!(!state.game_over);
+        assert_eq!(state.winner_idx, None);
+        assert!(state.in_progress());
+    }
+
+    #[test]
+    fn test_not_in_progress_when_player_is_out() {
+        let mut state = GameState::new(test_players());
+        state.players[1].decrease_vitality(100);
+        assert!(!state.in_progress());
+    }
+
+    #[test]
+    fn test_not_in_progress_when_game_over() {
+        let mut state = GameState::new(test_players());
+        state.game_over = true;
+        assert!(!state.in_progress());
+    }
+
+    #[test]
+    fn test_resolve_round_win() {
+        let state = GameState::new(test_players());
+        let (next, outcome) = state.resolve_round(played([50, 80]), 10, DamageModel::Difference, &[]);
+
+        assert_eq!(outcome, RoundOutcome::Win { winner: 1, loser: 0, diff: 30, damage: 30, pot: 10 });
+        assert_eq!(next.players[0].vitality(), 60);
+        assert_eq!(next.players[1].momentum(), state.players[1].momentum() + 1);
+        assert_eq!(next.tally.round_wins, [0, 1]);
+        assert_eq!(next.tally.scores, [50, 80]);
+        // The state the round started from is left as it was
+        assert_eq!(state.players[0].vitality(), 100);
+    }
+
+    #[test]
+    fn test_apply_round_matches_resolve_round() {
+        let state = GameState::new(test_players());
+        let (next, outcome) = state.resolve_round(played([50, 80]), 10, DamageModel::Difference, &[]);
+        let mut applied = state.clone();
+
+        assert_eq!(applied.apply_round(played([50, 80]), 10, DamageModel::Difference, &[]), outcome);
+        assert_eq!(applied, next);
+    }
+
+    #[test]
+    fn test_resolve_round_draw() {
+        let state = GameState::new(test_players());
+        let (next, outcome) = state.resolve_round(played([40, 40]), 10, DamageModel::Difference, &[]);
+
+        assert_eq!(outcome, RoundOutcome::Draw);
+        assert_eq!(next.players, state.players);
+        assert_eq!(next.tally.scores, [40, 40]);
+        assert_eq!(next.tally.round_wins, [0, 0]);
+    }
+
+    #[test]
+    fn test_resolve_round_with_a_pass() {
+        let state = GameState::new(test_players());
+        let (next, outcome) = state.resolve_round([TurnOutcome::Played(30), TurnOutcome::Passed], 10, DamageModel::Difference, &[]);
+
+        assert_eq!(outcome, RoundOutcome::Win { winner: 0, loser: 1, diff: 0, damage: 0, pot: 10 });
+        assert_eq!(next.players[1].vitality(), 90);
+        assert_eq!(next.tally.round_wins, [1, 0]);
+
+        let (_, outcome) = state.resolve_round([TurnOutcome::Passed; 2], 10, DamageModel::Difference, &[]);
+        assert_eq!(outcome, RoundOutcome::Draw);
+    }
+
+    #[test]
+    fn test_resolve_round_with_capped_damage() {
+        let state = GameState::new(test_players());
+        let (next, outcome) = state.resolve_round(played([90, 20]), 0, DamageModel::Capped(25), &[]);
+
+        assert_eq!(outcome, RoundOutcome::Win { winner: 0, loser: 1, diff: 70, damage: 25, pot: 0 });
+        assert_eq!(next.players[1].vitality(), 75);
+    }
+
+    #[test]
+    fn test_resolve_round_with_mutators() {
+        let state = GameState::new(test_players());
+        let mutators = crate::mutators::parse("double-damage").unwrap();
+        let (next, outcome) = state.resolve_round(played([90, 70]), 5, DamageModel::Difference, &mutators);
+
+        assert_eq!(outcome, RoundOutcome::Win { winner: 0, loser: 1, diff: 20, damage: 40, pot: 5 });
+        assert_eq!(next.players[1].vitality(), 55);
+    }
+
+    #[test]
+    fn test_end_round() {
+        let mut state = GameState::new(test_players());
+        state.end_round(WinCondition::Vitality);
+        assert_eq!(state.round, 2);
+        assert!(state.in_progress());
+
+        state.tally.round_wins = [0, 2];
+        state.end_round(WinCondition::RoundWins(2));
+        assert!(state.game_over);
+        assert_eq!(state.winner_idx, Some(1));
+
+        let mut state = GameState::new(test_players());
+        state.players[0].decrease_vitality(100);
+        state.end_round(WinCondition::Vitality);
+        assert!(state.game_over);
+        assert_eq!(state.winner_idx, Some(1));
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), FNV_OFFSET);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_hash_is_stable() {
+        let state = GameState::new(test_players());
+        assert_eq!(state.hash(), state.clone().hash());
+        assert_eq!(state.hash(), GameState::new(test_players()).hash());
+    }
+
+    #[test]
+    fn test_hash_follows_the_match() {
+        let state = GameState::new(test_players());
+        let mut next_round = state.clone();
+        next_round.round += 1;
+        let mut hurt = state.clone();
+        hurt.players[0].decrease_vitality(1);
+
+        assert_ne!(state.hash(), next_round.hash());
+        assert_ne!(state.hash(), hurt.hash());
+        assert_ne!(next_round.hash(), hurt.hash());
+    }
+
+    #[test]
+    fn test_hash_ignores_names() {
+        let renamed = GameState::new([
+            Player::new("Alice".to_string(), 100, 60, 70, 3),
+            Player::new("Bob".to_string(), 100, 60, 70, 3),
+        ]);
+        assert_eq!(renamed.hash(), GameState::new(test_players()).hash());
+    }
+
+    #[test]
+    fn test_first_player_is_saved_only_when_not_player_one() {
+        let state = GameState::new(test_players());
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(!json.contains("first_player"));
+
+        let mut swapped = state.clone();
+        swapped.first_player = 1;
+        let json = serde_json::to_string(&swapped).unwrap();
+        assert_eq!(serde_json::from_str::<GameState>(&json).unwrap(), swapped);
+        assert_ne!(swapped.hash(), state.hash());
+
+        swapped.first_player = 2;
+        assert!(swapped.validate().is_err());
+    }
+}
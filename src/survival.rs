@@ -0,0 +1,67 @@
+//! Survival module for the endless single-player mode.
+//!
+//! One player faces wave after wave of targets. Every stop too far from its
+//! target costs vitality, and the counter gets faster after each wave. The
+//! number of waves survived is the score, and the best one is kept in the
+//! player profile.
+
+use std::path::Path;
+
+use crate::config::GameConfig;
+use crate::error::GameResult;
+use crate::game::Game;
+use crate::i18n::{Msg, tr};
+use crate::profile::Profile;
+use crate::rules::{RuleSet, SpeedCurve};
+
+/// Largest distance from the target, in whole units, that does not cost vitality.
+pub const TOLERANCE: u32 = 10;
+
+/// Vitality lost for each stop further than `TOLERANCE` from its target.
+pub const MISS_DAMAGE: u32 = 10;
+
+/// Percentage of its starting pace the counter gains after each wave.
+pub const SPEED_UP: u32 = 10;
+
+/// Plays one survival run and records a new best score in the profile.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, player 1 plays alone
+/// * `profile_path` - The file the best score is kept in
+///
+/// # Returns
+///
+/// Result indicating whether the run could be played and saved
+pub fn play(config: &GameConfig, profile_path: &Path) -> GameResult<()> {
+    let mut profile = Profile::load(profile_path)?;
+    let mut renderer = config.render_mode.renderer();
+    renderer.info_wrapped(&tr!(Msg::SurvivalStart, TOLERANCE, MISS_DAMAGE, profile.survival_best));
+
+    // Both of these need a second player
+    let rules = RuleSet { speed_curve: SpeedCurve::Ramp(SPEED_UP), ..config.rules };
+    let config = GameConfig { duel: false, reaction_game: false, rules, ..config.clone() };
+
+    let name = config.player1_name.clone();
+    let waves = Game::new(config).play_survival()?;
+    renderer.info_wrapped(&tr!(Msg::SurvivalOver, name, waves));
+
+    if waves > profile.survival_best {
+        profile.survival_best = waves;
+        profile.save(profile_path)?;
+        renderer.info_wrapped(&tr!(Msg::SurvivalRecord, profile_path.display()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_speeds_up_every_wave() {
+        let curve = SpeedCurve::Ramp(SPEED_UP);
+        let delays: Vec<u32> = (1..=6).map(|wave| curve.counter_delay(50, wave)).collect();
+        assert_eq!(delays, [50, 45, 41, 38, 35, 33]);
+    }
+}
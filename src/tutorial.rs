@@ -0,0 +1,233 @@
+//! Tutorial module for walking a new player through one guided turn.
+//!
+//! The tutorial draws its targets from a fixed seed, so every new player sees
+//! the same turn. Each step is explained and waits for ENTER: the targets, the
+//! counter, then every stop with its misses and score. The tutor plays a turn
+//! of its own, and the round is resolved as in a match to show the damage and
+//! the penalty the winner picks.
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::audio::Audio;
+use crate::config::GameConfig;
+use crate::counter::{Counter, TurnEngine};
+use crate::error::GameResult;
+use crate::help::HELP_COMMAND;
+use crate::i18n::{Msg, tr};
+use crate::player::Player;
+use crate::rules::PENALTY_AMOUNT;
+use crate::runtime;
+use crate::scoring::{self, ScoreBreakdown};
+use crate::state::{GameState, RoundOutcome, TurnOutcome};
+use crate::ui::{self, CounterFrame};
+use crate::ui::render::Renderer;
+use crate::ui::source::InputSource;
+
+/// Seed the tutorial targets are drawn from.
+///
+/// With the default rules it draws 15, 50 and 75: one target in each third
+/// of the dial, in the order the counter reaches them, and none near either
+/// end, so the first turn never involves going around the dial.
+pub const TUTORIAL_SEED: u64 = 1447;
+
+/// Number of targets in the tutorial turn.
+pub const TUTORIAL_TARGETS: usize = 3;
+
+/// Where the tutor stops the counter for each target: whole units from the target, and misses.
+const TUTOR_STOPS: [(i32, u32); TUTORIAL_TARGETS] = [(2, 0), (-6, 0), (1, 1)];
+
+/// Draws the targets of the tutorial turn.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, for the rules the targets follow
+///
+/// # Returns
+///
+/// The targets, in ticks, the same every time
+pub fn tutorial_targets(config: &GameConfig) -> Vec<u32> {
+    let dial = config.rules.dial();
+    let mut rng = StdRng::seed_from_u64(TUTORIAL_SEED);
+    config.rules.targets(&mut rng, TUTORIAL_TARGETS).into_iter().map(|target| dial.ticks(target)).collect()
+}
+
+/// Shows a step of the tutorial and waits for the player to go on.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the step is shown
+/// * `input` - Where the player's ENTER is read from
+/// * `text` - The explanation of the step
+///
+/// # Returns
+///
+/// Result indicating whether reading input succeeded
+fn step(renderer: &mut dyn Renderer, input: &mut dyn InputSource, text: &str) -> GameResult<()> {
+    renderer.info_wrapped(text);
+    renderer.info(&tr!(Msg::TutorialContinue));
+    ui::wait_for_enter(input)
+}
+
+/// Runs the counter for one target until the player stops it.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the counter is drawn
+/// * `input` - Where the player's stop is read from
+/// * `config` - The game settings, for the dial, the view and the stop key
+/// * `target` - The target, in ticks
+/// * `delay` - The delay between two ticks, in milliseconds
+///
+/// # Returns
+///
+/// Result containing the counter value and the miss count when it was stopped
+fn run_counter(renderer: &mut dyn Renderer, input: &mut dyn InputSource, config: &GameConfig, target: u32, delay: u32)
+    -> GameResult<(u32, u32)> {
+    let dial = config.rules.dial();
+    let mut counter = Counter::with_dial(dial);
+    let ui_handle = renderer.display_counter(
+        counter.events(),
+        CounterFrame {
+            view: config.counter_view,
+            dial,
+            target,
+            moving_target: None,
+            hide_target: false,
+            hints: false,
+            combo: 0,
+            beat: None,
+            ghost: None,
+            started: None,
+        },
+        Audio::new(config.sound),
+    )?;
+    counter.start(&TurnEngine::new()?, delay)?;
+    let stopped = match config.keys.player1 {
+        Some(key) => renderer.wait_for_key(input, key),
+        None => ui::wait_for_enter(input),
+    };
+    let (value, miss) = counter.stop();
+    // Wait for the display task to finish, even if the wait was interrupted
+    runtime::join(ui_handle)?;
+    stopped?;
+    renderer.clear_counter(config.counter_view)?;
+    Ok((value, miss))
+}
+
+/// Walks the player through the tutorial turn and the round that follows.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the tutorial is shown
+/// * `input` - Where the player's presses are read from
+/// * `config` - The game settings
+///
+/// # Returns
+///
+/// Result containing how the round between the player and the tutor ended
+fn guide(renderer: &mut dyn Renderer, input: &mut dyn InputSource, config: &GameConfig) -> GameResult<RoundOutcome> {
+    let dial = config.rules.dial();
+    let player = Player::new(config.player1_name.clone(), config.vitality, config.speed, config.strength, config.stamina);
+    let tutor = Player::new(tr!(Msg::TutorialTutor), config.vitality, config.speed, config.strength, config.stamina);
+    let stop_key = config.keys.player1.map_or_else(|| tr!(Msg::TutorialEnterKey), |key| key.to_string());
+
+    step(renderer, input, &tr!(Msg::TutorialWelcome))?;
+    let targets = tutorial_targets(config);
+    let shown: Vec<_> = targets.iter().map(|&target| dial.format(target)).collect();
+    step(renderer, input, &tr!(Msg::TutorialTargets, dial.format(dial.max()), shown.join(", ")))?;
+    step(renderer, input, &tr!(Msg::TutorialCounter, dial.format(dial.max())))?;
+
+    let delay = config.rules.tick_interval(&player);
+    let mut scores = Vec::new();
+    for &target in &targets {
+        step(renderer, input, &tr!(Msg::TutorialStop, dial.format(target), stop_key))?;
+        let (value, miss) = run_counter(renderer, input, config, target, delay)?;
+        let breakdown = ScoreBreakdown::new(target, value, player.strength(), miss, 0, dial);
+        let score = breakdown.total();
+        scores.push(score);
+
+        renderer.info(&tr!(Msg::ObjectiveResult,
+                           dial.format(target), miss, dial.format(value), breakdown.base, breakdown.strength, miss + 1, score));
+        renderer.info(&ui::render_distance(target, value, dial));
+        match miss {
+            0 => renderer.info_wrapped(&tr!(Msg::TutorialNoMiss)),
+            _ => renderer.info_wrapped(&tr!(Msg::TutorialMiss, dial.format(dial.max()), miss, miss + 1)),
+        }
+        step(renderer, input, &tr!(Msg::TutorialScore,
+                                   breakdown.base, breakdown.strength, breakdown.base, breakdown.strength, miss + 1, score))?;
+    }
+    let average = scoring::calculate_average_score(&scores);
+    step(renderer, input, &tr!(Msg::TutorialAverage, average))?;
+
+    // The tutor's turn is scripted, so every tutorial ends the same way for the same stops
+    let (tutor_stops, tutor_scores): (Vec<_>, Vec<_>) = targets.iter().zip(TUTOR_STOPS)
+        .map(|(&target, (units, miss))| {
+            let value = dial.offset(target, units * dial.ticks(1) as i32);
+            (dial.format(value), ScoreBreakdown::new(target, value, tutor.strength(), miss, 0, dial).total())
+        })
+        .unzip();
+    let tutor_average = scoring::calculate_average_score(&tutor_scores);
+    step(renderer, input, &tr!(Msg::TutorialTutorTurn, tutor_stops.join(", "), tutor_average))?;
+
+    let state = GameState::new([player, tutor]);
+    let turns = [TurnOutcome::Played(average), TurnOutcome::Played(tutor_average)];
+    let (state, outcome) = state.resolve_round(turns, 0, config.rules.damage, &[]);
+    match outcome {
+        RoundOutcome::Win { winner, loser, diff, damage, .. } => {
+            let (winner, loser) = (&state.players[winner], &state.players[loser]);
+            step(renderer, input, &tr!(Msg::TutorialRoundWin, winner.name(), diff, loser.name(), damage))?;
+            step(renderer, input, &tr!(Msg::TutorialPenalty, PENALTY_AMOUNT, PENALTY_AMOUNT))?;
+        },
+        RoundOutcome::Draw => step(renderer, input, &tr!(Msg::TutorialRoundDraw))?,
+    }
+    renderer.info_wrapped(&tr!(Msg::TutorialEnd, HELP_COMMAND));
+    Ok(outcome)
+}
+
+/// Runs the tutorial.
+///
+/// # Arguments
+///
+/// * `config` - The game settings, for the output mode and the rules
+/// * `input` - Where the player's presses are read from
+///
+/// # Returns
+///
+/// Result indicating whether the tutorial could be played through
+pub fn play(config: &GameConfig, input: &mut dyn InputSource) -> GameResult<()> {
+    let mut renderer = config.render_mode.renderer();
+    guide(&mut *renderer, input, config)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::render::NullRenderer;
+    use crate::ui::source::ScriptedInput;
+
+    #[test]
+    fn test_tutorial_targets_are_the_same_every_time() {
+        let config = GameConfig::default();
+        let targets = tutorial_targets(&config);
+
+        assert_eq!(targets.len(), TUTORIAL_TARGETS);
+        assert_eq!(targets, tutorial_targets(&config));
+        assert!(targets.iter().all(|&target| target <= config.rules.dial().max()));
+    }
+
+    #[test]
+    fn test_tutorial_targets_are_spread_out() {
+        assert_eq!(tutorial_targets(&GameConfig::default()), [15, 50, 75]);
+    }
+
+    #[test]
+    fn test_tutor_wins_against_stops_at_zero() {
+        let config = GameConfig::default();
+        // Three steps before the targets, two steps and a stop per target, then the tutor's turn and the round
+        let mut input = ScriptedInput::new(vec!["0 enter".parse().unwrap(); 3 + 3 * TUTORIAL_TARGETS + 4]);
+        let outcome = guide(&mut NullRenderer, &mut input, &config).unwrap();
+
+        assert!(matches!(outcome, RoundOutcome::Win { winner: 1, loser: 0, .. }));
+    }
+}
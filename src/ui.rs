@@ -3,128 +3,1518 @@
 //! This module provides functions for displaying information and gathering input
 //! from users in a terminal environment.
 
+pub mod input;
+pub mod render;
+pub mod source;
+pub mod term;
+
 use std::{
+    f64::consts::TAU,
     io::{self, Write},
-    sync::{Arc, Mutex},
+    str::FromStr,
+    sync::{Mutex, atomic::{AtomicBool, Ordering}},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::task::JoinHandle;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::audio::{Audio, Sound};
+use crate::counter::{CounterClock, CounterEvent, CounterEvents, Dial, Shared};
 use crate::error::{GameError, GameResult};
+use crate::i18n::{Msg, tr};
+use crate::player::Player;
+use crate::runtime;
+use crate::scoring;
+use render::Renderer;
+use source::{InputSource, MenuKey};
+
+/// The scoreboard line kept at the top of the screen, while a match is shown.
+static SCOREBOARD: Mutex<Option<String>> = Mutex::new(None);
+
+/// Whether the terminal cannot keep the scoreboard out of the scrolling area,
+/// so it is printed with the rest of the output instead.
+static INLINE_SCOREBOARD: AtomicBool = AtomicBool::new(false);
+
+/// Number of cells used to draw the counter progress bar.
+const BAR_WIDTH: usize = 50;
+
+/// Width of the `[INFO ] ` prefix the logger adds to each message.
+const LOG_PREFIX_WIDTH: usize = 8;
+
+/// Time between two frames of the live counter, about 30 frames per second.
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+/// How long a beat stays highlighted on the counter in rhythm mode.
+const BEAT_FLASH: Duration = Duration::from_millis(150);
+
+/// Number of cells used to draw a vitality bar.
+const HP_BAR_WIDTH: usize = 10;
+
+/// Radius of the dial, in terminal rows.
+const DIAL_RADIUS: i32 = 4;
+
+/// Height of the block digits, in terminal rows.
+const BIG_DIGIT_HEIGHT: usize = 5;
+
+/// Block glyphs for the digits 0 to 9, one string per row.
+const BIG_DIGITS: [[&str; BIG_DIGIT_HEIGHT]; 10] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    [" █ ", "██ ", " █ ", " █ ", "███"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+];
+
+/// The available ways of drawing the live counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CounterView {
+    /// A single-line progress bar
+    #[default]
+    Bar,
+    /// A small clock face with a rotating hand
+    Dial,
+    /// The counter value in large block digits
+    Big,
+}
+
+impl CounterView {
+    /// Returns the number of terminal lines a frame of this view occupies.
+    pub fn height(self) -> usize {
+        match self {
+            CounterView::Bar => 1,
+            CounterView::Dial => (2 * DIAL_RADIUS + 2) as usize,
+            CounterView::Big => BIG_DIGIT_HEIGHT + 1,
+        }
+    }
+}
+
+impl FromStr for CounterView {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bar" => Ok(CounterView::Bar),
+            "dial" => Ok(CounterView::Dial),
+            "big" => Ok(CounterView::Big),
+            other => Err(GameError::ConfigError(format!("Unknown counter view: {}", other))),
+        }
+    }
+}
+
+/// How much detail is printed during a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Only turn and round results, without the per-target lines
+    Quiet,
+    /// The usual per-target results
+    #[default]
+    Normal,
+    /// Extra details such as the tick interval and reaction times
+    Verbose,
+}
+
+impl Verbosity {
+    /// Picks the verbosity from the `-v` and `-q` command line flags.
+    ///
+    /// # Arguments
+    ///
+    /// * `verbose` - Whether `-v` was given
+    /// * `quiet` - Whether `-q` was given, which takes precedence
+    ///
+    /// # Returns
+    ///
+    /// The selected verbosity
+    pub fn from_flags(verbose: bool, quiet: bool) -> Self {
+        if quiet {
+            Verbosity::Quiet
+        } else if verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+
+    /// Returns whether the result of each target is printed.
+    pub fn shows_targets(self) -> bool {
+        self >= Verbosity::Normal
+    }
+
+    /// Returns whether timing details are printed.
+    pub fn shows_details(self) -> bool {
+        self == Verbosity::Verbose
+    }
+}
+
+/// Renders a counter value as a 0–100 progress bar with a marker at the target.
+///
+/// # Arguments
+///
+/// * `value` - The current counter value (0–100)
+/// * `target` - The target number to mark on the bar (0–100), if shown
+/// * `width` - The number of cells in the bar
+///
+/// # Returns
+///
+/// The bar as a string, e.g. `[██████┃·····]`
+pub fn render_progress_bar(value: u32, target: Option<u32>, width: usize) -> String {
+    if width == 0 {
+        return "[]".to_string();
+    }
+
+    let filled = (value.min(100) as usize * width).div_ceil(100);
+    let marker = target.map(|target| target.min(100) as usize * (width - 1) / 100);
+
+    let cells: String = (0..width)
+        .map(|i| {
+            if Some(i) == marker {
+                '┃'
+            } else if i < filled {
+                '█'
+            } else {
+                '·'
+            }
+        })
+        .collect();
+
+    format!("[{}]", cells)
+}
+
+/// Renders a counter value as a small ASCII clock face.
+///
+/// Position 0 sits at the top of the dial and values increase clockwise.
+/// The hand points at the counter value and the target is marked with `◆`
+/// on the rim (`◉` when the hand is right on it).
+///
+/// # Arguments
+///
+/// * `value` - The current counter value (0–100)
+/// * `target` - The target number to mark on the rim (0–100), if shown
+///
+/// # Returns
+///
+/// The lines of the dial, from top to bottom
+pub fn render_dial(value: u32, target: Option<u32>) -> Vec<String> {
+    let radius = DIAL_RADIUS;
+    let height = (2 * radius + 1) as usize;
+    let width = (4 * radius + 1) as usize;
+    let mut grid = vec![vec![' '; width]; height];
+
+    // Terminal cells are roughly twice as tall as they are wide,
+    // so horizontal offsets are doubled to keep the dial round.
+    let point = |position: u32, distance: i32| -> (usize, usize) {
+        let angle = f64::from(position % 100) / 100.0 * TAU;
+        let distance = f64::from(distance);
+        let row = f64::from(radius) - (distance * angle.cos()).round();
+        let col = f64::from(2 * radius) + (2.0 * distance * angle.sin()).round();
+        (row as usize, col as usize)
+    };
+
+    // Rim graduations every 5 units
+    for position in (0..100).step_by(5) {
+        let (row, col) = point(position, radius);
+        grid[row][col] = '·';
+    }
+
+    // Hand from the center up to the rim
+    for distance in 1..radius {
+        let (row, col) = point(value, distance);
+        grid[row][col] = '•';
+    }
+    let tip = point(value, radius);
+    grid[tip.0][tip.1] = '●';
+
+    if let Some(target) = target {
+        let mark = point(target, radius);
+        grid[mark.0][mark.1] = if mark == tip { '◉' } else { '◆' };
+    }
+
+    grid[radius as usize][(2 * radius) as usize] = '+';
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect()
+}
+
+/// Renders a number in large block digits.
+///
+/// Decimal points are drawn as a block on the bottom row and any other
+/// character as a blank, so padded text keeps its width.
+///
+/// # Arguments
+///
+/// * `text` - The number to render, e.g. " 42" or "42.3"
+///
+/// # Returns
+///
+/// The lines of the rendered number, from top to bottom
+pub fn render_big_number(text: &str) -> Vec<String> {
+    (0..BIG_DIGIT_HEIGHT)
+        .map(|row| {
+            text.chars()
+                .map(|c| match c.to_digit(10) {
+                    Some(d) => BIG_DIGITS[d as usize][row],
+                    None if c == '.' && row == BIG_DIGIT_HEIGHT - 1 => " █ ",
+                    None => "   ",
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Feedback on whether the live counter is getting closer to a hidden target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// The counter moved closer to the target
+    Warmer,
+    /// The counter moved away from the target
+    Colder,
+}
+
+impl Hint {
+    /// Compares two successive counter values against the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The hidden target number
+    /// * `previous` - The counter value before the last tick
+    /// * `value` - The current counter value
+    /// * `dial` - The values the counter runs through
+    ///
+    /// # Returns
+    ///
+    /// The hint, or None if the counter is as far from the target as before
+    pub fn between(target: u32, previous: u32, value: u32, dial: Dial) -> Option<Self> {
+        let before = scoring::difference(target, previous, dial);
+        let after = scoring::difference(target, value, dial);
+        match after.cmp(&before) {
+            std::cmp::Ordering::Less => Some(Hint::Warmer),
+            std::cmp::Ordering::Greater => Some(Hint::Colder),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+    
+    /// Returns the label shown after the counter caption.
+    ///
+    /// # Returns
+    ///
+    /// The translated label
+    fn label(self) -> String {
+        match self {
+            Hint::Warmer => tr!(Msg::CounterWarmer),
+            Hint::Colder => tr!(Msg::CounterColder),
+        }
+    }
+}
+
+/// What is drawn around the live counter for one target.
+#[derive(Debug, Clone)]
+pub struct CounterFrame {
+    /// How the counter is drawn
+    pub view: CounterView,
+    /// The values the counter runs through
+    pub dial: Dial,
+    /// The target number, in ticks
+    pub target: u32,
+    /// The target as it moves, if it drifts while the counter runs
+    pub moving_target: Option<Shared<u32>>,
+    /// Whether the target is kept off the screen
+    pub hide_target: bool,
+    /// Whether warmer/colder hints are shown as the counter moves
+    pub hints: bool,
+    /// Accurate stops in a row before this target
+    pub combo: u32,
+    /// Number of ticks between two beats, in rhythm mode
+    pub beat: Option<u32>,
+    /// Where a recorded run stopped the counter for this target, in ticks
+    pub ghost: Option<u32>,
+    /// When the player started on this target, for the timer next to the counter
+    pub started: Option<Instant>,
+}
+
+/// Renders one frame of the live counter.
+///
+/// # Arguments
+///
+/// * `frame` - What is drawn around the counter
+/// * `value` - The current counter value
+/// * `miss` - The current miss count
+/// * `hint` - The last warmer/colder hint, if any
+/// * `beat` - Whether the counter just landed on a beat
+/// * `width` - The width of the terminal, in columns
+///
+/// # Returns
+///
+/// The lines of the frame, exactly `frame.view.height()` of them
+fn render_frame(frame: &CounterFrame, value: u32, miss: u32, hint: Option<Hint>, beat: bool, width: usize) -> Vec<String> {
+    // The bar and dial are drawn in percent of the range, the captions show the exact value
+    let dial = frame.dial;
+    let (target, marker) = if frame.hide_target {
+        ("?".to_string(), None)
+    } else {
+        (dial.format(frame.target), Some(dial.percent(frame.target)))
+    };
+    let shown_value = dial.format(value);
+
+    // The combo multiplier is shown after the caption once a combo is going
+    let mut combo_label = if frame.combo > 0 {
+        tr!(Msg::CounterCombo, format!("{:.1}", scoring::combo_multiplier(frame.combo)))
+    } else {
+        String::new()
+    };
+    if let Some(hint) = hint {
+        combo_label += &hint.label();
+    }
+    if let Some(ghost) = frame.ghost {
+        combo_label += &tr!(Msg::CounterGhost, dial.format(ghost));
+    }
+    if let Some(started) = frame.started {
+        combo_label += &tr!(Msg::CounterTimer, format_seconds(started.elapsed()));
+    }
+    let beat_label = tr!(Msg::CounterBeat);
+    if beat {
+        combo_label += &beat_label;
+    }
+
+    match frame.view {
+        CounterView::Bar => {
+            // Shrink the bar so the whole line fits on narrow terminals
+            // In rhythm mode room is kept for the beat, so the bar does not jump when it shows
+            let beat_width = if frame.beat.is_some() && !beat { beat_label.chars().count() } else { 0 };
+            let caption_width = tr!(Msg::CounterBar, target, "[]", miss, shown_value).chars().count()
+                + combo_label.chars().count() + beat_width;
+            let bar_width = BAR_WIDTH.min(width.saturating_sub(caption_width + 1));
+            let bar = render_progress_bar(dial.percent(value), marker, bar_width);
+            vec![tr!(Msg::CounterBar, target, bar, miss, shown_value) + &combo_label]
+        },
+        CounterView::Dial => {
+            let mut lines = render_dial(dial.percent(value), marker);
+            lines.push(tr!(Msg::CounterCaption, target, miss, shown_value) + &combo_label);
+            lines
+        },
+        CounterView::Big => {
+            // Right-aligned so that consecutive frames keep the same width
+            let width = dial.format(dial.max()).len();
+            let mut lines = render_big_number(&format!("{:>width$}", shown_value));
+            lines.push(tr!(Msg::CounterCaptionShort, target, miss) + &combo_label);
+            lines
+        },
+    }
+}
+
+/// What the display learned from the counter since it last looked.
+#[derive(Debug, Default, PartialEq)]
+struct CounterUpdate {
+    /// The counter's clock, if it started in the meantime
+    started: Option<CounterClock>,
+    /// The last value the counter moved to
+    value: Option<u32>,
+    /// Number of times the counter wrapped around
+    wraps: u32,
+    /// Whether the counter landed on a beat
+    beat: bool,
+    /// Whether the counter paused, and did not start again since
+    paused: bool,
+    /// Whether the counter stopped, or can no longer send anything
+    stopped: bool,
+}
+
+/// Reads every counter event waiting, without blocking.
+///
+/// # Arguments
+///
+/// * `events` - The counter's events
+/// * `beat` - Number of ticks between two beats, or None for no rhythm
+///
+/// # Returns
+///
+/// What changed since the events were last read
+fn read_counter_events(events: &mut CounterEvents, beat: Option<u32>) -> CounterUpdate {
+    let mut update = CounterUpdate::default();
+    loop {
+        match events.try_recv() {
+            Ok(CounterEvent::Started(clock)) => {
+                update.started = Some(clock);
+                update.paused = false;
+            },
+            Ok(CounterEvent::Paused) => update.paused = true,
+            Ok(CounterEvent::Tick(value)) => {
+                update.value = Some(value);
+                update.beat |= beat.is_some_and(|beat| value.is_multiple_of(beat));
+            },
+            Ok(CounterEvent::Wrapped) => update.wraps += 1,
+            Ok(CounterEvent::Stopped(..)) | Err(TryRecvError::Disconnected) => {
+                update.stopped = true;
+                return update;
+            },
+            Err(TryRecvError::Empty) => return update,
+        }
+    }
+}
 
 /// Displays a counter that increments in real-time.
 ///
 /// # Arguments
 ///
-/// * `value` - Shared counter value
-/// * `miss` - Shared miss counter
-/// * `running` - Shared flag indicating if counter is running
-/// * `target` - Target number to display
+/// * `events` - The counter's events, the display ends when the counter stops
+/// * `frame` - What is drawn around the counter
+/// * `audio` - Sound effects player for ticks and misses
 ///
 /// # Returns
 ///
-/// A handle to the display thread
-pub fn display_counter(
-    value: Arc<Mutex<u32>>,
-    miss: Arc<Mutex<u32>>,
-    running: Arc<Mutex<bool>>,
-    target: u32
-) -> GameResult<thread::JoinHandle<()>> {
+/// A handle to the display task
+pub fn display_counter(mut events: CounterEvents, mut frame: CounterFrame, audio: Audio) -> GameResult<JoinHandle<()>> {
+    let handle = runtime::get()?.spawn(async move {
+        let mut drawn = 0;
+        let mut size = term::size();
+        // Ignoring potential errors here as we can't propagate from the task
+        let _ = term::hide_cursor(&mut io::stdout());
+        let (mut clock, mut last_value, mut miss) = (None, 0, 0);
+        let mut hint = None;
+        let mut beat_shown = None;
+        let mut paused: Option<Instant> = None;
+        // Frames are drawn at a steady rate whatever the counter speed, and a slow frame is skipped rather than caught up
+        let mut frames = time::interval(FRAME_INTERVAL);
+        frames.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            let update = read_counter_events(&mut events, frame.beat);
+            if update.stopped {
+                break;
+            }
+            clock = update.started.or(clock);
+            miss += update.wraps;
+            if let (Some(_), Some(since)) = (update.started, paused.take()) {
+                // The timer next to the counter does not run while it is paused either
+                frame.started = frame.started.map(|started| started + since.elapsed());
+            }
+            if update.paused {
+                // Leave the frame for the line below, where a panel can be shown while the counter waits
+                let mut stdout = io::stdout().lock();
+                let _ = writeln!(stdout).and_then(|_| stdout.flush());
+                drawn += 1;
+                paused = Some(Instant::now());
+            }
+            if paused.is_some() {
+                frames.tick().await;
+                continue;
+            }
+            // The value shown follows the clock, so it never lags behind the counter's worker
+            let (v, m) = match clock {
+                Some(clock) => clock.reading_at(Instant::now()),
+                None => (update.value.unwrap_or(last_value), miss),
+            };
+            if let Some(target) = &frame.moving_target {
+                frame.target = *target.lock().unwrap();
+            }
+
+            // Keep the last hint while the distance to the target does not change
+            if frame.hints {
+                hint = Hint::between(frame.target, last_value, v, frame.dial).or(hint);
+            }
+
+            // Each beat stays on the screen for a moment, even when the counter ticks faster
+            if update.beat {
+                beat_shown = Some(Instant::now());
+            }
+            let beat = beat_shown.is_some_and(|shown| shown.elapsed() < BEAT_FLASH);
+
+            if update.wraps > 0 {
+                audio.play(Sound::Miss);
+            } else if update.beat {
+                audio.play(Sound::Beat);
+            } else if update.value.is_some() {
+                audio.play(Sound::Tick);
+            }
+            last_value = v;
+
+            // The lock is released before waiting for the next frame
+            {
+                let mut stdout = io::stdout().lock();
+
+                // The terminal rewraps its content when resized, so the previous frame
+                // can no longer be found reliably: start over from a clean screen
+                let current_size = term::size();
+                if current_size != size {
+                    let _ = term::clear_screen(&mut stdout);
+                    if SCOREBOARD.lock().unwrap().is_some() {
+                        let _ = reserve_scoreboard_row(&mut stdout, current_size);
+                    }
+                    size = current_size;
+                    drawn = 0;
+                }
+
+                let width = usize::from(size.0);
+                let lines = render_frame(&frame, v, m, hint, beat, width);
+                // Ignoring potential errors here as we can't propagate from the task
+                let _ = draw_frame(&mut stdout, &lines, drawn, width);
+                drawn = lines.len();
+            }
 
-    let handle = thread::spawn(move || {
-        while *running.lock().unwrap() {
-            let v = *value.lock().unwrap();
-            let m = *miss.lock().unwrap();
-            print!("\r\x1B[K→ Objective {}: Miss = {} | Counter = {}", target, m, v);
-            // Ignoring potential errors here as we can't propagate from thread
-            let _ = io::stdout().flush();
-            thread::sleep(Duration::from_millis(30));
+            frames.tick().await;
         }
     });
     
     Ok(handle)
 }
 
+/// Draws a frame over the previous one, leaving the cursor at the end of its last line.
+///
+/// # Arguments
+///
+/// * `out` - The writer to draw on
+/// * `frame` - The lines of the frame
+/// * `previous_height` - The number of lines of the previous frame (0 if none)
+/// * `width` - The width of the terminal, lines are truncated to it
+fn draw_frame<W: Write>(out: &mut W, frame: &[String], previous_height: usize, width: usize) -> io::Result<()> {
+    // Move back to the first line of the previous frame and redraw in place
+    term::move_up(out, previous_height.saturating_sub(1) as u16)?;
+    for (i, line) in frame.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        term::clear_line(out)?;
+        // A line that wraps would shift the frame and break the redraw
+        write!(out, "{}", fit_to_width(line, width))?;
+    }
+    out.flush()
+}
+
+/// Truncates a line so that it fits within the given width.
+///
+/// # Arguments
+///
+/// * `line` - The line to truncate
+/// * `width` - The maximum number of characters
+///
+/// # Returns
+///
+/// The line, cut to at most `width` characters
+pub fn fit_to_width(line: &str, width: usize) -> String {
+    line.chars().take(width).collect()
+}
+
+/// Wraps text on spaces so that no line is wider than the given width.
+///
+/// Words longer than the width are split.
+///
+/// # Arguments
+///
+/// * `text` - The text to wrap
+/// * `width` - The maximum number of characters per line
+///
+/// # Returns
+///
+/// The wrapped lines
+pub fn wrap_to_width(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+
+    for word in text.split(' ') {
+        let mut word: Vec<char> = word.chars().collect();
+
+        if current_width > 0 && current_width + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        while word.len() > width {
+            let rest = word.split_off(width);
+            if current_width > 0 {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.into_iter().collect());
+            current_width = 0;
+            word = rest;
+        }
+        if current_width > 0 {
+            current.push(' ');
+            current_width += 1;
+        }
+        current_width += word.len();
+        current.extend(word);
+    }
+    lines.push(current);
+
+    lines
+}
+
+/// Renders a series of values as a one-line chart of block characters.
+///
+/// Values are scaled so that the largest one reaches the full height.
+///
+/// # Arguments
+///
+/// * `values` - The values to chart
+///
+/// # Returns
+///
+/// One block character per value, e.g. `▁▄█▆`
+pub fn render_sparkline(values: &[u32]) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values.iter()
+        .map(|&value| LEVELS[(value as usize * (LEVELS.len() - 1)).div_ceil(max as usize)])
+        .collect()
+}
+
+/// Renders how far a stop landed from its target.
+///
+/// # Arguments
+///
+/// * `target` - The target of the stop
+/// * `value` - The counter value when stopped
+/// * `dial` - The values the counter runs through
+///
+/// # Returns
+///
+/// The translated feedback line, e.g. `  Distance from target: 4`
+pub fn render_distance(target: u32, value: u32, dial: Dial) -> String {
+    tr!(Msg::StopDistance, dial.format(scoring::difference(target, value, dial)))
+}
+
+/// Lays out a table, every column right-aligned to its widest cell.
+///
+/// Numbers of any length line up on their last digit, and a rule under the
+/// header is as wide as each column.
+///
+/// # Arguments
+///
+/// * `header` - The title of each column
+/// * `rows` - The cells of each row, one per column
+///
+/// # Returns
+///
+/// The lines of the table: the header, the rule, then one line per row
+pub fn table_lines(header: &[String], rows: &[Vec<String>]) -> Vec<String> {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            let cells = rows.iter().filter_map(|row| row.get(column));
+            cells.chain([&header[column]]).map(|cell| cell.chars().count()).max().unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: &mut dyn Iterator<Item = String>| -> String {
+        let cells: Vec<_> = cells.zip(&widths).map(|(cell, &width)| format!("{:>width$}", cell)).collect();
+        cells.join("  ")
+    };
+    let mut lines = vec![
+        line(&mut header.iter().cloned()),
+        line(&mut widths.iter().map(|&width| "─".repeat(width))),
+    ];
+    lines.extend(rows.iter().map(|row| line(&mut row.iter().cloned())));
+    lines
+}
+
+/// Prints a table, every column right-aligned to its widest cell.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the table is printed
+/// * `header` - The title of each column
+/// * `rows` - The cells of each row, one per column
+pub fn print_table<R: Renderer + ?Sized>(renderer: &mut R, header: &[String], rows: &[Vec<String>]) {
+    for line in table_lines(header, rows) {
+        renderer.info(&line);
+    }
+}
+
+/// Formats a duration in seconds, to a tenth.
+///
+/// # Arguments
+///
+/// * `duration` - The duration to format
+///
+/// # Returns
+///
+/// The seconds, e.g. `3.4`
+pub fn format_seconds(duration: Duration) -> String {
+    format!("{:.1}", duration.as_secs_f64())
+}
+
+/// Renders vitality as a bar proportional to the maximum, followed by the numbers.
+///
+/// # Arguments
+///
+/// * `vitality` - The current vitality
+/// * `max_vitality` - The vitality at full health
+///
+/// # Returns
+///
+/// The bar as a string, e.g. `████████░░ 40/50`
+pub fn render_hp_bar(vitality: u32, max_vitality: u32) -> String {
+    let filled = if max_vitality == 0 {
+        0
+    } else {
+        (vitality.min(max_vitality) as usize * HP_BAR_WIDTH).div_ceil(max_vitality as usize)
+    };
+    format!("{}{} {}/{}", "█".repeat(filled), "░".repeat(HP_BAR_WIDTH - filled), vitality, max_vitality)
+}
+
+/// Renders the one-line scoreboard shown at the top of the screen.
+///
+/// # Arguments
+///
+/// * `players` - The players of the match
+/// * `round` - The current round
+///
+/// # Returns
+///
+/// The scoreboard line
+pub fn render_scoreboard(players: &[Player], round: u32) -> String {
+    let mut line = tr!(Msg::ScoreboardRound, round);
+    for player in players {
+        line.push_str(" | ");
+        line.push_str(&tr!(Msg::ScoreboardPlayer, player.name(),
+                      render_hp_bar(player.vitality(), player.max_vitality()),
+                      player.speed(), player.max_speed(), player.strength(), player.max_strength(),
+                      player.stamina(), player.max_stamina()));
+    }
+    line
+}
+
+/// Shows or updates the scoreboard at the top of the screen.
+///
+/// The first call clears the screen and keeps the top row out of the
+/// scrolling area so the scoreboard stays visible during play. Consoles
+/// without scroll regions get the scoreboard printed inline instead.
+///
+/// # Arguments
+///
+/// * `line` - The scoreboard line, see `render_scoreboard`
+///
+/// # Returns
+///
+/// Result indicating whether writing to the terminal succeeded.
+pub fn show_scoreboard(line: String) -> GameResult<()> {
+    if INLINE_SCOREBOARD.load(Ordering::Relaxed) {
+        log::info!("{}", line);
+        return Ok(());
+    }
+    let mut scoreboard = SCOREBOARD.lock().unwrap();
+    let mut stdout = io::stdout().lock();
+    let first = scoreboard.is_none();
+    *scoreboard = Some(line);
+    drop(scoreboard);
+
+    if first {
+        term::clear_screen(&mut stdout)?;
+        match reserve_scoreboard_row(&mut stdout, term::size()) {
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => {
+                INLINE_SCOREBOARD.store(true, Ordering::Relaxed);
+                if let Some(line) = SCOREBOARD.lock().unwrap().take() {
+                    log::info!("{}", line);
+                }
+            }
+            result => result?,
+        }
+    } else {
+        draw_scoreboard(&mut stdout, usize::from(term::size().0))?;
+    }
+    stdout.flush().map_err(GameError::from)
+}
+
+/// Stops keeping the scoreboard at the top of the screen.
+///
+/// The last scoreboard stays visible and scrolls away with the output.
+///
+/// # Returns
+///
+/// Result indicating whether writing to the terminal succeeded.
+pub fn hide_scoreboard() -> GameResult<()> {
+    if SCOREBOARD.lock().unwrap().take().is_none() {
+        return Ok(());
+    }
+    let mut stdout = io::stdout().lock();
+    term::reset_scroll_region(&mut stdout)?;
+    stdout.flush().map_err(GameError::from)
+}
+
+/// Draws the scoreboard on the top row and limits scrolling to the rows below it.
+///
+/// Expects the cursor on the top row, as left by `term::clear_screen`.
+///
+/// # Arguments
+///
+/// * `out` - The writer to draw on
+/// * `size` - The size of the terminal as `(columns, rows)`
+fn reserve_scoreboard_row<W: Write>(out: &mut W, (columns, rows): (u16, u16)) -> io::Result<()> {
+    writeln!(out)?;
+    term::set_scroll_region(out, 1, rows.saturating_sub(1).max(1))?;
+    draw_scoreboard(out, usize::from(columns))
+}
+
+/// Redraws the scoreboard on the top row, if one is shown.
+///
+/// # Arguments
+///
+/// * `out` - The writer to draw on
+/// * `width` - The width of the terminal, the line is truncated to it
+fn draw_scoreboard<W: Write>(out: &mut W, width: usize) -> io::Result<()> {
+    match SCOREBOARD.lock().unwrap().as_deref() {
+        Some(line) => term::write_row(out, 0, &fit_to_width(line, width)),
+        None => Ok(()),
+    }
+}
+
+/// Logs a message wrapped to the width of the terminal.
+///
+/// # Arguments
+///
+/// * `text` - The message to log
+pub fn info_wrapped(text: &str) {
+    let width = usize::from(term::size().0).saturating_sub(LOG_PREFIX_WIDTH);
+    for line in wrap_to_width(text, width) {
+        log::info!("{}", line);
+    }
+}
+
+/// Logs a message wrapped to the width of the terminal, then erases it after a delay.
+///
+/// # Arguments
+///
+/// * `text` - The message to log
+/// * `duration` - How long the message stays on the screen
+///
+/// # Returns
+///
+/// Result indicating whether writing to the terminal succeeded.
+pub fn show_briefly(text: &str, duration: Duration) -> GameResult<()> {
+    let width = usize::from(term::size().0).saturating_sub(LOG_PREFIX_WIDTH);
+    let lines = wrap_to_width(text, width);
+    for line in &lines {
+        log::info!("{}", line);
+    }
+    thread::sleep(duration);
+    
+    let mut stdout = io::stdout().lock();
+    term::move_up(&mut stdout, lines.len() as u16)?;
+    term::clear_line(&mut stdout)?;
+    term::clear_below(&mut stdout)?;
+    stdout.flush().map_err(GameError::from)
+}
+
+/// Clears the last frame drawn by `display_counter`.
+///
+/// Expects the cursor on the line below the frame, where it lands after
+/// the player pressed ENTER or their stop key.
+///
+/// # Arguments
+///
+/// * `view` - The view the frame was drawn with
+///
+/// # Returns
+///
+/// Result indicating whether writing to the terminal succeeded.
+pub fn clear_counter(view: CounterView) -> GameResult<()> {
+    let mut stdout = io::stdout().lock();
+    term::move_up(&mut stdout, view.height() as u16)?;
+    term::clear_line(&mut stdout)?;
+    term::clear_below(&mut stdout)?;
+    term::show_cursor(&mut stdout)?;
+    stdout.flush().map_err(GameError::from)
+}
+
 /// Waits for the user to press ENTER.
 ///
+/// # Arguments
+///
+/// * `input` - Where the players' actions are read from
+///
 /// # Returns
 ///
 /// Result indicating whether reading input succeeded.
-pub fn wait_for_enter() -> GameResult<()> {
-    let mut buffer = String::new();
-    io::stdin().read_line(&mut buffer).map_err(GameError::from)?;
+pub fn wait_for_enter(input: &mut dyn InputSource) -> GameResult<()> {
+    input.read_line()?;
     Ok(())
 }
 
-/// Prompts the user for a choice between given options.
+/// What the players want to do once a match is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rematch {
+    /// Play again with the same settings
+    Same,
+    /// Play again, with the other player going first
+    SwapFirst,
+    /// Play again from the speed, strength and stamina left, with full vitality
+    CarryOver,
+    /// Stop playing
+    Quit,
+}
+
+/// The choices of the rematch menu, in the order they are listed.
+const REMATCH_OPTIONS: [(Rematch, Msg); 4] = [
+    (Rematch::Same, Msg::RematchSame),
+    (Rematch::SwapFirst, Msg::RematchSwap),
+    (Rematch::CarryOver, Msg::RematchCarryOver),
+    (Rematch::Quit, Msg::RematchQuit),
+];
+
+/// Asks the players what to do once a match is over.
+///
+/// Any typed answer that is not one of the listed numbers quits, like the
+/// "no" answer of the former play again question.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the menu is drawn
+/// * `input` - Where the players' answer is read from
+///
+/// # Returns
+///
+/// Result containing the players' choice
+pub fn rematch_menu(renderer: &mut dyn Renderer, input: &mut dyn InputSource) -> GameResult<Rematch> {
+    let options = REMATCH_OPTIONS.map(|(_, msg)| tr!(msg));
+    let options = options.each_ref().map(String::as_str);
+    let choice = select_menu(renderer, input, &tr!(Msg::RematchMenu), &options)?;
+    Ok(choice.map_or(Rematch::Quit, |idx| REMATCH_OPTIONS[idx].0))
+}
+
+/// Lets a player pick one of a list of options.
+///
+/// The options are listed with their numbers. With the keyboard, the arrow
+/// keys move a highlighted selection and ENTER chooses it, or a digit chooses
+/// an option at once. Sources that answer with lines, such as scripts, type
+/// the number of the option instead.
+///
+/// # Arguments
+///
+/// * `renderer` - Where the menu is drawn
+/// * `input` - Where the player's keys or answer are read from
+/// * `prompt` - The question above the options
+/// * `options` - The options, at least one
+///
+/// # Returns
+///
+/// Result containing the index of the option chosen, or the message explaining
+/// why a typed answer is not one of the options
+pub fn select_menu<R: Renderer + ?Sized>(renderer: &mut R, input: &mut dyn InputSource, prompt: &str, options: &[&str])
+    -> GameResult<Result<usize, Msg>> {
+    renderer.info(prompt);
+    let mut selected = 0;
+    renderer.draw_menu(options, selected, false)?;
+    loop {
+        selected = match input.read_menu_key()? {
+            None => break,
+            Some(MenuKey::Select) => return Ok(Ok(selected)),
+            Some(MenuKey::Number(idx)) if idx < options.len() => {
+                renderer.draw_menu(options, idx, true)?;
+                return Ok(Ok(idx));
+            },
+            Some(MenuKey::Number(_)) => continue,
+            Some(MenuKey::Up) => (selected + options.len() - 1) % options.len(),
+            Some(MenuKey::Down) => (selected + 1) % options.len(),
+        };
+        renderer.draw_menu(options, selected, true)?;
+    }
+
+    renderer.prompt()?;
+    Ok(parse_choice(&input.read_line()?, options.len()))
+}
+
+/// Parses the number of an option typed by a player.
 ///
 /// # Arguments
 ///
-/// * `prompt` - The message to display
-/// * `options` - The available options
-/// * `test_input` - Optional test input for automated testing
+/// * `input` - The line typed by the player
+/// * `count` - The number of options
 ///
 /// # Returns
 ///
-/// Result containing the selected option index (0-based)
-pub fn get_user_choice(prompt: &str, options: &[&str], test_input: Option<usize>) -> GameResult<usize> {
-    if let Some(choice) = test_input {
-        if choice < options.len() {
-            return Ok(choice);
+/// The index of the option, or the message explaining why it was rejected
+fn parse_choice(input: &str, count: usize) -> Result<usize, Msg> {
+    match input.trim().parse::<usize>() {
+        Ok(n) if n > 0 && n <= count => Ok(n - 1),
+        Ok(_) => Err(Msg::InvalidChoice),
+        Err(_) => Err(Msg::UnparsableChoice),
+    }
+}
+
+/// Draws a framed panel of text, then takes it off the screen once the player presses ENTER.
+///
+/// The game under the panel is left as it was, so the player picks up where they were.
+///
+/// # Arguments
+///
+/// * `input` - Where the player presses ENTER
+/// * `lines` - The lines of the panel
+///
+/// # Returns
+///
+/// Result indicating whether writing to the terminal and reading input succeeded.
+pub fn show_overlay(input: &mut dyn InputSource, lines: &[String]) -> GameResult<()> {
+    let width = usize::from(term::size().0).saturating_sub(LOG_PREFIX_WIDTH + 4);
+    let lines: Vec<String> = lines.iter().flat_map(|line| wrap_to_width(line, width)).collect();
+    let inner = lines.iter().map(|line| line.chars().count()).max().unwrap_or_default();
+
+    log::info!("┌{}┐", "─".repeat(inner + 2));
+    for line in &lines {
+        log::info!("│ {:<inner$} │", line);
+    }
+    log::info!("└{}┘", "─".repeat(inner + 2));
+    log::info!("{}", tr!(Msg::HelpDismiss));
+    input.read_line()?;
+
+    // The panel, the dismiss line and the line the ENTER moved to
+    let mut stdout = io::stdout().lock();
+    term::move_up(&mut stdout, lines.len() as u16 + 4)?;
+    term::clear_line(&mut stdout)?;
+    term::clear_below(&mut stdout)?;
+    stdout.flush().map_err(GameError::from)
+}
+
+/// Draws the options of a menu, the selected one highlighted.
+///
+/// # Arguments
+///
+/// * `options` - The options
+/// * `selected` - The index of the selected option
+/// * `redraw` - Whether the menu is right above the cursor, to be drawn over
+///
+/// # Returns
+///
+/// Result indicating whether writing to the terminal succeeded.
+pub fn draw_menu(options: &[&str], selected: usize, redraw: bool) -> GameResult<()> {
+    if redraw {
+        let mut stdout = io::stdout().lock();
+        term::move_up(&mut stdout, options.len() as u16)?;
+        term::clear_line(&mut stdout)?;
+        term::clear_below(&mut stdout)?;
+        stdout.flush()?;
+    }
+    for (i, option) in options.iter().enumerate() {
+        let line = format!("→ {}: {}", i + 1, option);
+        if i == selected {
+            log::info!("{}", term::highlight(&line));
         } else {
-            return Ok(0); // Default to first option if invalid test input
+            log::info!("{}", line);
         }
     }
-    
-    log::info!("{}", prompt);
-    
-    for (i, option) in options.iter().enumerate() {
-        log::info!("→ {}: {}", i + 1, option);
+    Ok(())
+}
+
+/// Moves to the next line like ENTER does, where `clear_counter` expects the cursor.
+pub fn end_key_wait() -> GameResult<()> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout)?;
+    stdout.flush().map_err(GameError::from)
+}
+
+/// Parses a wager typed by a player.
+///
+/// # Arguments
+///
+/// * `input` - The line typed by the player
+/// * `max` - The largest allowed wager
+///
+/// # Returns
+///
+/// The wager, or the message explaining why it was rejected
+fn parse_wager(input: &str, max: u32) -> Result<u32, Msg> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(0);
     }
     
-    print!(">");
-    io::stdout().flush().map_err(GameError::from)?;
-    
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).map_err(GameError::from)?;
-    
-    match input.trim().parse::<usize>() {
-        Ok(n) if n > 0 && n <= options.len() => Ok(n - 1),
-        Ok(_) => {
-            log::info!("Invalid choice. Selecting the first option by default.");
-            Ok(0)
-        },
-        Err(_) => {
-            log::info!("Could not parse input. Selecting the first option by default.");
-            Ok(0)
-        }
+    match input.parse::<u32>() {
+        Ok(wager) if wager <= max => Ok(wager),
+        Ok(_) => Err(Msg::WagerTooHigh),
+        Err(_) => Err(Msg::WagerUnparsable),
+    }
+}
+
+/// Parses a turn average predicted by a player.
+///
+/// # Arguments
+///
+/// * `input` - The line typed by the player
+///
+/// # Returns
+///
+/// The prediction, None for an empty line, or the message explaining why it was rejected
+fn parse_prediction(input: &str) -> Result<Option<u32>, Msg> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
     }
+    input.parse::<u32>().map(Some).map_err(|_| Msg::PredictionUnparsable)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::{Arc, Mutex};
+    use crate::counter::Precision;
+    use crate::ui::render::NullRenderer;
+    use crate::ui::source::testing::scripted;
+
+    #[test]
+    fn test_rematch_menu() {
+        let answers = ["1", "2", "3", "4", "n", "9"];
+        let mut input = scripted(&answers);
+        let choices: Vec<Rematch> = answers.iter()
+            .map(|_| rematch_menu(&mut NullRenderer, &mut input).unwrap())
+            .collect();
+
+        assert_eq!(choices, [Rematch::Same, Rematch::SwapFirst, Rematch::CarryOver, Rematch::Quit, Rematch::Quit, Rematch::Quit]);
+    }
+
+    /// Navigates menus with a fixed list of keys.
+    struct MenuKeys(Vec<MenuKey>);
+
+    impl InputSource for MenuKeys {
+        fn read_line(&mut self) -> GameResult<String> {
+            Err(GameError::Interrupted)
+        }
+
+        fn wait_for_key(&mut self, _keys: &[char]) -> GameResult<usize> {
+            Err(GameError::Interrupted)
+        }
+
+        fn wait_for_key_timeout(&mut self, _keys: &[char], _timeout: Duration) -> GameResult<Option<usize>> {
+            Err(GameError::Interrupted)
+        }
+
+        fn read_menu_key(&mut self) -> GameResult<Option<MenuKey>> {
+            Ok(Some(self.0.remove(0)))
+        }
+    }
+
+    #[test]
+    fn test_select_menu_with_keys() {
+        let options = ["a", "b", "c"];
+        let mut input = MenuKeys(vec![MenuKey::Up, MenuKey::Down, MenuKey::Down, MenuKey::Select]);
+        assert_eq!(select_menu(&mut NullRenderer, &mut input, "", &options).unwrap(), Ok(1));
+
+        // Out of range digits are ignored
+        let mut input = MenuKeys(vec![MenuKey::Number(5), MenuKey::Number(2)]);
+        assert_eq!(select_menu(&mut NullRenderer, &mut input, "", &options).unwrap(), Ok(2));
+
+        let mut input = MenuKeys(vec![MenuKey::Down, MenuKey::Down, MenuKey::Down, MenuKey::Select]);
+        assert_eq!(rematch_menu(&mut NullRenderer, &mut input).unwrap(), Rematch::Quit);
+    }
+
+    #[test]
+    fn test_parse_choice() {
+        assert_eq!(parse_choice(" 2\n", 3), Ok(1));
+        assert_eq!(parse_choice("0", 3), Err(Msg::InvalidChoice));
+        assert_eq!(parse_choice("4", 3), Err(Msg::InvalidChoice));
+        assert_eq!(parse_choice("two", 3), Err(Msg::UnparsableChoice));
+    }
+
+    #[test]
+    fn test_parse_prediction() {
+        assert_eq!(parse_prediction(""), Ok(None));
+        assert_eq!(parse_prediction(" 85\n"), Ok(Some(85)));
+        assert_eq!(parse_prediction("-3"), Err(Msg::PredictionUnparsable));
+        assert_eq!(parse_prediction("high"), Err(Msg::PredictionUnparsable));
+    }
+
+    #[test]
+    fn test_parse_wager() {
+        assert_eq!(parse_wager("", 20), Ok(0));
+        assert_eq!(parse_wager(" 15\n", 20), Ok(15));
+        assert_eq!(parse_wager("20", 20), Ok(20));
+        assert_eq!(parse_wager("21", 20), Err(Msg::WagerTooHigh));
+        assert_eq!(parse_wager("-3", 20), Err(Msg::WagerUnparsable));
+        assert_eq!(parse_wager("ten", 20), Err(Msg::WagerUnparsable));
+    }
+
+    #[test]
+    fn test_render_scoreboard() {
+        let mut alice = Player::new("Alice".to_string(), 50, 50, 45, 3);
+        alice.decrease_vitality(10);
+        alice.decrease_stamina(1);
+        let players = [alice, Player::new("Bob".to_string(), 50, 45, 50, 3)];
+        assert_eq!(render_scoreboard(&players, 3),
+                   concat!("Round 3 | Alice ████████░░ 40/50 Sp:50/50 Str:45/45 Sta:2/3",
+                           " | Bob ██████████ 50/50 Sp:45/45 Str:50/50 Sta:3/3"));
+    }
+
+    #[test]
+    fn test_render_hp_bar() {
+        assert_eq!(render_hp_bar(40, 50), "████████░░ 40/50");
+        assert_eq!(render_hp_bar(1, 100), "█░░░░░░░░░ 1/100");
+        assert_eq!(render_hp_bar(0, 50), "░░░░░░░░░░ 0/50");
+        assert_eq!(render_hp_bar(0, 0), "░░░░░░░░░░ 0/0");
+    }
+
+    #[test]
+    fn test_table_columns_line_up() {
+        let header = ["Target", "Score"].map(String::from);
+        let rows = [vec!["5".to_string(), "180".to_string()], vec!["100".to_string(), "7".to_string()]];
+        assert_eq!(table_lines(&header, &rows), [
+            "Target  Score",
+            "──────  ─────",
+            "     5    180",
+            "   100      7",
+        ]);
+    }
+
+    #[test]
+    fn test_render_distance() {
+        assert_eq!(render_distance(10, 14, Dial::default()), "  Distance from target: 4");
+        assert_eq!(render_distance(2, 99, Dial::default()), "  Distance from target: 4");
+        assert_eq!(render_distance(500, 512, Dial::new(100, Precision::Fine)), "  Distance from target: 1.2");
+    }
+
+    #[test]
+    fn test_render_sparkline() {
+        assert_eq!(render_sparkline(&[]), "");
+        assert_eq!(render_sparkline(&[0, 50, 100]), "▁▅█");
+        assert_eq!(render_sparkline(&[0, 0]), "▁▁");
+        assert_eq!(render_sparkline(&[200, 100, 1]), "█▅▂");
+    }
+
+    #[test]
+    fn test_verbosity_from_flags() {
+        assert_eq!(Verbosity::from_flags(false, false), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(true, false), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, true), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_flags(true, true), Verbosity::Quiet);
+    }
+
+    #[test]
+    fn test_verbosity_levels() {
+        assert!(!Verbosity::Quiet.shows_targets());
+        assert!(Verbosity::Normal.shows_targets());
+        assert!(!Verbosity::Normal.shows_details());
+        assert!(Verbosity::Verbose.shows_targets());
+        assert!(Verbosity::Verbose.shows_details());
+    }
+    use crate::audio::SoundMode;
+
+    #[test]
+    fn test_render_progress_bar() {
+        // Empty counter with the target at the start
+        assert_eq!(render_progress_bar(0, Some(0), 10), "[┃·········]");
+
+        // Half-full counter with the target at the end
+        assert_eq!(render_progress_bar(50, Some(100), 10), "[█████····┃]");
+
+        // Full counter, the marker stays visible over the filled cells
+        assert_eq!(render_progress_bar(100, Some(50), 10), "[████┃█████]");
+
+        // Values above 100 are clamped
+        assert_eq!(render_progress_bar(150, Some(150), 4), render_progress_bar(100, Some(100), 4));
+    }
+
+    #[test]
+    fn test_render_progress_bar_zero_width() {
+        assert_eq!(render_progress_bar(42, Some(42), 0), "[]");
+    }
+
+    #[test]
+    fn test_render_dial() {
+        let radius = DIAL_RADIUS as usize;
+        let lines = render_dial(0, Some(50));
+
+        assert_eq!(lines.len(), 2 * radius + 1);
+        assert_eq!(lines[radius].chars().nth(2 * radius), Some('+'));
+
+        // Value 0 points straight up, target 50 sits at the bottom of the rim
+        assert_eq!(lines[0].chars().nth(2 * radius), Some('●'));
+        assert_eq!(lines[radius - 1].chars().nth(2 * radius), Some('•'));
+        assert_eq!(lines[2 * radius].chars().nth(2 * radius), Some('◆'));
+    }
+
+    #[test]
+    fn test_render_dial_hand_on_target() {
+        let lines = render_dial(25, Some(25));
+        assert!(lines.iter().any(|line| line.contains('◉')));
+        assert!(!lines.iter().any(|line| line.contains('◆')));
+    }
+
+    #[test]
+    fn test_render_big_number() {
+        let lines = render_big_number("  7");
+        assert_eq!(lines.len(), BIG_DIGIT_HEIGHT);
+
+        // Right-aligned on three digits: two blank glyphs, then the 7
+        assert_eq!(lines[0], "        ███");
+        assert_eq!(lines[4], "          █");
+
+        let lines = render_big_number("100");
+        assert_eq!(lines[0], " █  ███ ███");
+        assert_eq!(lines[2], " █  █ █ █ █");
+    }
+
+    fn test_frame(view: CounterView, combo: u32) -> CounterFrame {
+        CounterFrame { view, dial: Dial::default(), target: 90, moving_target: None, hide_target: false, hints: false, combo, beat: None, ghost: None, started: None }
+    }
+
+    #[test]
+    fn test_frame_height_matches_view() {
+        for view in [CounterView::Bar, CounterView::Dial, CounterView::Big] {
+            assert_eq!(render_frame(&test_frame(view, 0), 10, 0, None, false, 80).len(), view.height());
+        }
+    }
+
+    #[test]
+    fn test_frame_fine_precision() {
+        let frame = CounterFrame { dial: Dial::new(100, Precision::Fine), target: 905, ..test_frame(CounterView::Bar, 0) };
+        let line = &render_frame(&frame, 423, 0, None, false, 200)[0];
+        assert!(line.starts_with("→ Objective 90.5:"));
+        assert!(line.ends_with("Counter = 42.3"));
+        
+        let big = CounterFrame { view: CounterView::Big, ..frame };
+        assert_eq!(render_frame(&big, 423, 0, None, false, 200)[BIG_DIGIT_HEIGHT - 1], render_big_number(" 42.3")[BIG_DIGIT_HEIGHT - 1]);
+    }
+
+    #[test]
+    fn test_hint_between() {
+        assert_eq!(Hint::between(50, 40, 41, Dial::default()), Some(Hint::Warmer));
+        assert_eq!(Hint::between(50, 55, 56, Dial::default()), Some(Hint::Colder));
+        // Going around the dial brings the counter closer to a low target
+        assert_eq!(Hint::between(2, 99, 100, Dial::default()), Some(Hint::Warmer));
+        assert_eq!(Hint::between(50, 10, 10, Dial::default()), None);
+    }
+
+    #[test]
+    fn test_frame_shows_beat() {
+        for view in [CounterView::Bar, CounterView::Dial, CounterView::Big] {
+            let frame = test_frame(view, 0);
+            assert!(render_frame(&frame, 10, 0, None, true, 200).last().unwrap().ends_with(" | ♪"));
+            assert!(!render_frame(&frame, 10, 0, None, false, 200).last().unwrap().contains('♪'));
+        }
+    }
+
+    #[test]
+    fn test_frame_shows_ghost() {
+        let frame = CounterFrame { ghost: Some(37), ..test_frame(CounterView::Dial, 0) };
+        assert!(render_frame(&frame, 10, 0, None, false, 200).last().unwrap().ends_with(" | Ghost 37"));
+    }
+
+    #[test]
+    fn test_frame_shows_time_on_target() {
+        let started = Instant::now() - Duration::from_millis(3420);
+        let frame = CounterFrame { started: Some(started), ..test_frame(CounterView::Bar, 0) };
+        assert!(render_frame(&frame, 10, 0, None, false, 200)[0].ends_with(" | ⏱ 3.4 s"));
+        assert_eq!(format_seconds(Duration::from_millis(61_250)), "61.2");
+    }
+
+    #[test]
+    fn test_bar_keeps_its_width_on_beats() {
+        let frame = CounterFrame { beat: Some(5), ..test_frame(CounterView::Bar, 0) };
+        let off_beat = render_frame(&frame, 10, 0, None, false, 60);
+        let on_beat = render_frame(&frame, 10, 0, None, true, 60);
+        assert_eq!(off_beat[0].chars().count() + " | ♪".chars().count(), on_beat[0].chars().count());
+    }
+
+    #[test]
+    fn test_frame_shows_hint() {
+        let frame = CounterFrame { hide_target: true, hints: true, ..test_frame(CounterView::Bar, 0) };
+        assert!(render_frame(&frame, 10, 0, Some(Hint::Warmer), false, 200)[0].ends_with(" | Warmer"));
+        assert!(render_frame(&frame, 10, 0, Some(Hint::Colder), false, 200)[0].ends_with(" | Colder"));
+    }
+
+    #[test]
+    fn test_frame_hides_target() {
+        for view in [CounterView::Bar, CounterView::Dial, CounterView::Big] {
+            let frame = CounterFrame { hide_target: true, ..test_frame(view, 0) };
+            let lines = render_frame(&frame, 10, 0, None, false, 200);
+            assert!(!lines.iter().any(|line| line.contains("90") || line.contains('┃') || line.contains('◆')));
+            assert!(lines.last().unwrap().contains('?'));
+        }
+    }
+
+    #[test]
+    fn test_draw_frame_redraws_in_place() {
+        let frame = vec!["a".to_string(), "b".to_string()];
+
+        let mut first = Vec::new();
+        draw_frame(&mut first, &frame, 0, 80).unwrap();
+        assert!(!String::from_utf8(first).unwrap().contains("\x1B[1A"));
+
+        // Redrawing a two-line frame moves back up one line first
+        let mut second = Vec::new();
+        draw_frame(&mut second, &frame, 2, 80).unwrap();
+        assert!(String::from_utf8(second).unwrap().starts_with("\x1B[1A"));
+    }
+
+    #[test]
+    fn test_frame_shows_combo() {
+        for view in [CounterView::Bar, CounterView::Dial, CounterView::Big] {
+            let lines = render_frame(&test_frame(view, 2), 10, 0, None, false, 200);
+            assert!(lines.last().unwrap().ends_with(" | Combo x1.2"));
+            assert!(!render_frame(&test_frame(view, 0), 10, 0, None, false, 200).last().unwrap().contains("Combo"));
+        }
+    }
+
+    #[test]
+    fn test_bar_frame_fits_narrow_terminal() {
+        let wide = render_frame(&test_frame(CounterView::Bar, 0), 10, 0, None, false, 200);
+        let narrow = render_frame(&test_frame(CounterView::Bar, 0), 10, 0, None, false, 60);
+
+        let cells = wide[0].chars().filter(|c| matches!(c, '█' | '·' | '┃')).count();
+        assert_eq!(cells, BAR_WIDTH);
+        assert!(narrow[0].chars().count() <= 60);
+    }
+
+    #[test]
+    fn test_fit_to_width() {
+        assert_eq!(fit_to_width("→ Objective", 3), "→ O");
+        assert_eq!(fit_to_width("short", 80), "short");
+    }
+
+    #[test]
+    fn test_wrap_to_width() {
+        assert_eq!(wrap_to_width("[1, 2, 3, 4]", 7), vec!["[1, 2,", "3, 4]"]);
+        assert_eq!(wrap_to_width("short", 80), vec!["short"]);
+
+        // Long words are split at the width
+        assert_eq!(wrap_to_width("abcdefgh ij", 3), vec!["abc", "def", "gh", "ij"]);
+    }
+
+    #[test]
+    fn test_counter_view_from_str() {
+        assert_eq!("bar".parse::<CounterView>().unwrap(), CounterView::Bar);
+        assert_eq!("DIAL".parse::<CounterView>().unwrap(), CounterView::Dial);
+        assert_eq!("big".parse::<CounterView>().unwrap(), CounterView::Big);
+        assert!("clock".parse::<CounterView>().is_err());
+    }
     
     #[test]
     fn test_display_counter() {
-        let value = Arc::new(Mutex::new(42));
-        let miss = Arc::new(Mutex::new(0));
-        let running = Arc::new(Mutex::new(true));
+        let (sender, events) = tokio::sync::mpsc::unbounded_channel();
+        sender.send(CounterEvent::Tick(42)).unwrap();
         
         let handle_result = display_counter(
-            Arc::clone(&value),
-            Arc::clone(&miss),
-            Arc::clone(&running),
-            50
+            events,
+            CounterFrame { view: CounterView::Bar, dial: Dial::default(), target: 50, moving_target: None, hide_target: false, hints: false, combo: 0, beat: None, ghost: None, started: None },
+            Audio::new(SoundMode::Off)
         );
         
         assert!(handle_result.is_ok());
         
-        // Stop the counter thread
-        *running.lock().unwrap() = false;
+        // Stop the display task
+        sender.send(CounterEvent::Stopped(42, 0)).unwrap();
         if let Ok(handle) = handle_result {
-            assert!(handle.join().is_ok());
+            assert!(runtime::join(handle).is_ok());
         }
     }
+    
+    #[test]
+    fn test_read_counter_events() {
+        let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+        for event in [CounterEvent::Tick(4), CounterEvent::Tick(5), CounterEvent::Wrapped, CounterEvent::Tick(0)] {
+            sender.send(event).unwrap();
+        }
+        let update = read_counter_events(&mut events, Some(5));
+        assert_eq!(update, CounterUpdate { value: Some(0), wraps: 1, beat: true, ..CounterUpdate::default() });
+        
+        // Nothing new, and no beat without a rhythm
+        sender.send(CounterEvent::Tick(5)).unwrap();
+        assert_eq!(read_counter_events(&mut events, None), CounterUpdate { value: Some(5), ..CounterUpdate::default() });
+        
+        drop(sender);
+        assert!(read_counter_events(&mut events, None).stopped);
+    }
 }
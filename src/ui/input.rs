@@ -0,0 +1,369 @@
+//! Line input shared between the game and the Ctrl-C handler.
+//!
+//! Lines are read from stdin on a dedicated thread, one at a time and only when
+//! the game asks for one. The Ctrl-C handler feeds the same channel, so a game
+//! waiting for input is woken up with `GameError::Interrupted` instead of the
+//! process dying in the middle of a redraw.
+//!
+//! Single key presses are read in raw mode instead, see `wait_for_key`. They are
+//! read on a blocking task of the shared runtime and passed on over a channel,
+//! where the wait selects between the next key press and its deadline.
+
+use std::io;
+use std::sync::{
+    Arc, Mutex, OnceLock,
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, Sender},
+};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use tokio::select;
+use tokio::sync::mpsc::{self as channel, UnboundedReceiver};
+use tokio::time;
+
+use super::source::MenuKey;
+use super::term;
+use crate::error::{GameError, GameResult};
+use crate::runtime;
+
+/// How long the key reader waits for a key press before checking whether it is still needed.
+const KEY_POLL: Duration = Duration::from_millis(10);
+
+/// What the input thread or the Ctrl-C handler hand over to the game.
+enum InputEvent {
+    /// A line read from stdin, including its line terminator
+    Line(String),
+    /// Reading from stdin failed
+    Failed(io::Error),
+    /// The user pressed Ctrl-C
+    Interrupted,
+}
+
+/// Receiving side of the input channel.
+struct InputState {
+    /// Whether the input thread is already reading a line for us
+    pending: bool,
+    /// Lines and interrupts, in the order they happened
+    events: Receiver<InputEvent>,
+}
+
+/// Channels to and from the input thread.
+struct Input {
+    /// Asks the input thread to read one line
+    requests: Sender<()>,
+    /// Lets the Ctrl-C handler inject interrupts
+    events: Sender<InputEvent>,
+    /// Receiving side, shared by everything reading input
+    state: Mutex<InputState>,
+}
+
+/// The input channels, created on first use.
+static INPUT: OnceLock<Input> = OnceLock::new();
+
+/// Returns the input channels, starting the input thread if needed.
+fn input() -> &'static Input {
+    INPUT.get_or_init(|| {
+        let (request_sender, request_receiver) = mpsc::channel::<()>();
+        let (event_sender, event_receiver) = mpsc::channel();
+
+        let lines = event_sender.clone();
+        thread::spawn(move || {
+            for () in request_receiver {
+                let mut line = String::new();
+                let event = match io::stdin().read_line(&mut line) {
+                    Ok(_) => InputEvent::Line(line),
+                    Err(e) => InputEvent::Failed(e),
+                };
+                if lines.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Input {
+            requests: request_sender,
+            events: event_sender,
+            state: Mutex::new(InputState { pending: false, events: event_receiver }),
+        }
+    })
+}
+
+/// Reads a line from stdin.
+///
+/// # Returns
+///
+/// Result containing the line (empty at end of input), or
+/// `GameError::Interrupted` if the user pressed Ctrl-C while waiting
+pub fn read_line() -> GameResult<String> {
+    let input = input();
+    let mut state = input.state.lock().unwrap();
+
+    // A read interrupted by Ctrl-C is still pending, its line is the one we want
+    if !state.pending {
+        input.requests.send(()).map_err(|_| input_thread_stopped())?;
+        state.pending = true;
+    }
+
+    match state.events.recv() {
+        Ok(InputEvent::Line(line)) => {
+            state.pending = false;
+            Ok(line)
+        },
+        Ok(InputEvent::Failed(e)) => {
+            state.pending = false;
+            Err(GameError::from(e))
+        },
+        Ok(InputEvent::Interrupted) => Err(GameError::Interrupted),
+        Err(_) => Err(input_thread_stopped()),
+    }
+}
+
+/// Waits for the user to press one of the given keys.
+///
+/// The terminal is in raw mode while waiting, so Ctrl-C arrives as a key press
+/// rather than a signal and is reported the same way as in `read_line`.
+///
+/// # Arguments
+///
+/// * `keys` - The keys to wait for, letters match in either case
+///
+/// # Returns
+///
+/// Result containing the index of the key that was pressed, or
+/// `GameError::Interrupted` if the user pressed Ctrl-C while waiting
+pub fn wait_for_key(keys: &[char]) -> GameResult<usize> {
+    read_keys(None, |event| key_result(event, keys))?.ok_or_else(input_thread_stopped)
+}
+
+/// Waits for the user to press one of the given keys, but no longer than `timeout`.
+///
+/// # Arguments
+///
+/// * `keys` - The keys to wait for, letters match in either case
+/// * `timeout` - How long to wait at most
+///
+/// # Returns
+///
+/// Result containing the index of the key that was pressed, None if the time ran
+/// out, or `GameError::Interrupted` if the user pressed Ctrl-C while waiting
+pub fn wait_for_key_timeout(keys: &[char], timeout: Duration) -> GameResult<Option<usize>> {
+    read_keys(Some(timeout), |event| key_result(event, keys))
+}
+
+/// Waits for the user to press a key moving through a menu.
+///
+/// The arrow keys (or `k` and `j`) move the selection, ENTER or SPACE choose
+/// the selected option, and a digit chooses an option by its number.
+///
+/// # Returns
+///
+/// Result containing the key, or `GameError::Interrupted` if the user pressed Ctrl-C while waiting
+pub fn read_menu_key() -> GameResult<MenuKey> {
+    read_keys(None, menu_key)?.ok_or_else(input_thread_stopped)
+}
+
+/// Reads key presses in raw mode until one of them decides the wait, or the time runs out.
+///
+/// The terminal is read on a blocking task of the shared runtime, and stopped
+/// before returning so that it does not take the next key press.
+///
+/// # Arguments
+///
+/// * `timeout` - How long to wait at most, or None to wait for as long as it takes
+/// * `decide` - What a key press means, None for a key press that is ignored
+///
+/// # Returns
+///
+/// Result containing what the deciding key press meant, or None if the time ran out
+fn read_keys<T>(timeout: Option<Duration>, decide: impl FnMut(&KeyEvent) -> Option<GameResult<T>>) -> GameResult<Option<T>> {
+    let _raw_mode = term::RawMode::enable()?;
+    let runtime = runtime::get()?;
+    let (presses, mut received) = channel::unbounded_channel();
+    let reading = Arc::new(AtomicBool::new(true));
+    let reader = {
+        let reading = Arc::clone(&reading);
+        runtime.spawn_blocking(move || {
+            while reading.load(Ordering::SeqCst) {
+                let event = match event::poll(KEY_POLL) {
+                    Ok(false) => continue,
+                    Ok(true) => event::read(),
+                    Err(e) => Err(e),
+                };
+                let failed = event.is_err();
+                if presses.send(event).is_err() || failed {
+                    break;
+                }
+            }
+        })
+    };
+    let result = runtime.block_on(first_key(&mut received, timeout, decide));
+    reading.store(false, Ordering::SeqCst);
+    let _ = runtime.block_on(reader);
+    result
+}
+
+/// Waits for the first key press that decides the wait, or for the time to run out.
+///
+/// # Arguments
+///
+/// * `events` - The terminal events as they are read
+/// * `timeout` - How long to wait at most, or None to wait for as long as it takes
+/// * `decide` - What a key press means, None for a key press that is ignored
+///
+/// # Returns
+///
+/// Result containing what the deciding key press meant, or None if the time ran out
+async fn first_key<T>(events: &mut UnboundedReceiver<io::Result<Event>>, timeout: Option<Duration>,
+                      mut decide: impl FnMut(&KeyEvent) -> Option<GameResult<T>>) -> GameResult<Option<T>> {
+    let deadline = async {
+        match timeout {
+            Some(timeout) => time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+    loop {
+        select! {
+            event = events.recv() => match event {
+                Some(Ok(Event::Key(event))) => if let Some(result) = decide(&event) {
+                    return result.map(Some);
+                },
+                Some(Ok(_)) => {},
+                Some(Err(e)) => return Err(GameError::from(e)),
+                None => return Err(input_thread_stopped()),
+            },
+            () = &mut deadline => return Ok(None),
+        }
+    }
+}
+
+/// Decides what a key press means in a menu.
+///
+/// # Arguments
+///
+/// * `event` - The key event read from the terminal
+///
+/// # Returns
+///
+/// The key, or None if the key press is ignored
+fn menu_key(event: &KeyEvent) -> Option<GameResult<MenuKey>> {
+    if event.kind != KeyEventKind::Press {
+        return None;
+    }
+    match event.code {
+        KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => Some(Err(GameError::Interrupted)),
+        KeyCode::Up | KeyCode::Char('k') => Some(Ok(MenuKey::Up)),
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => Some(Ok(MenuKey::Down)),
+        KeyCode::Enter | KeyCode::Char(' ') => Some(Ok(MenuKey::Select)),
+        KeyCode::Char(c) => c.to_digit(10)
+            .and_then(|n| (n as usize).checked_sub(1))
+            .map(|idx| Ok(MenuKey::Number(idx))),
+        _ => None,
+    }
+}
+
+/// Decides what a key press means while waiting for keys.
+///
+/// # Arguments
+///
+/// * `event` - The key event read from the terminal
+/// * `keys` - The keys being waited for
+///
+/// # Returns
+///
+/// The result of the wait, or None if the key press is ignored
+fn key_result(event: &KeyEvent, keys: &[char]) -> Option<GameResult<usize>> {
+    if event.kind != KeyEventKind::Press {
+        return None;
+    }
+    match event.code {
+        KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => Some(Err(GameError::Interrupted)),
+        KeyCode::Char(c) => keys.iter().position(|key| key.eq_ignore_ascii_case(&c)).map(Ok),
+        _ => None,
+    }
+}
+
+/// Wakes up whoever is waiting for input with `GameError::Interrupted`.
+///
+/// If nobody is waiting, the next call to `read_line` is interrupted instead.
+pub fn interrupt() {
+    // The receiver lives in a static, so sending cannot fail
+    let _ = input().events.send(InputEvent::Interrupted);
+}
+
+/// Installs a Ctrl-C handler that interrupts the game instead of killing it.
+///
+/// # Returns
+///
+/// Result indicating whether the handler could be installed
+pub fn install_interrupt_handler() -> GameResult<()> {
+    ctrlc::set_handler(interrupt)
+        .map_err(|e| GameError::LogicError(format!("Could not install Ctrl-C handler: {}", e)))
+}
+
+/// Builds the error returned when the input thread is gone.
+fn input_thread_stopped() -> GameError {
+    GameError::IoError(io::Error::new(io::ErrorKind::BrokenPipe, "input thread stopped"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interrupt_wakes_up_reader() {
+        // Queue the interrupt first so the read returns without touching stdin for long
+        interrupt();
+        assert!(matches!(read_line(), Err(GameError::Interrupted)));
+    }
+
+    #[test]
+    fn test_key_result() {
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+
+        assert!(matches!(key_result(&press(KeyCode::Char('f'), KeyModifiers::NONE), &['f']), Some(Ok(0))));
+        assert!(matches!(key_result(&press(KeyCode::Char('F'), KeyModifiers::SHIFT), &['f']), Some(Ok(0))));
+        assert!(matches!(key_result(&press(KeyCode::Char('j'), KeyModifiers::NONE), &['f', 'j']), Some(Ok(1))));
+        assert!(key_result(&press(KeyCode::Char('j'), KeyModifiers::NONE), &['f']).is_none());
+        assert!(key_result(&press(KeyCode::Enter, KeyModifiers::NONE), &['f']).is_none());
+        assert!(matches!(key_result(&press(KeyCode::Char('c'), KeyModifiers::CONTROL), &['f']),
+                         Some(Err(GameError::Interrupted))));
+    }
+
+    #[test]
+    fn test_first_key_races_the_deadline() {
+        let runtime = runtime::get().unwrap();
+        let (events, mut received) = channel::unbounded_channel();
+        let press = |c| Ok(Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)));
+
+        events.send(press('x')).unwrap();
+        events.send(Ok(Event::FocusGained)).unwrap();
+        events.send(press('j')).unwrap();
+        let pressed = runtime.block_on(first_key(&mut received, None, |event| key_result(event, &['f', 'j'])));
+        assert!(matches!(pressed, Ok(Some(1))));
+
+        events.send(press('x')).unwrap();
+        let timeout = Some(Duration::from_millis(10));
+        let timed_out = runtime.block_on(first_key(&mut received, timeout, |event| key_result(event, &['f'])));
+        assert!(matches!(timed_out, Ok(None)));
+
+        drop(events);
+        assert!(runtime.block_on(first_key(&mut received, None, |event| key_result(event, &['f']))).is_err());
+    }
+
+    #[test]
+    fn test_menu_key() {
+        let press = |code| menu_key(&KeyEvent::new(code, KeyModifiers::NONE));
+
+        assert!(matches!(press(KeyCode::Up), Some(Ok(MenuKey::Up))));
+        assert!(matches!(press(KeyCode::Char('j')), Some(Ok(MenuKey::Down))));
+        assert!(matches!(press(KeyCode::Enter), Some(Ok(MenuKey::Select))));
+        assert!(matches!(press(KeyCode::Char('3')), Some(Ok(MenuKey::Number(2)))));
+        assert!(press(KeyCode::Char('0')).is_none());
+        assert!(press(KeyCode::Char('x')).is_none());
+        assert!(matches!(menu_key(&KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+                         Some(Err(GameError::Interrupted))));
+    }
+}
@@ -0,0 +1,613 @@
+//! Render backends the game draws its output with.
+//!
+//! The game never writes to the terminal directly: every message, prompt and
+//! counter frame goes through a `Renderer`. `TuiRenderer` draws the full-screen
+//! interface, with the scoreboard kept at the top and the counter animated in
+//! place. `PlainRenderer` writes plain lines without escape sequences, for
+//! pipes, dumb terminals and screen readers. `NullRenderer` writes nothing, for
+//! headless runs such as scripted matches and tests.
+
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time;
+
+use super::source::InputSource;
+use super::{CounterFrame, CounterView};
+use crate::audio::Audio;
+use crate::counter::CounterEvents;
+use crate::error::{GameError, GameResult};
+use crate::events::PlayerSummary;
+use crate::i18n::{self, Msg, tr};
+use crate::runtime;
+
+/// How the game output is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    /// Full-screen terminal interface
+    #[default]
+    Tui,
+    /// Plain lines, without escape sequences
+    Plain,
+    /// No output at all
+    Null,
+}
+
+impl FromStr for RenderMode {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tui" => Ok(RenderMode::Tui),
+            "plain" => Ok(RenderMode::Plain),
+            "none" => Ok(RenderMode::Null),
+            _ => Err(GameError::ConfigError(format!("Unknown output mode: {}", s))),
+        }
+    }
+}
+
+impl RenderMode {
+    /// Creates the renderer drawing in this mode.
+    ///
+    /// # Returns
+    ///
+    /// A new renderer
+    pub fn renderer(self) -> Box<dyn Renderer> {
+        match self {
+            RenderMode::Tui => Box::new(TuiRenderer),
+            RenderMode::Plain => Box::new(PlainRenderer),
+            RenderMode::Null => Box::new(NullRenderer),
+        }
+    }
+}
+
+/// Where the game draws its messages, prompts and counter.
+pub trait Renderer {
+    /// Shows a line of game output.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The line to show
+    fn info(&mut self, text: &str);
+
+    /// Shows a message, wrapped to the width of the screen if it has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message to show
+    fn info_wrapped(&mut self, text: &str);
+
+    /// Shows a message, then takes it off the screen after a delay.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message to show
+    /// * `duration` - How long the message stays on the screen
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded
+    fn show_briefly(&mut self, text: &str, duration: Duration) -> GameResult<()>;
+
+    /// Shows or updates the scoreboard.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The scoreboard line, see `ui::render_scoreboard`
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded
+    fn show_scoreboard(&mut self, line: String) -> GameResult<()>;
+
+    /// Stops showing the scoreboard.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded
+    fn hide_scoreboard(&mut self) -> GameResult<()>;
+
+    /// Shows the live counter until it stops running.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The counter's events, the display ends when the counter stops
+    /// * `frame` - What is drawn around the counter
+    /// * `audio` - Sound effects player for ticks and misses
+    ///
+    /// # Returns
+    ///
+    /// A handle to the display task
+    fn display_counter(&mut self, events: CounterEvents, frame: CounterFrame, audio: Audio) -> GameResult<JoinHandle<()>>;
+
+    /// Takes the last counter frame off the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `view` - The view the frame was drawn with
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded
+    fn clear_counter(&mut self, view: CounterView) -> GameResult<()>;
+
+    /// Moves to the next line, past a key press the terminal did not echo.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded
+    fn new_line(&mut self) -> GameResult<()>;
+
+    /// Shows that an answer is expected.
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded
+    fn prompt(&mut self) -> GameResult<()>;
+
+    /// Shows the end-of-match summary of both players.
+    ///
+    /// # Arguments
+    ///
+    /// * `summaries` - The statistics of each player
+    fn print_summary(&mut self, summaries: &[PlayerSummary]) {
+        let join = |values: &[u32]| values.iter().map(u32::to_string).collect::<Vec<_>>().join(" ");
+
+        self.info(&tr!(Msg::SummaryHeader));
+        for summary in summaries {
+            self.info(&tr!(Msg::SummaryPlayer, summary.name));
+            self.info(&tr!(Msg::SummaryScores,
+                      super::render_sparkline(&summary.round_scores), join(&summary.round_scores)));
+            self.info(&tr!(Msg::SummaryVitality,
+                      super::render_sparkline(&summary.vitality), join(&summary.vitality)));
+            if let (Some((best_target, best)), Some((worst_target, worst))) = (summary.best, summary.worst) {
+                self.info(&tr!(Msg::SummaryTargets, best_target, best, worst_target, worst));
+            }
+            self.info(&tr!(Msg::SummaryExactHits, summary.exact_hits));
+            if let Some(round) = summary.resigned {
+                self.info(&tr!(Msg::SummaryResigned, round));
+            }
+        }
+    }
+
+    /// Waits for the user to press a given key, without ENTER.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' actions are read from
+    /// * `key` - The key to wait for
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether reading input succeeded
+    fn wait_for_key(&mut self, input: &mut dyn InputSource, key: char) -> GameResult<()> {
+        input.wait_for_key(&[key])?;
+        self.new_line()
+    }
+
+    /// Waits until both players have pressed their key, in any order.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' actions are read from
+    /// * `keys` - The key of each player
+    /// * `on_press` - Called with the index of each player, the first time they press their key
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether reading input succeeded
+    fn wait_for_both_keys(&mut self, input: &mut dyn InputSource, keys: [char; 2], on_press: &mut dyn FnMut(usize))
+        -> GameResult<()> {
+        let mut pressed = [false; 2];
+        while pressed != [true; 2] {
+            let player_idx = input.wait_for_key(&keys)?;
+            if !pressed[player_idx] {
+                pressed[player_idx] = true;
+                on_press(player_idx);
+            }
+        }
+        self.new_line()
+    }
+
+    /// Asks the user a yes/no question.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' actions are read from
+    /// * `prompt` - The question to display
+    ///
+    /// # Returns
+    ///
+    /// Result containing true if the user answered yes
+    fn confirm(&mut self, input: &mut dyn InputSource, prompt: &str) -> GameResult<bool> {
+        self.info(prompt);
+        self.prompt()?;
+
+        let answer = input.read_line()?;
+        Ok(answer.trim().eq_ignore_ascii_case(i18n::text(Msg::YesKey)))
+    }
+
+    /// Prompts the user for a choice between given options.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' actions are read from
+    /// * `prompt` - The message to display
+    /// * `options` - The available options
+    ///
+    /// # Returns
+    ///
+    /// Result containing the selected option index (0-based)
+    fn get_user_choice(&mut self, input: &mut dyn InputSource, prompt: &str, options: &[&str]) -> GameResult<usize> {
+        match super::select_menu(self, input, prompt, options)? {
+            Ok(choice) => Ok(choice),
+            Err(msg) => {
+                self.info(&tr!(msg));
+                Ok(0)
+            },
+        }
+    }
+
+    /// Shows a panel of text over the game, such as the help.
+    ///
+    /// Output that cannot be drawn over shows the lines and carries on.
+    ///
+    /// # Arguments
+    ///
+    /// * `_input` - Where the player dismisses the panel
+    /// * `lines` - The lines of the panel
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded and the panel was dismissed
+    fn show_overlay(&mut self, _input: &mut dyn InputSource, lines: &[String]) -> GameResult<()> {
+        for line in lines {
+            self.info(line);
+        }
+        Ok(())
+    }
+
+    /// Draws the options of a menu, the selected one highlighted where the output allows it.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options
+    /// * `selected` - The index of the selected option
+    /// * `redraw` - Whether the menu was drawn already and the selection moved
+    ///
+    /// # Returns
+    ///
+    /// Result indicating whether drawing succeeded
+    fn draw_menu(&mut self, options: &[&str], selected: usize, redraw: bool) -> GameResult<()> {
+        // Lines cannot be drawn over, so a move only shows the newly selected option
+        if redraw {
+            self.info(&format!("> {}: {}", selected + 1, options[selected]));
+            return Ok(());
+        }
+        for (i, option) in options.iter().enumerate() {
+            self.info(&format!("→ {}: {}", i + 1, option));
+        }
+        Ok(())
+    }
+
+    /// Prompts the user for a choice between given options, answered with a single key.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' actions are read from
+    /// * `prompt` - The message to display
+    /// * `options` - The available options, at most 9
+    /// * `timeout` - How long the user has to choose
+    ///
+    /// # Returns
+    ///
+    /// Result containing the selected option index (0-based), or None if the time ran out
+    fn get_timed_choice(&mut self, input: &mut dyn InputSource, prompt: &str, options: &[&str], timeout: Duration)
+        -> GameResult<Option<usize>> {
+        self.info(prompt);
+
+        for (i, option) in options.iter().enumerate() {
+            self.info(&format!("→ {}: {}", i + 1, option));
+        }
+        self.info(&tr!(Msg::ChoiceTimer, timeout.as_secs()));
+
+        let keys: Vec<char> = (1..=options.len() as u32).filter_map(|n| char::from_digit(n, 10)).collect();
+        let choice = input.wait_for_key_timeout(&keys, timeout)?;
+        self.new_line()?;
+        Ok(choice)
+    }
+
+    /// Asks a player how much vitality they wager, until the answer is within bounds.
+    ///
+    /// An empty answer wagers nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' actions are read from
+    /// * `prompt` - The message to display
+    /// * `max` - The largest allowed wager
+    ///
+    /// # Returns
+    ///
+    /// Result containing the wager, between 0 and `max`
+    fn get_wager(&mut self, input: &mut dyn InputSource, prompt: &str, max: u32) -> GameResult<u32> {
+        loop {
+            self.info(prompt);
+            self.prompt()?;
+
+            match super::parse_wager(&input.read_line()?, max) {
+                Ok(wager) => return Ok(wager),
+                Err(msg) => self.info(&tr!(msg, max)),
+            }
+        }
+    }
+
+    /// Asks a player to predict their turn average, until the answer is a number.
+    ///
+    /// An empty answer predicts nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the players' actions are read from
+    /// * `prompt` - The message to display
+    ///
+    /// # Returns
+    ///
+    /// Result containing the prediction, or None if the player made none
+    fn get_prediction(&mut self, input: &mut dyn InputSource, prompt: &str) -> GameResult<Option<u32>> {
+        loop {
+            self.info(prompt);
+            self.prompt()?;
+
+            match super::parse_prediction(&input.read_line()?) {
+                Ok(prediction) => return Ok(prediction),
+                Err(msg) => self.info(&tr!(msg)),
+            }
+        }
+    }
+}
+
+/// Draws the full-screen terminal interface.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TuiRenderer;
+
+impl Renderer for TuiRenderer {
+    fn info(&mut self, text: &str) {
+        log::info!("{}", text);
+    }
+
+    fn info_wrapped(&mut self, text: &str) {
+        super::info_wrapped(text);
+    }
+
+    fn show_briefly(&mut self, text: &str, duration: Duration) -> GameResult<()> {
+        super::show_briefly(text, duration)
+    }
+
+    fn show_scoreboard(&mut self, line: String) -> GameResult<()> {
+        super::show_scoreboard(line)
+    }
+
+    fn hide_scoreboard(&mut self) -> GameResult<()> {
+        super::hide_scoreboard()
+    }
+
+    fn show_overlay(&mut self, input: &mut dyn InputSource, lines: &[String]) -> GameResult<()> {
+        super::show_overlay(input, lines)
+    }
+
+    fn draw_menu(&mut self, options: &[&str], selected: usize, redraw: bool) -> GameResult<()> {
+        super::draw_menu(options, selected, redraw)
+    }
+
+    fn display_counter(&mut self, events: CounterEvents, frame: CounterFrame, audio: Audio) -> GameResult<JoinHandle<()>> {
+        super::display_counter(events, frame, audio)
+    }
+
+    fn clear_counter(&mut self, view: CounterView) -> GameResult<()> {
+        super::clear_counter(view)
+    }
+
+    fn new_line(&mut self) -> GameResult<()> {
+        super::end_key_wait()
+    }
+
+    fn prompt(&mut self) -> GameResult<()> {
+        print!("> ");
+        io::stdout().flush().map_err(GameError::from)
+    }
+}
+
+/// Writes plain lines, without escape sequences.
+///
+/// The counter is shown as a caption rewritten in place with a carriage return,
+/// and nothing is erased once written.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn info(&mut self, text: &str) {
+        println!("{}", text);
+    }
+
+    fn info_wrapped(&mut self, text: &str) {
+        self.info(text);
+    }
+
+    fn show_briefly(&mut self, text: &str, duration: Duration) -> GameResult<()> {
+        // Plain output cannot be taken back, the message only scrolls on
+        self.info(text);
+        thread::sleep(duration);
+        Ok(())
+    }
+
+    fn show_scoreboard(&mut self, line: String) -> GameResult<()> {
+        self.info(&line);
+        Ok(())
+    }
+
+    fn hide_scoreboard(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn display_counter(&mut self, events: CounterEvents, frame: CounterFrame, _audio: Audio) -> GameResult<JoinHandle<()>> {
+        show_captions(events, frame, |caption| {
+            let _ = write_plain_caption(&caption);
+        })
+    }
+
+    fn clear_counter(&mut self, _view: CounterView) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn new_line(&mut self) -> GameResult<()> {
+        println!();
+        Ok(())
+    }
+
+    fn prompt(&mut self) -> GameResult<()> {
+        print!("> ");
+        io::stdout().flush().map_err(GameError::from)
+    }
+}
+
+/// Shows the counter as a one-line caption each time it moves, until it stops running.
+///
+/// # Arguments
+///
+/// * `events` - The counter's events, the display ends when the counter stops
+/// * `frame` - What is drawn around the counter
+/// * `show` - Called with each new caption
+///
+/// # Returns
+///
+/// A handle to the display task
+pub fn show_captions(mut events: CounterEvents, frame: CounterFrame, mut show: impl FnMut(String) + Send + 'static)
+    -> GameResult<JoinHandle<()>> {
+    let handle = runtime::get()?.spawn(async move {
+        let (mut value, mut miss) = (0, 0);
+        let mut shown = None;
+        loop {
+            let update = super::read_counter_events(&mut events, None);
+            if update.stopped {
+                break;
+            }
+            value = update.value.unwrap_or(value);
+            miss += update.wraps;
+            if shown != Some((value, miss)) {
+                let target = if frame.hide_target { "?".to_string() } else { frame.dial.format(frame.target) };
+                show(tr!(Msg::CounterCaption, target, miss, frame.dial.format(value)));
+                shown = Some((value, miss));
+            }
+            time::sleep(Duration::from_millis(30)).await;
+        }
+    });
+    Ok(handle)
+}
+
+/// Rewrites the plain counter caption over the previous one.
+///
+/// # Arguments
+///
+/// * `caption` - The caption to write
+///
+/// # Returns
+///
+/// Result indicating whether writing succeeded
+pub fn write_plain_caption(caption: &str) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    write!(stdout, "\r{}", caption)?;
+    stdout.flush()
+}
+
+/// Draws nothing at all.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullRenderer;
+
+impl Renderer for NullRenderer {
+    fn info(&mut self, _text: &str) {}
+
+    fn info_wrapped(&mut self, _text: &str) {}
+
+    fn show_briefly(&mut self, _text: &str, _duration: Duration) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn show_scoreboard(&mut self, _line: String) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn hide_scoreboard(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn display_counter(&mut self, _events: CounterEvents, _frame: CounterFrame, _audio: Audio) -> GameResult<JoinHandle<()>> {
+        Ok(runtime::get()?.spawn(async {}))
+    }
+
+    fn clear_counter(&mut self, _view: CounterView) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn new_line(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+
+    fn prompt(&mut self) -> GameResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::source::{ScriptedAction, ScriptedEvent, ScriptedInput};
+    use crate::ui::source::testing::scripted;
+
+    #[test]
+    fn test_parse_render_mode() {
+        assert_eq!("tui".parse::<RenderMode>().unwrap(), RenderMode::Tui);
+        assert_eq!("Plain".parse::<RenderMode>().unwrap(), RenderMode::Plain);
+        assert_eq!("none".parse::<RenderMode>().unwrap(), RenderMode::Null);
+        assert!("fancy".parse::<RenderMode>().is_err());
+        assert_eq!(RenderMode::default(), RenderMode::Tui);
+    }
+
+    #[test]
+    fn test_confirm() {
+        let mut input = scripted(&["Y", "n", ""]);
+        assert!(NullRenderer.confirm(&mut input, "").unwrap());
+        assert!(!NullRenderer.confirm(&mut input, "").unwrap());
+        assert!(!NullRenderer.confirm(&mut input, "").unwrap());
+    }
+
+    #[test]
+    fn test_get_wager_asks_again_until_valid() {
+        let mut input = scripted(&["50", "abc", "5"]);
+        assert_eq!(NullRenderer.get_wager(&mut input, "", 20).unwrap(), 5);
+        assert!(input.read_line().is_err());
+    }
+
+    #[test]
+    fn test_get_timed_choice() {
+        let mut input = ScriptedInput::new([
+            ScriptedEvent { after: Duration::ZERO, action: ScriptedAction::Key('2') },
+            ScriptedEvent { after: Duration::from_millis(50), action: ScriptedAction::Key('1') },
+        ]);
+        let timeout = Duration::from_millis(10);
+        assert_eq!(NullRenderer.get_timed_choice(&mut input, "", &["a", "b"], timeout).unwrap(), Some(1));
+        // The second key comes too late
+        assert_eq!(NullRenderer.get_timed_choice(&mut input, "", &["a", "b"], timeout).unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_user_choice_falls_back_to_first_option() {
+        let mut input = scripted(&["2", "7", "x"]);
+        assert_eq!(NullRenderer.get_user_choice(&mut input, "", &["a", "b"]).unwrap(), 1);
+        assert_eq!(NullRenderer.get_user_choice(&mut input, "", &["a", "b"]).unwrap(), 0);
+        assert_eq!(NullRenderer.get_user_choice(&mut input, "", &["a", "b"]).unwrap(), 0);
+    }
+}
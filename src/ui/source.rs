@@ -0,0 +1,373 @@
+//! Input sources the game reads the players' actions from.
+//!
+//! The game never reads the terminal directly: every line and key press goes
+//! through an `InputSource`. `TerminalInput` reads the keyboard, while
+//! `ScriptedInput` plays back a fixed script with timings, so that a whole
+//! match can run without anybody at the keyboard. `NetworkInput`, in the
+//! server module, reads them from the players' connections.
+//!
+//! A script file holds one action per line, each preceded by the time to wait
+//! before it, in milliseconds. Blank lines and lines starting with `#` are
+//! skipped.
+//!
+//! ```text
+//! # Start the turn, then stop the counter after 120 ms
+//! 0 enter
+//! 120 enter
+//! # Answer a prompt, then press the F key after half a second
+//! 0 type 1
+//! 500 key f
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use super::input;
+use crate::error::{GameError, GameResult};
+use crate::persistence;
+
+/// A key moving through a menu, see `ui::select_menu`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuKey {
+    /// Select the option above, or the last one from the first
+    Up,
+    /// Select the option below, or the first one from the last
+    Down,
+    /// Choose the selected option
+    Select,
+    /// Choose an option by its number, from 0
+    Number(usize),
+}
+
+/// Where the game reads the players' lines and key presses from.
+pub trait InputSource {
+    /// Reads a line typed by a player.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the line, or `GameError::Interrupted` if the user pressed Ctrl-C
+    fn read_line(&mut self) -> GameResult<String>;
+
+    /// Waits for a player to press one of the given keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to wait for
+    ///
+    /// # Returns
+    ///
+    /// Result containing the index of the key that was pressed
+    fn wait_for_key(&mut self, keys: &[char]) -> GameResult<usize>;
+
+    /// Waits for a player to press one of the given keys, but no longer than `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to wait for
+    /// * `timeout` - How long to wait at most
+    ///
+    /// # Returns
+    ///
+    /// Result containing the index of the key that was pressed, or None if the time ran out
+    fn wait_for_key_timeout(&mut self, keys: &[char], timeout: Duration) -> GameResult<Option<usize>>;
+
+    /// Tells the source which player the game waits for next.
+    ///
+    /// Both players share the keyboard and a script, so those sources take
+    /// the next action whoever it comes from. A source with a connection per
+    /// player only takes the actions of that player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_idx` - The index of the player expected to answer
+    fn expect_player(&mut self, _player_idx: usize) {}
+
+    /// Reads the value the expected player stops the counter at, for sources that give one.
+    ///
+    /// The keyboard and scripts stop the counter with a key press while it
+    /// runs, so they give no value. A source that does give one is asked
+    /// before the counter starts, which then stops on its own at that value.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the value in ticks, or None to stop the counter with a key press
+    fn stop_at(&mut self) -> GameResult<Option<u32>> {
+        Ok(None)
+    }
+
+    /// Reads a key moving through a menu, for sources that navigate menus with keys.
+    ///
+    /// Scripts and remote players answer menus with the number of an option
+    /// instead, read as a line.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the key, or None if the source answers menus with a line
+    fn read_menu_key(&mut self) -> GameResult<Option<MenuKey>> {
+        Ok(None)
+    }
+}
+
+/// Reads the players' actions from the keyboard.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerminalInput;
+
+impl InputSource for TerminalInput {
+    fn read_line(&mut self) -> GameResult<String> {
+        input::read_line()
+    }
+
+    fn wait_for_key(&mut self, keys: &[char]) -> GameResult<usize> {
+        input::wait_for_key(keys)
+    }
+
+    fn wait_for_key_timeout(&mut self, keys: &[char], timeout: Duration) -> GameResult<Option<usize>> {
+        input::wait_for_key_timeout(keys, timeout)
+    }
+
+    fn read_menu_key(&mut self) -> GameResult<Option<MenuKey>> {
+        // Piped answers cannot be read in raw mode, they keep answering with lines
+        if !io::stdin().is_terminal() {
+            return Ok(None);
+        }
+        input::read_menu_key().map(Some)
+    }
+}
+
+/// One action of a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptedAction {
+    /// A line typed and confirmed with ENTER, empty for ENTER alone
+    Line(String),
+    /// A single key pressed
+    Key(char),
+}
+
+/// An action of a script and how long to wait before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptedEvent {
+    /// Time between the game asking for input and the action
+    pub after: Duration,
+    /// What the player does
+    pub action: ScriptedAction,
+}
+
+impl fmt::Display for ScriptedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ms = self.after.as_millis();
+        match &self.action {
+            ScriptedAction::Line(line) if line.is_empty() => write!(f, "{} enter", ms),
+            ScriptedAction::Line(line) => write!(f, "{} type {}", ms, line),
+            ScriptedAction::Key(key) => write!(f, "{} key {}", ms, key),
+        }
+    }
+}
+
+impl FromStr for ScriptedEvent {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || GameError::ParseError(format!("Invalid script line: {}", s));
+        let (ms, rest) = s.trim().split_once(' ').ok_or_else(invalid)?;
+        let after = Duration::from_millis(ms.parse().map_err(|_| invalid())?);
+        let (verb, arg) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        let action = match (verb, arg) {
+            ("enter", "") => ScriptedAction::Line(String::new()),
+            ("type", text) => ScriptedAction::Line(text.to_string()),
+            ("key", key) => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(key), None) => ScriptedAction::Key(key),
+                    _ => return Err(invalid()),
+                }
+            },
+            _ => return Err(invalid()),
+        };
+        Ok(Self { after, action })
+    }
+}
+
+/// Plays back the players' actions from a script.
+///
+/// Clones share the same script, so a script can carry on from one match to the next.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedInput {
+    /// The actions still to be played, in order
+    events: Rc<RefCell<VecDeque<ScriptedEvent>>>,
+}
+
+impl ScriptedInput {
+    /// Creates an input source playing back the given actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The actions, in the order the game asks for them
+    ///
+    /// # Returns
+    ///
+    /// A new ScriptedInput
+    pub fn new(events: impl IntoIterator<Item = ScriptedEvent>) -> Self {
+        Self { events: Rc::new(RefCell::new(events.into_iter().collect())) }
+    }
+
+    /// Loads a script from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read
+    ///
+    /// # Returns
+    ///
+    /// Result containing the input source, or an error naming the first invalid line
+    pub fn load(path: &Path) -> GameResult<Self> {
+        let script = persistence::read_file(path)?;
+        let events = script.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::parse)
+            .collect::<GameResult<Vec<ScriptedEvent>>>()?;
+        Ok(Self::new(events))
+    }
+
+    /// Takes the next action, after waiting for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - What the game asks for, to name in errors
+    ///
+    /// # Returns
+    ///
+    /// Result containing the action, or an error if the script ran out
+    fn next(&mut self, expected: &str) -> GameResult<ScriptedAction> {
+        let event = self.events.borrow_mut().pop_front()
+            .ok_or_else(|| GameError::LogicError(format!("The script ran out while waiting for {}", expected)))?;
+        thread::sleep(event.after);
+        Ok(event.action)
+    }
+}
+
+/// Builds the error returned when the script does not match what the game asks for.
+fn unexpected(expected: &str, action: &ScriptedAction) -> GameError {
+    GameError::LogicError(format!("The script has {:?} where the game waits for {}", action, expected))
+}
+
+/// Finds a scripted key among the keys the game waits for.
+fn key_index(keys: &[char], key: char) -> GameResult<usize> {
+    keys.iter()
+        .position(|k| k.eq_ignore_ascii_case(&key))
+        .ok_or_else(|| unexpected(&format!("one of the keys {:?}", keys), &ScriptedAction::Key(key)))
+}
+
+impl InputSource for ScriptedInput {
+    fn read_line(&mut self) -> GameResult<String> {
+        match self.next("a line")? {
+            ScriptedAction::Line(line) => Ok(line),
+            action => Err(unexpected("a line", &action)),
+        }
+    }
+
+    fn wait_for_key(&mut self, keys: &[char]) -> GameResult<usize> {
+        match self.next("a key")? {
+            ScriptedAction::Key(key) => key_index(keys, key),
+            action => Err(unexpected("a key", &action)),
+        }
+    }
+
+    fn wait_for_key_timeout(&mut self, keys: &[char], timeout: Duration) -> GameResult<Option<usize>> {
+        // A key pressed later than the timeout is still to come after it
+        let mut events = self.events.borrow_mut();
+        match events.front_mut() {
+            Some(event) if event.after > timeout => {
+                event.after -= timeout;
+                drop(events);
+                thread::sleep(timeout);
+                Ok(None)
+            },
+            _ => {
+                drop(events);
+                self.wait_for_key(keys).map(Some)
+            },
+        }
+    }
+}
+
+/// Helpers for the tests of the modules that read the players' actions.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+
+    /// Builds an input source typing the given lines right away.
+    pub(crate) fn scripted(lines: &[&str]) -> ScriptedInput {
+        ScriptedInput::new(lines.iter().map(|line| ScriptedEvent {
+            after: Duration::ZERO,
+            action: ScriptedAction::Line(line.to_string()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(ms: u64, action: ScriptedAction) -> ScriptedEvent {
+        ScriptedEvent { after: Duration::from_millis(ms), action }
+    }
+
+    #[test]
+    fn test_parse_script_lines() {
+        assert_eq!("0 enter".parse::<ScriptedEvent>().unwrap(), event(0, ScriptedAction::Line(String::new())));
+        assert_eq!("250 type 12".parse::<ScriptedEvent>().unwrap(), event(250, ScriptedAction::Line("12".to_string())));
+        assert_eq!("5 key f".parse::<ScriptedEvent>().unwrap(), event(5, ScriptedAction::Key('f')));
+        assert!("enter".parse::<ScriptedEvent>().is_err());
+        assert!("1 key fj".parse::<ScriptedEvent>().is_err());
+        assert!("1 jump".parse::<ScriptedEvent>().is_err());
+        assert!("-1 enter".parse::<ScriptedEvent>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for line in ["0 enter", "250 type y", "5 key f"] {
+            assert_eq!(line.parse::<ScriptedEvent>().unwrap().to_string(), line);
+        }
+    }
+
+    #[test]
+    fn test_scripted_input_plays_in_order() {
+        let mut input = ScriptedInput::new([
+            event(0, ScriptedAction::Line("y".to_string())),
+            event(0, ScriptedAction::Key('J')),
+        ]);
+        // Clones share the script
+        let mut clone = input.clone();
+
+        assert_eq!(input.read_line().unwrap(), "y");
+        assert_eq!(clone.wait_for_key(&['f', 'j']).unwrap(), 1);
+        assert!(matches!(input.read_line(), Err(GameError::LogicError(_))));
+    }
+
+    #[test]
+    fn test_scripted_input_rejects_mismatches() {
+        let mut input = ScriptedInput::new([
+            event(0, ScriptedAction::Key('f')),
+            event(0, ScriptedAction::Key('x')),
+        ]);
+        assert!(input.read_line().is_err());
+        assert!(input.wait_for_key(&['f', 'j']).is_err());
+    }
+
+    #[test]
+    fn test_scripted_key_timeout() {
+        let mut input = ScriptedInput::new([event(30, ScriptedAction::Key('j'))]);
+
+        assert_eq!(input.wait_for_key_timeout(&['f', 'j'], Duration::from_millis(10)).unwrap(), None);
+        assert_eq!(input.wait_for_key_timeout(&['f', 'j'], Duration::from_millis(50)).unwrap(), Some(1));
+    }
+}
@@ -0,0 +1,298 @@
+//! Terminal control abstraction.
+//!
+//! All cursor movement and screen clearing goes through this module, backed by
+//! crossterm, so the display behaves the same on Windows, macOS, and Linux.
+//! It also makes sure the terminal is left usable when the game exits or panics.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::panic;
+
+use crossterm::{
+    cursor,
+    queue,
+    style::Stylize,
+    terminal::{self, Clear, ClearType},
+    Command,
+};
+
+/// Size assumed when the terminal cannot be queried, e.g. when output is redirected.
+const FALLBACK_SIZE: (u16, u16) = (80, 24);
+
+/// Returns the size of the terminal as `(columns, rows)`.
+///
+/// Falls back to 80x24 when the size cannot be determined.
+pub fn size() -> (u16, u16) {
+    terminal::size()
+        .ok()
+        .filter(|&(columns, rows)| columns > 0 && rows > 0)
+        .unwrap_or(FALLBACK_SIZE)
+}
+
+/// Moves the cursor up by the given number of lines.
+///
+/// Moving by zero lines is a no-op; terminals treat a zero count as one.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+/// * `lines` - The number of lines to move up
+pub fn move_up<W: Write>(out: &mut W, lines: u16) -> io::Result<()> {
+    if lines > 0 {
+        queue!(out, cursor::MoveUp(lines))?;
+    }
+    Ok(())
+}
+
+/// Moves the cursor to the start of the current line and clears it.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+pub fn clear_line<W: Write>(out: &mut W) -> io::Result<()> {
+    queue!(out, cursor::MoveToColumn(0), Clear(ClearType::CurrentLine))
+}
+
+/// Clears everything from the cursor to the end of the screen.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+pub fn clear_below<W: Write>(out: &mut W) -> io::Result<()> {
+    queue!(out, Clear(ClearType::FromCursorDown))
+}
+
+/// Clears the whole screen and moves the cursor to the top-left corner.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+pub fn clear_screen<W: Write>(out: &mut W) -> io::Result<()> {
+    queue!(out, Clear(ClearType::All), cursor::MoveTo(0, 0))
+}
+
+/// Hides the cursor.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+pub fn hide_cursor<W: Write>(out: &mut W) -> io::Result<()> {
+    queue!(out, cursor::Hide)
+}
+
+/// Shows the cursor.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+pub fn show_cursor<W: Write>(out: &mut W) -> io::Result<()> {
+    queue!(out, cursor::Show)
+}
+
+/// Keeps the terminal in raw mode, where single key presses can be read, until dropped.
+pub struct RawMode {
+    _private: (),
+}
+
+impl RawMode {
+    /// Switches the terminal to raw mode.
+    ///
+    /// # Returns
+    ///
+    /// A guard switching back to cooked mode when it goes out of scope
+    pub fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(Self { _private: () })
+    }
+}
+
+impl Drop for RawMode {
+    /// Switches the terminal back to cooked mode.
+    fn drop(&mut self) {
+        // Nothing more can be done if the terminal cannot be switched back,
+        // `restore` tries again when the game ends
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Restricts scrolling to a range of rows (DECSTBM), or lifts the restriction.
+struct SetScrollRegion(Option<(u16, u16)>);
+
+impl Command for SetScrollRegion {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match self.0 {
+            Some((top, bottom)) => write!(f, "\x1B[{};{}r", top + 1, bottom + 1),
+            None => f.write_str("\x1B[r"),
+        }
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        match self.0 {
+            Some(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "scroll regions require ANSI support")),
+            // A legacy console never had a region set, there is nothing to lift
+            None => Ok(()),
+        }
+    }
+}
+
+/// Restricts scrolling to the rows from `top` to `bottom` (0-based, inclusive).
+///
+/// Rows outside the region stay in place while the output scrolls. The cursor
+/// position is preserved.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+/// * `top` - The first row of the region
+/// * `bottom` - The last row of the region
+pub fn set_scroll_region<W: Write>(out: &mut W, top: u16, bottom: u16) -> io::Result<()> {
+    // Setting the region homes the cursor, so keep it where it was
+    queue!(out, cursor::SavePosition, SetScrollRegion(Some((top, bottom))), cursor::RestorePosition)
+}
+
+/// Lets the whole screen scroll again, preserving the cursor position.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+pub fn reset_scroll_region<W: Write>(out: &mut W) -> io::Result<()> {
+    queue!(out, cursor::SavePosition, SetScrollRegion(None), cursor::RestorePosition)
+}
+
+/// Shows a text in reverse video, e.g. the selected option of a menu.
+///
+/// # Arguments
+///
+/// * `text` - The text to highlight
+///
+/// # Returns
+///
+/// The text with the escape sequences around it
+pub fn highlight(text: &str) -> String {
+    text.reverse().to_string()
+}
+
+/// Replaces the content of a row without moving the cursor.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+/// * `row` - The row to write (0-based)
+/// * `line` - The text to write, which should fit within the terminal width
+pub fn write_row<W: Write>(out: &mut W, row: u16, line: &str) -> io::Result<()> {
+    queue!(out, cursor::SavePosition, cursor::MoveTo(0, row), Clear(ClearType::CurrentLine))?;
+    write!(out, "{}", line)?;
+    queue!(out, cursor::RestorePosition)
+}
+
+/// Shows the cursor and clears any partially drawn line.
+///
+/// # Arguments
+///
+/// * `out` - The writer the commands are queued on
+fn reset_display<W: Write>(out: &mut W) -> io::Result<()> {
+    show_cursor(out)?;
+    clear_line(out)?;
+    out.flush()
+}
+
+/// Restores the terminal to cooked mode with a visible cursor and a clean line.
+///
+/// Every step is taken even if an earlier one failed.
+///
+/// # Returns
+///
+/// The first error met, if any
+pub fn restore() -> io::Result<()> {
+    let raw_mode = terminal::is_raw_mode_enabled()
+        .and_then(|enabled| if enabled { terminal::disable_raw_mode() } else { Ok(()) });
+    let mut stdout = io::stdout();
+    let scroll_region = reset_scroll_region(&mut stdout);
+    let display = reset_display(&mut stdout);
+    raw_mode.and(scroll_region).and(display)
+}
+
+/// Installs a panic hook that restores the terminal before the panic message is printed.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // Nothing more can be done if restoring fails while panicking
+        let _ = restore();
+        default_hook(info);
+    }));
+}
+
+/// Restores the terminal when dropped.
+///
+/// Keep one alive for the duration of the game so the user's shell is left
+/// in a usable state however the game ends.
+pub struct TerminalGuard {
+    _private: (),
+}
+
+impl TerminalGuard {
+    /// Creates a new TerminalGuard.
+    ///
+    /// # Returns
+    ///
+    /// A guard restoring the terminal when it goes out of scope
+    pub fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl Default for TerminalGuard {
+    /// Creates a new TerminalGuard.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Errors cannot be reported from drop, and the game is over anyway
+        let _ = restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_up() {
+        let mut out = Vec::new();
+        move_up(&mut out, 3).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1B[3A");
+    }
+
+    #[test]
+    fn test_move_up_zero_lines() {
+        let mut out = Vec::new();
+        move_up(&mut out, 0).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_reset_display() {
+        let mut out = Vec::new();
+        reset_display(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1B[?25h\x1B[1G\x1B[2K");
+    }
+
+    #[test]
+    fn test_scroll_region() {
+        let mut out = Vec::new();
+        set_scroll_region(&mut out, 1, 23).unwrap();
+        reset_scroll_region(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1B7\x1B[2;24r\x1B8\x1B7\x1B[r\x1B8");
+    }
+
+    #[test]
+    fn test_clear_line() {
+        let mut out = Vec::new();
+        clear_line(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1B[1G\x1B[2K");
+    }
+}
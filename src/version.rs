@@ -0,0 +1,208 @@
+//! Version module for keeping the formats the game writes and reads in step.
+//!
+//! Every save and replay file starts with a header line: magic bytes naming
+//! the kind of file, then the version of its format, such as
+//! `RUSTGAME-SAVE 1.0.0`. A network connection starts the same way, with a
+//! `hello` message from each side. Versions follow semver: a build reads a
+//! format with the same major version as its own (and the same minor version
+//! while the major one is 0), and rejects any other with
+//! `GameError::IncompatibleVersion` rather than misreading it.
+//!
+//! Files written before the header was introduced start straight with their
+//! JSON, and are read as the first version of their format.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{GameError, GameResult};
+
+/// A semantic version, `major.minor.patch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    /// Incremented for changes older builds cannot read
+    pub major: u32,
+    /// Incremented for additions older builds can ignore
+    pub minor: u32,
+    /// Incremented for fixes that leave the format unchanged
+    pub patch: u32,
+}
+
+impl Version {
+    /// Creates a version.
+    ///
+    /// # Arguments
+    ///
+    /// * `major` - The major version
+    /// * `minor` - The minor version
+    /// * `patch` - The patch version
+    ///
+    /// # Returns
+    ///
+    /// A new Version
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Tells whether a build at this version can read a format at another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The version of the format to read
+    ///
+    /// # Returns
+    ///
+    /// True if both share the same major version, and the same minor version before 1.0.0
+    pub fn is_compatible(&self, other: &Version) -> bool {
+        self.major == other.major && (self.major > 0 || self.minor == other.minor)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for Version {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || GameError::ParseError(format!("Invalid version: {}", s));
+        let mut parts = s.split('.').map(|part| part.parse::<u32>().map_err(|_| invalid()));
+        let version = Version::new(
+            parts.next().ok_or_else(invalid)??,
+            parts.next().ok_or_else(invalid)??,
+            parts.next().ok_or_else(invalid)??,
+        );
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(version)
+    }
+}
+
+/// A format the game writes and reads back, named by its magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Format {
+    /// What the format holds, for the error messages
+    pub name: &'static str,
+    /// Bytes every file or connection in this format starts with
+    pub magic: &'static str,
+    /// Version of the format this build writes
+    pub version: Version,
+}
+
+/// Format of the saved and autosaved matches, which keep the history of their rounds from 1.1.0.
+pub const SAVE_FORMAT: Format = Format { name: "save", magic: "RUSTGAME-SAVE", version: Version::new(1, 1, 0) };
+
+/// Format of the recorded practice and time attack runs, survival runs being recorded up to 1.0.0.
+pub const REPLAY_FORMAT: Format = Format { name: "replay", magic: "RUSTGAME-REPLAY", version: Version::new(2, 0, 0) };
+
+/// Protocol between the `connect` client and the matchmaking server.
+pub const PROTOCOL: Format = Format { name: "network protocol", magic: "RUSTGAME-NET", version: Version::new(2, 0, 0) };
+
+impl Format {
+    /// Checks that magic bytes and a version from the other side match this format.
+    ///
+    /// # Arguments
+    ///
+    /// * `magic` - The magic bytes found
+    /// * `version` - The version found
+    ///
+    /// # Returns
+    ///
+    /// Result with `GameError::ParseError` for other magic bytes or an invalid version, or
+    /// `GameError::IncompatibleVersion` for a version this build cannot read
+    pub fn check(&self, magic: &str, version: &str) -> GameResult<()> {
+        if magic != self.magic {
+            return Err(GameError::ParseError(format!("Not in the {} format", self.name)));
+        }
+        let found: Version = version.parse()?;
+        if !self.version.is_compatible(&found) {
+            return Err(GameError::IncompatibleVersion {
+                format: self.name.to_string(),
+                found: found.to_string(),
+                supported: self.version.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Puts the header of this format in front of the contents of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The contents of the file
+    ///
+    /// # Returns
+    ///
+    /// The contents, after a header line with the magic bytes and the version
+    pub fn write(&self, body: &str) -> String {
+        format!("{} {}\n{}", self.magic, self.version, body)
+    }
+
+    /// Checks the header of a file and returns what follows it.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The contents of the file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the contents after the header, all of them for a file
+    /// written before headers, or an error if the header does not match this format
+    pub fn read<'a>(&self, contents: &'a str) -> GameResult<&'a str> {
+        if contents.trim_start().starts_with('{') {
+            return Ok(contents);
+        }
+        let (header, body) = contents.split_once('\n').unwrap_or((contents, ""));
+        let (magic, version) = header.trim_end().split_once(' ').unwrap_or((header, ""));
+        self.check(magic, version)?;
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_round_trip() {
+        let version: Version = "1.12.3".parse().unwrap();
+        assert_eq!(version, Version::new(1, 12, 3));
+        assert_eq!(version.to_string(), "1.12.3");
+        for invalid in ["", "1", "1.2", "1.2.3.4", "1.x.3", "-1.0.0"] {
+            assert!(matches!(invalid.parse::<Version>(), Err(GameError::ParseError(_))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_compatibility_follows_semver() {
+        let current = Version::new(1, 2, 0);
+        assert!(current.is_compatible(&Version::new(1, 0, 5)));
+        assert!(current.is_compatible(&Version::new(1, 3, 0)));
+        assert!(!current.is_compatible(&Version::new(2, 0, 0)));
+        assert!(!Version::new(0, 2, 0).is_compatible(&Version::new(0, 3, 0)));
+        assert!(Version::new(0, 2, 0).is_compatible(&Version::new(0, 2, 1)));
+    }
+
+    #[test]
+    fn test_read_checks_the_header() {
+        let contents = SAVE_FORMAT.write("{}");
+        assert_eq!(SAVE_FORMAT.read(&contents).unwrap(), "{}");
+        assert!(matches!(REPLAY_FORMAT.read(&contents), Err(GameError::ParseError(_))));
+
+        let newer = contents.replace("1.1.0", "2.0.0");
+        assert!(matches!(SAVE_FORMAT.read(&newer),
+                         Err(GameError::IncompatibleVersion { found, supported, .. }) if found == "2.0.0" && supported == "1.1.0"));
+        // Saves of an earlier minor version only lack what was added since
+        let older = contents.replace("1.1.0", "1.0.0");
+        assert_eq!(SAVE_FORMAT.read(&older).unwrap(), "{}");
+        assert!(matches!(SAVE_FORMAT.read("RUSTGAME-SAVE\n{}"), Err(GameError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_read_takes_files_without_header() {
+        assert_eq!(SAVE_FORMAT.read("  { \"round\": 1 }").unwrap(), "  { \"round\": 1 }");
+    }
+}
@@ -0,0 +1,279 @@
+//! WASM module exposing the rules of a match to JavaScript, with the `wasm` feature.
+//!
+//! A browser UI draws the counter and reads the players' stops itself, then
+//! hands them to a `WasmMatch`, which scores them and resolves the rounds
+//! with the same code as the terminal game. Build it with:
+//!
+//! ```text
+//! wasm-pack build --target web --no-default-features --features wasm
+//! ```
+//!
+//! States and round outcomes cross over to JavaScript as JSON strings.
+
+use rand::{SeedableRng, rngs::StdRng};
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+
+use crate::player::{Attribute, Player};
+use crate::rules::{PENALTY_AMOUNT, RuleSet};
+use crate::scoring::{self, ScoreBreakdown};
+use crate::state::{GameState, RoundOutcome, TurnOutcome};
+
+/// A match played in the browser, from its first round to its winner.
+#[wasm_bindgen]
+pub struct WasmMatch {
+    /// The progress of the match
+    state: GameState,
+    /// The rules of the match, those of the classic game
+    rules: RuleSet,
+    /// The random number generator for the targets
+    rng: StdRng,
+}
+
+#[wasm_bindgen]
+impl WasmMatch {
+    /// Creates a match between two players with the same attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `player1` - The name of the first player
+    /// * `player2` - The name of the second player
+    /// * `vitality` - The starting vitality of both players
+    /// * `speed` - The starting speed of both players
+    /// * `strength` - The starting strength of both players
+    /// * `stamina` - The starting stamina of both players
+    /// * `seed` - The seed of the targets, the same seed gives the same targets
+    ///
+    /// # Returns
+    ///
+    /// A new WasmMatch at round 1
+    #[wasm_bindgen(constructor)]
+    pub fn new(player1: String, player2: String, vitality: u32, speed: u32, strength: u32, stamina: u32, seed: u64) -> Self {
+        let rules = RuleSet::default();
+        let players = [player1, player2]
+            .map(|name| Player::new(name, vitality, speed, strength, stamina).with_bounds(rules.bounds));
+        Self { state: GameState::new(players), rules, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    /// Returns the highest counter value, in whole units.
+    #[wasm_bindgen(js_name = counterMax)]
+    pub fn counter_max(&self) -> u32 {
+        self.rules.counter_max
+    }
+
+    /// Returns the delay between two counter ticks on a player's turn, in milliseconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The index of the player (0 or 1)
+    ///
+    /// # Returns
+    ///
+    /// Result containing the delay, or an error for an unknown player
+    #[wasm_bindgen(js_name = tickInterval)]
+    pub fn tick_interval(&self, player: usize) -> Result<u32, JsValue> {
+        Ok(self.rules.tick_interval(self.player(player).map_err(js_error)?))
+    }
+
+    /// Draws the targets of a turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of targets
+    ///
+    /// # Returns
+    ///
+    /// The targets, in whole units
+    pub fn targets(&mut self, count: usize) -> Vec<u32> {
+        self.rules.targets(&mut self.rng, count)
+    }
+
+    /// Scores one stop of the counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The index of the player who stopped it, for their strength
+    /// * `target` - The target, in whole units
+    /// * `stop` - The value the counter stopped at, in whole units
+    /// * `miss` - How many times the counter went around before it was stopped
+    ///
+    /// # Returns
+    ///
+    /// Result containing the score of the target, or an error for an unknown player
+    pub fn score(&self, player: usize, target: u32, stop: u32, miss: u32) -> Result<u32, JsValue> {
+        let strength = self.player(player).map_err(js_error)?.strength();
+        let dial = self.rules.dial();
+        Ok(ScoreBreakdown::new(dial.ticks(target), dial.ticks(stop), strength, miss, 0, dial).total())
+    }
+
+    /// Ends the round with both players' average scores.
+    ///
+    /// The loser loses vitality, the winner gains momentum, and the match is
+    /// over once a player runs out of vitality.
+    ///
+    /// # Arguments
+    ///
+    /// * `score1` - The average score of the first player
+    /// * `score2` - The average score of the second player
+    ///
+    /// # Returns
+    ///
+    /// The outcome as JSON: `{"draw": true}`, or the `winner`, `loser`, `diff` and `damage`
+    #[wasm_bindgen(js_name = resolveRound)]
+    pub fn resolve_round(&mut self, score1: u32, score2: u32) -> String {
+        let turns = [TurnOutcome::Played(score1), TurnOutcome::Played(score2)];
+        let (state, outcome) = self.state.resolve_round(turns, 0, self.rules.damage, &[]);
+        self.state = state;
+        self.state.first_player = self.rules.turn_order.next_first(self.state.first_player, &outcome);
+        self.state.end_round(self.rules.win_condition);
+        match outcome {
+            RoundOutcome::Win { winner, loser, diff, damage, .. } =>
+                json!({ "winner": winner, "loser": loser, "diff": diff, "damage": damage }).to_string(),
+            RoundOutcome::Draw => json!({ "draw": true }).to_string(),
+        }
+    }
+
+    /// Applies the penalty the round's winner chose to the loser.
+    ///
+    /// # Arguments
+    ///
+    /// * `loser` - The index of the player penalized (0 or 1)
+    /// * `attribute` - The attribute they lose points of, `speed` or `strength`
+    ///
+    /// # Returns
+    ///
+    /// Result with an error for an unknown player or attribute
+    pub fn penalize(&mut self, loser: usize, attribute: &str) -> Result<(), JsValue> {
+        self.apply_penalty(loser, attribute).map_err(js_error)
+    }
+
+    /// Returns whether the match goes on with another round.
+    #[wasm_bindgen(js_name = inProgress)]
+    pub fn in_progress(&self) -> bool {
+        self.state.in_progress()
+    }
+
+    /// Returns the index of the winner, once the match is over.
+    pub fn winner(&self) -> Option<usize> {
+        self.state.winner_idx
+    }
+
+    /// Returns the state of the match as JSON, in the format of the saved matches.
+    pub fn state(&self) -> String {
+        serde_json::to_string(&self.state).expect("a game state always serializes")
+    }
+
+    /// Returns the hash of the state, the same as the terminal game and the server compute.
+    pub fn hash(&self) -> u64 {
+        self.state.hash()
+    }
+}
+
+impl WasmMatch {
+    /// Finds a player from the index JavaScript gave.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The index of the player
+    ///
+    /// # Returns
+    ///
+    /// Result containing the player, or an error message if there is no such player
+    fn player(&self, player: usize) -> Result<&Player, String> {
+        self.state.players.get(player).ok_or_else(|| format!("Unknown player: {}, the players are 0 and 1", player))
+    }
+
+    /// Takes the points of a penalty from a player's attribute.
+    ///
+    /// # Arguments
+    ///
+    /// * `loser` - The index of the player penalized
+    /// * `attribute` - The attribute they lose points of, `speed` or `strength`
+    ///
+    /// # Returns
+    ///
+    /// Result with an error message for an unknown player or attribute
+    fn apply_penalty(&mut self, loser: usize, attribute: &str) -> Result<(), String> {
+        let attribute = serde_json::from_value(json!(attribute)).map_err(|_| format!("Unknown attribute: {}", attribute))?;
+        self.player(loser)?;
+        let player = &mut self.state.players[loser];
+        match attribute {
+            Attribute::Speed => player.decrease_speed(PENALTY_AMOUNT),
+            Attribute::Strength => player.decrease_strength(PENALTY_AMOUNT),
+        }
+        Ok(())
+    }
+}
+
+/// Turns an error message into the error JavaScript receives.
+fn js_error(message: String) -> JsValue {
+    JsValue::from_str(&message)
+}
+
+/// Averages the scores of a turn, rounding up like the terminal game.
+///
+/// # Arguments
+///
+/// * `scores` - The score of each target
+///
+/// # Returns
+///
+/// The average score of the turn
+#[wasm_bindgen(js_name = averageScore)]
+pub fn average_score(scores: Vec<u32>) -> u32 {
+    scoring::calculate_average_score(&scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_match() -> WasmMatch {
+        WasmMatch::new("Alice".to_string(), "Bob".to_string(), 10, 50, 50, 3, 7)
+    }
+
+    #[test]
+    fn test_same_seed_gives_the_same_targets() {
+        let targets = new_match().targets(5);
+        assert_eq!(targets, new_match().targets(5));
+        assert!(targets.iter().all(|&target| target <= new_match().counter_max()));
+    }
+
+    #[test]
+    fn test_scores_like_the_terminal_game() {
+        let game = new_match();
+        let dial = RuleSet::default().dial();
+        assert_eq!(game.score(0, 40, 42, 0).unwrap(), ScoreBreakdown::new(40, 42, 50, 0, 0, dial).total());
+        assert_eq!(average_score(vec![90, 81]), 86);
+    }
+
+    #[test]
+    fn test_match_ends_when_a_player_runs_out_of_vitality() {
+        let mut game = new_match();
+        assert_eq!(game.resolve_round(50, 50), r#"{"draw":true}"#);
+        let outcome: serde_json::Value = serde_json::from_str(&game.resolve_round(150, 40)).unwrap();
+        assert_eq!(outcome["winner"], 0);
+        assert!(!game.in_progress());
+        assert_eq!(game.winner(), Some(0));
+        assert_eq!(serde_json::from_str::<GameState>(&game.state()).unwrap().hash(), game.hash());
+    }
+
+    #[test]
+    fn test_penalty_lowers_the_attribute() {
+        let mut game = new_match();
+        game.penalize(1, "speed").unwrap();
+        let state: GameState = serde_json::from_str(&game.state()).unwrap();
+        assert_eq!(state.players[1].speed(), 50 - PENALTY_AMOUNT);
+    }
+
+    #[test]
+    fn test_unknown_players_are_rejected() {
+        // Checked without crossing over to JavaScript, which only a wasm target can do
+        let mut game = new_match();
+        assert!(game.player(1).is_ok());
+        assert!(game.player(2).is_err());
+        assert!(game.apply_penalty(2, "speed").is_err());
+        assert!(game.apply_penalty(0, "stamina").is_err());
+        assert_eq!(game.state, new_match().state);
+    }
+}